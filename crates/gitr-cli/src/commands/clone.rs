@@ -0,0 +1,151 @@
+use std::path::Path;
+
+use clap::Args;
+use gitr_core::config::GitrConfig;
+use gitr_core::layout::{CloneConfig, CloneProtocol};
+use gitr_core::models::host::Host;
+use gitr_core::models::repo::Repo;
+use gitr_discover::adopt;
+use gitr_sync::backend::create_backend;
+use gitr_sync::clone::{CloneStatus, CloneTarget};
+use gitr_sync::engine::SyncEngine;
+use gitr_sync::git_ops::CloneOptions;
+
+use super::scope::ScopeArgs;
+use crate::output::{CloneOutcome, Incomplete, OutputFormat};
+
+#[derive(Args)]
+pub struct CloneArgs {
+    /// Only repos matching this filter, e.g. "host:gh fork:true"
+    #[arg(value_name = "FILTER")]
+    query: Option<String>,
+    /// Only repos in this collection
+    #[arg(long)]
+    collection: Option<String>,
+    /// Fetch only the latest commit of history
+    #[arg(long)]
+    shallow: bool,
+    /// Partial clone filter passed to git, e.g. blob:none
+    #[arg(long = "filter", value_name = "SPEC")]
+    partial: Option<String>,
+    /// Clone over this protocol (https, ssh) instead of the configured one
+    #[arg(long)]
+    protocol: Option<String>,
+    /// Show where each repo would be cloned without cloning
+    #[arg(long)]
+    dry_run: bool,
+}
+
+pub async fn run(args: CloneArgs, format: OutputFormat) -> anyhow::Result<()> {
+    let config = GitrConfig::load()?;
+    let db_path = GitrConfig::db_path()?;
+    let conn = gitr_db::open_db(&db_path)?;
+    let hosts = gitr_db::ops::list_hosts(&conn)?;
+
+    let protocol = match args.protocol {
+        Some(ref p) => Some(p.parse::<CloneProtocol>().map_err(|e| anyhow::anyhow!(e))?),
+        None => None,
+    };
+    let scope = ScopeArgs {
+        collection: args.collection,
+        filter: args.query,
+    };
+    let mut repos = gitr_db::ops::list_repos(&conn)?;
+    scope.retain(&conn, &mut repos)?;
+    // A recorded clone that has since been deleted gets cloned again.
+    repos.retain(|r| !r.local_path.as_deref().is_some_and(Path::exists));
+    if repos.is_empty() {
+        if let Some(out) = format.structured() {
+            return out.emit::<CloneOutcome>(&[]);
+        }
+        println!("Nothing to clone: every matching repo has a local clone.");
+        return Ok(());
+    }
+
+    let targets = repos
+        .into_iter()
+        .map(|repo| clone_target(&config.clone, &hosts, protocol, repo))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    if args.dry_run {
+        let outcomes: Vec<CloneOutcome> = targets.iter().map(CloneOutcome::planned).collect();
+        if let Some(out) = format.structured() {
+            return out.emit(&outcomes);
+        }
+        println!("Would clone {} repos:", targets.len());
+        for target in &targets {
+            println!("  {} -> {}", target.url, target.dest.display());
+        }
+        return Ok(());
+    }
+
+    format.say(format_args!("Cloning {} repos...", targets.len()));
+    let opts = CloneOptions {
+        depth: args.shallow.then_some(1),
+        filter: args.partial,
+    };
+    let engine = SyncEngine::new(config.sync_concurrency, config.retry.clone())
+        .with_backend(create_backend(&config.git_backend));
+    let results = engine.clone_all(targets, &opts).await;
+
+    for result in &results {
+        if result.status != CloneStatus::Failed {
+            gitr_db::ops::update_repo_local_path(&conn, &result.repo_id, Some(&result.dest))?;
+        }
+    }
+
+    let count = |status| results.iter().filter(|r| r.status == status).count();
+    let failed = count(CloneStatus::Failed);
+    match format.structured() {
+        Some(out) => {
+            let outcomes: Vec<CloneOutcome> = results.into_iter().map(Into::into).collect();
+            out.emit(&outcomes)?;
+        }
+        None => {
+            println!(
+                "Cloned {} repos | {} already present | {failed} failed",
+                count(CloneStatus::Cloned),
+                count(CloneStatus::Existing)
+            );
+            for result in &results {
+                if let Some(err) = &result.error {
+                    println!("  {}: {err}", result.repo_full_name);
+                }
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(Incomplete::Failed(failed).into());
+    }
+    Ok(())
+}
+
+/// Where and how to clone `repo`: its layout path, its clone URL in the
+/// preferred protocol, and for forks an `upstream` URL in the protocol
+/// preferred for the upstream's own host.
+fn clone_target(
+    clone: &CloneConfig,
+    hosts: &[Host],
+    protocol: Option<CloneProtocol>,
+    repo: Repo,
+) -> anyhow::Result<CloneTarget> {
+    let protocol_for = |host: Option<&Host>| {
+        protocol.unwrap_or_else(|| clone.protocol_for(host.map(|h| h.label.as_str())))
+    };
+    let host = hosts.iter().find(|h| repo.host_id.as_ref() == Some(&h.id));
+    let dest = clone.path_for(&repo, host)?;
+    let url = protocol_for(host).rewrite(&repo.clone_url);
+    let upstream_url = match (&repo.upstream_clone_url, repo.is_fork) {
+        (Some(upstream), true) => {
+            Some(protocol_for(adopt::infer_host(upstream, hosts)).rewrite(upstream))
+        }
+        _ => None,
+    };
+    Ok(CloneTarget {
+        repo,
+        url,
+        dest,
+        upstream_url,
+    })
+}
@@ -0,0 +1,315 @@
+use std::collections::HashSet;
+
+use clap::{Args, Subcommand};
+use gitr_core::config::GitrConfig;
+use gitr_core::filter::Filter;
+use gitr_core::models::collection::{Collection, CollectionMember};
+use gitr_core::models::repo::{Repo, RepoId};
+use globset::Glob;
+use rusqlite::Connection;
+use serde::Serialize;
+
+use crate::output::{CsvRow, OutputFormat, Structured};
+
+#[derive(Subcommand)]
+pub enum CollectionAction {
+    /// Create a collection, empty or defined by a query
+    Create {
+        /// Collection name
+        name: String,
+        /// What the collection is for
+        #[arg(long)]
+        description: Option<String>,
+        /// Make a smart collection whose members are the repos matching this
+        /// filter at use time, e.g. "host:gh fork:true behind>0"
+        #[arg(long)]
+        query: Option<String>,
+    },
+    /// List collections
+    List,
+    /// Show a collection and its repos (evaluated now for smart collections)
+    Show {
+        /// Collection name
+        name: String,
+    },
+    /// Add repos to a collection
+    Add {
+        /// Collection name
+        name: String,
+        #[command(flatten)]
+        select: RepoSelector,
+    },
+    /// Remove repos from a collection
+    Remove {
+        /// Collection name
+        name: String,
+        #[command(flatten)]
+        select: RepoSelector,
+    },
+    /// Delete a collection; its repos stay tracked
+    Delete {
+        /// Collection name
+        name: String,
+    },
+}
+
+/// Which repos `collection add` and `collection remove` act on.
+#[derive(Args)]
+pub struct RepoSelector {
+    /// Repo names, full names (owner/repo) or globs such as "acme/*"
+    repos: Vec<String>,
+    /// Only repos on this host
+    #[arg(long)]
+    host: Option<String>,
+    /// Only forks
+    #[arg(long)]
+    forks: bool,
+    /// Only repos matching this filter, e.g. "owner:acme lang:rust"
+    #[arg(long = "where", value_name = "FILTER")]
+    filter: Option<String>,
+}
+
+impl RepoSelector {
+    /// The repos among `candidates` that this selector picks. With no
+    /// patterns, every candidate passing the filters is picked.
+    fn select(&self, conn: &Connection, mut candidates: Vec<Repo>) -> anyhow::Result<Vec<Repo>> {
+        if self.repos.is_empty() && self.host.is_none() && !self.forks && self.filter.is_none() {
+            anyhow::bail!("Give repo names or globs, or a filter such as --host, --forks or --where");
+        }
+        if let Some(label) = &self.host {
+            let host = gitr_db::ops::get_host_by_label(conn, label)?
+                .ok_or_else(|| anyhow::anyhow!("Host '{}' not found", label))?;
+            candidates.retain(|r| r.host_id.as_ref() == Some(&host.id));
+        }
+        if self.forks {
+            candidates.retain(|r| r.is_fork);
+        }
+        if let Some(query) = &self.filter {
+            let filter: Filter = query.parse()?;
+            let matching: HashSet<RepoId> = gitr_db::ops::list_repos_matching(conn, &filter)?
+                .into_iter()
+                .map(|r| r.id)
+                .collect();
+            candidates.retain(|r| matching.contains(&r.id));
+        }
+        if self.repos.is_empty() {
+            return Ok(candidates);
+        }
+
+        let mut picked = HashSet::new();
+        for pattern in &self.repos {
+            if pattern.contains(['*', '?', '[', '{']) {
+                let matcher = Glob::new(pattern)?.compile_matcher();
+                let matched: Vec<RepoId> = candidates
+                    .iter()
+                    .filter(|r| matcher.is_match(&r.full_name) || matcher.is_match(&r.name))
+                    .map(|r| r.id.clone())
+                    .collect();
+                if matched.is_empty() {
+                    anyhow::bail!("No repos match '{}'", pattern);
+                }
+                picked.extend(matched);
+            } else {
+                let repo = super::scope::find_repo(conn, &candidates, pattern)?;
+                picked.insert(repo.id.clone());
+            }
+        }
+        candidates.retain(|r| picked.contains(&r.id));
+        Ok(candidates)
+    }
+}
+
+/// `collection list` output: a collection with its size.
+#[derive(Serialize)]
+struct CollectionEntry {
+    #[serde(flatten)]
+    collection: Collection,
+    repos: usize,
+}
+
+impl CsvRow for CollectionEntry {
+    const HEADERS: &'static [&'static str] = &["id", "name", "description", "query", "repos"];
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.collection.id.to_string(),
+            self.collection.name.clone(),
+            self.collection.description.clone().unwrap_or_default(),
+            self.collection.query.clone().unwrap_or_default(),
+            self.repos.to_string(),
+        ]
+    }
+}
+
+/// `collection show` output: a collection and its repos.
+#[derive(Serialize)]
+struct CollectionInfo {
+    #[serde(flatten)]
+    collection: Collection,
+    repos: Vec<Repo>,
+}
+
+pub fn run(action: CollectionAction, format: OutputFormat) -> anyhow::Result<()> {
+    let db_path = GitrConfig::db_path()?;
+    let conn = gitr_db::open_db(&db_path)?;
+
+    match action {
+        CollectionAction::Create {
+            name,
+            description,
+            query,
+        } => {
+            if gitr_db::ops::get_collection_by_name(&conn, &name)?.is_some() {
+                anyhow::bail!("Collection '{}' already exists", name);
+            }
+            let mut collection = Collection::new(name.clone(), description);
+            if let Some(query) = query {
+                let filter: Filter = query.parse()?;
+                collection.query = Some(filter.to_string());
+            }
+            gitr_db::ops::insert_collection(&conn, &collection)?;
+            match &collection.query {
+                Some(query) => {
+                    let count = members(&conn, &collection)?.len();
+                    format.say(format_args!(
+                        "Created smart collection '{name}' ({query}), currently {count} repo(s)."
+                    ));
+                }
+                None => format.say(format_args!("Created collection '{name}'.")),
+            }
+            Ok(())
+        }
+        CollectionAction::List => {
+            let mut entries = Vec::new();
+            for collection in gitr_db::ops::list_collections(&conn)? {
+                let repos = members(&conn, &collection)?.len();
+                entries.push(CollectionEntry { collection, repos });
+            }
+
+            if let Some(out) = format.structured() {
+                return out.emit(&entries);
+            }
+            if entries.is_empty() {
+                println!("No collections. Use `gitr collection create` to make one.");
+                return Ok(());
+            }
+            println!("{:<24} {:<8} {:<40} QUERY", "NAME", "REPOS", "DESCRIPTION");
+            for entry in &entries {
+                println!(
+                    "{:<24} {:<8} {:<40} {}",
+                    entry.collection.name,
+                    entry.repos,
+                    entry.collection.description.as_deref().unwrap_or(""),
+                    entry.collection.query.as_deref().unwrap_or("—")
+                );
+            }
+            Ok(())
+        }
+        CollectionAction::Show { name } => {
+            let collection = find(&conn, &name)?;
+            let repos = members(&conn, &collection)?;
+
+            match format.structured() {
+                Some(Structured::Csv) => return Structured::Csv.emit(&repos),
+                Some(out) => return out.emit_value(&CollectionInfo { collection, repos }),
+                None => {}
+            }
+            println!("Collection:      {}", collection.name);
+            if let Some(description) = &collection.description {
+                println!("Description:     {description}");
+            }
+            if let Some(query) = &collection.query {
+                println!("Query:           {query}");
+            }
+            println!();
+            if collection.is_smart() && repos.is_empty() {
+                println!("No repos match the query right now.");
+            } else if repos.is_empty() {
+                println!("No repos yet. Use `gitr collection add {name} <repo>...` to add some.");
+            } else {
+                super::repo::print_repos(&repos);
+            }
+            Ok(())
+        }
+        CollectionAction::Add { name, select } => {
+            let collection = find_manual(&conn, &name)?;
+            let existing = member_ids(&conn, &name)?;
+            let repos = select.select(&conn, gitr_db::ops::list_repos(&conn)?)?;
+
+            let mut added = 0;
+            for repo in repos.iter().filter(|r| !existing.contains(&r.id)) {
+                gitr_db::ops::add_collection_member(
+                    &conn,
+                    &CollectionMember {
+                        collection_id: collection.id.clone(),
+                        repo_id: repo.id.clone(),
+                    },
+                )?;
+                format.say(format_args!("  + {}", repo.full_name));
+                added += 1;
+            }
+            format.say(format_args!("Added {added} repo(s) to '{name}'."));
+            Ok(())
+        }
+        CollectionAction::Remove { name, select } => {
+            let collection = find_manual(&conn, &name)?;
+            let members = gitr_db::ops::list_collection_members(&conn, &collection.id)?;
+            let repos = select.select(&conn, members)?;
+
+            for repo in &repos {
+                gitr_db::ops::remove_collection_member(&conn, &collection.id, &repo.id)?;
+                format.say(format_args!("  - {}", repo.full_name));
+            }
+            format.say(format_args!(
+                "Removed {} repo(s) from '{name}'.",
+                repos.len()
+            ));
+            Ok(())
+        }
+        CollectionAction::Delete { name } => {
+            let collection = find(&conn, &name)?;
+            gitr_db::ops::delete_collection(&conn, &collection.id)?;
+            format.say(format_args!("Deleted collection '{name}'."));
+            Ok(())
+        }
+    }
+}
+
+/// Look up a collection by name.
+pub(crate) fn find(conn: &Connection, name: &str) -> anyhow::Result<Collection> {
+    gitr_db::ops::get_collection_by_name(conn, name)?
+        .ok_or_else(|| anyhow::anyhow!("Collection '{}' not found", name))
+}
+
+/// Look up a collection whose membership is edited by hand.
+fn find_manual(conn: &Connection, name: &str) -> anyhow::Result<Collection> {
+    let collection = find(conn, name)?;
+    if collection.is_smart() {
+        anyhow::bail!(
+            "Collection '{}' is defined by a query; its members can't be edited by hand",
+            name
+        );
+    }
+    Ok(collection)
+}
+
+/// The repos in a collection: its member rows, or for a smart collection
+/// whichever repos match its query now.
+pub(crate) fn members(conn: &Connection, collection: &Collection) -> anyhow::Result<Vec<Repo>> {
+    match &collection.query {
+        Some(query) => {
+            let filter: Filter = query.parse()?;
+            gitr_db::ops::list_repos_matching(conn, &filter)
+        }
+        None => gitr_db::ops::list_collection_members(conn, &collection.id),
+    }
+}
+
+/// IDs of the repos in the named collection, for `--collection` filters.
+pub(crate) fn member_ids(conn: &Connection, name: &str) -> anyhow::Result<HashSet<RepoId>> {
+    let collection = find(conn, name)?;
+    Ok(members(conn, &collection)?
+        .into_iter()
+        .map(|r| r.id)
+        .collect())
+}
@@ -1,6 +1,8 @@
 use clap::Subcommand;
 use gitr_core::config::GitrConfig;
 
+use crate::output::OutputFormat;
+
 #[derive(Subcommand)]
 pub enum ConfigAction {
     /// Initialize ~/.gitr/ directory with default config and database
@@ -9,7 +11,7 @@ pub enum ConfigAction {
     Show,
 }
 
-pub fn run(action: ConfigAction) -> anyhow::Result<()> {
+pub fn run(action: ConfigAction, format: OutputFormat) -> anyhow::Result<()> {
     match action {
         ConfigAction::Init => {
             let home = GitrConfig::init()?;
@@ -25,6 +27,9 @@ pub fn run(action: ConfigAction) -> anyhow::Result<()> {
         }
         ConfigAction::Show => {
             let config = GitrConfig::load()?;
+            if let Some(out) = format.structured() {
+                return out.emit_value(&config);
+            }
             let toml_str = toml::to_string_pretty(&config)?;
             println!("{toml_str}");
             Ok(())
@@ -0,0 +1,515 @@
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use axum::body::Bytes;
+use axum::extract::{Path as UrlPath, State};
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::routing::{get, post};
+use axum::Router;
+use chrono::Utc;
+use clap::Args;
+use gitr_auth::{CredentialStore, KeyringStore};
+use gitr_core::config::GitrConfig;
+use gitr_core::models::host::HostId;
+use gitr_core::models::repo::{Repo, RepoId};
+use gitr_core::models::sync_link::{SyncLink, SyncTrigger};
+use gitr_core::models::sync_state::SyncStatus;
+use gitr_sync::backend::create_backend;
+use gitr_sync::engine::SyncEngine;
+use gitr_sync::scheduler::{JobKind, ScheduledJob};
+use gitr_sync::webhook::{self, Debouncer, PushEvent, PushTarget, WebhookSource};
+use rusqlite::Connection;
+use tokio::sync::mpsc;
+use tokio::task::{JoinSet, LocalSet};
+
+use super::refresh;
+use super::sync::{save_results, upstream_pairs};
+
+#[derive(Args)]
+pub struct DaemonArgs {
+    /// Run whatever is due now, wait for it to finish, and exit
+    #[arg(long)]
+    once: bool,
+    /// Also accept push webhooks on this address, e.g. 127.0.0.1:8787
+    #[arg(long, conflicts_with = "once")]
+    listen: Option<SocketAddr>,
+}
+
+/// Longest the scheduler sleeps between checks, so new links are picked up
+/// promptly and deferred jobs are retried.
+const MAX_TICK: Duration = Duration::from_secs(30);
+
+/// Work for one job run, with the repos it holds while running.
+enum Work {
+    Link(Box<LinkWork>),
+    Forks(Vec<Repo>),
+}
+
+struct LinkWork {
+    link: SyncLink,
+    source: Repo,
+    target: Repo,
+}
+
+impl Work {
+    fn repo_ids(&self) -> Vec<RepoId> {
+        match self {
+            Work::Link(w) => vec![w.source.id.clone(), w.target.id.clone()],
+            Work::Forks(forks) => forks.iter().map(|r| r.id.clone()).collect(),
+        }
+    }
+}
+
+pub async fn run(args: DaemonArgs) -> anyhow::Result<()> {
+    // Job futures hold a DB connection across awaits, so they run on a
+    // LocalSet rather than the multi-threaded runtime.
+    LocalSet::new().run_until(run_scheduler(args)).await
+}
+
+async fn run_scheduler(args: DaemonArgs) -> anyhow::Result<()> {
+    let config = GitrConfig::load()?;
+    let db_path = GitrConfig::db_path()?;
+    let conn = gitr_db::open_db(&db_path)?;
+
+    let mut jobs: HashMap<String, ScheduledJob> = HashMap::new();
+    let mut busy: HashSet<RepoId> = HashSet::new();
+    let mut running: JoinSet<Vec<RepoId>> = JoinSet::new();
+
+    let mut pushes = Debouncer::new(Duration::from_secs(config.daemon.webhook_debounce_secs));
+    let (push_tx, mut push_rx) = mpsc::unbounded_channel();
+    if let Some(addr) = args.listen {
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        let state = WebhookState {
+            hosts: Arc::new(load_webhook_hosts(&conn)?),
+            tx: push_tx,
+            db_path: db_path.clone(),
+        };
+        let app = Router::new()
+            .route("/webhook/{host}", post(handle_webhook))
+            .route("/metrics", get(handle_metrics))
+            .with_state(state);
+        tracing::info!(
+            "listening for webhooks on http://{addr}/webhook/<host>, metrics on /metrics"
+        );
+        tokio::spawn(async move {
+            if let Err(e) = axum::serve(listener, app).await {
+                tracing::error!("webhook listener stopped: {e}");
+            }
+        });
+    } else {
+        drop(push_tx);
+    }
+
+    tracing::info!("gitr daemon started");
+    loop {
+        load_jobs(&conn, &config, &mut jobs)?;
+
+        let now = Instant::now();
+        for target in pushes.take_due(now) {
+            let id = match &target {
+                PushTarget::Fork(repo_id) => format!("push:fork:{repo_id}"),
+                PushTarget::Link(link_id) => format!("push:link:{link_id}"),
+            };
+            match prepare_push(&conn, &target, &busy)? {
+                PushWork::Ready(work) => {
+                    busy.extend(work.repo_ids());
+                    tracing::info!("{id}: starting webhook-triggered run");
+                    running.spawn_local(run_job(config.clone(), db_path.clone(), id, work));
+                }
+                PushWork::Busy => {
+                    tracing::debug!("{id}: repos busy, deferring");
+                    pushes.touch(target, now);
+                }
+                PushWork::Gone => tracing::debug!("{id}: no longer tracked, dropping"),
+            }
+        }
+
+        let now = Utc::now();
+        let mut due: Vec<&String> = jobs
+            .iter()
+            .filter(|(_, job)| job.is_due(now))
+            .map(|(id, _)| id)
+            .collect();
+        due.sort();
+        let due: Vec<String> = due.into_iter().cloned().collect();
+
+        for id in due {
+            let job = jobs.get_mut(&id).expect("job listed above");
+            let Some(work) = prepare(&conn, &job.kind, &busy)? else {
+                tracing::debug!("{id}: repos busy, deferring");
+                continue;
+            };
+            busy.extend(work.repo_ids());
+            job.mark_run(now);
+            match job.state() {
+                Some(state) => gitr_db::ops::upsert_schedule_state(&conn, &state)?,
+                None => gitr_db::ops::delete_schedule_state(&conn, &id)?,
+            }
+            tracing::info!("{id}: starting scheduled run");
+            running.spawn_local(run_job(config.clone(), db_path.clone(), id, work));
+        }
+
+        if args.once {
+            while let Some(done) = running.join_next().await {
+                release(&mut busy, done);
+            }
+            return Ok(());
+        }
+
+        let sleep = jobs
+            .values()
+            .filter_map(|job| job.next_run)
+            .min()
+            .and_then(|next| (next - Utc::now()).to_std().ok())
+            .unwrap_or(Duration::ZERO)
+            .clamp(Duration::from_secs(1), MAX_TICK);
+        let sleep = match pushes.next_due() {
+            Some(due) => sleep.min(due.saturating_duration_since(Instant::now())),
+            None => sleep,
+        };
+
+        tokio::select! {
+            Some(done) = running.join_next() => release(&mut busy, done),
+            Some((host_id, event)) = push_rx.recv() => queue_push(&conn, &mut pushes, &host_id, &event)?,
+            _ = tokio::time::sleep(sleep) => {}
+            _ = tokio::signal::ctrl_c() => {
+                tracing::info!("shutting down; waiting for {} running job(s)", running.len());
+                while let Some(done) = running.join_next().await {
+                    release(&mut busy, done);
+                }
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn release(busy: &mut HashSet<RepoId>, done: Result<Vec<RepoId>, tokio::task::JoinError>) {
+    match done {
+        Ok(ids) => {
+            for id in ids {
+                busy.remove(&id);
+            }
+        }
+        // A panicked job keeps its repos locked until restart rather than
+        // risking a concurrent run on a half-synced clone.
+        Err(e) => tracing::error!("scheduled job panicked: {e}"),
+    }
+}
+
+/// Sync `jobs` with the scheduled links in the DB and the fork-sync cron in
+/// config, resuming new jobs from their persisted state.
+fn load_jobs(
+    conn: &Connection,
+    config: &GitrConfig,
+    jobs: &mut HashMap<String, ScheduledJob>,
+) -> anyhow::Result<()> {
+    let mut wanted: Vec<(JobKind, String)> = gitr_db::ops::list_sync_links(conn)?
+        .into_iter()
+        .filter(|link| link.enabled)
+        .filter_map(|link| match link.trigger {
+            SyncTrigger::Schedule { cron } => Some((JobKind::Link(link.id), cron)),
+            _ => None,
+        })
+        .collect();
+    if let Some(cron) = &config.daemon.fork_sync_cron {
+        wanted.push((JobKind::Forks, cron.clone()));
+    }
+
+    let wanted_ids: HashSet<String> = wanted.iter().map(|(kind, _)| kind.job_id()).collect();
+    jobs.retain(|id, _| wanted_ids.contains(id));
+
+    let mut persisted: Option<HashMap<String, _>> = None;
+    let now = Utc::now();
+    for (kind, cron) in wanted {
+        let id = kind.job_id();
+        if jobs.get(&id).is_some_and(|job| job.cron == cron) {
+            continue;
+        }
+        let persisted = match &mut persisted {
+            Some(map) => map,
+            None => persisted.insert(
+                gitr_db::ops::list_schedule_states(conn)?
+                    .into_iter()
+                    .map(|s| (s.job_id.clone(), s))
+                    .collect(),
+            ),
+        };
+        match ScheduledJob::resume(
+            kind,
+            &cron,
+            persisted.get(&id),
+            now,
+            config.daemon.missed_runs,
+        ) {
+            Ok(job) => {
+                match (&job.next_run, job.state()) {
+                    (Some(next), Some(state)) => {
+                        tracing::info!("{id}: scheduled '{cron}', next run {next}");
+                        gitr_db::ops::upsert_schedule_state(conn, &state)?;
+                    }
+                    _ => tracing::warn!("{id}: '{cron}' has no upcoming run times"),
+                }
+                jobs.insert(id, job);
+            }
+            Err(e) => tracing::warn!("{id}: {e}"),
+        }
+    }
+    Ok(())
+}
+
+/// Resolve a due job into work, or `None` if it must wait for busy repos.
+fn prepare(
+    conn: &Connection,
+    kind: &JobKind,
+    busy: &HashSet<RepoId>,
+) -> anyhow::Result<Option<Work>> {
+    match kind {
+        JobKind::Link(link_id) => {
+            let Some(link) = gitr_db::ops::list_sync_links(conn)?
+                .into_iter()
+                .find(|l| &l.id == link_id)
+            else {
+                return Ok(None);
+            };
+            if busy.contains(&link.source_repo_id) || busy.contains(&link.target_repo_id) {
+                return Ok(None);
+            }
+            let source = gitr_db::ops::get_repo_by_id(conn, &link.source_repo_id)?;
+            let target = gitr_db::ops::get_repo_by_id(conn, &link.target_repo_id)?;
+            match (source, target) {
+                (Some(source), Some(target)) => Ok(Some(Work::Link(Box::new(LinkWork {
+                    link,
+                    source,
+                    target,
+                })))),
+                _ => {
+                    tracing::warn!("link:{link_id}: source or target repo no longer tracked");
+                    Ok(None)
+                }
+            }
+        }
+        JobKind::Forks => {
+            let forks: Vec<Repo> = gitr_db::ops::list_fork_repos(conn)?
+                .into_iter()
+                .filter(|r| !busy.contains(&r.id))
+                .collect();
+            Ok((!forks.is_empty()).then_some(Work::Forks(forks)))
+        }
+    }
+}
+
+/// Outcome of resolving a debounced webhook push into work.
+enum PushWork {
+    Ready(Work),
+    Busy,
+    Gone,
+}
+
+fn prepare_push(
+    conn: &Connection,
+    target: &PushTarget,
+    busy: &HashSet<RepoId>,
+) -> anyhow::Result<PushWork> {
+    match target {
+        PushTarget::Fork(repo_id) => Ok(match gitr_db::ops::get_repo_by_id(conn, repo_id)? {
+            Some(_) if busy.contains(repo_id) => PushWork::Busy,
+            Some(repo) => PushWork::Ready(Work::Forks(vec![repo])),
+            None => PushWork::Gone,
+        }),
+        PushTarget::Link(link_id) => {
+            if !gitr_db::ops::list_sync_links(conn)?
+                .iter()
+                .any(|l| &l.id == link_id)
+            {
+                return Ok(PushWork::Gone);
+            }
+            Ok(
+                match prepare(conn, &JobKind::Link(link_id.clone()), busy)? {
+                    Some(work) => PushWork::Ready(work),
+                    None => PushWork::Busy,
+                },
+            )
+        }
+    }
+}
+
+/// Match a verified push against tracked forks and links and (re)start the
+/// debounce period for each.
+fn queue_push(
+    conn: &Connection,
+    pushes: &mut Debouncer<PushTarget>,
+    host_id: &HostId,
+    event: &PushEvent,
+) -> anyhow::Result<()> {
+    let repos = gitr_db::ops::list_repos(conn)?;
+    let links = gitr_db::ops::list_sync_links(conn)?;
+    let targets = webhook::push_targets(event, host_id, &repos, &links);
+    tracing::info!(
+        "{} push to {}:{} ({}) matched {} target(s)",
+        event.source,
+        event.repo_full_name,
+        event.branch,
+        &event.after[..event.after.len().min(7)],
+        targets.len()
+    );
+    let now = Instant::now();
+    for target in targets {
+        pushes.touch(target, now);
+    }
+    Ok(())
+}
+
+#[derive(Clone)]
+struct WebhookState {
+    /// Host label → host ID and webhook secret, if one is set.
+    hosts: Arc<HashMap<String, (HostId, Option<String>)>>,
+    tx: mpsc::UnboundedSender<(HostId, PushEvent)>,
+    db_path: PathBuf,
+}
+
+fn load_webhook_hosts(
+    conn: &Connection,
+) -> anyhow::Result<HashMap<String, (HostId, Option<String>)>> {
+    let cred_store = KeyringStore::new();
+    let mut hosts = HashMap::new();
+    for host in gitr_db::ops::list_hosts(conn)? {
+        let secret = cred_store.get(&host.webhook_secret_key())?;
+        if secret.is_none() {
+            tracing::warn!(
+                "host '{}' has no webhook secret; its webhooks will be rejected \
+                 (set one with `gitr host webhook-secret {}`)",
+                host.label,
+                host.label
+            );
+        }
+        hosts.insert(host.label, (host.id, secret));
+    }
+    Ok(hosts)
+}
+
+async fn handle_webhook(
+    State(state): State<WebhookState>,
+    UrlPath(label): UrlPath<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> (StatusCode, String) {
+    let Some((host_id, secret)) = state.hosts.get(&label) else {
+        return (StatusCode::NOT_FOUND, format!("unknown host '{label}'\n"));
+    };
+    let headers: HashMap<String, String> = headers
+        .iter()
+        .filter_map(|(name, value)| {
+            Some((name.as_str().to_string(), value.to_str().ok()?.to_string()))
+        })
+        .collect();
+    let Some(source) = WebhookSource::detect(&headers) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            "unrecognized webhook sender\n".to_string(),
+        );
+    };
+    let verified = secret
+        .as_deref()
+        .is_some_and(|secret| webhook::verify_signature(source, &headers, &body, secret));
+    if !verified {
+        tracing::warn!("{label}: rejected {source} webhook with a bad or missing signature");
+        return (
+            StatusCode::UNAUTHORIZED,
+            "signature verification failed\n".to_string(),
+        );
+    }
+
+    match webhook::parse_push(source, &headers, &body) {
+        Ok(Some(event)) => {
+            let _ = state.tx.send((host_id.clone(), event));
+            (StatusCode::ACCEPTED, "queued\n".to_string())
+        }
+        Ok(None) => (StatusCode::OK, "ignored\n".to_string()),
+        Err(e) => (StatusCode::BAD_REQUEST, format!("{e}\n")),
+    }
+}
+
+async fn handle_metrics(State(state): State<WebhookState>) -> (StatusCode, HeaderMap, String) {
+    let loaded =
+        gitr_db::open_db(&state.db_path).and_then(|conn| crate::metrics::load_behind_gauges(&conn));
+    if let Err(e) = loaded {
+        tracing::warn!("metrics: could not load branch snapshots: {e}");
+    }
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("text/plain; version=0.0.4"),
+    );
+    (StatusCode::OK, headers, gitr_core::metrics::render())
+}
+
+/// Run one job to completion and return the repos it held.
+async fn run_job(config: GitrConfig, db_path: PathBuf, id: String, work: Work) -> Vec<RepoId> {
+    let repo_ids = work.repo_ids();
+    if let Err(e) = execute(&config, &db_path, &work).await {
+        tracing::error!("{id}: {e}");
+    }
+    repo_ids
+}
+
+async fn execute(config: &GitrConfig, db_path: &Path, work: &Work) -> anyhow::Result<()> {
+    let conn = gitr_db::open_db(db_path)?;
+    let clone_base = GitrConfig::home_dir()?.join("repos");
+    std::fs::create_dir_all(&clone_base)?;
+    let engine = SyncEngine::new(config.sync_concurrency, config.retry.clone())
+        .with_backend(create_backend(&config.git_backend))
+        .with_hooks(config.hooks.clone());
+
+    let results = match work {
+        Work::Link(w) => {
+            engine
+                .sync_link(&w.link, &w.source, &w.target, &clone_base)
+                .await
+        }
+        Work::Forks(forks) => {
+            let pairs = upstream_pairs(&conn, config, forks).await?;
+            engine
+                .sync_all_forks(pairs, &clone_base, &config.default_merge_strategy, false)
+                .await
+        }
+    };
+    save_results(&conn, &results)?;
+
+    let failed = results
+        .iter()
+        .filter(|r| r.record.status == SyncStatus::Failed)
+        .count();
+    tracing::info!(
+        "scheduled run finished: {} synced | {failed} failed",
+        results.len() - failed
+    );
+    for result in results.iter().filter(|r| !r.record.errors.is_empty()) {
+        tracing::warn!(
+            "{}: {}",
+            result.repo_full_name,
+            result.record.errors.join("; ")
+        );
+    }
+
+    if let Work::Forks(forks) = work {
+        let synced: Vec<Repo> = forks
+            .iter()
+            .filter(|f| {
+                results
+                    .iter()
+                    .any(|r| r.record.repo_id == f.id && r.record.status == SyncStatus::Success)
+            })
+            .cloned()
+            .collect();
+        for result in refresh::refresh_repos(&conn, config, synced, false).await? {
+            if let Some(err) = result.error {
+                tracing::warn!("{}: refresh failed: {err}", result.repo_full_name);
+            }
+        }
+    }
+    super::notify::send_alerts(&conn, config).await;
+    Ok(())
+}
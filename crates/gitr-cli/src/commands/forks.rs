@@ -0,0 +1,484 @@
+use std::collections::HashMap;
+use std::io::IsTerminal;
+
+use chrono::Utc;
+use clap::Subcommand;
+use gitr_auth::{CredentialStore, KeyringStore};
+use gitr_core::config::GitrConfig;
+use gitr_core::error::GitrError;
+use gitr_core::models::host::HostId;
+use gitr_core::models::repo::Repo;
+use gitr_discover::lineage::ForkTree;
+use gitr_host::HostProvider;
+use gitr_sync::fork_audit::{self, ForkClass, ForkFacts};
+use rusqlite::Connection;
+use serde::Serialize;
+
+use super::scope::ScopeArgs;
+use crate::output::{CsvRow, Incomplete, OutputFormat, PruneOutcome};
+
+#[derive(Subcommand)]
+pub enum ForksAction {
+    /// Sort forks by what they hold beyond their upstream and suggest what to do
+    Audit {
+        #[command(flatten)]
+        scope: ScopeArgs,
+        /// Use the behind/ahead counts from the last refresh instead of comparing again
+        #[arg(long)]
+        no_refresh: bool,
+    },
+    /// Archive (or delete) forks that have nothing the upstream lacks
+    Prune {
+        #[command(flatten)]
+        scope: ScopeArgs,
+        /// Delete the forks from their hosts instead of archiving them
+        #[arg(long)]
+        delete: bool,
+        /// Show which forks would be pruned without changing anything
+        #[arg(long)]
+        dry_run: bool,
+        /// Don't ask for confirmation
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
+/// One fork's line in `gitr forks audit`.
+#[derive(Serialize)]
+struct ForkAudit {
+    repo: String,
+    host: Option<String>,
+    /// identical-to-upstream, behind-only, has-own-commits, has-open-prs or
+    /// upstream-gone; unset if the fork couldn't be audited
+    class: Option<String>,
+    behind: Option<u32>,
+    ahead: Option<u32>,
+    own_branches: Vec<String>,
+    /// URLs of open pull requests from the fork into its upstream
+    open_prs: Vec<String>,
+    suggestion: Option<&'static str>,
+    error: Option<String>,
+}
+
+impl CsvRow for ForkAudit {
+    const HEADERS: &'static [&'static str] = &[
+        "repo",
+        "host",
+        "class",
+        "behind",
+        "ahead",
+        "own_branches",
+        "open_prs",
+        "suggestion",
+        "error",
+    ];
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.repo.clone(),
+            self.host.clone().unwrap_or_default(),
+            self.class.clone().unwrap_or_default(),
+            self.behind.map(|n| n.to_string()).unwrap_or_default(),
+            self.ahead.map(|n| n.to_string()).unwrap_or_default(),
+            self.own_branches.join(";"),
+            self.open_prs.join(";"),
+            self.suggestion.unwrap_or_default().to_string(),
+            self.error.clone().unwrap_or_default(),
+        ]
+    }
+}
+
+/// A fork and what the audit made of it.
+struct Audited {
+    repo: Repo,
+    class: Option<ForkClass>,
+    facts: ForkFacts,
+    error: Option<String>,
+}
+
+pub async fn run(action: ForksAction, format: OutputFormat) -> anyhow::Result<()> {
+    let config = GitrConfig::load()?;
+    let db_path = GitrConfig::db_path()?;
+    let conn = gitr_db::open_db(&db_path)?;
+
+    match action {
+        ForksAction::Audit { scope, no_refresh } => {
+            let forks = forks_in_scope(&conn, &scope)?;
+            let audited = audit(&conn, &config, forks, !no_refresh, format).await?;
+            let hosts = gitr_db::ops::list_hosts(&conn)?;
+            let rows: Vec<ForkAudit> = audited
+                .iter()
+                .map(|a| ForkAudit {
+                    repo: a.repo.full_name.clone(),
+                    host: hosts
+                        .iter()
+                        .find(|h| a.repo.host_id.as_ref() == Some(&h.id))
+                        .map(|h| h.label.clone()),
+                    class: a.class.map(|c| c.to_string()),
+                    behind: a.facts.compare.map(|(behind, _)| behind),
+                    ahead: a.facts.compare.map(|(_, ahead)| ahead),
+                    own_branches: a.facts.own_branches.clone(),
+                    open_prs: a.facts.open_prs.iter().map(|pr| pr.url.clone()).collect(),
+                    suggestion: a.class.map(ForkClass::suggestion),
+                    error: a.error.clone(),
+                })
+                .collect();
+            let failed = rows.iter().filter(|r| r.class.is_none()).count();
+            match format.structured() {
+                Some(out) => out.emit(&rows)?,
+                None => print_audit(&audited),
+            }
+            if failed > 0 {
+                return Err(Incomplete::Failed(failed).into());
+            }
+            Ok(())
+        }
+        ForksAction::Prune {
+            scope,
+            delete,
+            dry_run,
+            yes,
+        } => {
+            let forks = forks_in_scope(&conn, &scope)?;
+            // Always compare afresh: stale counts could hide new work.
+            let audited = audit(&conn, &config, forks, true, format).await?;
+            prune(&conn, &config, audited, delete, dry_run, yes, format).await
+        }
+    }
+}
+
+fn forks_in_scope(conn: &Connection, scope: &ScopeArgs) -> anyhow::Result<Vec<Repo>> {
+    let mut forks = gitr_db::ops::list_fork_repos(conn)?;
+    scope.retain(conn, &mut forks)?;
+    Ok(forks)
+}
+
+/// Classify each fork: refresh its behind/ahead counts (unless `refresh`
+/// is off), then ask its host about the upstream.
+async fn audit(
+    conn: &Connection,
+    config: &GitrConfig,
+    forks: Vec<Repo>,
+    refresh: bool,
+    format: OutputFormat,
+) -> anyhow::Result<Vec<Audited>> {
+    let mut refresh_errors = HashMap::new();
+    if refresh && !forks.is_empty() {
+        format.say(format_args!(
+            "Comparing {} forks with their upstreams...",
+            forks.len()
+        ));
+        for result in super::refresh::refresh_repos(conn, config, forks.clone(), true).await? {
+            if let Some(err) = result.error {
+                refresh_errors.insert(result.repo_full_name, err);
+            }
+        }
+    }
+
+    let mut providers = HostProviders::default();
+    let mut audited = Vec::new();
+    for repo in forks {
+        // A failed refresh leaves the previous snapshot, which may be stale.
+        let refresh_error = refresh_errors.remove(&repo.full_name);
+        let compare = match refresh_error {
+            Some(_) => None,
+            None => gitr_db::ops::get_branch_snapshots(conn, &repo.id)?
+                .into_iter()
+                .find(|s| s.branch == repo.default_branch),
+        };
+        let Some(provider) = providers.get(conn, config, &repo)? else {
+            audited.push(Audited {
+                repo,
+                class: None,
+                facts: ForkFacts::default(),
+                error: Some("no API access for its host".to_string()),
+            });
+            continue;
+        };
+        let (facts, error) = match fork_audit::gather(&repo, compare.as_ref(), provider).await {
+            Ok(facts) => (facts, None),
+            Err(e) => (ForkFacts::default(), Some(e.to_string())),
+        };
+        let class = error
+            .is_none()
+            .then(|| fork_audit::classify(&facts))
+            .flatten();
+        let error =
+            match (class, error) {
+                (Some(_), _) => None,
+                (None, Some(e)) => Some(e),
+                (None, None) => Some(refresh_error.unwrap_or_else(|| {
+                    "no behind/ahead counts; run `gitr refresh` first".to_string()
+                })),
+            };
+        audited.push(Audited {
+            repo,
+            class,
+            facts,
+            error,
+        });
+    }
+    Ok(audited)
+}
+
+/// API clients for the hosts forks are on, created as needed.
+#[derive(Default)]
+struct HostProviders {
+    providers: HashMap<HostId, Option<Box<dyn HostProvider>>>,
+}
+
+impl HostProviders {
+    /// The client for `repo`'s host, or `None` if it has no host or token.
+    fn get(
+        &mut self,
+        conn: &Connection,
+        config: &GitrConfig,
+        repo: &Repo,
+    ) -> anyhow::Result<Option<&dyn HostProvider>> {
+        let Some(host_id) = &repo.host_id else {
+            return Ok(None);
+        };
+        if !self.providers.contains_key(host_id) {
+            let provider = match gitr_db::ops::get_host_by_id(conn, host_id)? {
+                Some(host) => match KeyringStore::new().get(&host.credential_key)? {
+                    Some(token) => gitr_host::create_provider(
+                        &host.kind,
+                        &host.api_url,
+                        &token,
+                        &host.username,
+                        &config.retry,
+                    )
+                    .ok(),
+                    None => None,
+                },
+                None => None,
+            };
+            self.providers.insert(host_id.clone(), provider);
+        }
+        Ok(self.providers[host_id].as_deref())
+    }
+}
+
+fn print_audit(audited: &[Audited]) {
+    if audited.is_empty() {
+        println!("No forks tracked. Use `gitr scan` to discover repos.");
+        return;
+    }
+    let mut classes: Vec<ForkClass> = audited.iter().filter_map(|a| a.class).collect();
+    classes.sort();
+    classes.dedup();
+    for class in classes {
+        let members: Vec<&Audited> = audited.iter().filter(|a| a.class == Some(class)).collect();
+        println!("\n{class} ({}): {}", members.len(), class.suggestion());
+        for a in members {
+            let mut details = Vec::new();
+            if let Some((behind, ahead)) = a.facts.compare {
+                if behind > 0 || ahead > 0 {
+                    details.push(format!("behind {behind}, ahead {ahead}"));
+                }
+            }
+            if !a.facts.own_branches.is_empty() {
+                details.push(format!("branches: {}", a.facts.own_branches.join(", ")));
+            }
+            for pr in &a.facts.open_prs {
+                details.push(format!("#{} {}", pr.number, pr.title));
+            }
+            match details.is_empty() {
+                true => println!("  {}", a.repo.full_name),
+                false => println!("  {}  ({})", a.repo.full_name, details.join("; ")),
+            }
+        }
+    }
+
+    let redundant = audited
+        .iter()
+        .filter(|a| a.class.is_some_and(ForkClass::is_redundant))
+        .count();
+    let failed: Vec<&Audited> = audited.iter().filter(|a| a.class.is_none()).collect();
+    if !failed.is_empty() {
+        println!("\nCould not audit ({}):", failed.len());
+        for a in failed {
+            println!(
+                "  {}: {}",
+                a.repo.full_name,
+                a.error.as_deref().unwrap_or("")
+            );
+        }
+    }
+    if redundant > 0 {
+        println!(
+            "\n{redundant} forks have nothing of their own; `gitr forks prune --dry-run` lists what would be archived."
+        );
+    }
+}
+
+/// Archive or delete the audited forks with nothing of their own, after
+/// confirmation. Forks other tracked forks were forked from are kept.
+async fn prune(
+    conn: &Connection,
+    config: &GitrConfig,
+    audited: Vec<Audited>,
+    delete: bool,
+    dry_run: bool,
+    yes: bool,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    let action = if delete { "delete" } else { "archive" };
+    let all = gitr_db::ops::list_repos(conn)?;
+    let tree = ForkTree::new(&all);
+
+    let mut candidates = Vec::new();
+    let mut unaudited = 0;
+    for a in audited {
+        let Some(class) = a.class else {
+            unaudited += 1;
+            format.say(format_args!(
+                "  Leaving {} alone: {}",
+                a.repo.full_name,
+                a.error.as_deref().unwrap_or("could not audit it")
+            ));
+            continue;
+        };
+        if !class.is_redundant() {
+            continue;
+        }
+        if !tree.children(&a.repo).is_empty() {
+            format.say(format_args!(
+                "  Keeping {}: tracked forks were forked from it",
+                a.repo.full_name
+            ));
+            continue;
+        }
+        if !delete && a.repo.is_archived {
+            continue;
+        }
+        candidates.push((a.repo, class));
+    }
+
+    if candidates.is_empty() {
+        if let Some(out) = format.structured() {
+            return out.emit::<PruneOutcome>(&[]);
+        }
+        match unaudited {
+            0 => println!(
+                "Nothing to prune: every fork has something of its own or is already archived."
+            ),
+            n => println!("Nothing to prune among the audited forks; {n} could not be audited."),
+        }
+        return Ok(());
+    }
+
+    format.say(format_args!(
+        "\n{} {action} {} forks:",
+        if dry_run { "Would" } else { "Will" },
+        candidates.len()
+    ));
+    for (repo, class) in &candidates {
+        format.say(format_args!("  {} ({class})", repo.full_name));
+    }
+    if dry_run {
+        if let Some(out) = format.structured() {
+            let outcomes: Vec<PruneOutcome> = candidates
+                .iter()
+                .map(|(repo, class)| PruneOutcome {
+                    repo: repo.full_name.clone(),
+                    class: class.to_string(),
+                    action: action.to_string(),
+                    status: "planned".to_string(),
+                    error: None,
+                })
+                .collect();
+            return out.emit(&outcomes);
+        }
+        return Ok(());
+    }
+    if !yes && !confirm(action, candidates.len())? {
+        println!("Nothing changed.");
+        return Ok(());
+    }
+
+    let mut providers = HostProviders::default();
+    let mut outcomes = Vec::new();
+    for (repo, class) in candidates {
+        let result = match providers.get(conn, config, &repo)? {
+            None => Err("no API access for its host".to_string()),
+            Some(provider) => {
+                let done = if delete {
+                    // Gone already, e.g. deleted by hand: what prune wanted.
+                    match provider.delete_repo(&repo.owner, &repo.name).await {
+                        Err(GitrError::RepoNotFound { .. }) => Ok(()),
+                        done => done,
+                    }
+                } else {
+                    provider.archive_repo(&repo.owner, &repo.name).await
+                };
+                done.map_err(|e| e.to_string())
+            }
+        };
+        if result.is_ok() {
+            if delete {
+                gitr_db::ops::set_repo_gone(conn, &repo.id, Some(&Utc::now()))?;
+            } else {
+                gitr_db::ops::update_repo_metadata(
+                    conn,
+                    &repo.id,
+                    repo.is_private,
+                    true,
+                    repo.language.as_deref(),
+                    repo.description.as_deref(),
+                )?;
+            }
+        }
+        outcomes.push(PruneOutcome {
+            repo: repo.full_name,
+            class: class.to_string(),
+            action: action.to_string(),
+            status: match (&result, delete) {
+                (Err(_), _) => "failed",
+                (Ok(()), true) => "deleted",
+                (Ok(()), false) => "archived",
+            }
+            .to_string(),
+            error: result.err(),
+        });
+    }
+
+    let failed = outcomes.iter().filter(|o| o.status == "failed").count();
+    match format.structured() {
+        Some(out) => out.emit(&outcomes)?,
+        None => {
+            let verb = if delete { "Deleted" } else { "Archived" };
+            println!("{verb} {} forks | {failed} failed", outcomes.len() - failed);
+            for outcome in &outcomes {
+                if let Some(err) = &outcome.error {
+                    println!("  {}: {err}", outcome.repo);
+                }
+            }
+            if delete {
+                println!("Deleted forks are marked gone; `gitr scan --prune` untracks them.");
+            }
+        }
+    }
+    if failed > 0 {
+        return Err(Incomplete::Failed(failed).into());
+    }
+    Ok(())
+}
+
+/// Ask on the terminal before changing forks on their hosts. Deleting
+/// needs the word `delete` typed out; archiving, a yes.
+fn confirm(action: &str, count: usize) -> anyhow::Result<bool> {
+    if !std::io::stdin().is_terminal() {
+        anyhow::bail!("Refusing to {action} {count} forks without confirmation; pass --yes");
+    }
+    let expected = if action == "delete" {
+        eprint!("Delete {count} forks from their hosts? This cannot be undone. Type 'delete' to confirm: ");
+        "delete"
+    } else {
+        eprint!("Archive {count} forks on their hosts? [y/N] ");
+        "y"
+    };
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input)?;
+    Ok(input.trim().eq_ignore_ascii_case(expected))
+}
@@ -1,32 +1,63 @@
-use clap::Args;
+use std::collections::HashMap;
+
+use clap::{Args, Subcommand};
 use gitr_core::config::GitrConfig;
+use gitr_core::models::repo::RepoId;
+use gitr_core::models::sync_state::{SyncBranchDetail, SyncRecord};
+
+use super::scope::ScopeArgs;
+use crate::output::{OutputFormat, Structured, SyncOutcome};
 
 #[derive(Args)]
+#[command(args_conflicts_with_subcommands = true)]
 pub struct HistoryArgs {
+    #[command(subcommand)]
+    action: Option<HistoryAction>,
     /// Filter by repo name or full name
     repo: Option<String>,
+    #[command(flatten)]
+    scope: ScopeArgs,
     /// Number of records to show
     #[arg(long, default_value = "20")]
     limit: u32,
+    /// Show per-branch SHAs and incoming commits for each record
+    #[arg(long, short)]
+    verbose: bool,
 }
 
-pub fn run(args: HistoryArgs) -> anyhow::Result<()> {
+#[derive(Subcommand)]
+pub enum HistoryAction {
+    /// Show a single sync record with its incoming commits
+    Show {
+        /// Sync ID (or a unique prefix of it)
+        id: String,
+        /// Render incoming commits as a changelog grouped by conventional-commit type
+        #[arg(long)]
+        changelog: bool,
+    },
+}
+
+pub fn run(args: HistoryArgs, format: OutputFormat) -> anyhow::Result<()> {
     let db_path = GitrConfig::db_path()?;
     let conn = gitr_db::open_db(&db_path)?;
 
-    let repo_id = if let Some(ref name) = args.repo {
-        let repos = gitr_db::ops::list_repos(&conn)?;
-        let repo = repos
-            .iter()
-            .find(|r| r.full_name == *name || r.name == *name)
-            .ok_or_else(|| anyhow::anyhow!("Repo '{}' not found", name))?;
-        Some(repo.id.clone())
+    if let Some(HistoryAction::Show { id, changelog }) = args.action {
+        return show(&conn, &id, changelog, format);
+    }
+
+    let records = if let Some(ref name) = args.repo {
+        let repo = args.scope.find_repo(&conn, name)?;
+        gitr_db::ops::list_sync_history(&conn, Some(&repo.id), args.limit)?
+    } else if let Some(ids) = args.scope.repo_ids(&conn)? {
+        let ids: Vec<RepoId> = ids.into_iter().collect();
+        gitr_db::ops::list_sync_history_for_repos(&conn, &ids, args.limit)?
     } else {
-        None
+        gitr_db::ops::list_sync_history(&conn, None, args.limit)?
     };
 
-    let records =
-        gitr_db::ops::list_sync_history(&conn, repo_id.as_ref(), args.limit)?;
+    if let Some(out) = format.structured() {
+        return emit(&conn, out, records, args.verbose);
+    }
 
     if records.is_empty() {
         println!("No sync history found.");
@@ -34,8 +65,8 @@ pub fn run(args: HistoryArgs) -> anyhow::Result<()> {
     }
 
     println!(
-        "{:<20} {:<10} {:<8} {:<8} {:<8} {}",
-        "STARTED", "STATUS", "SYNCED", "FAILED", "COMMITS", "ERRORS"
+        "{:<36} {:<20} {:<10} {:<8} {:<8} {:<8} ERRORS",
+        "ID", "STARTED", "STATUS", "SYNCED", "FAILED", "COMMITS"
     );
     for record in &records {
         let errors_str = if record.errors.is_empty() {
@@ -44,7 +75,8 @@ pub fn run(args: HistoryArgs) -> anyhow::Result<()> {
             format!("{} error(s)", record.errors.len())
         };
         println!(
-            "{:<20} {:<10} {:<8} {:<8} {:<8} {}",
+            "{:<36} {:<20} {:<10} {:<8} {:<8} {:<8} {}",
+            record.id,
             record.started_at.format("%Y-%m-%d %H:%M:%S"),
             record.status,
             record.branches_synced,
@@ -52,7 +84,122 @@ pub fn run(args: HistoryArgs) -> anyhow::Result<()> {
             record.commits_transferred,
             errors_str,
         );
+        if args.verbose {
+            for detail in gitr_db::ops::get_sync_details(&conn, &record.id)? {
+                print_detail(&detail);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Write records with their repo names, and per-branch details if `details`.
+fn emit(
+    conn: &rusqlite::Connection,
+    out: Structured,
+    records: Vec<SyncRecord>,
+    details: bool,
+) -> anyhow::Result<()> {
+    let mut names: HashMap<RepoId, String> = HashMap::new();
+    let mut outcomes = Vec::with_capacity(records.len());
+    for mut record in records {
+        if !names.contains_key(&record.repo_id) {
+            let name = gitr_db::ops::get_repo_by_id(conn, &record.repo_id)?
+                .map(|r| r.full_name)
+                .unwrap_or_else(|| "(deleted)".to_string());
+            names.insert(record.repo_id.clone(), name);
+        }
+        if details {
+            record.details = gitr_db::ops::get_sync_details(conn, &record.id)?;
+        }
+        outcomes.push(SyncOutcome {
+            repo: names[&record.repo_id].clone(),
+            record,
+        });
+    }
+    out.emit(&outcomes)
+}
+
+fn show(
+    conn: &rusqlite::Connection,
+    id: &str,
+    changelog: bool,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    let record = gitr_db::ops::get_sync_record(conn, id)?
+        .ok_or_else(|| anyhow::anyhow!("Sync record '{}' not found", id))?;
+    let repo = gitr_db::ops::get_repo_by_id(conn, &record.repo_id)?;
+
+    if let Some(out) = format.structured() {
+        return out.emit_one(&SyncOutcome {
+            repo: repo
+                .map(|r| r.full_name)
+                .unwrap_or_else(|| "(deleted)".to_string()),
+            record,
+        });
+    }
+
+    println!("Sync {}", record.id);
+    println!(
+        "  Repo:     {}",
+        repo.map(|r| r.full_name).unwrap_or_else(|| "(deleted)".to_string())
+    );
+    println!("  Status:   {}", record.status);
+    println!(
+        "  Started:  {}",
+        record.started_at.format("%Y-%m-%d %H:%M:%S")
+    );
+    println!(
+        "  Duration: {}s",
+        (record.finished_at - record.started_at).num_seconds()
+    );
+    println!("  Attempts: {}", record.attempts);
+    println!("  Commits:  {}", record.commits_transferred);
+    for err in &record.errors {
+        println!("  Error:    {err}");
+    }
+
+    if record.details.is_empty() {
+        println!("\nNo commit details recorded.");
+        return Ok(());
+    }
+
+    for detail in &record.details {
+        println!();
+        if changelog {
+            println!("## {}", detail.branch);
+            print!("{}", gitr_core::changelog::render(&detail.commits));
+        } else {
+            print_detail(detail);
+        }
     }
 
     Ok(())
 }
+
+fn print_detail(detail: &SyncBranchDetail) {
+    println!(
+        "    {}: {} → {} ({} incoming)",
+        detail.branch,
+        short_sha(detail.before_sha.as_deref()),
+        short_sha(detail.after_sha.as_deref()),
+        detail.commits.len()
+    );
+    for c in &detail.commits {
+        println!(
+            "      {} {} {:<16} {}",
+            short_sha(Some(&c.sha)),
+            c.date.format("%Y-%m-%d"),
+            c.author,
+            c.subject
+        );
+    }
+}
+
+fn short_sha(sha: Option<&str>) -> &str {
+    match sha {
+        Some(s) => &s[..s.len().min(7)],
+        None => "—",
+    }
+}
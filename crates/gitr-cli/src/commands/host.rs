@@ -1,7 +1,11 @@
+use chrono::{DateTime, Utc};
 use clap::Subcommand;
 use gitr_auth::{CredentialStore, KeyringStore};
 use gitr_core::config::GitrConfig;
 use gitr_core::models::host::{Host, HostKind};
+use serde::Serialize;
+
+use crate::output::{CsvRow, OutputFormat};
 
 #[derive(Subcommand)]
 pub enum HostAction {
@@ -31,6 +35,14 @@ pub enum HostAction {
         /// Host label
         name: String,
     },
+    /// Set the shared secret that `gitr daemon --listen` checks on this host's webhooks
+    WebhookSecret {
+        /// Host label
+        name: String,
+        /// Secret to use (a random one is generated if not provided)
+        #[arg(long)]
+        secret: Option<String>,
+    },
     /// Remove a registered host
     Remove {
         /// Host label
@@ -38,7 +50,59 @@ pub enum HostAction {
     },
 }
 
-pub async fn run(action: HostAction) -> anyhow::Result<()> {
+/// `host info` output: the host and how many repos it has.
+#[derive(Serialize)]
+struct HostInfo<'a> {
+    #[serde(flatten)]
+    host: &'a Host,
+    repos: usize,
+    forks: usize,
+}
+
+impl CsvRow for HostInfo<'_> {
+    const HEADERS: &'static [&'static str] =
+        &["id", "label", "kind", "api_url", "username", "repos", "forks"];
+
+    fn fields(&self) -> Vec<String> {
+        let mut fields = self.host.fields();
+        fields.push(self.repos.to_string());
+        fields.push(self.forks.to_string());
+        fields
+    }
+}
+
+/// `host verify` output.
+#[derive(Serialize)]
+struct HostCheck {
+    host: String,
+    valid: bool,
+    rate_limit: Option<u32>,
+    rate_limit_remaining: Option<u32>,
+    rate_limit_reset_at: Option<DateTime<Utc>>,
+}
+
+impl CsvRow for HostCheck {
+    const HEADERS: &'static [&'static str] = &[
+        "host",
+        "valid",
+        "rate_limit",
+        "rate_limit_remaining",
+        "rate_limit_reset_at",
+    ];
+
+    fn fields(&self) -> Vec<String> {
+        let opt = |v: Option<String>| v.unwrap_or_default();
+        vec![
+            self.host.clone(),
+            self.valid.to_string(),
+            opt(self.rate_limit.map(|v| v.to_string())),
+            opt(self.rate_limit_remaining.map(|v| v.to_string())),
+            opt(self.rate_limit_reset_at.map(|t| t.to_rfc3339())),
+        ]
+    }
+}
+
+pub async fn run(action: HostAction, format: OutputFormat) -> anyhow::Result<()> {
     match action {
         HostAction::Add {
             name,
@@ -91,12 +155,15 @@ pub async fn run(action: HostAction) -> anyhow::Result<()> {
             let conn = gitr_db::open_db(&db_path)?;
             let hosts = gitr_db::ops::list_hosts(&conn)?;
 
+            if let Some(out) = format.structured() {
+                return out.emit(&hosts);
+            }
             if hosts.is_empty() {
                 println!("No hosts registered. Use `gitr host add` to register one.");
                 return Ok(());
             }
 
-            println!("{:<12} {:<10} {:<20} {}", "LABEL", "PROVIDER", "USERNAME", "API URL");
+            println!("{:<12} {:<10} {:<20} API URL", "LABEL", "PROVIDER", "USERNAME");
             for host in &hosts {
                 println!(
                     "{:<12} {:<10} {:<20} {}",
@@ -110,16 +177,23 @@ pub async fn run(action: HostAction) -> anyhow::Result<()> {
             let conn = gitr_db::open_db(&db_path)?;
             let host = gitr_db::ops::get_host_by_label(&conn, &name)?
                 .ok_or_else(|| anyhow::anyhow!("Host '{}' not found", name))?;
+            let repos = gitr_db::ops::list_repos_for_host(&conn, &host.id)?;
+            let forks = repos.iter().filter(|r| r.is_fork).count();
+
+            if let Some(out) = format.structured() {
+                return out.emit_one(&HostInfo {
+                    host: &host,
+                    repos: repos.len(),
+                    forks,
+                });
+            }
 
             println!("Label:          {}", host.label);
             println!("Provider:       {}", host.kind);
             println!("Username:       {}", host.username);
             println!("API URL:        {}", host.api_url);
             println!("Credential key: {}", host.credential_key);
-
-            let repos = gitr_db::ops::list_repos_for_host(&conn, &host.id)?;
             println!("Tracked repos:  {}", repos.len());
-            let forks = repos.iter().filter(|r| r.is_fork).count();
             println!("  Forks:        {}", forks);
             Ok(())
         }
@@ -134,9 +208,31 @@ pub async fn run(action: HostAction) -> anyhow::Result<()> {
                 .get(&host.credential_key)?
                 .ok_or_else(|| anyhow::anyhow!("No token found in keychain for '{}'", name))?;
 
-            let provider = gitr_host::create_provider(&host.kind, &host.api_url, &token, &host.username)?;
+            let config = GitrConfig::load()?;
+            let provider = gitr_host::create_provider(
+                &host.kind,
+                &host.api_url,
+                &token,
+                &host.username,
+                &config.retry,
+            )?;
             let valid = provider.validate_credentials().await?;
 
+            if let Some(out) = format.structured() {
+                let rl = if valid {
+                    Some(provider.rate_limit_status().await?)
+                } else {
+                    None
+                };
+                return out.emit_one(&HostCheck {
+                    host: name,
+                    valid,
+                    rate_limit: rl.as_ref().map(|r| r.limit),
+                    rate_limit_remaining: rl.as_ref().map(|r| r.remaining),
+                    rate_limit_reset_at: rl.map(|r| r.reset_at),
+                });
+            }
+
             if valid {
                 println!("Credentials for '{}' are valid", name);
 
@@ -150,6 +246,26 @@ pub async fn run(action: HostAction) -> anyhow::Result<()> {
             }
             Ok(())
         }
+        HostAction::WebhookSecret { name, secret } => {
+            let db_path = GitrConfig::db_path()?;
+            let conn = gitr_db::open_db(&db_path)?;
+            let host = gitr_db::ops::get_host_by_label(&conn, &name)?
+                .ok_or_else(|| anyhow::anyhow!("Host '{}' not found", name))?;
+
+            let secret = secret.unwrap_or_else(|| {
+                format!(
+                    "{}{}",
+                    uuid::Uuid::new_v4().simple(),
+                    uuid::Uuid::new_v4().simple()
+                )
+            });
+            let cred_store = KeyringStore::new();
+            cred_store.store(&host.webhook_secret_key(), &secret)?;
+
+            println!("Webhook secret for '{}': {}", name, secret);
+            println!("Point the host's push webhook at http://<listen-addr>/webhook/{name}");
+            Ok(())
+        }
         HostAction::Remove { name } => {
             let db_path = GitrConfig::db_path()?;
             let conn = gitr_db::open_db(&db_path)?;
@@ -159,6 +275,7 @@ pub async fn run(action: HostAction) -> anyhow::Result<()> {
             // Delete token from keychain
             let cred_store = KeyringStore::new();
             let _ = cred_store.delete(&host.credential_key);
+            let _ = cred_store.delete(&host.webhook_secret_key());
 
             // Delete from DB (cascades to repos)
             gitr_db::ops::delete_host(&conn, &host.id)?;
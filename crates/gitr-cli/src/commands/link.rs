@@ -0,0 +1,164 @@
+use clap::Subcommand;
+use gitr_core::config::GitrConfig;
+use gitr_core::models::sync_link::{MergeStrategy, SyncDirection, SyncLink, SyncTrigger};
+use gitr_sync::scheduler::{parse_cron, JobKind};
+use serde::Serialize;
+
+use crate::output::{CsvRow, OutputFormat};
+
+#[derive(Subcommand)]
+pub enum LinkAction {
+    /// Link two tracked repos so one is kept in sync with the other
+    Add {
+        /// Source repo (owner/repo or name; host:owner/repo when ambiguous)
+        source: String,
+        /// Target repo (owner/repo or name; host:owner/repo when ambiguous)
+        target: String,
+        /// push (update target from source), pull (update source from target), or both
+        #[arg(long, default_value = "push")]
+        direction: String,
+        /// Merge strategy (ff, merge, rebase, force_push)
+        #[arg(long, default_value = "ff")]
+        strategy: String,
+        /// Cron expression for `gitr daemon`, e.g. "0 */6 * * *"
+        #[arg(long)]
+        cron: Option<String>,
+        /// Sync whenever the pulled-from repo is pushed (needs `gitr daemon --listen`)
+        #[arg(long, conflicts_with = "cron")]
+        on_push: bool,
+    },
+    /// List sync links
+    List,
+    /// Remove a sync link
+    Remove {
+        /// Link ID
+        id: String,
+    },
+}
+
+/// `link list` output: a link with the names of the repos it joins.
+#[derive(Serialize)]
+struct LinkEntry<'a> {
+    #[serde(flatten)]
+    link: &'a SyncLink,
+    source: String,
+    target: String,
+}
+
+impl CsvRow for LinkEntry<'_> {
+    const HEADERS: &'static [&'static str] = &[
+        "id",
+        "source_repo_id",
+        "target_repo_id",
+        "direction",
+        "merge_strategy",
+        "trigger",
+        "enabled",
+        "source",
+        "target",
+    ];
+
+    fn fields(&self) -> Vec<String> {
+        let mut fields = self.link.fields();
+        fields.push(self.source.clone());
+        fields.push(self.target.clone());
+        fields
+    }
+}
+
+pub fn run(action: LinkAction, format: OutputFormat) -> anyhow::Result<()> {
+    let db_path = GitrConfig::db_path()?;
+    let conn = gitr_db::open_db(&db_path)?;
+
+    match action {
+        LinkAction::Add {
+            source,
+            target,
+            direction,
+            strategy,
+            cron,
+            on_push,
+        } => {
+            let repos = gitr_db::ops::list_repos(&conn)?;
+            let source = super::scope::find_repo(&conn, &repos, &source)?;
+            let target = super::scope::find_repo(&conn, &repos, &target)?;
+            let direction: SyncDirection = direction.parse().map_err(|e| anyhow::anyhow!("{e}"))?;
+            let strategy: MergeStrategy = strategy.parse().map_err(|e| anyhow::anyhow!("{e}"))?;
+
+            let mut link = SyncLink::new(source.id.clone(), target.id.clone(), direction, strategy);
+            if let Some(cron) = cron {
+                parse_cron(&cron)?;
+                link.trigger = SyncTrigger::Schedule { cron };
+            } else if on_push {
+                link.trigger = SyncTrigger::Always;
+            }
+            gitr_db::ops::insert_sync_link(&conn, &link)?;
+
+            println!(
+                "Linked {} → {} ({}, {}, {})",
+                source.full_name,
+                target.full_name,
+                link.direction,
+                link.merge_strategy,
+                link.trigger
+            );
+            println!("  ID: {}", link.id);
+            Ok(())
+        }
+        LinkAction::List => {
+            let links = gitr_db::ops::list_sync_links(&conn)?;
+            let name_of = |id| -> anyhow::Result<String> {
+                Ok(gitr_db::ops::get_repo_by_id(&conn, id)?
+                    .map(|r| r.full_name)
+                    .unwrap_or_else(|| "(deleted)".to_string()))
+            };
+
+            if let Some(out) = format.structured() {
+                let entries = links
+                    .iter()
+                    .map(|link| {
+                        Ok(LinkEntry {
+                            link,
+                            source: name_of(&link.source_repo_id)?,
+                            target: name_of(&link.target_repo_id)?,
+                        })
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+                return out.emit(&entries);
+            }
+            if links.is_empty() {
+                println!("No sync links. Use `gitr link add` to create one.");
+                return Ok(());
+            }
+
+            println!(
+                "{:<36} {:<30} {:<30} {:<6} {:<8} TRIGGER",
+                "ID", "SOURCE", "TARGET", "DIR", "STRATEGY"
+            );
+            for link in &links {
+                println!(
+                    "{:<36} {:<30} {:<30} {:<6} {:<8} {}{}",
+                    link.id,
+                    name_of(&link.source_repo_id)?,
+                    name_of(&link.target_repo_id)?,
+                    link.direction,
+                    link.merge_strategy,
+                    link.trigger,
+                    if link.enabled { "" } else { " (disabled)" },
+                );
+            }
+            Ok(())
+        }
+        LinkAction::Remove { id } => {
+            let links = gitr_db::ops::list_sync_links(&conn)?;
+            let link = links
+                .iter()
+                .find(|l| l.id.to_string() == id)
+                .ok_or_else(|| anyhow::anyhow!("Sync link '{}' not found", id))?;
+            gitr_db::ops::delete_sync_link(&conn, &link.id)?;
+            gitr_db::ops::delete_schedule_state(&conn, &JobKind::Link(link.id.clone()).job_id())?;
+            println!("Removed sync link {id}");
+            Ok(())
+        }
+    }
+}
@@ -1,13 +1,24 @@
+pub mod clone;
+pub mod collection;
 pub mod config;
+pub mod daemon;
+pub mod forks;
 pub mod history;
 pub mod host;
+pub mod link;
+pub mod notify;
+pub mod refresh;
 pub mod repo;
 pub mod scan;
+pub mod scope;
+pub mod serve;
 pub mod status;
 pub mod sync;
 
 use clap::Subcommand;
 
+use crate::output::OutputFormat;
+
 #[derive(Subcommand)]
 pub enum Command {
     /// Initialize and manage Gitr configuration
@@ -27,22 +38,58 @@ pub enum Command {
         #[command(subcommand)]
         action: repo::RepoAction,
     },
+    /// Clone tracked repos into the configured directory layout
+    Clone(clone::CloneArgs),
     /// Sync repos with upstream
     Sync(sync::SyncArgs),
+    /// Recompute behind/ahead counts for tracked forks
+    Refresh(refresh::RefreshArgs),
+    /// Audit forks and prune the redundant ones
+    Forks {
+        #[command(subcommand)]
+        action: forks::ForksAction,
+    },
     /// Show status of all tracked repos
     Status(status::StatusArgs),
     /// Show sync history
     History(history::HistoryArgs),
+    /// Group repos into named collections
+    Collection {
+        #[command(subcommand)]
+        action: collection::CollectionAction,
+    },
+    /// Manage sync links between repos
+    Link {
+        #[command(subcommand)]
+        action: link::LinkAction,
+    },
+    /// Configure and test sync failure and drift alerts
+    Notify {
+        #[command(subcommand)]
+        action: notify::NotifyAction,
+    },
+    /// Run scheduled syncs in the background
+    Daemon(daemon::DaemonArgs),
+    /// Serve a local JSON API over the database
+    Serve(serve::ServeArgs),
 }
 
-pub async fn run(cmd: Command) -> anyhow::Result<()> {
+pub async fn run(cmd: Command, format: OutputFormat) -> anyhow::Result<()> {
     match cmd {
-        Command::Config { action } => config::run(action),
-        Command::Host { action } => host::run(action).await,
-        Command::Scan(args) => scan::run(args).await,
-        Command::Repo { action } => repo::run(action),
-        Command::Sync(args) => sync::run(args).await,
-        Command::Status(args) => status::run(args),
-        Command::History(args) => history::run(args),
+        Command::Config { action } => config::run(action, format),
+        Command::Host { action } => host::run(action, format).await,
+        Command::Scan(args) => scan::run(args, format).await,
+        Command::Repo { action } => repo::run(action, format).await,
+        Command::Clone(args) => clone::run(args, format).await,
+        Command::Sync(args) => sync::run(args, format).await,
+        Command::Refresh(args) => refresh::run(args, format).await,
+        Command::Forks { action } => forks::run(action, format).await,
+        Command::Status(args) => status::run(args, format),
+        Command::History(args) => history::run(args, format),
+        Command::Collection { action } => collection::run(action, format),
+        Command::Link { action } => link::run(action, format),
+        Command::Notify { action } => notify::run(action, format).await,
+        Command::Daemon(args) => daemon::run(args).await,
+        Command::Serve(args) => serve::run(args).await,
     }
 }
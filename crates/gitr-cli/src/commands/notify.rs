@@ -0,0 +1,164 @@
+use chrono::Utc;
+use clap::Subcommand;
+use gitr_auth::{CredentialStore, KeyringStore};
+use gitr_core::config::GitrConfig;
+use gitr_core::models::repo::RepoId;
+use gitr_core::notify::{Alert, ChannelKind};
+use gitr_sync::notify::build_notifiers;
+use rusqlite::Connection;
+
+use crate::output::OutputFormat;
+
+#[derive(Subcommand)]
+pub enum NotifyAction {
+    /// List configured channels and rules
+    List,
+    /// Send a test alert to a channel
+    Test {
+        /// Channel name
+        channel: String,
+    },
+    /// Store the SMTP password for an email channel in the system keyring
+    SetPassword {
+        /// Channel name
+        channel: String,
+        /// SMTP password (will prompt if not provided)
+        #[arg(long)]
+        password: Option<String>,
+    },
+    /// Evaluate alert rules now and send any new alerts
+    Check,
+}
+
+pub async fn run(action: NotifyAction, format: OutputFormat) -> anyhow::Result<()> {
+    let config = GitrConfig::load()?;
+
+    match action {
+        NotifyAction::List => {
+            if let Some(out) = format.structured() {
+                return out.emit_value(&config.notify);
+            }
+            if config.notify.channels.is_empty() {
+                println!(
+                    "No notification channels configured. Add [[notify.channels]] to config.toml."
+                );
+                return Ok(());
+            }
+            println!("{:<16} {:<10} TARGET", "CHANNEL", "KIND");
+            for channel in &config.notify.channels {
+                let (kind, target) = match &channel.kind {
+                    ChannelKind::Webhook { url } => ("webhook", url.clone()),
+                    ChannelKind::Slack { url } => ("slack", url.clone()),
+                    ChannelKind::Email { to, .. } => ("email", to.join(", ")),
+                    ChannelKind::Command { command } => ("command", command.clone()),
+                };
+                println!("{:<16} {:<10} {}", channel.name, kind, target);
+            }
+            println!();
+            for rule in &config.notify.rules {
+                let channels = if rule.channels.is_empty() {
+                    "all channels".to_string()
+                } else {
+                    rule.channels.join(", ")
+                };
+                println!("rule {} -> {}", rule.key(), channels);
+            }
+            Ok(())
+        }
+        NotifyAction::Test { channel } => {
+            let channel = config
+                .notify
+                .channels
+                .iter()
+                .find(|c| c.name == channel)
+                .ok_or_else(|| anyhow::anyhow!("Channel '{}' not found", channel))?;
+            let cred_store = KeyringStore::new();
+            let notifiers = build_notifiers(std::slice::from_ref(channel), &cred_store)?;
+            let alert = Alert {
+                rule: "test".to_string(),
+                repo: "gitr/test".to_string(),
+                repo_id: RepoId::new(),
+                value: 0,
+                message: "test notification from gitr".to_string(),
+                fired_at: Utc::now(),
+            };
+            notifiers[0].send(&alert).await?;
+            format.say(format_args!("Sent test notification to '{}'.", channel.name));
+            Ok(())
+        }
+        NotifyAction::SetPassword { channel, password } => {
+            let channel = config
+                .notify
+                .channels
+                .iter()
+                .find(|c| c.name == channel)
+                .ok_or_else(|| anyhow::anyhow!("Channel '{}' not found", channel))?;
+            if !matches!(channel.kind, ChannelKind::Email { .. }) {
+                anyhow::bail!("Channel '{}' is not an email channel", channel.name);
+            }
+            let password = match password {
+                Some(p) => p,
+                None => {
+                    let term = console::Term::stderr();
+                    term.write_str(&format!("Enter SMTP password for {}: ", channel.name))?;
+                    term.read_secure_line()?
+                }
+            };
+            if password.is_empty() {
+                anyhow::bail!("Password cannot be empty");
+            }
+
+            let cred_store = KeyringStore::new();
+            cred_store.store(&channel.password_key(), &password)?;
+            format.say(format_args!("Stored SMTP password for '{}'.", channel.name));
+            Ok(())
+        }
+        NotifyAction::Check => {
+            let conn = gitr_db::open_db(&GitrConfig::db_path()?)?;
+            let alerts = gitr_sync::notify::evaluate(&conn, &config.notify.rules)?;
+            let sent = if alerts.is_empty() {
+                0
+            } else {
+                let notifiers = build_notifiers(&config.notify.channels, &KeyringStore::new())?;
+                let sent =
+                    gitr_sync::notify::dispatch(&alerts, &config.notify.rules, &notifiers).await;
+                gitr_sync::notify::record_delivered(&conn, &alerts, &sent)?;
+                sent.iter().sum()
+            };
+            if let Some(out) = format.structured() {
+                return out.emit(&alerts);
+            }
+            if alerts.is_empty() {
+                println!("No new alerts.");
+                return Ok(());
+            }
+            for alert in &alerts {
+                println!("{}: {}", alert.repo, alert.message);
+            }
+            println!("{} alert(s), {sent} notification(s) sent", alerts.len());
+            Ok(())
+        }
+    }
+}
+
+/// Send alerts for rules that started matching after a sync or refresh.
+/// Failures are logged rather than failing the run that triggered them.
+pub async fn send_alerts(conn: &Connection, config: &GitrConfig) {
+    if config.notify.rules.is_empty() {
+        return;
+    }
+    if let Err(e) = gitr_sync::notify::notify(conn, &config.notify, &KeyringStore::new()).await {
+        tracing::warn!("notifications: {e}");
+    }
+}
+
+/// [`send_alerts`] with the on-disk config and database, for one-shot commands.
+pub async fn send_configured_alerts() -> anyhow::Result<()> {
+    let config = GitrConfig::load()?;
+    if config.notify.rules.is_empty() {
+        return Ok(());
+    }
+    let conn = gitr_db::open_db(&GitrConfig::db_path()?)?;
+    send_alerts(&conn, &config).await;
+    Ok(())
+}
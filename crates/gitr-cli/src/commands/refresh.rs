@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use clap::Args;
+use gitr_auth::{CredentialStore, KeyringStore};
+use gitr_core::config::GitrConfig;
+use gitr_core::models::host::HostId;
+use gitr_core::models::repo::Repo;
+use gitr_host::HostProvider;
+use gitr_sync::backend::create_backend;
+use gitr_sync::engine::SyncEngine;
+use gitr_sync::fork_sync::local_clone_path;
+use gitr_sync::refresh::{is_clone_of, RefreshResult, RefreshTarget};
+use rusqlite::Connection;
+
+use super::scope::ScopeArgs;
+use crate::output::{Incomplete, OutputFormat, RefreshOutcome};
+
+#[derive(Args)]
+pub struct RefreshArgs {
+    /// Repo name or full name (owner/repo); refreshes all forks if omitted
+    target: Option<String>,
+    /// Only refresh forks on this host
+    #[arg(long)]
+    host: Option<String>,
+    #[command(flatten)]
+    scope: ScopeArgs,
+    /// Don't fetch local clones first; use the refs already on disk
+    #[arg(long)]
+    no_fetch: bool,
+}
+
+pub async fn run(args: RefreshArgs, format: OutputFormat) -> anyhow::Result<()> {
+    let config = GitrConfig::load()?;
+    let db_path = GitrConfig::db_path()?;
+    let conn = gitr_db::open_db(&db_path)?;
+
+    let mut forks = gitr_db::ops::list_fork_repos(&conn)?;
+    if let Some(label) = &args.host {
+        let host = gitr_db::ops::get_host_by_label(&conn, label)?
+            .ok_or_else(|| anyhow::anyhow!("Host '{}' not found", label))?;
+        forks.retain(|r| r.host_id.as_ref() == Some(&host.id));
+    }
+    args.scope.retain(&conn, &mut forks)?;
+    if let Some(name) = &args.target {
+        if super::scope::matching(&conn, &forks, name)?.is_empty() {
+            anyhow::bail!("Fork '{}' not found", name);
+        }
+        forks = vec![super::scope::find_repo(&conn, &forks, name)?.clone()];
+    }
+    if forks.is_empty() {
+        if let Some(out) = format.structured() {
+            return out.emit::<RefreshOutcome>(&[]);
+        }
+        println!("No forks tracked. Use `gitr scan` to discover repos.");
+        return Ok(());
+    }
+
+    format.say(format_args!("Refreshing {} forks...", forks.len()));
+    let results = refresh_repos(&conn, &config, forks, !args.no_fetch).await?;
+    let failed = results.iter().filter(|r| r.error.is_some()).count();
+    match format.structured() {
+        Some(out) => {
+            let outcomes: Vec<RefreshOutcome> = results.into_iter().map(Into::into).collect();
+            out.emit(&outcomes)?;
+        }
+        None => print_summary(&results, format),
+    }
+
+    if failed > 0 {
+        return Err(Incomplete::Failed(failed).into());
+    }
+    Ok(())
+}
+
+/// Refresh branch snapshots for `repos` and store them.
+///
+/// Repos with a local clone (recorded `local_path`, or the sync clone under
+/// `~/.gitr/repos`) are inspected with git; the rest use the host API.
+pub async fn refresh_repos(
+    conn: &Connection,
+    config: &GitrConfig,
+    repos: Vec<Repo>,
+    fetch: bool,
+) -> anyhow::Result<Vec<RefreshResult>> {
+    let targets = refresh_targets(conn, config, repos)?;
+    let engine = SyncEngine::new(config.sync_concurrency, config.retry.clone())
+        .with_backend(create_backend(&config.git_backend));
+    let results = engine.refresh_all(targets, fetch).await;
+    save_snapshots(conn, &results)?;
+    Ok(results)
+}
+
+/// Pair each repo with its local clone, or a host API client when there is
+/// no clone or the clone's `origin` is some other repo.
+pub fn refresh_targets(
+    conn: &Connection,
+    config: &GitrConfig,
+    repos: Vec<Repo>,
+) -> anyhow::Result<Vec<RefreshTarget>> {
+    let clone_base = GitrConfig::home_dir()?.join("repos");
+    let cred_store = KeyringStore::new();
+    let mut providers: HashMap<HostId, Option<Arc<dyn HostProvider>>> = HashMap::new();
+
+    let mut targets = Vec::new();
+    for repo in repos {
+        let path = local_clone_path(&repo, &clone_base);
+        let local_path = path.join(".git").exists().then_some(path).filter(|path| {
+            let ours = is_clone_of(path, &repo);
+            if !ours {
+                tracing::warn!(
+                    "{}: {} is not a clone of it; asking the host instead",
+                    repo.full_name,
+                    path.display()
+                );
+            }
+            ours
+        });
+
+        // Host-less repos without a clone have nothing to ask.
+        let provider = match (&local_path, &repo.host_id) {
+            (None, Some(host_id)) => {
+                if !providers.contains_key(host_id) {
+                    let provider = match gitr_db::ops::get_host_by_id(conn, host_id)? {
+                        Some(host) => match cred_store.get(&host.credential_key)? {
+                            Some(token) => gitr_host::create_provider(
+                                &host.kind,
+                                &host.api_url,
+                                &token,
+                                &host.username,
+                                &config.retry,
+                            )
+                            .ok()
+                            .map(Arc::from),
+                            None => None,
+                        },
+                        None => None,
+                    };
+                    providers.insert(host_id.clone(), provider);
+                }
+                providers[host_id].clone()
+            }
+            _ => None,
+        };
+
+        targets.push(RefreshTarget {
+            upstream_clone_url: repo.upstream_clone_url.clone(),
+            repo,
+            local_path,
+            provider,
+        });
+    }
+
+    Ok(targets)
+}
+
+/// Store the snapshots from a refresh.
+pub fn save_snapshots(conn: &Connection, results: &[RefreshResult]) -> anyhow::Result<()> {
+    for result in results {
+        for snap in &result.snapshots {
+            gitr_db::ops::upsert_branch_snapshot(conn, snap)?;
+        }
+    }
+    Ok(())
+}
+
+/// Print totals and any per-repo errors.
+pub fn print_summary(results: &[RefreshResult], format: OutputFormat) {
+    let snapshots = || results.iter().flat_map(|r| r.snapshots.iter());
+    let behind = snapshots().filter(|s| s.behind_count > 0).count();
+    let ahead = snapshots().filter(|s| s.ahead_count > 0).count();
+    let failed = results.iter().filter(|r| r.error.is_some()).count();
+
+    format.say(format_args!(
+        "Refreshed {} repos: {behind} branches behind | {ahead} ahead | {failed} failed",
+        results.len() - failed
+    ));
+    for result in results {
+        if let Some(err) = &result.error {
+            format.say(format_args!("  {}: {err}", result.repo_full_name));
+        }
+    }
+}
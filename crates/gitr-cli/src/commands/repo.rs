@@ -1,5 +1,21 @@
+use std::collections::HashSet;
+use std::path::Path;
+
 use clap::Subcommand;
+use gitr_auth::{CredentialStore, KeyringStore};
 use gitr_core::config::GitrConfig;
+use gitr_core::models::host::Host;
+use gitr_core::models::repo::{DiscoverySource, Repo, RepoId};
+use gitr_core::models::sync_state::BranchSnapshot;
+use gitr_discover::adopt;
+use gitr_discover::lineage::ForkTree;
+use gitr_discover::reconcile::normalize_url;
+use gitr_sync::relocate;
+use rusqlite::Connection;
+use serde::Serialize;
+
+use super::scope::ScopeArgs;
+use crate::output::{CsvRow, Incomplete, OutputFormat, RelocateOutcome};
 
 #[derive(Subcommand)]
 pub enum RepoAction {
@@ -11,21 +27,88 @@ pub enum RepoAction {
         /// Filter by host label
         #[arg(long)]
         host: Option<String>,
+        #[command(flatten)]
+        scope: ScopeArgs,
+    },
+    /// Start tracking a local clone or a clone URL
+    Add {
+        /// Path to a repo's working tree (or bare repo), or a clone URL
+        target: String,
+    },
+    /// Move local clones to where the clone layout puts them
+    Relocate {
+        /// Only repos matching this filter, e.g. "host:gh owner:acme"
+        #[arg(value_name = "FILTER")]
+        query: Option<String>,
+        /// Only repos in this collection
+        #[arg(long)]
+        collection: Option<String>,
+        /// Show what would move without moving anything
+        #[arg(long)]
+        dry_run: bool,
     },
     /// Show details of a repo
     Info {
-        /// Full name (owner/repo) or repo name
+        /// Full name (owner/repo) or repo name, optionally as host:owner/repo
         name: String,
+        #[command(flatten)]
+        scope: ScopeArgs,
     },
 }
 
-pub fn run(action: RepoAction) -> anyhow::Result<()> {
+/// `repo info` output: the repo, its branch snapshots and its tracked
+/// relatives.
+#[derive(Serialize)]
+struct RepoInfo<'a> {
+    #[serde(flatten)]
+    repo: &'a Repo,
+    branches: Vec<BranchSnapshot>,
+    /// The repo's upstream, its upstream's upstream and so on, nearest first.
+    parents: Vec<ForkRelative>,
+    /// Forks of the repo and their forks, depth first.
+    forks: Vec<ForkRelative>,
+}
+
+/// A tracked repo in another's fork tree.
+#[derive(Serialize)]
+struct ForkRelative {
+    repo_id: RepoId,
+    full_name: String,
+    host: Option<String>,
+    /// Links between it and the repo: 1 for a direct parent or fork.
+    depth: usize,
+}
+
+impl ForkRelative {
+    fn new(hosts: &[Host], depth: usize, repo: &Repo) -> Self {
+        Self {
+            repo_id: repo.id.clone(),
+            full_name: repo.full_name.clone(),
+            host: host_label(hosts, repo).map(str::to_string),
+            depth,
+        }
+    }
+}
+
+impl CsvRow for RepoInfo<'_> {
+    const HEADERS: &'static [&'static str] = Repo::HEADERS;
+
+    fn fields(&self) -> Vec<String> {
+        self.repo.fields()
+    }
+}
+
+pub async fn run(action: RepoAction, format: OutputFormat) -> anyhow::Result<()> {
     let db_path = GitrConfig::db_path()?;
     let conn = gitr_db::open_db(&db_path)?;
 
     match action {
-        RepoAction::List { forks, host } => {
-            let repos = if forks {
+        RepoAction::List {
+            forks,
+            host,
+            scope,
+        } => {
+            let mut repos = if forks {
                 gitr_db::ops::list_fork_repos(&conn)?
             } else if let Some(label) = host {
                 let h = gitr_db::ops::get_host_by_label(&conn, &label)?
@@ -34,40 +117,120 @@ pub fn run(action: RepoAction) -> anyhow::Result<()> {
             } else {
                 gitr_db::ops::list_repos(&conn)?
             };
+            scope.retain(&conn, &mut repos)?;
 
+            if let Some(out) = format.structured() {
+                return out.emit(&repos);
+            }
             if repos.is_empty() {
                 println!("No repos tracked. Use `gitr scan` to discover repos.");
                 return Ok(());
             }
 
-            println!(
-                "{:<40} {:<8} {:<8} {:<10} {}",
-                "REPO", "FORK", "BRANCH", "SOURCE", "LAST SYNC"
-            );
-            for repo in &repos {
-                let fork_str = if repo.is_fork { "yes" } else { "no" };
-                let last_sync = repo
-                    .last_synced_at
-                    .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
-                    .unwrap_or_else(|| "never".to_string());
-                println!(
-                    "{:<40} {:<8} {:<8} {:<10} {}",
-                    repo.full_name, fork_str, repo.default_branch, repo.discovery_source, last_sync
-                );
+            print_repos(&repos);
+            Ok(())
+        }
+        RepoAction::Add { target } => {
+            let config = GitrConfig::load()?;
+            let hosts = gitr_db::ops::list_hosts(&conn)?;
+            let path = Path::new(&target);
+            let mut repo = if path.exists() {
+                let local = gitr_discover::scanner::scan_directory(path, 0)
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| anyhow::anyhow!("No git repo at {}", path.display()))?;
+                adopt::adopt(&local, &hosts, DiscoverySource::Manual)
+                    .ok_or_else(|| anyhow::anyhow!("{} has no remotes", path.display()))?
+            } else {
+                let full_name = adopt::full_name_from_url(&target).ok_or_else(|| {
+                    anyhow::anyhow!("'{}' is neither a path nor a clone URL", target)
+                })?;
+                let host = adopt::infer_host(&target, &hosts);
+                let branch = "main".to_string();
+                let mut repo = match host {
+                    Some(host) => Repo::new(
+                        full_name,
+                        host.id.clone(),
+                        target,
+                        branch,
+                        DiscoverySource::Manual,
+                    ),
+                    None => Repo::without_host(full_name, target, branch, DiscoverySource::Manual),
+                };
+                if host.is_none() {
+                    repo.upstream_full_name = Some(repo.full_name.clone());
+                    repo.upstream_clone_url = Some(repo.clone_url.clone());
+                }
+                if let Some(branch) = adopt::remote_default_branch(&repo.clone_url) {
+                    repo.default_branch = branch;
+                }
+                repo
+            };
+            describe_from_host(&hosts, &config, &mut repo).await;
+
+            let tracked = track(&conn, &repo)?;
+            if tracked.id == repo.id {
+                // A new repo may be the upstream of tracked forks, or a fork itself.
+                super::scan::link_upstreams(&conn)?;
+            }
+            if let Some(out) = format.structured() {
+                return out.emit_one(&tracked);
             }
-            println!("\n{} repos total", repos.len());
+            if tracked.id != repo.id {
+                println!("{} is already tracked.", tracked.full_name);
+                return Ok(());
+            }
+            let on = match host_label(&hosts, &tracked) {
+                Some(label) => format!("on host '{label}'"),
+                None => "without a host".to_string(),
+            };
+            println!("Tracking {} {on}.", tracked.full_name);
             Ok(())
         }
-        RepoAction::Info { name } => {
-            let repos = gitr_db::ops::list_repos(&conn)?;
-            let repo = repos
-                .iter()
-                .find(|r| r.full_name == name || r.name == name)
-                .ok_or_else(|| anyhow::anyhow!("Repo '{}' not found", name))?;
+        RepoAction::Relocate {
+            query,
+            collection,
+            dry_run,
+        } => {
+            let scope = ScopeArgs {
+                collection,
+                filter: query,
+            };
+            relocate(&conn, &scope, dry_run, format)
+        }
+        RepoAction::Info { name, scope } => {
+            let repo = &scope.find_repo(&conn, &name)?;
+            let snapshots = gitr_db::ops::get_branch_snapshots(&conn, &repo.id)?;
+            let hosts = gitr_db::ops::list_hosts(&conn)?;
+            let all = gitr_db::ops::list_repos(&conn)?;
+            let tree = ForkTree::new(&all);
+            let parents: Vec<ForkRelative> = tree
+                .ancestors(repo)
+                .into_iter()
+                .enumerate()
+                .map(|(i, parent)| ForkRelative::new(&hosts, i + 1, parent))
+                .collect();
+            let forks: Vec<ForkRelative> = tree
+                .descendants(repo)
+                .into_iter()
+                .map(|(depth, fork)| ForkRelative::new(&hosts, depth, fork))
+                .collect();
+
+            if let Some(out) = format.structured() {
+                return out.emit_one(&RepoInfo {
+                    repo,
+                    branches: snapshots,
+                    parents,
+                    forks,
+                });
+            }
 
             println!("Full name:       {}", repo.full_name);
             println!("Owner:           {}", repo.owner);
             println!("Name:            {}", repo.name);
+            if let Some(ref description) = repo.description {
+                println!("Description:     {}", description);
+            }
             println!("Clone URL:       {}", repo.clone_url);
             println!("Default branch:  {}", repo.default_branch);
             println!("Fork:            {}", repo.is_fork);
@@ -78,6 +241,9 @@ pub fn run(action: RepoAction) -> anyhow::Result<()> {
                 println!("Local path:      {}", path.display());
             }
             println!("Discovery:       {}", repo.discovery_source);
+            if let Some(gone_at) = repo.gone_at {
+                println!("Gone since:      {}", gone_at.format("%Y-%m-%d %H:%M:%S"));
+            }
             println!(
                 "Last synced:     {}",
                 repo.last_synced_at
@@ -85,8 +251,32 @@ pub fn run(action: RepoAction) -> anyhow::Result<()> {
                     .unwrap_or_else(|| "never".to_string())
             );
 
+            if !parents.is_empty() || !forks.is_empty() {
+                println!("\nFork tree:");
+                let line = |indent: usize, relative: &ForkRelative, note: &str| {
+                    let host = relative.host.as_deref().map(|h| format!(" [{h}]"));
+                    println!(
+                        "  {:indent$}{}{}{note}",
+                        "",
+                        relative.full_name,
+                        host.unwrap_or_default(),
+                        indent = indent * 2
+                    );
+                };
+                for (indent, parent) in parents.iter().rev().enumerate() {
+                    line(indent, parent, "");
+                }
+                line(
+                    parents.len(),
+                    &ForkRelative::new(&hosts, 0, repo),
+                    "  (this repo)",
+                );
+                for fork in &forks {
+                    line(parents.len() + fork.depth, fork, "");
+                }
+            }
+
             // Show branch snapshots
-            let snapshots = gitr_db::ops::get_branch_snapshots(&conn, &repo.id)?;
             if !snapshots.is_empty() {
                 println!("\nBranches:");
                 for snap in &snapshots {
@@ -101,3 +291,181 @@ pub fn run(action: RepoAction) -> anyhow::Result<()> {
         }
     }
 }
+
+/// Move each in-scope clone to its layout path, refusing dirty trees and
+/// taken destinations, and record the new paths.
+fn relocate(
+    conn: &Connection,
+    scope: &ScopeArgs,
+    dry_run: bool,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    let config = GitrConfig::load()?;
+    let hosts = gitr_db::ops::list_hosts(conn)?;
+    let mut repos = gitr_db::ops::list_repos(conn)?;
+    scope.retain(conn, &mut repos)?;
+
+    let mut outcomes = Vec::new();
+    let mut in_place = 0;
+    let mut destinations = HashSet::new();
+    for repo in repos {
+        let Some(from) = repo.local_path.clone().filter(|p| p.exists()) else {
+            continue;
+        };
+        let host = hosts.iter().find(|h| repo.host_id.as_ref() == Some(&h.id));
+        let to = config.clone.path_for(&repo, host)?;
+        if from == to {
+            in_place += 1;
+            continue;
+        }
+        let mut outcome = RelocateOutcome {
+            repo: repo.full_name.clone(),
+            from: from.clone(),
+            to: to.clone(),
+            status: if dry_run { "planned" } else { "moved" }.to_string(),
+            error: None,
+        };
+        let refused = if destinations.insert(to.clone()) {
+            relocate::check(&from, &to).err().map(|e| e.to_string())
+        } else {
+            Some(format!("another repo is moving to {}", to.display()))
+        };
+        if let Some(reason) = refused {
+            outcome.status = "skipped".to_string();
+            outcome.error = Some(reason);
+        } else if !dry_run {
+            match relocate::relocate(&from, &to) {
+                Ok(()) => gitr_db::ops::update_repo_local_path(conn, &repo.id, Some(&to))?,
+                Err(e) => {
+                    outcome.status = "failed".to_string();
+                    outcome.error = Some(e.to_string());
+                }
+            }
+        }
+        outcomes.push(outcome);
+    }
+
+    let count = |status: &str| outcomes.iter().filter(|o| o.status == status).count();
+    let failed = count("failed");
+    if let Some(out) = format.structured() {
+        out.emit(&outcomes)?;
+    } else if dry_run {
+        println!(
+            "Would move {} repos | {} skipped | {in_place} already in place",
+            count("planned"),
+            count("skipped")
+        );
+        for o in &outcomes {
+            match &o.error {
+                None => println!("  {} -> {}", o.from.display(), o.to.display()),
+                Some(reason) => println!("  {}: skipped, {reason}", o.repo),
+            }
+        }
+    } else {
+        println!(
+            "Moved {} repos | {} skipped | {failed} failed | {in_place} already in place",
+            count("moved"),
+            count("skipped")
+        );
+        for o in &outcomes {
+            if let Some(reason) = &o.error {
+                println!("  {}: {} ({reason})", o.repo, o.status);
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(Incomplete::Failed(failed).into());
+    }
+    Ok(())
+}
+
+/// Print repos as the `repo list` table.
+pub(crate) fn print_repos(repos: &[Repo]) {
+    println!(
+        "{:<40} {:<8} {:<8} {:<10} LAST SYNC",
+        "REPO", "FORK", "BRANCH", "SOURCE"
+    );
+    for repo in repos {
+        let fork_str = if repo.is_fork { "yes" } else { "no" };
+        let last_sync = repo
+            .last_synced_at
+            .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_else(|| "never".to_string());
+        println!(
+            "{:<40} {:<8} {:<8} {:<10} {}",
+            repo.full_name, fork_str, repo.default_branch, repo.discovery_source, last_sync
+        );
+    }
+    println!("\n{} repos total", repos.len());
+}
+
+/// Start tracking `repo` unless a repo with the same clone URL already is,
+/// in which case that one is returned, given `repo`'s local path if it had
+/// none.
+pub(crate) fn track(conn: &Connection, repo: &Repo) -> anyhow::Result<Repo> {
+    let url = normalize_url(&repo.clone_url);
+    let existing = gitr_db::ops::list_repos(conn)?
+        .into_iter()
+        .find(|r| normalize_url(&r.clone_url) == url);
+    match existing {
+        Some(mut existing) => {
+            if let (None, Some(path)) = (&existing.local_path, &repo.local_path) {
+                gitr_db::ops::update_repo_local_path(conn, &existing.id, Some(path))?;
+                existing.local_path = Some(path.clone());
+            }
+            Ok(existing)
+        }
+        None => {
+            gitr_db::ops::insert_repo(conn, repo)?;
+            Ok(repo.clone())
+        }
+    }
+}
+
+/// Fill in what `repo`'s host knows about it: default branch, fork parent
+/// and metadata. Does nothing for host-less repos, or if the host has no
+/// token or doesn't know the repo.
+pub(crate) async fn describe_from_host(hosts: &[Host], config: &GitrConfig, repo: &mut Repo) {
+    let Some(host) = hosts.iter().find(|h| repo.host_id.as_ref() == Some(&h.id)) else {
+        return;
+    };
+    let Ok(Some(token)) = KeyringStore::new().get(&host.credential_key) else {
+        return;
+    };
+    let Ok(provider) = gitr_host::create_provider(
+        &host.kind,
+        &host.api_url,
+        &token,
+        &host.username,
+        &config.retry,
+    ) else {
+        return;
+    };
+    let remote = match provider.get_repo(&repo.owner, &repo.name).await {
+        Ok(Some(remote)) => remote,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::warn!(
+                "could not look up {} on '{}': {e}",
+                repo.full_name,
+                host.label
+            );
+            return;
+        }
+    };
+    repo.default_branch = remote.default_branch;
+    if remote.is_fork {
+        repo.is_fork = true;
+        repo.upstream_full_name = remote.upstream_full_name;
+        repo.upstream_clone_url = remote.upstream_clone_url;
+    }
+    repo.is_private = remote.is_private;
+    repo.is_archived = remote.is_archived;
+    repo.language = remote.language;
+}
+
+fn host_label<'a>(hosts: &'a [Host], repo: &Repo) -> Option<&'a str> {
+    let id = repo.host_id.as_ref()?;
+    hosts.iter().find(|h| &h.id == id).map(|h| h.label.as_str())
+}
@@ -1,8 +1,24 @@
+use std::path::PathBuf;
+use std::time::Instant;
+
+use chrono::Utc;
 use clap::Args;
 use gitr_auth::{CredentialStore, KeyringStore};
 use gitr_core::config::GitrConfig;
+use gitr_core::metrics;
+use gitr_core::models::host::Host;
 use gitr_core::models::repo::{DiscoverySource, Repo};
-use gitr_discover::reconcile::RepoMatch;
+use gitr_discover::adopt;
+use gitr_discover::changes::{self, RemoteChange, Resolution};
+use gitr_discover::lineage;
+use gitr_discover::reconcile::{ReconcileResult, RepoMatch};
+use gitr_discover::scanner::{ScanOptions, ScannedRepo};
+use gitr_host::HostProvider;
+use gitr_sync::fork_sync;
+use rusqlite::Connection;
+use serde::Serialize;
+
+use crate::output::{CsvRow, OutputFormat};
 
 #[derive(Args)]
 pub struct ScanArgs {
@@ -12,9 +28,62 @@ pub struct ScanArgs {
     /// Only scan for a specific host
     #[arg(long)]
     host: Option<String>,
+    /// Read every directory again instead of reusing the last scan's index
+    #[arg(long)]
+    full: bool,
+    /// Also track local-only repos, on the host their URL points at or without one
+    #[arg(long)]
+    adopt_local: bool,
+    /// Untrack repos marked gone from the scanned hosts
+    #[arg(long)]
+    prune: bool,
+}
+
+/// Per-host totals from one scan.
+#[derive(Serialize)]
+struct ScanSummary {
+    host: String,
+    matched: usize,
+    /// Local repos matching no scanned host (the same on every row)
+    local_only: usize,
+    remote_only: usize,
+    /// Repos added to the database by this scan
+    tracked: u32,
+    /// Renames, deletions and metadata changes found since the last scan
+    changes: Vec<RemoteChange>,
+    /// Gone repos untracked by `--prune`
+    pruned: usize,
 }
 
-pub async fn run(args: ScanArgs) -> anyhow::Result<()> {
+impl CsvRow for ScanSummary {
+    const HEADERS: &'static [&'static str] = &[
+        "host",
+        "matched",
+        "local_only",
+        "remote_only",
+        "tracked",
+        "changes",
+        "pruned",
+    ];
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.host.clone(),
+            self.matched.to_string(),
+            self.local_only.to_string(),
+            self.remote_only.to_string(),
+            self.tracked.to_string(),
+            self.changes
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("; "),
+            self.pruned.to_string(),
+        ]
+    }
+}
+
+pub async fn run(args: ScanArgs, format: OutputFormat) -> anyhow::Result<()> {
     let config = GitrConfig::load()?;
     let db_path = GitrConfig::db_path()?;
     let conn = gitr_db::open_db(&db_path)?;
@@ -45,35 +114,59 @@ pub async fn run(args: ScanArgs) -> anyhow::Result<()> {
         return Ok(());
     }
 
+    let options = ScanOptions {
+        max_depth: config.max_scan_depth,
+        ignore: config.scan_ignore.clone(),
+        nested: config.scan_nested_repos,
+    };
+    let started = Instant::now();
+    let local_repos = scan_local(&conn, &scan_paths, &options, args.full, format)?;
+    let scan_seconds = started.elapsed().as_secs_f64();
+
     let cred_store = KeyringStore::new();
+    let mut summaries = Vec::new();
 
+    let mut providers = Vec::new();
     for host in &hosts {
-        println!("\nScanning host: {} ({})", host.label, host.kind);
-
+        metrics::observe_scan(&host.label, "filesystem", scan_seconds);
         let token = cred_store
             .get(&host.credential_key)?
             .ok_or_else(|| anyhow::anyhow!("No token for host '{}'", host.label))?;
+        providers.push(gitr_host::create_provider(
+            &host.kind,
+            &host.api_url,
+            &token,
+            &host.username,
+            &config.retry,
+        )?);
+    }
+    let targets: Vec<_> = hosts
+        .iter()
+        .zip(&providers)
+        .map(|(host, provider)| (host, provider.as_ref()))
+        .collect();
+    let result = gitr_discover::discover(&targets, &local_repos).await?;
+    let local_only = result.local_only_count();
 
-        let provider =
-            gitr_host::create_provider(&host.kind, &host.api_url, &token, &host.username)?;
-
-        let result =
-            gitr_discover::discover(host, provider.as_ref(), &scan_paths, config.max_scan_depth)
-                .await?;
+    for (host, provider) in hosts.iter().zip(&providers) {
+        format.say(format_args!("\nScanning host: {} ({})", host.label, host.kind));
+        let changes = record_changes(&conn, host, provider.as_ref(), &result).await?;
+        let (mut matched, mut remote_only) = (0, 0);
+        for m in result.for_host(&host.label) {
+            match m {
+                RepoMatch::Matched { .. } => matched += 1,
+                _ => remote_only += 1,
+            }
+        }
 
         // Print reconciliation results
-        println!(
-            "  Matched: {}  |  Local-only: {}  |  Remote-only: {}",
-            result.matched_count(),
-            result.local_only_count(),
-            result.remote_only_count()
-        );
+        format.say(format_args!("  Matched: {matched}  |  Remote-only: {remote_only}"));
 
         // Track remote-only repos in DB
         let mut tracked = 0u32;
-        for m in &result.matches {
+        for m in result.for_host(&host.label) {
             match m {
-                RepoMatch::RemoteOnly(remote) => {
+                RepoMatch::RemoteOnly { remote, .. } => {
                     // Check if already tracked
                     if let Some(existing) = gitr_db::ops::get_repo_by_full_name(&conn, &host.id, &remote.full_name)? {
                         // Update upstream info if we now have it but the DB entry didn't
@@ -85,6 +178,17 @@ pub async fn run(args: ScanArgs) -> anyhow::Result<()> {
                                 remote.upstream_clone_url.as_deref(),
                             )?;
                         }
+                        gitr_db::ops::update_repo_metadata(
+                            &conn,
+                            &existing.id,
+                            remote.is_private,
+                            remote.is_archived,
+                            remote.language.as_deref(),
+                            remote.description.as_deref(),
+                        )?;
+                        if existing.gone_at.is_some() {
+                            gitr_db::ops::set_repo_gone(&conn, &existing.id, None)?;
+                        }
                         continue;
                     }
 
@@ -98,13 +202,31 @@ pub async fn run(args: ScanArgs) -> anyhow::Result<()> {
                     repo.is_fork = remote.is_fork;
                     repo.upstream_full_name = remote.upstream_full_name.clone();
                     repo.upstream_clone_url = remote.upstream_clone_url.clone();
+                    repo.is_private = remote.is_private;
+                    repo.is_archived = remote.is_archived;
+                    repo.language = remote.language.clone();
+                    repo.description = remote.description.clone();
 
                     gitr_db::ops::insert_repo(&conn, &repo)?;
                     tracked += 1;
                 }
-                RepoMatch::Matched { local, remote } => {
-                    // Upsert — ensure tracked with local path
+                RepoMatch::Matched {
+                    local,
+                    remote,
+                    primary,
+                    ..
+                } => {
+                    // Upsert — ensure tracked, with the checkout as local path
+                    // if it is this repo's own (not one tracking it as upstream)
+                    let local_path = local.working_tree().filter(|_| *primary);
                     if let Some(existing) = gitr_db::ops::get_repo_by_full_name(&conn, &host.id, &remote.full_name)? {
+                        if let (None, Some(path)) = (&existing.local_path, local_path) {
+                            gitr_db::ops::update_repo_local_path(
+                                &conn,
+                                &existing.id,
+                                Some(path),
+                            )?;
+                        }
                         // Update upstream info if we now have it but the DB entry didn't
                         if existing.upstream_full_name.is_none() && remote.upstream_full_name.is_some() {
                             gitr_db::ops::update_repo_upstream(
@@ -114,6 +236,17 @@ pub async fn run(args: ScanArgs) -> anyhow::Result<()> {
                                 remote.upstream_clone_url.as_deref(),
                             )?;
                         }
+                        gitr_db::ops::update_repo_metadata(
+                            &conn,
+                            &existing.id,
+                            remote.is_private,
+                            remote.is_archived,
+                            remote.language.as_deref(),
+                            remote.description.as_deref(),
+                        )?;
+                        if existing.gone_at.is_some() {
+                            gitr_db::ops::set_repo_gone(&conn, &existing.id, None)?;
+                        }
                     } else {
                         let mut repo = Repo::new(
                             remote.full_name.clone(),
@@ -125,7 +258,11 @@ pub async fn run(args: ScanArgs) -> anyhow::Result<()> {
                         repo.is_fork = remote.is_fork;
                         repo.upstream_full_name = remote.upstream_full_name.clone();
                         repo.upstream_clone_url = remote.upstream_clone_url.clone();
-                        repo.local_path = Some(local.path.clone());
+                        repo.is_private = remote.is_private;
+                        repo.is_archived = remote.is_archived;
+                        repo.language = remote.language.clone();
+                        repo.description = remote.description.clone();
+                        repo.local_path = local_path.map(Into::into);
                         gitr_db::ops::insert_repo(&conn, &repo)?;
                         tracked += 1;
                     }
@@ -135,12 +272,193 @@ pub async fn run(args: ScanArgs) -> anyhow::Result<()> {
         }
 
         if tracked > 0 {
-            println!("  Tracked {tracked} new repos.");
+            format.say(format_args!("  Tracked {tracked} new repos."));
+        }
+        if !changes.is_empty() {
+            format.say(format_args!("  Changed on the host since the last scan:"));
+            for change in &changes {
+                format.say(format_args!("    {change}"));
+            }
         }
+
+        let mut pruned = 0;
+        if args.prune {
+            for repo in gitr_db::ops::list_repos_for_host(&conn, &host.id)? {
+                if repo.gone_at.is_some() {
+                    gitr_db::ops::delete_repo(&conn, &repo.id)?;
+                    pruned += 1;
+                }
+            }
+            format.say(format_args!("  Pruned {pruned} gone repos."));
+        }
+        summaries.push(ScanSummary {
+            host: host.label.clone(),
+            matched,
+            local_only,
+            remote_only,
+            tracked,
+            changes,
+            pruned,
+        });
     }
 
+    format.say(format_args!("\nLocal-only: {local_only}"));
+    if args.adopt_local && local_only > 0 {
+        let all_hosts = gitr_db::ops::list_hosts(&conn)?;
+        let mut adopted = 0u32;
+        for m in &result.matches {
+            let RepoMatch::LocalOnly(local) = m else {
+                continue;
+            };
+            let Some(mut repo) = adopt::adopt(local, &all_hosts, DiscoverySource::Filesystem)
+            else {
+                continue;
+            };
+            super::repo::describe_from_host(&all_hosts, &config, &mut repo).await;
+            if super::repo::track(&conn, &repo)?.id == repo.id {
+                adopted += 1;
+            }
+        }
+        format.say(format_args!("  Adopted {adopted} new repos."));
+    }
+    let linked = link_upstreams(&conn)?;
+    if linked > 0 {
+        format.say(format_args!("Linked {linked} forks to their tracked upstreams."));
+    }
+    if let Some(out) = format.structured() {
+        return out.emit(&summaries);
+    }
     let total = gitr_db::ops::list_repos(&conn)?.len();
     println!("\nTotal tracked repos: {total}");
 
     Ok(())
 }
+
+/// Point each tracked fork's `upstream_repo_id` at its upstream, if that is
+/// tracked too (on any host), and clear links that no longer hold. Returns
+/// how many forks were newly linked.
+pub(crate) fn link_upstreams(conn: &Connection) -> anyhow::Result<usize> {
+    let repos = gitr_db::ops::list_repos(conn)?;
+    let mut linked = 0;
+    for (id, upstream) in lineage::resolve_upstreams(&repos) {
+        gitr_db::ops::set_repo_upstream_id(conn, &id, upstream.as_ref())?;
+        linked += usize::from(upstream.is_some());
+    }
+    Ok(linked)
+}
+
+/// Compare the repos tracked on `host` with what it listed, and bring the
+/// database up to date for the ones it left out: follow renames and
+/// transfers, and mark repos the host no longer has as gone. Metadata of
+/// listed repos is stored by the caller.
+async fn record_changes(
+    conn: &Connection,
+    host: &Host,
+    provider: &dyn HostProvider,
+    result: &ReconcileResult,
+) -> anyhow::Result<Vec<RemoteChange>> {
+    let tracked = gitr_db::ops::list_repos_for_host(conn, &host.id)?;
+    let diff = changes::diff(
+        &tracked,
+        result.for_host(&host.label).filter_map(RepoMatch::remote),
+    );
+    let mut found = diff.changes;
+    for repo in diff.missing {
+        let resolution = match changes::resolve_missing(provider, repo).await {
+            Ok(resolution) => resolution,
+            Err(e) => {
+                tracing::warn!("{}: could not look up {}: {e}", host.label, repo.full_name);
+                continue;
+            }
+        };
+        let mark_gone = |found: &mut Vec<RemoteChange>| -> anyhow::Result<()> {
+            if repo.gone_at.is_none() {
+                gitr_db::ops::set_repo_gone(conn, &repo.id, Some(&Utc::now()))?;
+                found.push(RemoteChange::Gone {
+                    repo: repo.full_name.clone(),
+                });
+            }
+            Ok(())
+        };
+        let remote = match resolution {
+            Resolution::Deleted => {
+                mark_gone(&mut found)?;
+                continue;
+            }
+            Resolution::Moved(remote) => {
+                let taken = gitr_db::ops::get_repo_by_full_name(conn, &host.id, &remote.full_name)?
+                    .is_some_and(|other| other.id != repo.id);
+                if taken {
+                    // The new name is tracked already, so this row is a stale duplicate.
+                    mark_gone(&mut found)?;
+                    continue;
+                }
+                gitr_db::ops::rename_repo(conn, &repo.id, &remote.full_name, &remote.clone_url)?;
+                let clone_base = GitrConfig::home_dir()?.join("repos");
+                if let Err(e) = fork_sync::follow_rename(
+                    repo,
+                    &remote.full_name,
+                    &remote.clone_url,
+                    &clone_base,
+                ) {
+                    tracing::warn!("could not move the clone of {}: {e}", repo.full_name);
+                }
+                found.push(RemoteChange::Renamed {
+                    from: repo.full_name.clone(),
+                    to: remote.full_name.clone(),
+                });
+                remote
+            }
+            Resolution::Exists(remote) => remote,
+        };
+        found.extend(changes::metadata_changes(repo, &remote));
+        gitr_db::ops::update_repo_metadata(
+            conn,
+            &repo.id,
+            remote.is_private,
+            remote.is_archived,
+            remote.language.as_deref(),
+            remote.description.as_deref(),
+        )?;
+        if repo.gone_at.is_some() {
+            gitr_db::ops::set_repo_gone(conn, &repo.id, None)?;
+        }
+    }
+    Ok(found)
+}
+
+/// Scan each path for local repos, reusing the index its last scan left in
+/// the database (unless `full`) and saving the new one.
+fn scan_local(
+    conn: &Connection,
+    paths: &[PathBuf],
+    options: &ScanOptions,
+    full: bool,
+    format: OutputFormat,
+) -> anyhow::Result<Vec<ScannedRepo>> {
+    let mut repos = Vec::new();
+    for path in paths {
+        if !path.exists() {
+            continue;
+        }
+        // Normalized so the index is keyed the same way however the path was typed.
+        let root: PathBuf = path.components().collect();
+        let key = root.to_string_lossy();
+        let previous = if full {
+            Vec::new()
+        } else {
+            gitr_db::ops::get_scan_index(conn, &key)?
+        };
+        let scan = gitr_discover::scanner::scan(&root, options, previous)?;
+        gitr_db::ops::replace_scan_index(conn, &key, &scan.index)?;
+        format.say(format_args!(
+            "Scanned {}: {} repos in {} directories ({} unchanged)",
+            root.display(),
+            scan.repos.len(),
+            scan.index.len(),
+            scan.unchanged
+        ));
+        repos.extend(scan.repos);
+    }
+    Ok(repos)
+}
@@ -0,0 +1,141 @@
+//! Choosing which repos a command acts on: `--collection`, `--where`, and
+//! looking a repo up by name.
+
+use std::collections::{HashMap, HashSet};
+
+use clap::Args;
+use gitr_core::filter::Filter;
+use gitr_core::models::repo::{Repo, RepoId};
+use rusqlite::Connection;
+
+/// `--collection` and `--where`, for commands that act on a set of repos.
+#[derive(Args, Default)]
+pub struct ScopeArgs {
+    /// Only repos in this collection
+    #[arg(long)]
+    pub collection: Option<String>,
+    /// Only repos matching this filter, e.g. "host:gh fork:true (behind>0 or synced>7d)"
+    #[arg(long = "where", value_name = "FILTER")]
+    pub filter: Option<String>,
+}
+
+impl ScopeArgs {
+    /// IDs of the repos in scope, or `None` if neither option was given.
+    pub fn repo_ids(&self, conn: &Connection) -> anyhow::Result<Option<HashSet<RepoId>>> {
+        let members = match &self.collection {
+            Some(name) => Some(super::collection::member_ids(conn, name)?),
+            None => None,
+        };
+        let matching = match &self.filter {
+            Some(query) => {
+                let filter: Filter = query.parse()?;
+                let ids: HashSet<RepoId> = gitr_db::ops::list_repos_matching(conn, &filter)?
+                    .into_iter()
+                    .map(|r| r.id)
+                    .collect();
+                Some(ids)
+            }
+            None => None,
+        };
+        Ok(match (members, matching) {
+            (Some(members), Some(matching)) => {
+                Some(members.intersection(&matching).cloned().collect())
+            }
+            (members, matching) => members.or(matching),
+        })
+    }
+
+    /// Keep only the repos in scope.
+    pub fn retain(&self, conn: &Connection, repos: &mut Vec<Repo>) -> anyhow::Result<()> {
+        if let Some(ids) = self.repo_ids(conn)? {
+            repos.retain(|r| ids.contains(&r.id));
+        }
+        Ok(())
+    }
+
+    /// Look up the repo `target` names among the repos in scope.
+    pub fn find_repo(&self, conn: &Connection, target: &str) -> anyhow::Result<Repo> {
+        let mut repos = gitr_db::ops::list_repos(conn)?;
+        self.retain(conn, &mut repos)?;
+        if let Some(scope) = self.describe() {
+            if matching(conn, &repos, target)?.is_empty() {
+                anyhow::bail!("Repo '{}' not found in {}", target, scope);
+            }
+        }
+        find_repo(conn, &repos, target).cloned()
+    }
+
+    /// How the scope reads in messages, e.g. "collection 'work'".
+    fn describe(&self) -> Option<String> {
+        match (&self.collection, &self.filter) {
+            (Some(name), Some(query)) => Some(format!("collection '{name}' where '{query}'")),
+            (Some(name), None) => Some(format!("collection '{name}'")),
+            (None, Some(query)) => Some(format!("repos where '{query}'")),
+            (None, None) => None,
+        }
+    }
+}
+
+/// The repos among `repos` that `target` names. A target is a full name
+/// (owner/repo) or a bare name, optionally qualified with a host label as
+/// `label:owner/repo`. Full-name matches win over bare-name ones.
+pub fn matching<'a>(
+    conn: &Connection,
+    repos: &'a [Repo],
+    target: &str,
+) -> anyhow::Result<Vec<&'a Repo>> {
+    let (host, name) = match target.split_once(':') {
+        Some((label, name)) => {
+            let host = gitr_db::ops::get_host_by_label(conn, label)?
+                .ok_or_else(|| anyhow::anyhow!("Host '{}' not found", label))?;
+            (Some(host.id), name)
+        }
+        None => (None, target),
+    };
+    let on_host = |r: &&Repo| host.is_none() || r.host_id == host;
+
+    let by_full_name: Vec<&Repo> = repos
+        .iter()
+        .filter(on_host)
+        .filter(|r| r.full_name == name)
+        .collect();
+    if !by_full_name.is_empty() {
+        return Ok(by_full_name);
+    }
+    Ok(repos
+        .iter()
+        .filter(on_host)
+        .filter(|r| r.name == name)
+        .collect())
+}
+
+/// Look up the one repo `target` names among `repos`. Fails if none match
+/// or if the name is ambiguous, listing the candidates.
+pub fn find_repo<'a>(
+    conn: &Connection,
+    repos: &'a [Repo],
+    target: &str,
+) -> anyhow::Result<&'a Repo> {
+    let found = matching(conn, repos, target)?;
+    match found.as_slice() {
+        [] => anyhow::bail!("Repo '{}' not found", target),
+        [repo] => Ok(repo),
+        candidates => {
+            let labels: HashMap<_, _> = gitr_db::ops::list_hosts(conn)?
+                .into_iter()
+                .map(|h| (h.id, h.label))
+                .collect();
+            let mut message = format!("'{target}' matches {} repos:", candidates.len());
+            for repo in candidates {
+                let label = match &repo.host_id {
+                    Some(id) => labels.get(id).map(String::as_str).unwrap_or("?"),
+                    None => "",
+                };
+                message.push_str(&format!("\n  {label}:{}", repo.full_name));
+            }
+            message
+                .push_str("\nUse one of the names above, or narrow it with --where host:<label>");
+            Err(anyhow::anyhow!(message))
+        }
+    }
+}
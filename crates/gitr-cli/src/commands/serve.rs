@@ -0,0 +1,586 @@
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use axum::body::Bytes;
+use axum::extract::{Path, Query, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use chrono::{DateTime, Utc};
+use clap::Args;
+use gitr_auth::{CredentialStore, KeyringStore};
+use gitr_core::config::GitrConfig;
+use gitr_core::error::GitrError;
+use gitr_core::filter::Filter;
+use gitr_core::models::host::Host;
+use gitr_core::models::repo::{Repo, RepoId};
+use gitr_core::models::sync_link::SyncLink;
+use gitr_core::models::sync_state::{BranchSnapshot, SyncRecord, SyncStatus};
+use gitr_sync::backend::create_backend;
+use gitr_sync::engine::SyncEngine;
+use gitr_sync::webhook::constant_time_eq;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use crate::output::{RefreshOutcome, SyncOutcome};
+
+use super::refresh::refresh_repos;
+use super::sync::{save_results, upstream_pairs};
+
+#[derive(Args)]
+pub struct ServeArgs {
+    /// Address to listen on; must be a loopback address
+    #[arg(long, default_value = "127.0.0.1:8788")]
+    listen: SocketAddr,
+    /// Bearer token clients must send (default: $GITR_API_TOKEN, else one
+    /// generated and kept in the OS keychain)
+    #[arg(long)]
+    token: Option<String>,
+}
+
+/// Keychain entry holding the API token when none is given.
+const TOKEN_KEY: &str = "gitr:api-token";
+
+/// Finished jobs kept for polling; older ones are dropped first.
+const MAX_FINISHED_JOBS: usize = 200;
+
+pub async fn run(args: ServeArgs) -> anyhow::Result<()> {
+    if !args.listen.ip().is_loopback() {
+        anyhow::bail!(
+            "refusing to listen on {}: the API is only served on localhost",
+            args.listen
+        );
+    }
+    let config = GitrConfig::load()?;
+    let db_path = GitrConfig::db_path()?;
+    let conn = gitr_db::open_db(&db_path)?;
+    let token = resolve_token(args.token)?;
+
+    let jobs: Jobs = Arc::new(Mutex::new(HashMap::new()));
+    let (queue, rx) = mpsc::unbounded_channel();
+    spawn_worker(config, db_path, jobs.clone(), rx);
+
+    let state = ApiState {
+        conn: Arc::new(Mutex::new(conn)),
+        jobs,
+        queue,
+        token: Arc::new(token),
+    };
+    let api = Router::new()
+        .route("/api/hosts", get(list_hosts))
+        .route("/api/repos", get(list_repos))
+        .route("/api/repos/{id}", get(get_repo))
+        .route("/api/repos/{id}/snapshots", get(repo_snapshots))
+        .route("/api/repos/{id}/history", get(repo_history))
+        .route("/api/history", get(list_history))
+        .route("/api/history/{id}", get(get_history))
+        .route("/api/links", get(list_links))
+        .route("/api/sync", post(start_sync))
+        .route("/api/refresh", post(start_refresh))
+        .route("/api/jobs", get(list_jobs))
+        .route("/api/jobs/{id}", get(get_job))
+        .layer(middleware::from_fn_with_state(state.clone(), require_token))
+        .with_state(state);
+
+    let listener = tokio::net::TcpListener::bind(args.listen).await?;
+    tracing::info!("serving the gitr API on http://{}/api", args.listen);
+    axum::serve(listener, api)
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await?;
+    Ok(())
+}
+
+fn resolve_token(flag: Option<String>) -> anyhow::Result<String> {
+    if let Some(token) = flag.or_else(|| std::env::var("GITR_API_TOKEN").ok()) {
+        if token.is_empty() {
+            anyhow::bail!("API token cannot be empty");
+        }
+        return Ok(token);
+    }
+    let cred_store = KeyringStore::new();
+    if let Some(token) = cred_store.get(TOKEN_KEY)? {
+        return Ok(token);
+    }
+    let token = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+    cred_store.store(TOKEN_KEY, &token)?;
+    println!("Generated API token (stored in OS keychain as '{TOKEN_KEY}'): {token}");
+    Ok(token)
+}
+
+type Jobs = Arc<Mutex<HashMap<Uuid, Job>>>;
+
+#[derive(Clone)]
+struct ApiState {
+    conn: Arc<Mutex<Connection>>,
+    jobs: Jobs,
+    queue: mpsc::UnboundedSender<(Uuid, JobRequest)>,
+    token: Arc<String>,
+}
+
+impl ApiState {
+    fn db(&self) -> MutexGuard<'_, Connection> {
+        self.conn.lock().expect("db mutex poisoned")
+    }
+}
+
+/// JSON error body with a status code.
+struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(e: anyhow::Error) -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (
+            self.status,
+            Json(serde_json::json!({ "error": self.message })),
+        )
+            .into_response()
+    }
+}
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+async fn require_token(State(state): State<ApiState>, req: Request, next: Next) -> Response {
+    let authorized = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .is_some_and(|t| constant_time_eq(t.as_bytes(), state.token.as_bytes()));
+    if !authorized {
+        return ApiError::new(StatusCode::UNAUTHORIZED, "missing or invalid bearer token")
+            .into_response();
+    }
+    next.run(req).await
+}
+
+/// Look up a repo by ID, full name (owner/repo) or name.
+fn find_repo(conn: &Connection, key: &str) -> Result<Repo, ApiError> {
+    let found = match Uuid::parse_str(key) {
+        Ok(id) => gitr_db::ops::get_repo_by_id(conn, &RepoId::from_uuid(id))?,
+        Err(_) => {
+            let repos = gitr_db::ops::list_repos(conn)?;
+            let found = super::scope::matching(conn, &repos, key)?;
+            if found.len() > 1 {
+                return Err(ApiError::new(
+                    StatusCode::CONFLICT,
+                    format!("'{key}' matches several repos; use its ID or host:owner/repo"),
+                ));
+            }
+            found.first().map(|r| (*r).clone())
+        }
+    };
+    found.ok_or_else(|| ApiError::new(StatusCode::NOT_FOUND, format!("repo '{key}' not found")))
+}
+
+async fn list_hosts(State(state): State<ApiState>) -> ApiResult<Vec<Host>> {
+    Ok(Json(gitr_db::ops::list_hosts(&state.db())?))
+}
+
+#[derive(Deserialize)]
+struct ReposQuery {
+    /// Host label.
+    host: Option<String>,
+    /// Only forks (`true`) or only non-forks (`false`).
+    fork: Option<bool>,
+    /// A repo filter, as taken by `--where`.
+    #[serde(rename = "where")]
+    filter: Option<String>,
+}
+
+async fn list_repos(
+    State(state): State<ApiState>,
+    Query(query): Query<ReposQuery>,
+) -> ApiResult<Vec<Repo>> {
+    let conn = state.db();
+    let mut repos = match &query.host {
+        Some(label) => {
+            let host = gitr_db::ops::get_host_by_label(&conn, label)?.ok_or_else(|| {
+                ApiError::new(StatusCode::NOT_FOUND, format!("host '{label}' not found"))
+            })?;
+            gitr_db::ops::list_repos_for_host(&conn, &host.id)?
+        }
+        None => gitr_db::ops::list_repos(&conn)?,
+    };
+    if let Some(fork) = query.fork {
+        repos.retain(|r| r.is_fork == fork);
+    }
+    if let Some(filter) = &query.filter {
+        let filter: Filter = filter
+            .parse()
+            .map_err(|e: GitrError| ApiError::new(StatusCode::BAD_REQUEST, e.to_string()))?;
+        let matching: HashSet<RepoId> = gitr_db::ops::list_repos_matching(&conn, &filter)?
+            .into_iter()
+            .map(|r| r.id)
+            .collect();
+        repos.retain(|r| matching.contains(&r.id));
+    }
+    Ok(Json(repos))
+}
+
+async fn get_repo(State(state): State<ApiState>, Path(id): Path<String>) -> ApiResult<Repo> {
+    Ok(Json(find_repo(&state.db(), &id)?))
+}
+
+async fn repo_snapshots(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> ApiResult<Vec<BranchSnapshot>> {
+    let conn = state.db();
+    let repo = find_repo(&conn, &id)?;
+    Ok(Json(gitr_db::ops::get_branch_snapshots(&conn, &repo.id)?))
+}
+
+#[derive(Deserialize)]
+struct HistoryQuery {
+    limit: Option<u32>,
+}
+
+async fn repo_history(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    Query(query): Query<HistoryQuery>,
+) -> ApiResult<Vec<SyncRecord>> {
+    let conn = state.db();
+    let repo = find_repo(&conn, &id)?;
+    Ok(Json(gitr_db::ops::list_sync_history(
+        &conn,
+        Some(&repo.id),
+        query.limit.unwrap_or(20),
+    )?))
+}
+
+async fn list_history(
+    State(state): State<ApiState>,
+    Query(query): Query<HistoryQuery>,
+) -> ApiResult<Vec<SyncRecord>> {
+    Ok(Json(gitr_db::ops::list_sync_history(
+        &state.db(),
+        None,
+        query.limit.unwrap_or(20),
+    )?))
+}
+
+async fn get_history(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> ApiResult<SyncRecord> {
+    gitr_db::ops::get_sync_record(&state.db(), &id)?
+        .map(Json)
+        .ok_or_else(|| ApiError::new(StatusCode::NOT_FOUND, format!("sync '{id}' not found")))
+}
+
+async fn list_links(State(state): State<ApiState>) -> ApiResult<Vec<SyncLink>> {
+    Ok(Json(gitr_db::ops::list_sync_links(&state.db())?))
+}
+
+// ── Jobs ────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum JobStatus {
+    Queued,
+    Running,
+    /// Ran to completion; individual repos may still have failed.
+    Finished,
+    /// Stopped before completing, see `error`.
+    Failed,
+}
+
+/// A sync or refresh started through the API, polled at `/api/jobs/{id}`.
+#[derive(Debug, Clone, Serialize)]
+struct Job {
+    id: Uuid,
+    kind: &'static str,
+    status: JobStatus,
+    repos: Vec<String>,
+    created_at: DateTime<Utc>,
+    started_at: Option<DateTime<Utc>>,
+    finished_at: Option<DateTime<Utc>>,
+    error: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    syncs: Vec<SyncOutcome>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    refreshes: Vec<RefreshOutcome>,
+}
+
+enum JobRequest {
+    Sync { forks: Vec<Repo>, dry_run: bool },
+    Refresh { forks: Vec<Repo>, fetch: bool },
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct SyncBody {
+    /// Repo IDs or names; all forks when empty.
+    repos: Vec<String>,
+    dry_run: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+struct RefreshBody {
+    repos: Vec<String>,
+    fetch: bool,
+}
+
+impl Default for RefreshBody {
+    fn default() -> Self {
+        Self {
+            repos: Vec::new(),
+            fetch: true,
+        }
+    }
+}
+
+/// Parse an optional JSON body, treating an empty one as all defaults.
+fn parse_body<T: for<'de> Deserialize<'de> + Default>(body: &Bytes) -> Result<T, ApiError> {
+    if body.iter().all(u8::is_ascii_whitespace) {
+        return Ok(T::default());
+    }
+    serde_json::from_slice(body).map_err(|e| {
+        ApiError::new(
+            StatusCode::BAD_REQUEST,
+            format!("invalid request body: {e}"),
+        )
+    })
+}
+
+fn resolve_forks(conn: &Connection, keys: &[String]) -> Result<Vec<Repo>, ApiError> {
+    if keys.is_empty() {
+        return Ok(gitr_db::ops::list_fork_repos(conn)?);
+    }
+    keys.iter()
+        .map(|key| {
+            let repo = find_repo(conn, key)?;
+            if !repo.is_fork {
+                return Err(ApiError::new(
+                    StatusCode::BAD_REQUEST,
+                    format!("{} is not a fork", repo.full_name),
+                ));
+            }
+            Ok(repo)
+        })
+        .collect()
+}
+
+fn enqueue(state: &ApiState, kind: &'static str, forks: &[Repo], request: JobRequest) -> Job {
+    let job = Job {
+        id: Uuid::now_v7(),
+        kind,
+        status: JobStatus::Queued,
+        repos: forks.iter().map(|r| r.full_name.clone()).collect(),
+        created_at: Utc::now(),
+        started_at: None,
+        finished_at: None,
+        error: None,
+        syncs: Vec::new(),
+        refreshes: Vec::new(),
+    };
+    let mut jobs = state.jobs.lock().expect("jobs mutex poisoned");
+    jobs.insert(job.id, job.clone());
+    let finished: Vec<(DateTime<Utc>, Uuid)> = jobs
+        .values()
+        .filter_map(|j| j.finished_at.map(|at| (at, j.id)))
+        .collect();
+    if finished.len() > MAX_FINISHED_JOBS {
+        let mut finished = finished;
+        finished.sort();
+        for (_, id) in &finished[..finished.len() - MAX_FINISHED_JOBS] {
+            jobs.remove(id);
+        }
+    }
+    let _ = state.queue.send((job.id, request));
+    job
+}
+
+async fn start_sync(
+    State(state): State<ApiState>,
+    body: Bytes,
+) -> Result<(StatusCode, Json<Job>), ApiError> {
+    let body: SyncBody = parse_body(&body)?;
+    let forks = resolve_forks(&state.db(), &body.repos)?;
+    let request = JobRequest::Sync {
+        forks: forks.clone(),
+        dry_run: body.dry_run,
+    };
+    let job = enqueue(&state, "sync", &forks, request);
+    Ok((StatusCode::ACCEPTED, Json(job)))
+}
+
+async fn start_refresh(
+    State(state): State<ApiState>,
+    body: Bytes,
+) -> Result<(StatusCode, Json<Job>), ApiError> {
+    let body: RefreshBody = parse_body(&body)?;
+    let forks = resolve_forks(&state.db(), &body.repos)?;
+    let request = JobRequest::Refresh {
+        forks: forks.clone(),
+        fetch: body.fetch,
+    };
+    let job = enqueue(&state, "refresh", &forks, request);
+    Ok((StatusCode::ACCEPTED, Json(job)))
+}
+
+async fn list_jobs(State(state): State<ApiState>) -> ApiResult<Vec<Job>> {
+    let mut jobs: Vec<Job> = state
+        .jobs
+        .lock()
+        .expect("jobs mutex poisoned")
+        .values()
+        .cloned()
+        .collect();
+    jobs.sort_by_key(|job| std::cmp::Reverse(job.created_at));
+    Ok(Json(jobs))
+}
+
+async fn get_job(State(state): State<ApiState>, Path(id): Path<String>) -> ApiResult<Job> {
+    let job = Uuid::parse_str(&id).ok().and_then(|id| {
+        state
+            .jobs
+            .lock()
+            .expect("jobs mutex poisoned")
+            .get(&id)
+            .cloned()
+    });
+    job.map(Json)
+        .ok_or_else(|| ApiError::new(StatusCode::NOT_FOUND, format!("job '{id}' not found")))
+}
+
+/// Run queued jobs one at a time on a dedicated thread. Sync and refresh hold
+/// a DB connection across awaits, so they can't run on the shared runtime.
+fn spawn_worker(
+    config: GitrConfig,
+    db_path: PathBuf,
+    jobs: Jobs,
+    mut rx: mpsc::UnboundedReceiver<(Uuid, JobRequest)>,
+) {
+    std::thread::spawn(move || {
+        let runtime = match tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+        {
+            Ok(runtime) => runtime,
+            Err(e) => {
+                tracing::error!("job worker failed to start: {e}");
+                return;
+            }
+        };
+        runtime.block_on(async move {
+            while let Some((id, request)) = rx.recv().await {
+                update_job(&jobs, id, |job| {
+                    job.status = JobStatus::Running;
+                    job.started_at = Some(Utc::now());
+                });
+                let outcome = run_request(&config, &db_path, request).await;
+                update_job(&jobs, id, |job| {
+                    job.finished_at = Some(Utc::now());
+                    match outcome {
+                        Ok((syncs, refreshes)) => {
+                            job.status = JobStatus::Finished;
+                            job.syncs = syncs;
+                            job.refreshes = refreshes;
+                        }
+                        Err(e) => {
+                            tracing::error!("job {id}: {e}");
+                            job.status = JobStatus::Failed;
+                            job.error = Some(e.to_string());
+                        }
+                    }
+                });
+            }
+        });
+    });
+}
+
+fn update_job(jobs: &Jobs, id: Uuid, f: impl FnOnce(&mut Job)) {
+    if let Some(job) = jobs.lock().expect("jobs mutex poisoned").get_mut(&id) {
+        f(job);
+    }
+}
+
+async fn run_request(
+    config: &GitrConfig,
+    db_path: &std::path::Path,
+    request: JobRequest,
+) -> anyhow::Result<(Vec<SyncOutcome>, Vec<RefreshOutcome>)> {
+    let conn = gitr_db::open_db(db_path)?;
+    let outcome = match request {
+        JobRequest::Sync { forks, dry_run } => {
+            let clone_base = GitrConfig::home_dir()?.join("repos");
+            std::fs::create_dir_all(&clone_base)?;
+            let engine = SyncEngine::new(config.sync_concurrency, config.retry.clone())
+                .with_backend(create_backend(&config.git_backend))
+                .with_hooks(config.hooks.clone());
+            let pairs = upstream_pairs(&conn, config, &forks).await?;
+            let results = engine
+                .sync_all_forks(pairs, &clone_base, &config.default_merge_strategy, dry_run)
+                .await;
+
+            let mut refreshes = Vec::new();
+            if !dry_run {
+                save_results(&conn, &results)?;
+                let synced: Vec<Repo> = forks
+                    .into_iter()
+                    .filter(|f| {
+                        results.iter().any(|r| {
+                            r.record.repo_id == f.id && r.record.status == SyncStatus::Success
+                        })
+                    })
+                    .collect();
+                refreshes = refresh_outcomes(&conn, config, synced, false).await?;
+            }
+            let syncs = results
+                .into_iter()
+                .map(SyncOutcome::from)
+                .collect();
+            Ok((syncs, refreshes))
+        }
+        JobRequest::Refresh { forks, fetch } => Ok((
+            Vec::new(),
+            refresh_outcomes(&conn, config, forks, fetch).await?,
+        )),
+    };
+    super::notify::send_alerts(&conn, config).await;
+    outcome
+}
+
+async fn refresh_outcomes(
+    conn: &Connection,
+    config: &GitrConfig,
+    repos: Vec<Repo>,
+    fetch: bool,
+) -> anyhow::Result<Vec<RefreshOutcome>> {
+    if repos.is_empty() {
+        return Ok(Vec::new());
+    }
+    Ok(refresh_repos(conn, config, repos, fetch)
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect())
+}
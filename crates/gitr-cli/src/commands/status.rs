@@ -1,26 +1,148 @@
+use chrono::{DateTime, Utc};
 use clap::Args;
 use comfy_table::{Cell, Color, Table};
 use gitr_core::config::GitrConfig;
+use gitr_core::models::repo::{Repo, RepoId};
+use gitr_discover::lineage::ForkTree;
+use serde::Serialize;
+
+use super::scope::ScopeArgs;
+use crate::output::{CsvRow, OutputFormat};
 
 #[derive(Args)]
 pub struct StatusArgs {
     /// Filter by host label
     #[arg(long)]
     host: Option<String>,
+    #[command(flatten)]
+    scope: ScopeArgs,
+    /// Group repos by fork family instead of host, leaving out repos with
+    /// no tracked upstream or forks
+    #[arg(long)]
+    families: bool,
+    /// Open the interactive dashboard, updated live as syncs run
+    #[arg(long, conflicts_with_all = ["collection", "filter", "families"])]
+    pub watch: bool,
+}
+
+/// One repo's line in `gitr status`.
+#[derive(Serialize)]
+struct StatusEntry {
+    host: String,
+    repo: String,
+    repo_id: RepoId,
+    is_fork: bool,
+    branch: String,
+    behind: u32,
+    ahead: u32,
+    last_synced_at: Option<DateTime<Utc>>,
+    /// synced, behind, ahead, unknown, or tracked for non-forks
+    status: &'static str,
+    /// Full name of the family's root, for repos with tracked relatives
+    family: Option<String>,
+}
+
+impl CsvRow for StatusEntry {
+    const HEADERS: &'static [&'static str] = &[
+        "host",
+        "repo",
+        "repo_id",
+        "is_fork",
+        "branch",
+        "behind",
+        "ahead",
+        "last_synced_at",
+        "status",
+        "family",
+    ];
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.host.clone(),
+            self.repo.clone(),
+            self.repo_id.to_string(),
+            self.is_fork.to_string(),
+            self.branch.clone(),
+            self.behind.to_string(),
+            self.ahead.to_string(),
+            self.last_synced_at
+                .map(|dt| dt.to_rfc3339())
+                .unwrap_or_default(),
+            self.status.to_string(),
+            self.family.clone().unwrap_or_default(),
+        ]
+    }
 }
 
-pub fn run(args: StatusArgs) -> anyhow::Result<()> {
+pub fn run(args: StatusArgs, format: OutputFormat) -> anyhow::Result<()> {
+    if args.watch {
+        return crate::tui::run(args.host);
+    }
+
     let db_path = GitrConfig::db_path()?;
     let conn = gitr_db::open_db(&db_path)?;
 
     let hosts = if let Some(label) = &args.host {
-        let h = gitr_db::ops::get_host_by_label(&conn, &label)?
+        let h = gitr_db::ops::get_host_by_label(&conn, label)?
             .ok_or_else(|| anyhow::anyhow!("Host '{}' not found", label))?;
         vec![h]
     } else {
         gitr_db::ops::list_hosts(&conn)?
     };
 
+    let members = args.scope.repo_ids(&conn)?;
+    let all = gitr_db::ops::list_repos(&conn)?;
+    let tree = ForkTree::new(&all);
+
+    let mut entries = Vec::new();
+    for host in &hosts {
+        for repo in gitr_db::ops::list_repos_for_host(&conn, &host.id)? {
+            if members.as_ref().is_some_and(|m| !m.contains(&repo.id)) {
+                continue;
+            }
+            let family = tree
+                .has_family(&repo)
+                .then(|| tree.root(&repo).full_name.clone());
+            if args.families && family.is_none() {
+                continue;
+            }
+            let snapshots = gitr_db::ops::get_branch_snapshots(&conn, &repo.id)?;
+            let default_snap = snapshots.iter().find(|s| s.branch == repo.default_branch);
+
+            let behind = default_snap.map(|s| s.behind_count).unwrap_or(0);
+            let ahead = default_snap.map(|s| s.ahead_count).unwrap_or(0);
+
+            let status = if !repo.is_fork {
+                "tracked"
+            } else if behind == 0 && ahead == 0 && repo.last_synced_at.is_some() {
+                "synced"
+            } else if behind > 0 {
+                "behind"
+            } else if ahead > 0 {
+                "ahead"
+            } else {
+                "unknown"
+            };
+
+            entries.push(StatusEntry {
+                host: host.label.clone(),
+                repo: repo.full_name,
+                repo_id: repo.id,
+                is_fork: repo.is_fork,
+                branch: repo.default_branch,
+                behind,
+                ahead,
+                last_synced_at: repo.last_synced_at,
+                status,
+                family,
+            });
+        }
+    }
+
+    if let Some(out) = format.structured() {
+        return out.emit(&entries);
+    }
+
     if hosts.is_empty() {
         println!("No hosts registered. Use `gitr host add` to register one.");
         return Ok(());
@@ -28,7 +150,11 @@ pub fn run(args: StatusArgs) -> anyhow::Result<()> {
 
     let mut table = Table::new();
     table.set_header(vec![
-        "HOST / REPO",
+        if args.families {
+            "FAMILY / REPO"
+        } else {
+            "HOST / REPO"
+        },
         "BRANCH",
         "BEHIND",
         "AHEAD",
@@ -37,70 +163,86 @@ pub fn run(args: StatusArgs) -> anyhow::Result<()> {
         "STATUS",
     ]);
 
-    let mut total_synced = 0u32;
-    let mut total_behind = 0u32;
-    let mut total_ahead = 0u32;
-    let total_errors = 0u32;
+    let header = |table: &mut Table, title: String| {
+        let mut row = vec![Cell::new(title).fg(Color::Cyan)];
+        row.extend((0..6).map(|_| Cell::new("")));
+        table.add_row(row);
+    };
+    let add_row = |table: &mut Table, name: String, entry: &StatusEntry| {
+        let last_sync = entry
+            .last_synced_at
+            .map(|dt| dt.format("%H:%M").to_string())
+            .unwrap_or_else(|| "—".to_string());
 
-    for host in &hosts {
-        let repos = gitr_db::ops::list_repos_for_host(&conn, &host.id)?;
+        let status_color = match entry.status {
+            "synced" => Color::Green,
+            "behind" => Color::Yellow,
+            "ahead" => Color::Blue,
+            _ => Color::White,
+        };
+
+        let strategy = if entry.is_fork { "ff" } else { "—" };
 
-        // Host header row
         table.add_row(vec![
-            Cell::new(format!("{} ({})", host.label, host.kind)).fg(Color::Cyan),
-            Cell::new(""),
-            Cell::new(""),
-            Cell::new(""),
-            Cell::new(""),
-            Cell::new(""),
-            Cell::new(""),
+            Cell::new(name),
+            Cell::new(&entry.branch),
+            Cell::new(entry.behind.to_string()),
+            Cell::new(entry.ahead.to_string()),
+            Cell::new(strategy),
+            Cell::new(&last_sync),
+            Cell::new(entry.status).fg(status_color),
         ]);
+    };
 
-        for repo in &repos {
-            let snapshots = gitr_db::ops::get_branch_snapshots(&conn, &repo.id)?;
-            let default_snap = snapshots.iter().find(|s| s.branch == repo.default_branch);
-
-            let behind = default_snap.map(|s| s.behind_count).unwrap_or(0);
-            let ahead = default_snap.map(|s| s.ahead_count).unwrap_or(0);
-
-            let last_sync = repo
-                .last_synced_at
-                .map(|dt| dt.format("%H:%M").to_string())
-                .unwrap_or_else(|| "—".to_string());
-
-            let (status_str, status_color) = if !repo.is_fork {
-                ("tracked", Color::White)
-            } else if behind == 0 && ahead == 0 && repo.last_synced_at.is_some() {
-                total_synced += 1;
-                ("synced", Color::Green)
-            } else if behind > 0 {
-                total_behind += 1;
-                ("behind", Color::Yellow)
-            } else if ahead > 0 {
-                total_ahead += 1;
-                ("ahead", Color::Blue)
-            } else {
-                ("unknown", Color::White)
-            };
-
-            let strategy = if repo.is_fork { "ff" } else { "—" };
-
-            table.add_row(vec![
-                Cell::new(format!("  {}", repo.name)),
-                Cell::new(&repo.default_branch),
-                Cell::new(behind.to_string()),
-                Cell::new(ahead.to_string()),
-                Cell::new(strategy),
-                Cell::new(&last_sync),
-                Cell::new(status_str).fg(status_color),
-            ]);
+    if args.families {
+        // Each family in tree order, root first, forks indented under
+        // their parents, with host labels since families cross hosts.
+        let mut roots: Vec<&Repo> = all
+            .iter()
+            .filter(|r| tree.parent(r).is_none() && tree.has_family(r))
+            .collect();
+        roots.retain(|root| {
+            entries
+                .iter()
+                .any(|e| e.family.as_ref() == Some(&root.full_name))
+        });
+        for root in roots {
+            header(&mut table, format!("{} family", root.full_name));
+            for (depth, member) in std::iter::once((0, root)).chain(tree.descendants(root)) {
+                let Some(entry) = entries.iter().find(|e| e.repo_id == member.id) else {
+                    continue;
+                };
+                let name = format!(
+                    "{:indent$}{} [{}]",
+                    "",
+                    entry.repo,
+                    entry.host,
+                    indent = 2 * (depth + 1)
+                );
+                add_row(&mut table, name, entry);
+            }
+        }
+    } else {
+        for host in &hosts {
+            if members.is_some() && !entries.iter().any(|e| e.host == host.label) {
+                continue;
+            }
+            header(&mut table, format!("{} ({})", host.label, host.kind));
+            for entry in entries.iter().filter(|e| e.host == host.label) {
+                let name = entry.repo.rsplit('/').next().unwrap_or(&entry.repo);
+                add_row(&mut table, format!("  {name}"), entry);
+            }
         }
     }
 
+    let count = |status| entries.iter().filter(|e| e.status == status).count();
     println!("{table}");
     println!(
         "Summary: {} synced | {} behind | {} ahead | {} errors",
-        total_synced, total_behind, total_ahead, total_errors
+        count("synced"),
+        count("behind"),
+        count("ahead"),
+        0
     );
 
     Ok(())
@@ -1,18 +1,32 @@
+use std::collections::HashSet;
 use std::sync::Arc;
 
+use chrono::Utc;
 use clap::Args;
 use gitr_auth::{CredentialStore, KeyringStore};
 use gitr_core::config::GitrConfig;
+use gitr_core::metrics;
+use gitr_core::models::repo::{Repo, RepoId};
 use gitr_core::models::sync_link::MergeStrategy;
-use gitr_core::models::sync_state::SyncStatus;
+use gitr_core::models::sync_state::{SyncRecord, SyncStatus};
+use gitr_discover::lineage::ForkTree;
+use gitr_sync::backend::create_backend;
 use gitr_sync::engine::SyncEngine;
-use gitr_sync::fork_sync;
+use gitr_sync::fork_sync::{self, ForkSyncResult};
+use rusqlite::Connection;
 use tokio::task::JoinSet;
 
+use super::refresh;
+use super::scope::ScopeArgs;
+use crate::output::{Incomplete, OutputFormat, SyncOutcome};
+
 #[derive(Args)]
 pub struct SyncArgs {
     /// Repo name, full name (owner/repo), or "all" to sync all forks
-    target: String,
+    #[arg(required_unless_present_any = ["collection", "filter"])]
+    target: Option<String>,
+    #[command(flatten)]
+    scope: ScopeArgs,
     /// Dry run — show what would be done without making changes
     #[arg(long)]
     dry_run: bool,
@@ -25,7 +39,7 @@ pub struct SyncArgs {
     api: bool,
 }
 
-pub async fn run(args: SyncArgs) -> anyhow::Result<()> {
+pub async fn run(args: SyncArgs, format: OutputFormat) -> anyhow::Result<()> {
     let config = GitrConfig::load()?;
     let db_path = GitrConfig::db_path()?;
     let conn = gitr_db::open_db(&db_path)?;
@@ -37,17 +51,28 @@ pub async fn run(args: SyncArgs) -> anyhow::Result<()> {
         None => config.default_merge_strategy.clone(),
     };
 
+    let backend = create_backend(&config.git_backend);
     let clone_base = GitrConfig::home_dir()?.join("repos");
     std::fs::create_dir_all(&clone_base)?;
 
-    if args.target == "all" {
-        let forks = gitr_db::ops::list_fork_repos(&conn)?;
+    let mut outcomes: Vec<SyncOutcome> = Vec::new();
+    // Syncs that died without a record, e.g. a panicked task.
+    let mut lost = 0;
+
+    let target = args.target.as_deref().unwrap_or("all");
+
+    if target == "all" {
+        let mut forks = gitr_db::ops::list_fork_repos(&conn)?;
+        args.scope.retain(&conn, &mut forks)?;
         if forks.is_empty() {
+            if let Some(out) = format.structured() {
+                return out.emit::<SyncOutcome>(&[]);
+            }
             println!("No forks tracked. Use `gitr scan` to discover repos.");
             return Ok(());
         }
 
-        println!("Syncing {} forks...", forks.len());
+        format.say(format_args!("Syncing {} forks...", forks.len()));
 
         if args.api {
             // ── API sync path ─────────────────────────────────────────────────
@@ -55,166 +80,148 @@ pub async fn run(args: SyncArgs) -> anyhow::Result<()> {
             // fully server-side, no local clone needed.
             let cred_store = KeyringStore::new();
             let sem = Arc::new(tokio::sync::Semaphore::new(10));
-            let mut join_set: JoinSet<anyhow::Result<bool>> = JoinSet::new();
-
-            for fork in forks {
-                let host = match gitr_db::ops::get_host_by_id(&conn, &fork.host_id)? {
-                    Some(h) => h,
-                    None => {
-                        eprintln!("  Skipping {} — host not found", fork.full_name);
+            let mut join_set: JoinSet<(Repo, anyhow::Result<bool>)> = JoinSet::new();
+            let mut submitted = Vec::new();
+            let rounds = chain_rounds(&conn, &forks)?;
+            say_rounds(&rounds, format);
+
+            // A fork of a fork waits for its parent, so the parent's new
+            // commits reach it in the same run.
+            for round in rounds {
+                for fork in round {
+                    let Some(host_id) = &fork.host_id else {
+                        eprintln!("  Skipping {} — not on a registered host", fork.full_name);
                         continue;
-                    }
-                };
-                let token = match cred_store.get(&host.credential_key)? {
-                    Some(t) => t,
-                    None => {
-                        eprintln!("  Skipping {} — no token for host", fork.full_name);
+                    };
+                    let host = match gitr_db::ops::get_host_by_id(&conn, host_id)? {
+                        Some(h) => h,
+                        None => {
+                            eprintln!("  Skipping {} — host not found", fork.full_name);
+                            continue;
+                        }
+                    };
+                    let token = match cred_store.get(&host.credential_key)? {
+                        Some(t) => t,
+                        None => {
+                            eprintln!("  Skipping {} — no token for host", fork.full_name);
+                            continue;
+                        }
+                    };
+
+                    if args.dry_run {
+                        format.say(format_args!(
+                            "  [dry-run] would API-sync {}",
+                            fork.full_name
+                        ));
                         continue;
                     }
-                };
-
-                if args.dry_run {
-                    println!("  [dry-run] would API-sync {}", fork.full_name);
-                    continue;
-                }
 
-                let sem = sem.clone();
-                let owner = fork.owner.clone();
-                let name = fork.name.clone();
-                let branch = fork.default_branch.clone();
-                let api_url = host.api_url.clone();
-                let username = host.username.clone();
-                let kind = host.kind.clone();
-
-                join_set.spawn(async move {
-                    let Ok(_permit) = sem.acquire_owned().await else {
-                        anyhow::bail!("semaphore closed");
-                    };
-                    let provider =
-                        gitr_host::create_provider(&kind, &api_url, &token, &username)
+                    let sem = sem.clone();
+                    let api_url = host.api_url.clone();
+                    let username = host.username.clone();
+                    let kind = host.kind.clone();
+                    let retry = config.retry.clone();
+                    let repo = fork.clone();
+                    submitted.push(fork);
+
+                    join_set.spawn(async move {
+                        let synced = async {
+                            let Ok(_permit) = sem.acquire_owned().await else {
+                                anyhow::bail!("semaphore closed");
+                            };
+                            let provider = gitr_host::create_provider(
+                                &kind, &api_url, &token, &username, &retry,
+                            )
                             .map_err(|e| anyhow::anyhow!("{e}"))?;
-                    let synced = provider
-                        .sync_fork_upstream(&owner, &name, &branch)
-                        .await
-                        .map_err(|e| anyhow::anyhow!("{e}"))?;
-                    Ok(synced)
-                });
-            }
-
-            let (mut synced, mut skipped, mut failed) = (0u32, 0u32, 0u32);
-            while let Some(result) = join_set.join_next().await {
-                match result {
-                    Ok(Ok(true)) => synced += 1,
-                    Ok(Ok(false)) => skipped += 1,
-                    Ok(Err(e)) => {
-                        failed += 1;
-                        eprintln!("  error: {e}");
-                    }
-                    Err(e) => {
-                        failed += 1;
-                        eprintln!("  task error: {e}");
-                    }
+                            provider
+                                .sync_fork_upstream(&repo.owner, &repo.name, &repo.default_branch)
+                                .await
+                                .map_err(|e| anyhow::anyhow!("{e}"))
+                        }
+                        .await;
+                        (repo, synced)
+                    });
                 }
-            }
 
-            println!("\nAPI sync complete: {synced} synced | {skipped} skipped/diverged | {failed} failed");
-            return Ok(());
-        }
-
-        // ── Git sync path (local clone) ───────────────────────────────────────
-        let cred_store = KeyringStore::new();
-        let mut repo_pairs = Vec::new();
-
-        for fork in &forks {
-            let upstream_url = match &fork.upstream_clone_url {
-                // Fast path: URL already stored in DB from scan
-                Some(url) => url.clone(),
-                None => match &fork.upstream_full_name {
-                    Some(upstream_name) => {
-                        // Fall back to an API call to resolve the clone URL
-                        let host = gitr_db::ops::get_host_by_id(&conn, &fork.host_id)?;
-                        if let Some(host) = host {
-                            let token = cred_store.get(&host.credential_key)?;
-                            if let Some(token) = token {
-                                let parts: Vec<&str> = upstream_name.splitn(2, '/').collect();
-                                if parts.len() == 2 {
-                                    let provider = gitr_host::create_provider(
-                                        &host.kind,
-                                        &host.api_url,
-                                        &token,
-                                        &host.username,
-                                    )?;
-                                    match provider.get_repo(parts[0], parts[1]).await? {
-                                        Some(r) => r.clone_url,
-                                        None => format!("https://github.com/{upstream_name}.git"),
-                                    }
-                                } else {
-                                    format!("https://github.com/{upstream_name}.git")
-                                }
-                            } else {
-                                format!("https://github.com/{upstream_name}.git")
+                while let Some(result) = join_set.join_next().await {
+                    match result {
+                        Ok((repo, synced)) => {
+                            let outcome = api_outcome(&repo, synced);
+                            if let Some(err) = outcome.record.errors.first() {
+                                eprintln!("  error: {}: {err}", repo.full_name);
                             }
-                        } else {
-                            format!("https://github.com/{upstream_name}.git")
+                            outcomes.push(outcome);
+                        }
+                        Err(e) => {
+                            lost += 1;
+                            eprintln!("  task error: {e}");
                         }
                     }
-                    None => {
-                        println!("  Skipping {} — no upstream known", fork.full_name);
-                        continue;
-                    }
-                },
-            };
-            repo_pairs.push((fork.clone(), upstream_url));
-        }
-
-        let engine = SyncEngine::new(config.sync_concurrency);
-        let results = engine
-            .sync_all_forks(repo_pairs, &clone_base, &strategy, args.dry_run)
-            .await;
-
-        let success = results
-            .iter()
-            .filter(|r| r.record.status == SyncStatus::Success)
-            .count();
-        let failed = results
-            .iter()
-            .filter(|r| r.record.status == SyncStatus::Failed)
-            .count();
-        let skipped = results
-            .iter()
-            .filter(|r| r.record.status == SyncStatus::Skipped)
-            .count();
-
-        println!("\nSync complete: {success} synced | {failed} failed | {skipped} skipped");
-
-        if !args.dry_run {
-            for result in &results {
-                gitr_db::ops::insert_sync_record(&conn, &result.record)?;
-                if result.record.status == SyncStatus::Success {
-                    gitr_db::ops::update_repo_last_synced(
-                        &conn,
-                        &result.record.repo_id,
-                        &result.record.finished_at,
-                    )?;
                 }
             }
-        }
+            save_records(&conn, outcomes.iter().map(|o| &o.record))?;
+
+            let count = |status| outcomes.iter().filter(|o| o.record.status == status).count();
+            format.say(format_args!(
+                "\nAPI sync complete: {} synced | {} skipped/diverged | {} failed",
+                count(SyncStatus::Success),
+                count(SyncStatus::Skipped),
+                count(SyncStatus::Failed) + lost
+            ));
+            refresh_after_sync(&conn, &config, submitted, true, format).await?;
+        } else {
+            // ── Git sync path (local clone) ───────────────────────────────────
+            let rounds = chain_rounds(&conn, &forks)?;
+            say_rounds(&rounds, format);
+
+            let engine = SyncEngine::new(config.sync_concurrency, config.retry.clone())
+                .with_backend(backend.clone())
+                .with_hooks(config.hooks.clone());
+            let mut results = Vec::new();
+            for round in &rounds {
+                let repo_pairs = upstream_pairs(&conn, &config, round).await?;
+                results.extend(
+                    engine
+                        .sync_all_forks(repo_pairs, &clone_base, &strategy, args.dry_run)
+                        .await,
+                );
+            }
 
-        for result in &results {
-            if !result.record.errors.is_empty() {
-                println!("\nErrors for {}:", result.repo_full_name);
-                for err in &result.record.errors {
-                    println!("  {err}");
+            let count = |status| results.iter().filter(|r| r.record.status == status).count();
+            format.say(format_args!(
+                "\nSync complete: {} synced | {} failed | {} skipped",
+                count(SyncStatus::Success),
+                count(SyncStatus::Failed),
+                count(SyncStatus::Skipped)
+            ));
+
+            if !args.dry_run {
+                save_results(&conn, &results)?;
+
+                let synced: Vec<Repo> = forks
+                    .into_iter()
+                    .filter(|f| {
+                        results.iter().any(|r| {
+                            r.record.repo_id == f.id && r.record.status == SyncStatus::Success
+                        })
+                    })
+                    .collect();
+                refresh_after_sync(&conn, &config, synced, false, format).await?;
+            }
+
+            for result in &results {
+                if !result.record.errors.is_empty() {
+                    format.say(format_args!("\nErrors for {}:", result.repo_full_name));
+                    for err in &result.record.errors {
+                        format.say(format_args!("  {err}"));
+                    }
                 }
             }
+            outcomes.extend(results.into_iter().map(SyncOutcome::from));
         }
     } else {
         // ── Single repo sync ──────────────────────────────────────────────────
-        let repos = gitr_db::ops::list_repos(&conn)?;
-        let repo = repos
-            .iter()
-            .find(|r| r.full_name == args.target || r.name == args.target)
-            .ok_or_else(|| anyhow::anyhow!("Repo '{}' not found", args.target))?;
+        let repo = &args.scope.find_repo(&conn, target)?;
 
         if !repo.is_fork {
             anyhow::bail!("{} is not a fork", repo.full_name);
@@ -225,81 +232,274 @@ pub async fn run(args: SyncArgs) -> anyhow::Result<()> {
             .as_ref()
             .ok_or_else(|| anyhow::anyhow!("No upstream known for {}", repo.full_name))?;
 
-        println!("Syncing {} (strategy: {strategy})...", repo.full_name);
+        let all = gitr_db::ops::list_repos(&conn)?;
+        let tree = ForkTree::new(&all);
+        let parent = tree.parent(repo);
+
+        format.say(format_args!("Syncing {} (strategy: {strategy})...", repo.full_name));
         if args.dry_run {
-            println!("  (dry run)");
+            format.say("  (dry run)");
+        }
+        if let Some(grandparent) = parent.and_then(|p| tree.parent(p)) {
+            format.say(format_args!(
+                "  {upstream_name} is itself a fork of {}; sync it first to pass on its changes",
+                grandparent.full_name
+            ));
         }
 
         if args.api {
             // API-based single-repo sync
-            let host = gitr_db::ops::get_host_by_id(&conn, &repo.host_id)?
+            let host_id = repo.host_id.as_ref().ok_or_else(|| {
+                anyhow::anyhow!("{} is not on a registered host; sync it without --api", repo.full_name)
+            })?;
+            let host = gitr_db::ops::get_host_by_id(&conn, host_id)?
                 .ok_or_else(|| anyhow::anyhow!("Host not found for {}", repo.full_name))?;
             let cred_store = KeyringStore::new();
             let token = cred_store
                 .get(&host.credential_key)?
                 .ok_or_else(|| anyhow::anyhow!("No token for host '{}'", host.label))?;
             let provider =
-                gitr_host::create_provider(&host.kind, &host.api_url, &token, &host.username)?;
+                gitr_host::create_provider(&host.kind, &host.api_url, &token, &host.username, &config.retry)?;
 
             if args.dry_run {
-                println!("  [dry-run] would API-sync {}", repo.full_name);
+                format.say(format_args!("  [dry-run] would API-sync {}", repo.full_name));
                 return Ok(());
             }
 
             let synced = provider
                 .sync_fork_upstream(&repo.owner, &repo.name, &repo.default_branch)
                 .await
-                .map_err(|e| anyhow::anyhow!("{e}"))?;
+                .map_err(|e| anyhow::anyhow!("{e}"));
+            let outcome = api_outcome(repo, synced);
+            save_records(&conn, [&outcome.record])?;
+            match outcome.record.status {
+                SyncStatus::Success => {
+                    format.say(format_args!("  API-synced {} ← {upstream_name}", repo.full_name));
+                    refresh_after_sync(&conn, &config, vec![repo.clone()], true, format).await?;
+                }
+                SyncStatus::Failed => {
+                    format.say("  Failed:");
+                    for err in &outcome.record.errors {
+                        format.say(format_args!("    {err}"));
+                    }
+                }
+                _ => format.say(format_args!(
+                    "  Skipped {} — already up-to-date or diverged",
+                    repo.full_name
+                )),
+            }
+            outcomes.push(outcome);
+        } else {
+            // Git-based single-repo sync
+            let upstream_url = match (&repo.upstream_clone_url, parent) {
+                (Some(url), _) => url.clone(),
+                (None, Some(parent)) => parent.clone_url.clone(),
+                (None, None) => format!("https://github.com/{upstream_name}.git"),
+            };
 
-            if synced {
-                println!("  API-synced {} ← {upstream_name}", repo.full_name);
-            } else {
-                println!("  Skipped {} — already up-to-date or diverged", repo.full_name);
+            let hooks = config.hooks.for_repo(&repo.full_name);
+            let opts = fork_sync::ForkSyncOptions {
+                strategy: &strategy,
+                retry: &config.retry,
+                hooks: &hooks,
+                upstream_remote: "upstream",
+                dry_run: args.dry_run,
+            };
+            let result = fork_sync::sync_fork(
+                repo,
+                &upstream_url,
+                &clone_base,
+                backend.as_ref(),
+                &opts,
+            );
+            if !args.dry_run {
+                save_results(&conn, std::slice::from_ref(&result))?;
             }
-            return Ok(());
+
+            match result.record.status {
+                SyncStatus::Success => {
+                    format.say(format_args!(
+                        "  Synced: {} commits transferred on {}",
+                        result.record.commits_transferred, repo.default_branch
+                    ));
+                    if !args.dry_run {
+                        refresh_after_sync(&conn, &config, vec![repo.clone()], false, format)
+                            .await?;
+                    }
+                }
+                SyncStatus::Skipped => {
+                    format.say(format_args!(
+                        "  [dry-run] {} commits behind on {}",
+                        result.record.commits_transferred, repo.default_branch
+                    ));
+                }
+                SyncStatus::Failed => {
+                    format.say("  Failed:");
+                    for err in &result.record.errors {
+                        format.say(format_args!("    {err}"));
+                    }
+                }
+                SyncStatus::PartialSuccess => {
+                    format.say("  Partial success");
+                }
+            }
+            outcomes.push(result.into());
         }
+    }
 
-        // Git-based single-repo sync
-        let upstream_url = match &repo.upstream_clone_url {
-            Some(url) => url.clone(),
-            None => format!("https://github.com/{upstream_name}.git"),
-        };
+    if let Some(out) = format.structured() {
+        out.emit(&outcomes)?;
+    }
+    let lost = std::iter::repeat_n(&SyncStatus::Failed, lost);
+    Incomplete::check(outcomes.iter().map(|o| &o.record.status).chain(lost))?;
+    Ok(())
+}
 
-        let result =
-            fork_sync::sync_fork(repo, &upstream_url, &clone_base, &strategy, args.dry_run);
+/// Split forks into sync rounds so that each fork comes after any fork it
+/// descends from that is also being synced: round `n` holds the forks with
+/// `n` such ancestors.
+fn chain_rounds(conn: &Connection, forks: &[Repo]) -> anyhow::Result<Vec<Vec<Repo>>> {
+    let all = gitr_db::ops::list_repos(conn)?;
+    let tree = ForkTree::new(&all);
+    let syncing: HashSet<&RepoId> = forks.iter().map(|f| &f.id).collect();
+    let mut rounds: Vec<Vec<Repo>> = Vec::new();
+    for fork in forks {
+        let round = tree
+            .ancestors(fork)
+            .iter()
+            .filter(|a| syncing.contains(&a.id))
+            .count();
+        if rounds.len() <= round {
+            rounds.resize_with(round + 1, Vec::new);
+        }
+        rounds[round].push(fork.clone());
+    }
+    Ok(rounds)
+}
 
-        match result.record.status {
-            SyncStatus::Success => {
-                println!(
-                    "  Synced: {} commits transferred on {}",
-                    result.record.commits_transferred, repo.default_branch
-                );
-                if !args.dry_run {
-                    gitr_db::ops::insert_sync_record(&conn, &result.record)?;
-                    gitr_db::ops::update_repo_last_synced(
-                        &conn,
-                        &result.record.repo_id,
-                        &result.record.finished_at,
-                    )?;
+fn say_rounds(rounds: &[Vec<Repo>], format: OutputFormat) {
+    if rounds.len() > 1 {
+        let chained: usize = rounds[1..].iter().map(Vec::len).sum();
+        format.say(format_args!(
+            "  Forks of forks: {chained}; syncing in {} rounds, parents first",
+            rounds.len()
+        ));
+    }
+}
+
+/// Record the result of a server-side merge-upstream call: `Ok(false)` means
+/// the fork was already current or has diverged.
+fn api_outcome(repo: &Repo, synced: anyhow::Result<bool>) -> SyncOutcome {
+    let mut record = SyncRecord::new(repo.id.clone());
+    record.status = match synced {
+        Ok(true) => SyncStatus::Success,
+        Ok(false) => SyncStatus::Skipped,
+        Err(e) => {
+            record.errors.push(e.to_string());
+            SyncStatus::Failed
+        }
+    };
+    record.finished_at = Utc::now();
+    metrics::record_sync(&repo.full_name, &record.status, 0);
+    SyncOutcome {
+        repo: repo.full_name.clone(),
+        record,
+    }
+}
+
+/// Update branch snapshots for just-synced repos so `gitr status` reflects
+/// the new state. `fetch` is off after a local sync, whose clone is already
+/// current.
+pub(crate) async fn refresh_after_sync(
+    conn: &Connection,
+    config: &GitrConfig,
+    repos: Vec<Repo>,
+    fetch: bool,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    if repos.is_empty() {
+        return Ok(());
+    }
+    format.say("\nRefreshing branch snapshots...");
+    let results = refresh::refresh_repos(conn, config, repos, fetch).await?;
+    refresh::print_summary(&results, format);
+    Ok(())
+}
+
+/// Pair each fork with its upstream clone URL, resolving it through the host
+/// API when the scan didn't record one. Forks with no known upstream are skipped.
+pub(crate) async fn upstream_pairs(
+    conn: &Connection,
+    config: &GitrConfig,
+    forks: &[Repo],
+) -> anyhow::Result<Vec<(Repo, String)>> {
+    let cred_store = KeyringStore::new();
+    let mut repo_pairs = Vec::new();
+
+    for fork in forks {
+        let upstream_url = match &fork.upstream_clone_url {
+            // Fast path: URL already stored in DB from scan
+            Some(url) => url.clone(),
+            None => match &fork.upstream_full_name {
+                Some(upstream_name) => {
+                    // Fall back to an API call to resolve the clone URL
+                    let host = match &fork.host_id {
+                        Some(host_id) => gitr_db::ops::get_host_by_id(conn, host_id)?,
+                        None => None,
+                    };
+                    if let Some(host) = host {
+                        let token = cred_store.get(&host.credential_key)?;
+                        if let Some(token) = token {
+                            let parts: Vec<&str> = upstream_name.splitn(2, '/').collect();
+                            if parts.len() == 2 {
+                                let provider = gitr_host::create_provider(
+                                    &host.kind,
+                                    &host.api_url,
+                                    &token,
+                                    &host.username,
+                                    &config.retry,
+                                )?;
+                                match provider.get_repo(parts[0], parts[1]).await? {
+                                    Some(r) => r.clone_url,
+                                    None => format!("https://github.com/{upstream_name}.git"),
+                                }
+                            } else {
+                                format!("https://github.com/{upstream_name}.git")
+                            }
+                        } else {
+                            format!("https://github.com/{upstream_name}.git")
+                        }
+                    } else {
+                        format!("https://github.com/{upstream_name}.git")
+                    }
                 }
-            }
-            SyncStatus::Skipped => {
-                println!(
-                    "  [dry-run] {} commits behind on {}",
-                    result.record.commits_transferred, repo.default_branch
-                );
-            }
-            SyncStatus::Failed => {
-                println!("  Failed:");
-                for err in &result.record.errors {
-                    println!("    {err}");
+                None => {
+                    eprintln!("  Skipping {} — no upstream known", fork.full_name);
+                    continue;
                 }
-            }
-            SyncStatus::PartialSuccess => {
-                println!("  Partial success");
-            }
-        }
+            },
+        };
+        repo_pairs.push((fork.clone(), upstream_url));
     }
 
+    Ok(repo_pairs)
+}
+
+/// Store sync records and bump last-synced times for successful syncs.
+pub(crate) fn save_results(conn: &Connection, results: &[ForkSyncResult]) -> anyhow::Result<()> {
+    save_records(conn, results.iter().map(|r| &r.record))
+}
+
+/// [`save_results`] for bare records, such as those of API syncs.
+fn save_records<'a>(
+    conn: &Connection,
+    records: impl IntoIterator<Item = &'a SyncRecord>,
+) -> anyhow::Result<()> {
+    for record in records {
+        gitr_db::ops::insert_sync_record(conn, record)?;
+        if record.status == SyncStatus::Success {
+            gitr_db::ops::update_repo_last_synced(conn, &record.repo_id, &record.finished_at)?;
+        }
+    }
     Ok(())
 }
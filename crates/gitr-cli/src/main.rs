@@ -1,4 +1,9 @@
 mod commands;
+mod metrics;
+mod output;
+mod tui;
+
+use std::process::ExitCode;
 
 use clap::Parser;
 
@@ -7,14 +12,55 @@ use clap::Parser;
 struct Cli {
     #[command(subcommand)]
     command: commands::Command,
+    /// Output format for command results
+    #[arg(long, global = true, value_enum, default_value_t)]
+    format: output::OutputFormat,
 }
 
 #[tokio::main]
-async fn main() -> anyhow::Result<()> {
-    tracing_subscriber::fmt()
-        .with_writer(std::io::stderr)
-        .init();
-
+async fn main() -> ExitCode {
     let cli = Cli::parse();
-    commands::run(cli.command).await
+
+    // The dashboard owns the terminal; log lines would corrupt it.
+    let dashboard = matches!(&cli.command, commands::Command::Status(args) if args.watch);
+    if dashboard {
+        tracing_subscriber::fmt().with_writer(std::io::sink).init();
+    } else {
+        tracing_subscriber::fmt()
+            .with_writer(std::io::stderr)
+            .init();
+    }
+    let exports_metrics = matches!(
+        cli.command,
+        commands::Command::Sync(_) | commands::Command::Refresh(_) | commands::Command::Scan(_)
+    );
+    let sends_alerts = matches!(
+        cli.command,
+        commands::Command::Sync(_) | commands::Command::Refresh(_)
+    );
+    let result = commands::run(cli.command, cli.format).await;
+    if sends_alerts {
+        if let Err(e) = commands::notify::send_configured_alerts().await {
+            tracing::warn!("could not send notifications: {e}");
+        }
+    }
+    if exports_metrics {
+        if let Err(e) = metrics::export_textfile() {
+            tracing::warn!("could not write metrics textfile: {e}");
+        }
+    }
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => match e.downcast_ref::<output::Incomplete>() {
+            Some(incomplete) => {
+                eprintln!("Error: {incomplete}");
+                ExitCode::from(incomplete.exit_code())
+            }
+            None => {
+                eprintln!("Error: {e:?}");
+                ExitCode::FAILURE
+            }
+        },
+    }
 }
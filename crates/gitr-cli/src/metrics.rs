@@ -0,0 +1,26 @@
+use gitr_core::config::GitrConfig;
+use gitr_core::metrics;
+use rusqlite::Connection;
+
+/// Set behind gauges for every fork from its stored branch snapshots, so an
+/// export covers all repos rather than only those this process refreshed.
+pub fn load_behind_gauges(conn: &Connection) -> anyhow::Result<()> {
+    for fork in gitr_db::ops::list_fork_repos(conn)? {
+        for snap in gitr_db::ops::get_branch_snapshots(conn, &fork.id)? {
+            metrics::set_behind(&fork.full_name, &snap.branch, snap.behind_count);
+        }
+    }
+    Ok(())
+}
+
+/// Write the node_exporter textfile, if one is configured.
+pub fn export_textfile() -> anyhow::Result<()> {
+    let config = GitrConfig::load()?;
+    let Some(path) = &config.metrics.textfile else {
+        return Ok(());
+    };
+    let conn = gitr_db::open_db(&GitrConfig::db_path()?)?;
+    load_behind_gauges(&conn)?;
+    metrics::write_textfile(path)?;
+    Ok(())
+}
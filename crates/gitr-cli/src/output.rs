@@ -0,0 +1,464 @@
+//! `--format` handling and exit codes shared by all commands.
+
+use std::io::Write;
+
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use gitr_core::models::host::Host;
+use gitr_core::models::repo::Repo;
+use gitr_core::models::sync_link::SyncLink;
+use gitr_core::models::sync_state::{BranchSnapshot, SyncRecord, SyncStatus};
+use gitr_core::notify::Alert;
+use gitr_sync::clone::{CloneResult, CloneTarget};
+use gitr_sync::fork_sync::ForkSyncResult;
+use gitr_sync::refresh::RefreshResult;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable tables and messages
+    #[default]
+    Table,
+    Json,
+    Yaml,
+    Csv,
+}
+
+/// The machine-readable formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Structured {
+    Json,
+    Yaml,
+    Csv,
+}
+
+impl OutputFormat {
+    pub fn structured(self) -> Option<Structured> {
+        match self {
+            OutputFormat::Table => None,
+            OutputFormat::Json => Some(Structured::Json),
+            OutputFormat::Yaml => Some(Structured::Yaml),
+            OutputFormat::Csv => Some(Structured::Csv),
+        }
+    }
+
+    /// Print a progress or summary line meant for people. With a structured
+    /// format it goes to stderr so stdout stays parseable.
+    pub fn say(self, line: impl std::fmt::Display) {
+        if self == OutputFormat::Table {
+            println!("{line}");
+        } else {
+            eprintln!("{line}");
+        }
+    }
+}
+
+impl Structured {
+    /// Write `items` to stdout.
+    pub fn emit<T: Serialize + CsvRow>(self, items: &[T]) -> anyhow::Result<()> {
+        let mut stdout = std::io::stdout().lock();
+        match self {
+            Structured::Json => {
+                serde_json::to_writer_pretty(&mut stdout, items)?;
+                writeln!(stdout)?;
+            }
+            Structured::Yaml => serde_yaml::to_writer(&mut stdout, items)?,
+            Structured::Csv => {
+                let mut writer = csv::Writer::from_writer(stdout);
+                writer.write_record(T::HEADERS)?;
+                for item in items {
+                    writer.write_record(item.fields())?;
+                }
+                writer.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write a single record: an object in JSON/YAML, one row in CSV.
+    pub fn emit_one<T: Serialize + CsvRow>(self, item: &T) -> anyhow::Result<()> {
+        match self {
+            Structured::Csv => self.emit(std::slice::from_ref(item)),
+            _ => self.emit_value(item),
+        }
+    }
+
+    /// Write a nested value with no flat form, such as the config.
+    pub fn emit_value<T: Serialize>(self, value: &T) -> anyhow::Result<()> {
+        let mut stdout = std::io::stdout().lock();
+        match self {
+            Structured::Json => {
+                serde_json::to_writer_pretty(&mut stdout, value)?;
+                writeln!(stdout)?;
+            }
+            Structured::Yaml => serde_yaml::to_writer(&mut stdout, value)?,
+            Structured::Csv => {
+                anyhow::bail!("CSV output isn't available for this command; use json or yaml")
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A record's flat form for `--format csv`. Lists are joined with `;`.
+pub trait CsvRow {
+    const HEADERS: &'static [&'static str];
+
+    fn fields(&self) -> Vec<String>;
+}
+
+fn opt<T: ToString>(value: &Option<T>) -> String {
+    value.as_ref().map(ToString::to_string).unwrap_or_default()
+}
+
+fn time(value: &DateTime<Utc>) -> String {
+    value.to_rfc3339()
+}
+
+impl CsvRow for Host {
+    const HEADERS: &'static [&'static str] = &["id", "label", "kind", "api_url", "username"];
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.id.to_string(),
+            self.label.clone(),
+            self.kind.to_string(),
+            self.api_url.to_string(),
+            self.username.clone(),
+        ]
+    }
+}
+
+impl CsvRow for Repo {
+    const HEADERS: &'static [&'static str] = &[
+        "id",
+        "full_name",
+        "host_id",
+        "clone_url",
+        "local_path",
+        "is_fork",
+        "is_private",
+        "is_archived",
+        "language",
+        "upstream_full_name",
+        "upstream_clone_url",
+        "default_branch",
+        "discovery_source",
+        "last_synced_at",
+        "description",
+        "gone_at",
+    ];
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.id.to_string(),
+            self.full_name.clone(),
+            opt(&self.host_id.as_ref().map(ToString::to_string)),
+            self.clone_url.clone(),
+            opt(&self.local_path.as_ref().map(|p| p.display().to_string())),
+            self.is_fork.to_string(),
+            self.is_private.to_string(),
+            self.is_archived.to_string(),
+            opt(&self.language),
+            opt(&self.upstream_full_name),
+            opt(&self.upstream_clone_url),
+            self.default_branch.clone(),
+            self.discovery_source.to_string(),
+            opt(&self.last_synced_at.as_ref().map(time)),
+            opt(&self.description),
+            opt(&self.gone_at.as_ref().map(time)),
+        ]
+    }
+}
+
+impl CsvRow for BranchSnapshot {
+    const HEADERS: &'static [&'static str] = &[
+        "repo_id",
+        "branch",
+        "local_sha",
+        "remote_sha",
+        "upstream_sha",
+        "behind",
+        "ahead",
+        "updated_at",
+    ];
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.repo_id.to_string(),
+            self.branch.clone(),
+            opt(&self.local_sha),
+            opt(&self.remote_sha),
+            opt(&self.upstream_sha),
+            self.behind_count.to_string(),
+            self.ahead_count.to_string(),
+            time(&self.updated_at),
+        ]
+    }
+}
+
+impl CsvRow for SyncLink {
+    const HEADERS: &'static [&'static str] = &[
+        "id",
+        "source_repo_id",
+        "target_repo_id",
+        "direction",
+        "merge_strategy",
+        "trigger",
+        "enabled",
+    ];
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.id.to_string(),
+            self.source_repo_id.to_string(),
+            self.target_repo_id.to_string(),
+            self.direction.to_string(),
+            self.merge_strategy.to_string(),
+            self.trigger.to_string(),
+            self.enabled.to_string(),
+        ]
+    }
+}
+
+impl CsvRow for Alert {
+    const HEADERS: &'static [&'static str] = &["rule", "repo", "value", "message", "fired_at"];
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.rule.clone(),
+            self.repo.clone(),
+            self.value.to_string(),
+            self.message.clone(),
+            time(&self.fired_at),
+        ]
+    }
+}
+
+/// A sync record with the name of its repo; the shape of `sync` and
+/// `history` output.
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncOutcome {
+    pub repo: String,
+    pub record: SyncRecord,
+}
+
+impl From<ForkSyncResult> for SyncOutcome {
+    fn from(result: ForkSyncResult) -> Self {
+        Self {
+            repo: result.repo_full_name,
+            record: result.record,
+        }
+    }
+}
+
+impl CsvRow for SyncOutcome {
+    const HEADERS: &'static [&'static str] = &[
+        "id",
+        "repo",
+        "repo_id",
+        "status",
+        "branches_synced",
+        "branches_failed",
+        "commits_transferred",
+        "attempts",
+        "errors",
+        "started_at",
+        "finished_at",
+    ];
+
+    fn fields(&self) -> Vec<String> {
+        let r = &self.record;
+        vec![
+            r.id.to_string(),
+            self.repo.clone(),
+            r.repo_id.to_string(),
+            r.status.to_string(),
+            r.branches_synced.to_string(),
+            r.branches_failed.to_string(),
+            r.commits_transferred.to_string(),
+            r.attempts.to_string(),
+            r.errors.join("; "),
+            time(&r.started_at),
+            time(&r.finished_at),
+        ]
+    }
+}
+
+/// The result of refreshing one repo; the shape of `refresh` output.
+#[derive(Debug, Clone, Serialize)]
+pub struct RefreshOutcome {
+    pub repo: String,
+    pub snapshots: Vec<BranchSnapshot>,
+    pub error: Option<String>,
+}
+
+impl From<RefreshResult> for RefreshOutcome {
+    fn from(result: RefreshResult) -> Self {
+        Self {
+            repo: result.repo_full_name,
+            snapshots: result.snapshots,
+            error: result.error,
+        }
+    }
+}
+
+impl CsvRow for RefreshOutcome {
+    const HEADERS: &'static [&'static str] = &["repo", "branches", "behind", "ahead", "error"];
+
+    fn fields(&self) -> Vec<String> {
+        let join = |f: fn(&BranchSnapshot) -> String| {
+            self.snapshots.iter().map(f).collect::<Vec<_>>().join(";")
+        };
+        vec![
+            self.repo.clone(),
+            join(|s| s.branch.clone()),
+            join(|s| s.behind_count.to_string()),
+            join(|s| s.ahead_count.to_string()),
+            opt(&self.error),
+        ]
+    }
+}
+
+/// Where one repo was (or would be) cloned; the shape of `clone` output.
+#[derive(Debug, Clone, Serialize)]
+pub struct CloneOutcome {
+    pub repo: String,
+    pub path: std::path::PathBuf,
+    /// `cloned`, `existing` or `failed`; `planned` in a dry run.
+    pub status: String,
+    pub error: Option<String>,
+}
+
+impl CloneOutcome {
+    /// A clone a dry run would make.
+    pub fn planned(target: &CloneTarget) -> Self {
+        Self {
+            repo: target.repo.full_name.clone(),
+            path: target.dest.clone(),
+            status: "planned".to_string(),
+            error: None,
+        }
+    }
+}
+
+impl From<CloneResult> for CloneOutcome {
+    fn from(result: CloneResult) -> Self {
+        Self {
+            repo: result.repo_full_name,
+            path: result.dest,
+            status: result.status.to_string(),
+            error: result.error,
+        }
+    }
+}
+
+impl CsvRow for CloneOutcome {
+    const HEADERS: &'static [&'static str] = &["repo", "path", "status", "error"];
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.repo.clone(),
+            self.path.display().to_string(),
+            self.status.clone(),
+            opt(&self.error),
+        ]
+    }
+}
+
+/// A clone `repo relocate` moved or would move.
+#[derive(Debug, Clone, Serialize)]
+pub struct RelocateOutcome {
+    pub repo: String,
+    pub from: std::path::PathBuf,
+    pub to: std::path::PathBuf,
+    /// `moved`, `skipped` or `failed`; `planned` in a dry run.
+    pub status: String,
+    /// Why the move was skipped or failed.
+    pub error: Option<String>,
+}
+
+impl CsvRow for RelocateOutcome {
+    const HEADERS: &'static [&'static str] = &["repo", "from", "to", "status", "error"];
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.repo.clone(),
+            self.from.display().to_string(),
+            self.to.display().to_string(),
+            self.status.clone(),
+            opt(&self.error),
+        ]
+    }
+}
+
+/// A fork `forks prune` archived, deleted, or would have.
+#[derive(Debug, Clone, Serialize)]
+pub struct PruneOutcome {
+    pub repo: String,
+    /// The fork's audit class, e.g. `behind-only`.
+    pub class: String,
+    /// `archive` or `delete`.
+    pub action: String,
+    /// `archived`, `deleted` or `failed`; `planned` in a dry run.
+    pub status: String,
+    /// Why the host refused.
+    pub error: Option<String>,
+}
+
+impl CsvRow for PruneOutcome {
+    const HEADERS: &'static [&'static str] = &["repo", "class", "action", "status", "error"];
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.repo.clone(),
+            self.class.clone(),
+            self.action.clone(),
+            self.status.clone(),
+            opt(&self.error),
+        ]
+    }
+}
+
+/// Exit code when at least one repo failed to sync or refresh.
+pub const EXIT_FAILED: u8 = 3;
+/// Exit code when nothing failed outright but some syncs only partly succeeded.
+pub const EXIT_PARTIAL: u8 = 4;
+
+/// A run that finished but not cleanly. `main` maps it to an exit code
+/// after the command has printed its results.
+#[derive(Debug, thiserror::Error)]
+pub enum Incomplete {
+    #[error("{0} repo(s) failed")]
+    Failed(usize),
+    #[error("{0} repo(s) only partially synced")]
+    Partial(usize),
+}
+
+impl Incomplete {
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Incomplete::Failed(_) => EXIT_FAILED,
+            Incomplete::Partial(_) => EXIT_PARTIAL,
+        }
+    }
+
+    /// Check the statuses of a sync run: failures win over partial successes.
+    pub fn check<'a>(statuses: impl IntoIterator<Item = &'a SyncStatus>) -> Result<(), Self> {
+        let (mut failed, mut partial) = (0, 0);
+        for status in statuses {
+            match status {
+                SyncStatus::Failed => failed += 1,
+                SyncStatus::PartialSuccess => partial += 1,
+                SyncStatus::Success | SyncStatus::Skipped => {}
+            }
+        }
+        if failed > 0 {
+            Err(Incomplete::Failed(failed))
+        } else if partial > 0 {
+            Err(Incomplete::Partial(partial))
+        } else {
+            Ok(())
+        }
+    }
+}
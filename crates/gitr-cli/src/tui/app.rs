@@ -0,0 +1,450 @@
+use std::collections::HashMap;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use gitr_core::models::host::Host;
+use gitr_core::models::repo::{Repo, RepoId};
+use gitr_core::models::sync_state::{BranchSnapshot, SyncRecord, SyncStatus};
+use gitr_sync::engine::SyncEvent;
+use ratatui::widgets::{ListState, TableState};
+use rusqlite::Connection;
+
+/// How many sync records the detail view shows.
+const DETAIL_HISTORY: u32 = 20;
+
+pub struct HostEntry {
+    pub host: Host,
+    pub repos: usize,
+}
+
+/// A repo as shown in the table: its default-branch snapshot and last sync.
+pub struct RepoRow {
+    pub repo: Repo,
+    pub behind: u32,
+    pub ahead: u32,
+    pub last_status: Option<SyncStatus>,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepoFilter {
+    All,
+    Forks,
+    Behind,
+    Failed,
+}
+
+impl RepoFilter {
+    fn next(self) -> Self {
+        match self {
+            RepoFilter::All => RepoFilter::Forks,
+            RepoFilter::Forks => RepoFilter::Behind,
+            RepoFilter::Behind => RepoFilter::Failed,
+            RepoFilter::Failed => RepoFilter::All,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            RepoFilter::All => "all",
+            RepoFilter::Forks => "forks",
+            RepoFilter::Behind => "behind",
+            RepoFilter::Failed => "failed",
+        }
+    }
+
+    fn matches(self, row: &RepoRow) -> bool {
+        match self {
+            RepoFilter::All => true,
+            RepoFilter::Forks => row.repo.is_fork,
+            RepoFilter::Behind => row.behind > 0,
+            RepoFilter::Failed => row.last_status == Some(SyncStatus::Failed),
+        }
+    }
+}
+
+/// Work the background worker has queued or is running for a repo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Activity {
+    Queued,
+    Syncing,
+    Refreshing,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Focus {
+    Hosts,
+    Repos,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Normal,
+    Search,
+    Detail,
+}
+
+/// Everything the detail view shows for one repo.
+pub struct Detail {
+    pub repo: Repo,
+    pub host_label: String,
+    pub snapshots: Vec<BranchSnapshot>,
+    pub history: Vec<SyncRecord>,
+    pub scroll: u16,
+}
+
+/// What the event loop should do after a key press.
+pub enum Action {
+    Quit,
+    Detail(RepoId),
+    Sync(Vec<Repo>),
+    Refresh(Vec<Repo>),
+    Open(String),
+}
+
+pub struct App {
+    pub hosts: Vec<HostEntry>,
+    pub rows: Vec<RepoRow>,
+    pub activity: HashMap<RepoId, Activity>,
+    /// Index 0 is "all hosts"; host `i` is at `i + 1`.
+    pub host_state: ListState,
+    pub repo_state: TableState,
+    pub focus: Focus,
+    pub mode: Mode,
+    pub filter: RepoFilter,
+    pub search: String,
+    pub detail: Option<Detail>,
+    pub message: Option<String>,
+}
+
+impl App {
+    pub fn new() -> Self {
+        Self {
+            hosts: Vec::new(),
+            rows: Vec::new(),
+            activity: HashMap::new(),
+            host_state: ListState::default().with_selected(Some(0)),
+            repo_state: TableState::default(),
+            focus: Focus::Repos,
+            mode: Mode::Normal,
+            filter: RepoFilter::All,
+            search: String::new(),
+            detail: None,
+            message: None,
+        }
+    }
+
+    /// Reload hosts and repos from the database, keeping the selection.
+    pub fn load(&mut self, conn: &Connection) -> anyhow::Result<()> {
+        let selected_host = self.selected_host().map(|h| h.id.clone());
+        let selected_repo = self.selected_row().map(|r| r.repo.id.clone());
+
+        let repos = gitr_db::ops::list_repos(conn)?;
+        self.hosts = gitr_db::ops::list_hosts(conn)?
+            .into_iter()
+            .map(|host| HostEntry {
+                repos: repos
+                    .iter()
+                    .filter(|r| r.host_id.as_ref() == Some(&host.id))
+                    .count(),
+                host,
+            })
+            .collect();
+
+        self.rows = Vec::with_capacity(repos.len());
+        for repo in repos {
+            let snapshots = gitr_db::ops::get_branch_snapshots(conn, &repo.id)?;
+            let default_snap = snapshots.iter().find(|s| s.branch == repo.default_branch);
+            let last = gitr_db::ops::list_sync_history(conn, Some(&repo.id), 1)?
+                .into_iter()
+                .next();
+            self.rows.push(RepoRow {
+                behind: default_snap.map(|s| s.behind_count).unwrap_or(0),
+                ahead: default_snap.map(|s| s.ahead_count).unwrap_or(0),
+                last_status: last.as_ref().map(|r| r.status.clone()),
+                last_error: last.and_then(|r| r.errors.into_iter().next()),
+                repo,
+            });
+        }
+        self.rows
+            .sort_by(|a, b| a.repo.full_name.cmp(&b.repo.full_name));
+
+        let host_index = selected_host
+            .and_then(|id| self.hosts.iter().position(|h| h.host.id == id))
+            .map_or(0, |i| i + 1);
+        self.host_state.select(Some(host_index));
+        self.reselect(selected_repo.as_ref());
+        Ok(())
+    }
+
+    /// Start on the host labelled `label`.
+    pub fn select_host(&mut self, label: &str) -> anyhow::Result<()> {
+        let index = self
+            .hosts
+            .iter()
+            .position(|h| h.host.label == label)
+            .ok_or_else(|| anyhow::anyhow!("Host '{}' not found", label))?;
+        self.host_state.select(Some(index + 1));
+        self.reselect(None);
+        Ok(())
+    }
+
+    pub fn selected_host(&self) -> Option<&Host> {
+        match self.host_state.selected() {
+            Some(i) if i > 0 => self.hosts.get(i - 1).map(|h| &h.host),
+            _ => None,
+        }
+    }
+
+    /// Rows that pass the host, filter and search selection, in table order.
+    pub fn visible(&self) -> Vec<&RepoRow> {
+        let host = self.selected_host().map(|h| &h.id);
+        let search = self.search.to_lowercase();
+        self.rows
+            .iter()
+            .filter(|row| host.is_none_or(|id| row.repo.host_id.as_ref() == Some(id)))
+            .filter(|row| self.filter.matches(row))
+            .filter(|row| search.is_empty() || row.repo.full_name.to_lowercase().contains(&search))
+            .collect()
+    }
+
+    pub fn selected_row(&self) -> Option<&RepoRow> {
+        self.repo_state
+            .selected()
+            .and_then(|i| self.visible().get(i).copied())
+    }
+
+    pub fn host_label(&self, row: &RepoRow) -> &str {
+        self.hosts
+            .iter()
+            .find(|h| row.repo.host_id.as_ref() == Some(&h.host.id))
+            .map_or("?", |h| h.host.label.as_str())
+    }
+
+    /// Select `repo` if it is visible, else keep the index in range.
+    fn reselect(&mut self, repo: Option<&RepoId>) {
+        let visible = self.visible();
+        let index = repo
+            .and_then(|id| visible.iter().position(|r| &r.repo.id == id))
+            .or(self.repo_state.selected())
+            .map(|i| i.min(visible.len().saturating_sub(1)));
+        let index = if visible.is_empty() {
+            None
+        } else {
+            index.or(Some(0))
+        };
+        self.repo_state.select(index);
+    }
+
+    pub fn open_detail(&mut self, conn: &Connection, id: &RepoId) -> anyhow::Result<()> {
+        let Some(row) = self.rows.iter().find(|r| &r.repo.id == id) else {
+            return Ok(());
+        };
+        self.detail = Some(Detail {
+            repo: row.repo.clone(),
+            host_label: self.host_label(row).to_string(),
+            snapshots: gitr_db::ops::get_branch_snapshots(conn, id)?,
+            history: gitr_db::ops::list_sync_history(conn, Some(id), DETAIL_HISTORY)?,
+            scroll: 0,
+        });
+        self.mode = Mode::Detail;
+        Ok(())
+    }
+
+    /// Mark repos as waiting for the worker.
+    pub fn queue(&mut self, repos: &[Repo]) {
+        for repo in repos {
+            self.activity.insert(repo.id.clone(), Activity::Queued);
+        }
+    }
+
+    /// Apply live progress from the sync engine.
+    pub fn apply_event(&mut self, event: SyncEvent) {
+        match event {
+            SyncEvent::SyncStarted { repo_id, .. } => {
+                self.activity.insert(repo_id, Activity::Syncing);
+            }
+            SyncEvent::RefreshStarted { repo_id, .. } => {
+                self.activity.insert(repo_id, Activity::Refreshing);
+            }
+            SyncEvent::SyncFinished {
+                repo_id,
+                repo,
+                status,
+                errors,
+            } => {
+                self.activity.remove(&repo_id);
+                if let Some(row) = self.rows.iter_mut().find(|r| r.repo.id == repo_id) {
+                    row.last_status = Some(status.clone());
+                    row.last_error = errors.into_iter().next();
+                }
+                self.message = Some(format!("{repo}: {status}"));
+            }
+            SyncEvent::RefreshFinished {
+                repo_id,
+                repo,
+                error,
+            } => {
+                self.activity.remove(&repo_id);
+                if let Some(error) = error {
+                    self.message = Some(format!("{repo}: {error}"));
+                }
+            }
+        }
+    }
+
+    /// Forget activity for repos a finished job never reported on, e.g.
+    /// forks it skipped.
+    pub fn job_finished(&mut self, message: String) {
+        self.activity.retain(|_, a| *a != Activity::Queued);
+        self.message = Some(message);
+    }
+
+    pub fn on_key(&mut self, key: KeyEvent) -> Option<Action> {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return Some(Action::Quit);
+        }
+        match self.mode {
+            Mode::Search => {
+                match key.code {
+                    KeyCode::Char(c) => self.search.push(c),
+                    KeyCode::Backspace => {
+                        self.search.pop();
+                    }
+                    KeyCode::Enter => self.mode = Mode::Normal,
+                    KeyCode::Esc => {
+                        self.search.clear();
+                        self.mode = Mode::Normal;
+                    }
+                    _ => {}
+                }
+                self.reselect(None);
+                None
+            }
+            Mode::Detail => {
+                let detail = self.detail.as_mut()?;
+                match key.code {
+                    KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => {
+                        self.detail = None;
+                        self.mode = Mode::Normal;
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => detail.scroll += 1,
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        detail.scroll = detail.scroll.saturating_sub(1)
+                    }
+                    KeyCode::Char('o') => return Some(Action::Open(detail.repo.web_url())),
+                    _ => {}
+                }
+                None
+            }
+            Mode::Normal => self.on_normal_key(key.code),
+        }
+    }
+
+    fn on_normal_key(&mut self, code: KeyCode) -> Option<Action> {
+        match code {
+            KeyCode::Char('q') => return Some(Action::Quit),
+            KeyCode::Esc if !self.search.is_empty() => {
+                self.search.clear();
+                self.reselect(None);
+            }
+            KeyCode::Esc => return Some(Action::Quit),
+            KeyCode::Tab => {
+                self.focus = match self.focus {
+                    Focus::Hosts => Focus::Repos,
+                    Focus::Repos => Focus::Hosts,
+                }
+            }
+            KeyCode::Left | KeyCode::Char('h') => self.focus = Focus::Hosts,
+            KeyCode::Right | KeyCode::Char('l') => self.focus = Focus::Repos,
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::Char('/') => {
+                self.focus = Focus::Repos;
+                self.mode = Mode::Search;
+            }
+            KeyCode::Char('f') => {
+                self.filter = self.filter.next();
+                self.reselect(None);
+            }
+            KeyCode::Enter => match self.focus {
+                Focus::Hosts => self.focus = Focus::Repos,
+                Focus::Repos => {
+                    return self
+                        .selected_row()
+                        .map(|r| Action::Detail(r.repo.id.clone()))
+                }
+            },
+            KeyCode::Char('s') => return self.sync_action(false),
+            KeyCode::Char('S') => return self.sync_action(true),
+            KeyCode::Char('r') => return self.refresh_action(false),
+            KeyCode::Char('R') => return self.refresh_action(true),
+            KeyCode::Char('o') => {
+                return self.selected_row().map(|r| Action::Open(r.repo.web_url()))
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        self.message = None;
+        match self.focus {
+            Focus::Hosts => {
+                let len = self.hosts.len() + 1;
+                let current = self.host_state.selected().unwrap_or(0);
+                self.host_state
+                    .select(Some(current.saturating_add_signed(delta).min(len - 1)));
+                self.reselect(None);
+            }
+            Focus::Repos => {
+                let len = self.visible().len();
+                if len == 0 {
+                    return;
+                }
+                let current = self.repo_state.selected().unwrap_or(0);
+                self.repo_state
+                    .select(Some(current.saturating_add_signed(delta).min(len - 1)));
+            }
+        }
+    }
+
+    /// Forks to act on: the selected one, or every visible one. Forks that
+    /// already have work queued or running are left out.
+    fn targets(&self, all: bool) -> Vec<Repo> {
+        let rows = if all {
+            self.visible()
+        } else {
+            self.selected_row().into_iter().collect()
+        };
+        rows.into_iter()
+            .filter(|r| r.repo.is_fork && !self.activity.contains_key(&r.repo.id))
+            .map(|r| r.repo.clone())
+            .collect()
+    }
+
+    fn sync_action(&mut self, all: bool) -> Option<Action> {
+        let (forks, no_upstream): (Vec<Repo>, Vec<Repo>) = self
+            .targets(all)
+            .into_iter()
+            .partition(|r| r.upstream_clone_url.is_some() || r.upstream_full_name.is_some());
+        if forks.is_empty() {
+            self.message = Some(match no_upstream.first() {
+                Some(repo) => format!("{}: no upstream known", repo.full_name),
+                None => "Nothing to sync: select an idle fork".to_string(),
+            });
+            return None;
+        }
+        self.message = Some(format!("Queued sync of {} fork(s)", forks.len()));
+        Some(Action::Sync(forks))
+    }
+
+    fn refresh_action(&mut self, all: bool) -> Option<Action> {
+        let forks = self.targets(all);
+        if forks.is_empty() {
+            self.message = Some("Nothing to refresh: select an idle fork".to_string());
+            return None;
+        }
+        self.message = Some(format!("Queued refresh of {} fork(s)", forks.len()));
+        Some(Action::Refresh(forks))
+    }
+}
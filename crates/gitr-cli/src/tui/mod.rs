@@ -0,0 +1,91 @@
+//! Interactive dashboard for `gitr status --watch`.
+
+mod app;
+mod ui;
+mod worker;
+
+use std::time::{Duration, Instant};
+
+use crossterm::event::{self, Event, KeyEventKind};
+use gitr_core::config::GitrConfig;
+use ratatui::DefaultTerminal;
+use rusqlite::Connection;
+
+use app::{Action, App};
+use worker::{Job, Worker};
+
+/// How long to wait for a key before checking for engine events.
+const TICK: Duration = Duration::from_millis(200);
+/// How often to re-read the database, picking up changes made by other
+/// processes such as `gitr daemon`.
+const RELOAD_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Run the dashboard until the user quits. `host` preselects a host.
+pub fn run(host: Option<String>) -> anyhow::Result<()> {
+    let config = GitrConfig::load()?;
+    let db_path = GitrConfig::db_path()?;
+    let conn = gitr_db::open_db(&db_path)?;
+
+    let mut app = App::new();
+    app.load(&conn)?;
+    if let Some(label) = &host {
+        app.select_host(label)?;
+    }
+    let mut worker = Worker::spawn(config, db_path);
+
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &mut app, &conn, &mut worker);
+    ratatui::restore();
+    result
+}
+
+fn event_loop(
+    terminal: &mut DefaultTerminal,
+    app: &mut App,
+    conn: &Connection,
+    worker: &mut Worker,
+) -> anyhow::Result<()> {
+    let mut last_reload = Instant::now();
+    loop {
+        terminal.draw(|frame| ui::draw(frame, app))?;
+
+        if event::poll(TICK)? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    match app.on_key(key) {
+                        Some(Action::Quit) => return Ok(()),
+                        Some(Action::Detail(id)) => app.open_detail(conn, &id)?,
+                        Some(Action::Sync(forks)) => {
+                            app.queue(&forks);
+                            worker.submit(Job::Sync(forks))?;
+                        }
+                        Some(Action::Refresh(forks)) => {
+                            app.queue(&forks);
+                            worker.submit(Job::Refresh(forks))?;
+                        }
+                        Some(Action::Open(url)) => {
+                            app.message = Some(match open::that_detached(&url) {
+                                Ok(()) => format!("Opened {url}"),
+                                Err(e) => format!("Could not open {url}: {e}"),
+                            });
+                        }
+                        None => {}
+                    }
+                }
+            }
+        }
+
+        while let Ok(event) = worker.events.try_recv() {
+            app.apply_event(event);
+        }
+        let mut reload = last_reload.elapsed() >= RELOAD_INTERVAL;
+        while let Ok(outcome) = worker.done.try_recv() {
+            app.job_finished(outcome.unwrap_or_else(|e| format!("Job failed: {e}")));
+            reload = true;
+        }
+        if reload {
+            app.load(conn)?;
+            last_reload = Instant::now();
+        }
+    }
+}
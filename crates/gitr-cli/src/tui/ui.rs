@@ -0,0 +1,306 @@
+use gitr_core::models::sync_state::SyncStatus;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Cell, Clear, List, ListItem, Paragraph, Row, Table, Wrap};
+use ratatui::Frame;
+
+use super::app::{Activity, App, Detail, Focus, Mode, RepoRow};
+
+pub fn draw(frame: &mut Frame, app: &mut App) {
+    let [header, body, status, keys] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Min(3),
+        Constraint::Length(1),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [hosts, repos] =
+        Layout::horizontal([Constraint::Length(30), Constraint::Min(40)]).areas(body);
+
+    draw_header(frame, app, header);
+    draw_hosts(frame, app, hosts);
+    draw_repos(frame, app, repos);
+    draw_status(frame, app, status);
+    draw_keys(frame, app, keys);
+
+    if let Some(detail) = &app.detail {
+        draw_detail(frame, detail, popup_area(frame.area()));
+    }
+}
+
+fn draw_header(frame: &mut Frame, app: &App, area: Rect) {
+    let mut spans = vec![
+        " gitr ".bold().reversed(),
+        Span::raw("  filter: "),
+        Span::raw(app.filter.label()).bold(),
+    ];
+    if app.mode == Mode::Search || !app.search.is_empty() {
+        spans.push(Span::raw("  search: /"));
+        spans.push(Span::raw(app.search.clone()).bold());
+        if app.mode == Mode::Search {
+            spans.push(Span::raw("▏"));
+        }
+    }
+    frame.render_widget(Line::from(spans), area);
+}
+
+fn pane(title: String, focused: bool) -> Block<'static> {
+    let style = if focused {
+        Style::default().fg(Color::Cyan)
+    } else {
+        Style::default()
+    };
+    Block::default()
+        .borders(Borders::ALL)
+        .border_style(style)
+        .title(title)
+}
+
+fn draw_hosts(frame: &mut Frame, app: &mut App, area: Rect) {
+    let mut items = vec![ListItem::new(format!("All hosts ({})", app.rows.len()))];
+    for (i, entry) in app.hosts.iter().enumerate() {
+        let branch = if i + 1 == app.hosts.len() {
+            "└"
+        } else {
+            "├"
+        };
+        items.push(ListItem::new(format!(
+            " {branch} {} ({}) {}",
+            entry.host.label, entry.host.kind, entry.repos
+        )));
+    }
+    let list = List::new(items)
+        .block(pane("Hosts".to_string(), app.focus == Focus::Hosts))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    frame.render_stateful_widget(list, area, &mut app.host_state);
+}
+
+/// Status label and color for a row, live activity first.
+fn status_of(app: &App, row: &RepoRow) -> (&'static str, Color) {
+    match app.activity.get(&row.repo.id) {
+        Some(Activity::Queued) => return ("queued", Color::Magenta),
+        Some(Activity::Syncing) => return ("syncing…", Color::Magenta),
+        Some(Activity::Refreshing) => return ("refreshing…", Color::Magenta),
+        None => {}
+    }
+    if !row.repo.is_fork {
+        ("tracked", Color::White)
+    } else if row.last_status == Some(SyncStatus::Failed) {
+        ("failed", Color::Red)
+    } else if row.behind > 0 {
+        ("behind", Color::Yellow)
+    } else if row.ahead > 0 {
+        ("ahead", Color::Blue)
+    } else if row.repo.last_synced_at.is_some() {
+        ("synced", Color::Green)
+    } else {
+        ("unknown", Color::White)
+    }
+}
+
+fn draw_repos(frame: &mut Frame, app: &mut App, area: Rect) {
+    let visible = app.visible();
+    let rows: Vec<Row> = visible
+        .iter()
+        .map(|row| {
+            let (status, color) = status_of(app, row);
+            let last_sync = row
+                .repo
+                .last_synced_at
+                .map(|dt| dt.format("%m-%d %H:%M").to_string())
+                .unwrap_or_else(|| "—".to_string());
+            Row::new(vec![
+                Cell::from(row.repo.full_name.clone()),
+                Cell::from(row.repo.default_branch.clone()),
+                Cell::from(row.behind.to_string()),
+                Cell::from(row.ahead.to_string()),
+                Cell::from(last_sync),
+                Cell::from(status).style(Style::default().fg(color)),
+            ])
+        })
+        .collect();
+    let title = match app.selected_host() {
+        Some(host) => format!("Repos on {} ({})", host.label, visible.len()),
+        None => format!("Repos ({})", visible.len()),
+    };
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(24),
+            Constraint::Length(14),
+            Constraint::Length(7),
+            Constraint::Length(7),
+            Constraint::Length(12),
+            Constraint::Length(12),
+        ],
+    )
+    .header(
+        Row::new(["REPO", "BRANCH", "BEHIND", "AHEAD", "LAST SYNC", "STATUS"])
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+    )
+    .block(pane(title, app.focus == Focus::Repos))
+    .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    frame.render_stateful_widget(table, area, &mut app.repo_state);
+}
+
+fn draw_status(frame: &mut Frame, app: &App, area: Rect) {
+    let forks = app.rows.iter().filter(|r| r.repo.is_fork);
+    let behind = forks.clone().filter(|r| r.behind > 0).count();
+    let failed = forks
+        .filter(|r| r.last_status == Some(SyncStatus::Failed))
+        .count();
+    let summary = format!(
+        "{} repos | {behind} behind | {failed} failed | {} active ",
+        app.rows.len(),
+        app.activity.len()
+    );
+
+    let [left, right] = Layout::horizontal([
+        Constraint::Min(0),
+        Constraint::Length(summary.chars().count() as u16),
+    ])
+    .areas(area);
+    if let Some(message) = &app.message {
+        frame.render_widget(Line::raw(format!(" {message}")), left);
+    } else if let Some(error) = app.selected_row().and_then(|r| r.last_error.as_ref()) {
+        frame.render_widget(
+            Line::styled(
+                format!(" last sync: {error}"),
+                Style::default().fg(Color::Red),
+            ),
+            left,
+        );
+    }
+    frame.render_widget(Line::raw(summary).dim(), right);
+}
+
+fn draw_keys(frame: &mut Frame, app: &App, area: Rect) {
+    let keys: &[(&str, &str)] = match app.mode {
+        Mode::Normal => &[
+            ("q", "quit"),
+            ("tab", "pane"),
+            ("/", "search"),
+            ("f", "filter"),
+            ("enter", "details"),
+            ("s/S", "sync one/all"),
+            ("r/R", "refresh one/all"),
+            ("o", "open"),
+        ],
+        Mode::Search => &[("enter", "apply"), ("esc", "clear")],
+        Mode::Detail => &[("esc", "back"), ("j/k", "scroll"), ("o", "open")],
+    };
+    let mut spans = Vec::new();
+    for (key, what) in keys {
+        spans.push(Span::raw(format!(" {key} ")).reversed());
+        spans.push(Span::raw(format!(" {what}  ")));
+    }
+    frame.render_widget(Line::from(spans), area);
+}
+
+fn popup_area(area: Rect) -> Rect {
+    let [_, middle, _] = Layout::vertical([
+        Constraint::Percentage(10),
+        Constraint::Percentage(80),
+        Constraint::Percentage(10),
+    ])
+    .areas(area);
+    let [_, center, _] = Layout::horizontal([
+        Constraint::Percentage(10),
+        Constraint::Percentage(80),
+        Constraint::Percentage(10),
+    ])
+    .areas(middle);
+    center
+}
+
+fn status_color(status: &SyncStatus) -> Color {
+    match status {
+        SyncStatus::Success => Color::Green,
+        SyncStatus::Failed => Color::Red,
+        _ => Color::Yellow,
+    }
+}
+
+fn draw_detail(frame: &mut Frame, detail: &Detail, area: Rect) {
+    let repo = &detail.repo;
+    let field = |name: &str, value: String| {
+        Line::from(vec![
+            Span::raw(format!("{name:<12}")).dim(),
+            Span::raw(value),
+        ])
+    };
+    let mut lines = vec![
+        field("Host", detail.host_label.clone()),
+        field("Clone URL", repo.clone_url.clone()),
+        field("Web", repo.web_url()),
+        field(
+            "Upstream",
+            repo.upstream_full_name
+                .clone()
+                .unwrap_or_else(|| "—".to_string()),
+        ),
+        field(
+            "Local path",
+            repo.local_path
+                .as_ref()
+                .map(|p| p.display().to_string())
+                .unwrap_or_else(|| "—".to_string()),
+        ),
+        Line::raw(""),
+        Line::raw("Branches").bold(),
+    ];
+    if detail.snapshots.is_empty() {
+        lines.push(Line::raw("  no snapshots yet; press r to refresh").dim());
+    }
+    for snap in &detail.snapshots {
+        lines.push(Line::raw(format!(
+            "  {:<24} {} behind, {} ahead  (as of {})",
+            snap.branch,
+            snap.behind_count,
+            snap.ahead_count,
+            snap.updated_at.format("%Y-%m-%d %H:%M")
+        )));
+    }
+
+    lines.push(Line::raw(""));
+    lines.push(Line::raw("Sync history").bold());
+    if detail.history.is_empty() {
+        lines.push(Line::raw("  never synced").dim());
+    }
+    for record in &detail.history {
+        lines.push(Line::from(vec![
+            Span::raw(format!(
+                "  {}  ",
+                record.started_at.format("%Y-%m-%d %H:%M")
+            )),
+            Span::styled(
+                format!("{:<8}", record.status.to_string()),
+                Style::default().fg(status_color(&record.status)),
+            ),
+            Span::raw(format!(
+                " {} branches, {} commits, {} attempt(s)",
+                record.branches_synced, record.commits_transferred, record.attempts
+            )),
+        ]));
+        for error in &record.errors {
+            lines.push(Line::styled(
+                format!("      {error}"),
+                Style::default().fg(Color::Red),
+            ));
+        }
+    }
+
+    let paragraph = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan))
+                .title(format!(" {} ", repo.full_name)),
+        )
+        .wrap(Wrap { trim: false })
+        .scroll((detail.scroll, 0));
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
@@ -0,0 +1,132 @@
+use std::path::{Path, PathBuf};
+
+use gitr_core::config::GitrConfig;
+use gitr_core::models::repo::Repo;
+use gitr_core::models::sync_state::SyncStatus;
+use gitr_sync::backend::create_backend;
+use gitr_sync::engine::{SyncEngine, SyncEvent};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+use crate::commands::notify::send_alerts;
+use crate::commands::refresh::{refresh_targets, save_snapshots};
+use crate::commands::sync::{save_results, upstream_pairs};
+
+pub enum Job {
+    Sync(Vec<Repo>),
+    Refresh(Vec<Repo>),
+}
+
+/// Runs sync and refresh jobs one at a time on a background thread, so the
+/// dashboard stays responsive. Progress comes back on `events`, and a summary
+/// line (or error) on `done` when each job ends.
+pub struct Worker {
+    jobs: UnboundedSender<Job>,
+    pub events: UnboundedReceiver<SyncEvent>,
+    pub done: UnboundedReceiver<Result<String, String>>,
+}
+
+impl Worker {
+    pub fn spawn(config: GitrConfig, db_path: PathBuf) -> Self {
+        let (jobs_tx, mut jobs_rx) = mpsc::unbounded_channel();
+        let (events_tx, events_rx) = mpsc::unbounded_channel();
+        let (done_tx, done_rx) = mpsc::unbounded_channel();
+
+        // The database connection isn't Send, so jobs run on their own
+        // single-threaded runtime.
+        std::thread::spawn(move || {
+            let runtime = match tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+            {
+                Ok(runtime) => runtime,
+                Err(e) => {
+                    let _ = done_tx.send(Err(format!("job worker failed to start: {e}")));
+                    return;
+                }
+            };
+            runtime.block_on(async move {
+                while let Some(job) = jobs_rx.recv().await {
+                    let outcome = run_job(&config, &db_path, job, events_tx.clone())
+                        .await
+                        .map_err(|e| e.to_string());
+                    if done_tx.send(outcome).is_err() {
+                        break;
+                    }
+                }
+            });
+        });
+
+        Self {
+            jobs: jobs_tx,
+            events: events_rx,
+            done: done_rx,
+        }
+    }
+
+    pub fn submit(&self, job: Job) -> anyhow::Result<()> {
+        self.jobs
+            .send(job)
+            .map_err(|_| anyhow::anyhow!("job worker has stopped"))
+    }
+}
+
+async fn run_job(
+    config: &GitrConfig,
+    db_path: &Path,
+    job: Job,
+    events: UnboundedSender<SyncEvent>,
+) -> anyhow::Result<String> {
+    let conn = gitr_db::open_db(db_path)?;
+    let engine = SyncEngine::new(config.sync_concurrency, config.retry.clone())
+        .with_backend(create_backend(&config.git_backend))
+        .with_hooks(config.hooks.clone())
+        .with_events(events);
+
+    let summary = match job {
+        Job::Sync(forks) => {
+            let clone_base = GitrConfig::home_dir()?.join("repos");
+            std::fs::create_dir_all(&clone_base)?;
+            let pairs = upstream_pairs(&conn, config, &forks).await?;
+            let results = engine
+                .sync_all_forks(pairs, &clone_base, &config.default_merge_strategy, false)
+                .await;
+            save_results(&conn, &results)?;
+
+            let synced: Vec<Repo> = forks
+                .into_iter()
+                .filter(|f| {
+                    results
+                        .iter()
+                        .any(|r| r.record.repo_id == f.id && r.record.status == SyncStatus::Success)
+                })
+                .collect();
+            let refreshed = engine
+                .refresh_all(refresh_targets(&conn, config, synced)?, false)
+                .await;
+            save_snapshots(&conn, &refreshed)?;
+
+            let failed = results
+                .iter()
+                .filter(|r| r.record.status == SyncStatus::Failed)
+                .count();
+            format!(
+                "Sync finished: {} synced | {failed} failed",
+                results.len() - failed
+            )
+        }
+        Job::Refresh(forks) => {
+            let results = engine
+                .refresh_all(refresh_targets(&conn, config, forks)?, true)
+                .await;
+            save_snapshots(&conn, &results)?;
+            let failed = results.iter().filter(|r| r.error.is_some()).count();
+            format!(
+                "Refresh finished: {} refreshed | {failed} failed",
+                results.len() - failed
+            )
+        }
+    };
+
+    send_alerts(&conn, config).await;
+    Ok(summary)
+}
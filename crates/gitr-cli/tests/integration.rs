@@ -158,15 +158,18 @@ fn test_scanner_finds_git_repos() {
 
 #[test]
 fn test_reconcile() {
-    use gitr_discover::reconcile::reconcile;
+    use gitr_discover::reconcile::{reconcile, HostRepos};
     use gitr_discover::scanner::{ScannedRemote, ScannedRepo};
     use gitr_host::RemoteRepo;
 
     let local = vec![ScannedRepo {
         path: "/repos/myrepo".into(),
+        git_dir: "/repos/myrepo/.git".into(),
+        checkouts: Vec::new(),
         remotes: vec![ScannedRemote {
             name: "origin".to_string(),
             url: "https://github.com/user/myrepo.git".to_string(),
+            push_urls: Vec::new(),
         }],
     }];
 
@@ -184,6 +187,7 @@ fn test_reconcile() {
             description: None,
             is_private: false,
             is_archived: false,
+            language: None,
             updated_at: None,
         },
         RemoteRepo {
@@ -199,11 +203,16 @@ fn test_reconcile() {
             description: None,
             is_private: false,
             is_archived: false,
+            language: None,
             updated_at: None,
         },
     ];
 
-    let result = reconcile(&local, &remote, "gh");
+    let hosts = vec![HostRepos {
+        host_label: "gh".to_string(),
+        repos: remote,
+    }];
+    let result = reconcile(&local, &hosts);
     assert_eq!(result.matched_count(), 1);
     assert_eq!(result.local_only_count(), 0);
     assert_eq!(result.remote_only_count(), 1);
@@ -238,3 +247,59 @@ fn test_merge_strategy_roundtrip() {
         assert_eq!(parsed, expected);
     }
 }
+
+#[cfg(unix)]
+#[test]
+fn test_failing_cli_syncs_trigger_failure_alert() {
+    let home = tempfile::tempdir().unwrap();
+    let gitr_home = home.path().join(".gitr");
+    std::fs::create_dir_all(&gitr_home).unwrap();
+    let alerts = home.path().join("alerts");
+    std::fs::write(
+        gitr_home.join("config.toml"),
+        format!(
+            r#"
+            [[notify.channels]]
+            name = "log"
+            kind = "command"
+            command = "echo \"$GITR_NOTIFY_RULE\" >> '{}'"
+
+            [[notify.rules]]
+            when = "consecutive_failures"
+            count = 2
+            "#,
+            alerts.display()
+        ),
+    )
+    .unwrap();
+
+    // Neither URL exists, so every sync fails.
+    let missing = home.path().join("missing.git").display().to_string();
+    let mut fork = Repo::without_host(
+        "me/tool".to_string(),
+        missing.clone(),
+        "main".to_string(),
+        DiscoverySource::Manual,
+    );
+    fork.is_fork = true;
+    fork.upstream_full_name = Some("them/tool".to_string());
+    fork.upstream_clone_url = Some(missing);
+    let conn = gitr_db::open_db(&gitr_home.join("gitr.db")).unwrap();
+    ops::insert_repo(&conn, &fork).unwrap();
+
+    let sync = || {
+        std::process::Command::new(env!("CARGO_BIN_EXE_gitr"))
+            .args(["sync", "me/tool"])
+            .env("HOME", home.path())
+            .output()
+            .unwrap()
+    };
+    assert!(!sync().status.success());
+    assert!(!alerts.exists());
+    assert!(!sync().status.success());
+
+    let history = ops::list_sync_history(&conn, Some(&fork.id), 10).unwrap();
+    assert_eq!(history.len(), 2);
+    assert!(history.iter().all(|r| r.status == SyncStatus::Failed));
+    assert_eq!(std::fs::read_to_string(&alerts).unwrap(), "failures>=2\n");
+}
@@ -0,0 +1,103 @@
+use std::fmt::Write;
+
+use crate::models::sync_state::IncomingCommit;
+
+/// Changelog sections, in display order, keyed by conventional-commit type.
+const SECTIONS: &[(&str, &str)] = &[
+    ("feat", "Features"),
+    ("fix", "Bug Fixes"),
+    ("perf", "Performance"),
+    ("refactor", "Refactoring"),
+    ("docs", "Documentation"),
+    ("test", "Tests"),
+    ("build", "Build"),
+    ("ci", "CI"),
+    ("style", "Style"),
+    ("chore", "Chores"),
+    ("revert", "Reverts"),
+];
+
+/// Render commits as a Markdown changelog grouped by conventional-commit type.
+///
+/// Commits without a recognised type go under "Other Changes". Empty
+/// sections are omitted; commit order within a section is preserved.
+pub fn render(commits: &[IncomingCommit]) -> String {
+    let mut out = String::new();
+    let section_of = |c: &IncomingCommit| {
+        c.conventional_type()
+            .map(|t| t.to_ascii_lowercase())
+            .and_then(|t| SECTIONS.iter().position(|(ty, _)| *ty == t))
+    };
+
+    let titles = SECTIONS
+        .iter()
+        .map(|(_, title)| *title)
+        .chain(std::iter::once("Other Changes"));
+    for (idx, title) in titles.enumerate() {
+        let entries: Vec<&IncomingCommit> = commits
+            .iter()
+            .filter(|c| section_of(c).unwrap_or(SECTIONS.len()) == idx)
+            .collect();
+        if entries.is_empty() {
+            continue;
+        }
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        let _ = writeln!(out, "### {title}");
+        for c in entries {
+            let short = &c.sha[..c.sha.len().min(7)];
+            let _ = writeln!(out, "- {} ({short}, {})", c.subject, c.author);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn commit(subject: &str) -> IncomingCommit {
+        IncomingCommit {
+            sha: "0123456789abcdef".into(),
+            author: "dev".into(),
+            subject: subject.into(),
+            date: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_conventional_type() {
+        assert_eq!(commit("feat: add x").conventional_type(), Some("feat"));
+        assert_eq!(commit("fix(parser): y").conventional_type(), Some("fix"));
+        assert_eq!(commit("feat(api)!: drop v1").conventional_type(), Some("feat"));
+        assert_eq!(commit("refactor!: z").conventional_type(), Some("refactor"));
+        assert_eq!(commit("Merge pull request #1").conventional_type(), None);
+        assert_eq!(commit("see http://x: y").conventional_type(), None);
+    }
+
+    #[test]
+    fn test_render_groups_by_type() {
+        let commits = vec![
+            commit("fix: crash on empty input"),
+            commit("Update README"),
+            commit("feat(cli): add --verbose"),
+            commit("fix(db): lock timeout"),
+        ];
+        let out = render(&commits);
+        assert_eq!(
+            out,
+            "### Features\n\
+             - feat(cli): add --verbose (0123456, dev)\n\
+             \n\
+             ### Bug Fixes\n\
+             - fix: crash on empty input (0123456, dev)\n\
+             - fix(db): lock timeout (0123456, dev)\n\
+             \n\
+             ### Other Changes\n\
+             - Update README (0123456, dev)\n"
+        );
+        assert_eq!(render(&[]), "");
+    }
+}
@@ -2,7 +2,11 @@ use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 
 use crate::error::GitrError;
+use crate::hooks::HooksConfig;
+use crate::layout::CloneConfig;
 use crate::models::sync_link::MergeStrategy;
+use crate::notify::NotifyConfig;
+use crate::retry::RetryPolicy;
 
 /// Top-level Gitr configuration, stored at `~/.gitr/config.toml`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,6 +26,108 @@ pub struct GitrConfig {
     /// Maximum directory depth for filesystem scanning.
     #[serde(default = "default_max_scan_depth")]
     pub max_scan_depth: usize,
+
+    /// Directories the filesystem scan skips, as gitignore-style patterns
+    /// relative to each scan path, on top of `.gitrignore` files.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scan_ignore: Vec<String>,
+
+    /// Keep looking inside repos the scan finds, for nested clones and
+    /// submodules. Off by default: the scan stops at the first repo.
+    #[serde(default)]
+    pub scan_nested_repos: bool,
+
+    /// Where `gitr clone` puts new clones and which protocol it uses.
+    #[serde(default)]
+    pub clone: CloneConfig,
+
+    /// Retry budget for transient git and API failures.
+    #[serde(default)]
+    pub retry: RetryPolicy,
+
+    /// Which implementation runs git operations.
+    #[serde(default)]
+    pub git_backend: GitBackendKind,
+
+    /// Shell commands run around fork syncs, globally and per repo.
+    #[serde(default)]
+    pub hooks: HooksConfig,
+
+    /// Settings for `gitr daemon`.
+    #[serde(default)]
+    pub daemon: DaemonConfig,
+
+    /// Prometheus metrics export.
+    #[serde(default)]
+    pub metrics: MetricsConfig,
+
+    /// Alert channels and the rules that trigger them.
+    #[serde(default)]
+    pub notify: NotifyConfig,
+}
+
+/// Where metrics go outside daemon mode.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetricsConfig {
+    /// node_exporter textfile written after `sync`, `refresh` and `scan`,
+    /// e.g. `/var/lib/node_exporter/textfile_collector/gitr.prom`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub textfile: Option<PathBuf>,
+}
+
+/// Git backend selection. `gitoxide` runs read-only operations in-process and
+/// requires gitr to be built with the `gitoxide` feature; otherwise the CLI is used.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GitBackendKind {
+    /// Shell out to the `git` binary for everything.
+    #[default]
+    Cli,
+    /// Use gitoxide for read-only work, the `git` binary for the rest.
+    Gitoxide,
+}
+
+/// Settings for the long-running `gitr daemon` scheduler.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DaemonConfig {
+    /// Cron expression for syncing all tracked forks (5-field, or 6-field
+    /// with seconds). Unset means forks are only synced by scheduled links.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fork_sync_cron: Option<String>,
+
+    /// What to do with runs missed while the daemon was down.
+    #[serde(default)]
+    pub missed_runs: MissedRunPolicy,
+
+    /// Seconds to wait after a webhook push before syncing, so a burst of
+    /// pushes to the same repo results in one sync.
+    #[serde(default = "default_webhook_debounce_secs")]
+    pub webhook_debounce_secs: u64,
+}
+
+impl Default for DaemonConfig {
+    fn default() -> Self {
+        Self {
+            fork_sync_cron: None,
+            missed_runs: MissedRunPolicy::default(),
+            webhook_debounce_secs: default_webhook_debounce_secs(),
+        }
+    }
+}
+
+/// Handling of scheduled runs that fell due while the daemon wasn't running.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MissedRunPolicy {
+    /// Run once on startup, however many runs were missed.
+    #[default]
+    CatchUp,
+    /// Drop missed runs and wait for the next scheduled time.
+    Skip,
+}
+
+fn default_webhook_debounce_secs() -> u64 {
+    10
 }
 
 fn default_merge_strategy() -> MergeStrategy {
@@ -43,6 +149,15 @@ impl Default for GitrConfig {
             sync_concurrency: 8,
             scan_paths: Vec::new(),
             max_scan_depth: 4,
+            scan_ignore: Vec::new(),
+            scan_nested_repos: false,
+            clone: CloneConfig::default(),
+            retry: RetryPolicy::default(),
+            git_backend: GitBackendKind::default(),
+            hooks: HooksConfig::default(),
+            daemon: DaemonConfig::default(),
+            metrics: MetricsConfig::default(),
+            notify: NotifyConfig::default(),
         }
     }
 }
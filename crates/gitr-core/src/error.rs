@@ -30,12 +30,21 @@ pub enum GitrError {
     #[error("fast-forward failed on branch {branch}: {message}")]
     FastForwardFailed { branch: String, message: String },
 
+    #[error("{hook} hook failed: {output}")]
+    HookFailed { hook: String, output: String },
+
+    #[error("notification via {channel} failed: {message}")]
+    NotifyFailed { channel: String, message: String },
+
     #[error("sync link not found: {id}")]
     SyncLinkNotFound { id: String },
 
     #[error("collection not found: {name}")]
     CollectionNotFound { name: String },
 
+    #[error("invalid filter: {message}")]
+    InvalidFilter { message: String },
+
     #[error("provider not implemented: {kind}")]
     ProviderNotImplemented { kind: String },
 
@@ -60,3 +69,118 @@ pub enum GitrError {
     #[error("{0}")]
     Other(String),
 }
+
+/// Substrings of git/HTTP error output that indicate a network-level failure
+/// worth retrying.
+const TRANSIENT_PATTERNS: &[&str] = &[
+    "could not resolve host",
+    "temporary failure in name resolution",
+    "connection timed out",
+    "operation timed out",
+    "connection reset",
+    "connection refused",
+    "failed to connect",
+    "remote end hung up",
+    "early eof",
+    "rpc failed",
+    "unexpected disconnect",
+    "the requested url returned error: 5",
+    "internal server error",
+    "bad gateway",
+    "service unavailable",
+    "gateway timeout",
+];
+
+/// Substrings that mark a failure as permanent even if it also matches a
+/// transient pattern (e.g. a rejected push reported alongside a hang-up).
+const PERMANENT_PATTERNS: &[&str] = &[
+    "authentication failed",
+    "permission denied",
+    "could not read username",
+    "repository not found",
+    "non-fast-forward",
+    "[rejected]",
+    "conflict",
+];
+
+impl GitrError {
+    /// Whether this error is likely to succeed on retry: network failures,
+    /// 5xx responses, rate limits and dropped connections. Auth failures,
+    /// conflicts and non-fast-forward rejections are permanent.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            GitrError::RateLimited { .. } => true,
+            GitrError::ApiError { status, message } => match status {
+                0 => !matches_any(message, PERMANENT_PATTERNS),
+                408 | 429 => true,
+                500..=599 => true,
+                _ => false,
+            },
+            GitrError::GitError { message } => {
+                matches_any(message, TRANSIENT_PATTERNS)
+                    && !matches_any(message, PERMANENT_PATTERNS)
+            }
+            GitrError::Io(e) => matches!(
+                e.kind(),
+                std::io::ErrorKind::TimedOut
+                    | std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::ConnectionAborted
+                    | std::io::ErrorKind::Interrupted
+            ),
+            _ => false,
+        }
+    }
+}
+
+fn matches_any(message: &str, patterns: &[&str]) -> bool {
+    let lower = message.to_lowercase();
+    patterns.iter().any(|p| lower.contains(p))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transient_classification() {
+        let hung_up = GitrError::GitError {
+            message: "git fetch upstream --prune failed: fatal: the remote end hung up unexpectedly"
+                .into(),
+        };
+        assert!(hung_up.is_transient());
+
+        let http_503 = GitrError::GitError {
+            message: "git push origin main failed: fatal: unable to access 'https://github.com/a/b.git/': The requested URL returned error: 503".into(),
+        };
+        assert!(http_503.is_transient());
+
+        assert!(GitrError::ApiError { status: 502, message: String::new() }.is_transient());
+        assert!(GitrError::ApiError { status: 0, message: "connection closed".into() }.is_transient());
+        assert!(!GitrError::ApiError { status: 404, message: String::new() }.is_transient());
+    }
+
+    #[test]
+    fn test_permanent_classification() {
+        let rejected = GitrError::GitError {
+            message: "git push origin main failed: ! [rejected] main -> main (non-fast-forward)"
+                .into(),
+        };
+        assert!(!rejected.is_transient());
+
+        let auth = GitrError::GitError {
+            message: "git fetch upstream --prune failed: fatal: Authentication failed for 'https://github.com/a/b.git/'".into(),
+        };
+        assert!(!auth.is_transient());
+
+        assert!(!GitrError::FastForwardFailed {
+            branch: "upstream/main".into(),
+            message: "Not possible to fast-forward".into(),
+        }
+        .is_transient());
+        assert!(!GitrError::AuthFailed {
+            host: "gh".into(),
+            message: "bad token".into(),
+        }
+        .is_transient());
+    }
+}
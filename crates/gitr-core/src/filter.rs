@@ -0,0 +1,560 @@
+//! Repo filter expressions, e.g. `host:gh fork:true (behind>0 or synced>7d)`.
+//!
+//! A term is a field, an operator and a value. Text fields take `:` and
+//! compare case-insensitively (`name` also accepts `*` and `?` wildcards);
+//! boolean fields take `:true` or `:false`; count fields take `:`, `>`, `>=`,
+//! `<` or `<=`; `synced` takes an age such as `synced>7d` or `synced:never`.
+//!
+//! Terms next to each other must all match. `or`, `not` (also `-term` or
+//! `!term`) and parentheses combine them; `and` may be written out. Values
+//! with spaces can be quoted: `owner:"my org"`.
+//!
+//! Filters are evaluated in SQL by `gitr-db`.
+
+use chrono::Duration;
+
+use crate::error::GitrError;
+
+/// A repo attribute a filter term can test.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    /// Label of the repo's host.
+    Host,
+    Owner,
+    Name,
+    Fork,
+    Private,
+    Archived,
+    /// Whether a scan found the repo deleted on its host.
+    Gone,
+    /// Whether the repo has a local clone.
+    Local,
+    /// Primary language reported by the host.
+    Lang,
+    /// How the repo was discovered: api, filesystem or manual.
+    Source,
+    /// Commits the default branch is behind upstream.
+    Behind,
+    /// Commits the default branch is ahead of upstream.
+    Ahead,
+    /// Time since the last successful sync.
+    Synced,
+}
+
+/// The type of value a field compares against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    Text,
+    Bool,
+    Count,
+    Age,
+}
+
+impl Field {
+    pub fn kind(self) -> FieldKind {
+        match self {
+            Field::Host | Field::Owner | Field::Name | Field::Lang | Field::Source => {
+                FieldKind::Text
+            }
+            Field::Fork | Field::Private | Field::Archived | Field::Gone | Field::Local => {
+                FieldKind::Bool
+            }
+            Field::Behind | Field::Ahead => FieldKind::Count,
+            Field::Synced => FieldKind::Age,
+        }
+    }
+}
+
+impl std::fmt::Display for Field {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Field::Host => write!(f, "host"),
+            Field::Owner => write!(f, "owner"),
+            Field::Name => write!(f, "name"),
+            Field::Fork => write!(f, "fork"),
+            Field::Private => write!(f, "private"),
+            Field::Archived => write!(f, "archived"),
+            Field::Gone => write!(f, "gone"),
+            Field::Local => write!(f, "local"),
+            Field::Lang => write!(f, "lang"),
+            Field::Source => write!(f, "source"),
+            Field::Behind => write!(f, "behind"),
+            Field::Ahead => write!(f, "ahead"),
+            Field::Synced => write!(f, "synced"),
+        }
+    }
+}
+
+impl std::str::FromStr for Field {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "host" => Ok(Field::Host),
+            "owner" => Ok(Field::Owner),
+            "name" => Ok(Field::Name),
+            "fork" => Ok(Field::Fork),
+            "private" => Ok(Field::Private),
+            "archived" => Ok(Field::Archived),
+            "gone" => Ok(Field::Gone),
+            "local" => Ok(Field::Local),
+            "lang" | "language" => Ok(Field::Lang),
+            "source" => Ok(Field::Source),
+            "behind" => Ok(Field::Behind),
+            "ahead" => Ok(Field::Ahead),
+            "synced" => Ok(Field::Synced),
+            _ => Err(format!("unknown filter field: {s}")),
+        }
+    }
+}
+
+/// How a term compares its field with its value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Eq,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+impl std::fmt::Display for Op {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Op::Eq => write!(f, ":"),
+            Op::Gt => write!(f, ">"),
+            Op::Ge => write!(f, ">="),
+            Op::Lt => write!(f, "<"),
+            Op::Le => write!(f, "<="),
+        }
+    }
+}
+
+/// One `field<op>value` test.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Term {
+    pub field: Field,
+    pub op: Op,
+    pub value: String,
+}
+
+impl Term {
+    /// The value of a count term.
+    pub fn count(&self) -> u32 {
+        self.value.parse().unwrap_or(0)
+    }
+
+    /// The value of an age term; `None` for `synced:never`.
+    pub fn age(&self) -> Option<Duration> {
+        parse_age(&self.value)
+    }
+}
+
+/// Parse an age such as `30m`, `12h`, `7d` or `2w`.
+pub fn parse_age(s: &str) -> Option<Duration> {
+    let unit = s.chars().last()?;
+    let n: i64 = s[..s.len() - unit.len_utf8()].parse().ok()?;
+    match unit {
+        'm' => Some(Duration::minutes(n)),
+        'h' => Some(Duration::hours(n)),
+        'd' => Some(Duration::days(n)),
+        'w' => Some(Duration::weeks(n)),
+        _ => None,
+    }
+}
+
+impl std::str::FromStr for Term {
+    type Err = GitrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let at = s.find([':', '=', '>', '<']).ok_or_else(|| {
+            invalid(format!("'{s}' has no operator (e.g. host:gh, behind>0)"))
+        })?;
+        let (field, rest) = s.split_at(at);
+        let (op, value) = if let Some(v) = rest.strip_prefix(">=") {
+            (Op::Ge, v)
+        } else if let Some(v) = rest.strip_prefix("<=") {
+            (Op::Le, v)
+        } else if let Some(v) = rest.strip_prefix('>') {
+            (Op::Gt, v)
+        } else if let Some(v) = rest.strip_prefix('<') {
+            (Op::Lt, v)
+        } else {
+            (Op::Eq, &rest[1..])
+        };
+        let field: Field = field.parse().map_err(invalid)?;
+        if value.is_empty() {
+            return Err(invalid(format!("'{s}' has no value")));
+        }
+
+        match field.kind() {
+            FieldKind::Text | FieldKind::Bool if op != Op::Eq => {
+                return Err(invalid(format!("{field} only supports ':'")));
+            }
+            FieldKind::Bool if value != "true" && value != "false" => {
+                return Err(invalid(format!(
+                    "{field} must be true or false, not '{value}'"
+                )));
+            }
+            FieldKind::Count if value.parse::<u32>().is_err() => {
+                return Err(invalid(format!("{field} needs a number, not '{value}'")));
+            }
+            FieldKind::Age if op == Op::Eq && value != "never" => {
+                return Err(invalid(format!(
+                    "{field} needs >, >=, < or <= with an age, or ':never'"
+                )));
+            }
+            FieldKind::Age if op != Op::Eq && parse_age(value).is_none() => {
+                return Err(invalid(format!(
+                    "{field} needs an age such as 30m, 12h, 7d or 2w, not '{value}'"
+                )));
+            }
+            _ => {}
+        }
+
+        Ok(Term {
+            field,
+            op,
+            value: value.to_string(),
+        })
+    }
+}
+
+impl std::fmt::Display for Term {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.value.contains(|c: char| c.is_whitespace() || c == '(' || c == ')') {
+            write!(f, "{}{}\"{}\"", self.field, self.op, self.value)
+        } else {
+            write!(f, "{}{}{}", self.field, self.op, self.value)
+        }
+    }
+}
+
+fn invalid(message: String) -> GitrError {
+    GitrError::InvalidFilter { message }
+}
+
+/// A filter expression tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Term(Term),
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+    Not(Box<Expr>),
+}
+
+impl Expr {
+    /// Binding strength, for deciding where `Display` needs parentheses.
+    fn precedence(&self) -> u8 {
+        match self {
+            Expr::Or(_) => 1,
+            Expr::And(_) => 2,
+            Expr::Not(_) | Expr::Term(_) => 3,
+        }
+    }
+
+    fn fmt_within(&self, f: &mut std::fmt::Formatter<'_>, parent: u8) -> std::fmt::Result {
+        if self.precedence() < parent {
+            write!(f, "(")?;
+            self.fmt_within(f, 0)?;
+            return write!(f, ")");
+        }
+        let join = |f: &mut std::fmt::Formatter<'_>, exprs: &[Expr], sep: &str| {
+            for (i, expr) in exprs.iter().enumerate() {
+                if i > 0 {
+                    write!(f, "{sep}")?;
+                }
+                expr.fmt_within(f, self.precedence() + 1)?;
+            }
+            Ok(())
+        };
+        match self {
+            Expr::Term(term) => write!(f, "{term}"),
+            Expr::And(exprs) => join(f, exprs, " "),
+            Expr::Or(exprs) => join(f, exprs, " or "),
+            Expr::Not(expr) => {
+                write!(f, "not ")?;
+                expr.fmt_within(f, 3)
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Expr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_within(f, 0)
+    }
+}
+
+/// A parsed filter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filter {
+    pub expr: Expr,
+}
+
+impl std::str::FromStr for Filter {
+    type Err = GitrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens = lex(s)?;
+        if tokens.is_empty() {
+            return Err(invalid("empty filter".to_string()));
+        }
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser.or()?;
+        if let Some(token) = parser.tokens.get(parser.pos) {
+            return Err(invalid(format!("unexpected {token}")));
+        }
+        Ok(Filter { expr })
+    }
+}
+
+impl std::fmt::Display for Filter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.expr)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Open,
+    Close,
+    And,
+    Or,
+    Not,
+    Term(String),
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Open => write!(f, "'('"),
+            Token::Close => write!(f, "')'"),
+            Token::And => write!(f, "'and'"),
+            Token::Or => write!(f, "'or'"),
+            Token::Not => write!(f, "'not'"),
+            Token::Term(term) => write!(f, "'{term}'"),
+        }
+    }
+}
+
+fn lex(s: &str) -> Result<Vec<Token>, GitrError> {
+    let mut tokens = Vec::new();
+    let mut chars = s.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::Open);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::Close);
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '(' || c == ')' {
+                        break;
+                    }
+                    chars.next();
+                    if c == '"' {
+                        loop {
+                            match chars.next() {
+                                Some('"') => break,
+                                Some(c) => word.push(c),
+                                None => return Err(invalid("unclosed quote".to_string())),
+                            }
+                        }
+                    } else {
+                        word.push(c);
+                    }
+                }
+                match word.to_lowercase().as_str() {
+                    "and" | "&&" => tokens.push(Token::And),
+                    "or" | "||" => tokens.push(Token::Or),
+                    "not" | "!" | "-" => tokens.push(Token::Not),
+                    _ => match word.strip_prefix(['-', '!']) {
+                        Some(rest) => {
+                            tokens.push(Token::Not);
+                            tokens.push(Token::Term(rest.to_string()));
+                        }
+                        None => tokens.push(Token::Term(word)),
+                    },
+                }
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive-descent parser: `or` binds loosest, then `and` (explicit or
+/// by juxtaposition), then `not`.
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn or(&mut self) -> Result<Expr, GitrError> {
+        let mut exprs = vec![self.and()?];
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            exprs.push(self.and()?);
+        }
+        Ok(if exprs.len() == 1 {
+            exprs.remove(0)
+        } else {
+            Expr::Or(exprs)
+        })
+    }
+
+    fn and(&mut self) -> Result<Expr, GitrError> {
+        let mut exprs = vec![self.unary()?];
+        loop {
+            match self.peek() {
+                Some(Token::And) => {
+                    self.pos += 1;
+                    exprs.push(self.unary()?);
+                }
+                Some(Token::Open | Token::Not | Token::Term(_)) => exprs.push(self.unary()?),
+                _ => break,
+            }
+        }
+        Ok(if exprs.len() == 1 {
+            exprs.remove(0)
+        } else {
+            Expr::And(exprs)
+        })
+    }
+
+    fn unary(&mut self) -> Result<Expr, GitrError> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or_else(|| invalid("filter ends early".to_string()))?;
+        self.pos += 1;
+        match token {
+            Token::Not => Ok(Expr::Not(Box::new(self.unary()?))),
+            Token::Open => {
+                let expr = self.or()?;
+                if self.peek() != Some(&Token::Close) {
+                    return Err(invalid("missing ')'".to_string()));
+                }
+                self.pos += 1;
+                Ok(expr)
+            }
+            Token::Term(term) => Ok(Expr::Term(term.parse()?)),
+            other => Err(invalid(format!("unexpected {other}"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn term(field: Field, op: Op, value: &str) -> Expr {
+        Expr::Term(Term {
+            field,
+            op,
+            value: value.to_string(),
+        })
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let filter: Filter = "host:gh fork:true owner:acme behind>0 lang:rust archived:false"
+            .parse()
+            .unwrap();
+        let Expr::And(terms) = &filter.expr else {
+            panic!("expected a conjunction, got {:?}", filter.expr);
+        };
+        assert_eq!(terms.len(), 6);
+        assert_eq!(terms[3], term(Field::Behind, Op::Gt, "0"));
+        assert_eq!(
+            filter.to_string(),
+            "host:gh fork:true owner:acme behind>0 lang:rust archived:false"
+        );
+        assert_eq!(
+            "language=Go ahead>=2".parse::<Filter>().unwrap().to_string(),
+            "lang:Go ahead>=2"
+        );
+    }
+
+    #[test]
+    fn test_boolean_operators() {
+        let filter: Filter = "fork:true and (host:gh OR -local:true) not archived:true"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            filter.expr,
+            Expr::And(vec![
+                term(Field::Fork, Op::Eq, "true"),
+                Expr::Or(vec![
+                    term(Field::Host, Op::Eq, "gh"),
+                    Expr::Not(Box::new(term(Field::Local, Op::Eq, "true"))),
+                ]),
+                Expr::Not(Box::new(term(Field::Archived, Op::Eq, "true"))),
+            ])
+        );
+        assert_eq!(
+            filter.to_string(),
+            "fork:true (host:gh or not local:true) not archived:true"
+        );
+
+        // `or` binds looser than juxtaposition.
+        let filter: Filter = "owner:a name:x or owner:b".parse().unwrap();
+        assert!(matches!(&filter.expr, Expr::Or(branches) if branches.len() == 2));
+        assert_eq!(filter.to_string(), "owner:a name:x or owner:b");
+
+        let filter: Filter = "!(behind>0 || synced>7d) owner:\"my org\"".parse().unwrap();
+        assert_eq!(
+            filter.to_string(),
+            "not (behind>0 or synced>7d) owner:\"my org\""
+        );
+        // Display output parses back to the same tree.
+        assert_eq!(filter.to_string().parse::<Filter>().unwrap(), filter);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for bad in [
+            "",
+            "fork",
+            "color:red",
+            "fork:maybe",
+            "behind>lots",
+            "owner>acme",
+            "host:",
+            "synced:7d",
+            "synced>soon",
+            "(fork:true",
+            "fork:true)",
+            "host:gh or",
+            "not",
+            "owner:\"acme",
+        ] {
+            assert!(
+                matches!(bad.parse::<Filter>(), Err(GitrError::InvalidFilter { .. })),
+                "{bad:?} should not parse"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_age() {
+        assert_eq!(parse_age("30m"), Some(Duration::minutes(30)));
+        assert_eq!(parse_age("2w"), Some(Duration::weeks(2)));
+        assert_eq!(parse_age("7"), None);
+        assert_eq!(parse_age("d"), None);
+    }
+}
@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Points in the fork sync flow where a user hook can run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HookPoint {
+    /// Before fetching upstream. Non-zero exit aborts the sync.
+    PreFetch,
+    /// After fetching upstream, before merging. Non-zero exit aborts the sync.
+    PostFetch,
+    /// After merging, before pushing to origin. Non-zero exit aborts the push.
+    PrePush,
+    /// After a successful sync.
+    PostSync,
+    /// After a failed sync.
+    OnFailure,
+}
+
+impl HookPoint {
+    /// Whether a non-zero exit from this hook fails the sync.
+    pub fn is_blocking(&self) -> bool {
+        matches!(
+            self,
+            HookPoint::PreFetch | HookPoint::PostFetch | HookPoint::PrePush
+        )
+    }
+}
+
+impl std::fmt::Display for HookPoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HookPoint::PreFetch => write!(f, "pre-fetch"),
+            HookPoint::PostFetch => write!(f, "post-fetch"),
+            HookPoint::PrePush => write!(f, "pre-push"),
+            HookPoint::PostSync => write!(f, "post-sync"),
+            HookPoint::OnFailure => write!(f, "on-failure"),
+        }
+    }
+}
+
+/// Shell commands to run at each hook point. Unset points are skipped.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncHooks {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_fetch: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_fetch: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_push: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_sync: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_failure: Option<String>,
+}
+
+impl SyncHooks {
+    /// The command configured for `point`, if any.
+    pub fn get(&self, point: HookPoint) -> Option<&str> {
+        match point {
+            HookPoint::PreFetch => self.pre_fetch.as_deref(),
+            HookPoint::PostFetch => self.post_fetch.as_deref(),
+            HookPoint::PrePush => self.pre_push.as_deref(),
+            HookPoint::PostSync => self.post_sync.as_deref(),
+            HookPoint::OnFailure => self.on_failure.as_deref(),
+        }
+    }
+
+    /// These hooks with any points set in `overrides` replaced.
+    pub fn merged(&self, overrides: &SyncHooks) -> SyncHooks {
+        let pick = |o: &Option<String>, base: &Option<String>| o.clone().or_else(|| base.clone());
+        SyncHooks {
+            pre_fetch: pick(&overrides.pre_fetch, &self.pre_fetch),
+            post_fetch: pick(&overrides.post_fetch, &self.post_fetch),
+            pre_push: pick(&overrides.pre_push, &self.pre_push),
+            post_sync: pick(&overrides.post_sync, &self.post_sync),
+            on_failure: pick(&overrides.on_failure, &self.on_failure),
+        }
+    }
+}
+
+/// The `[hooks]` config section: global hooks plus per-repo overrides.
+///
+/// ```toml
+/// [hooks]
+/// pre_push = "cargo check"
+///
+/// [hooks.repos."me/my-fork"]
+/// post_sync = "./scripts/regen-lockfile.sh"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HooksConfig {
+    #[serde(flatten)]
+    pub global: SyncHooks,
+
+    /// Overrides keyed by repo full name (`owner/name`).
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub repos: HashMap<String, SyncHooks>,
+}
+
+impl HooksConfig {
+    /// Effective hooks for a repo: the global set with its overrides applied.
+    pub fn for_repo(&self, full_name: &str) -> SyncHooks {
+        match self.repos.get(full_name) {
+            Some(overrides) => self.global.merged(overrides),
+            None => self.global.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_per_repo_overrides() {
+        let config: HooksConfig = toml::from_str(
+            r#"
+            pre_push = "cargo check"
+            post_sync = "echo done"
+
+            [repos."me/fork"]
+            post_sync = "./regen.sh"
+            "#,
+        )
+        .unwrap();
+
+        let other = config.for_repo("me/other");
+        assert_eq!(other.get(HookPoint::PrePush), Some("cargo check"));
+        assert_eq!(other.get(HookPoint::PostSync), Some("echo done"));
+
+        let fork = config.for_repo("me/fork");
+        assert_eq!(fork.get(HookPoint::PrePush), Some("cargo check"));
+        assert_eq!(fork.get(HookPoint::PostSync), Some("./regen.sh"));
+        assert_eq!(fork.get(HookPoint::OnFailure), None);
+    }
+}
@@ -0,0 +1,335 @@
+//! Where gitr puts the clones it makes: the `[clone]` config section and its
+//! path template.
+//!
+//! ```toml
+//! [clone]
+//! root = "~/src"
+//! layout = "{root}/{host}/{owner}/{name}"
+//! protocol = "ssh"
+//!
+//! [clone.hosts]
+//! work = "https"
+//! ```
+//!
+//! A template may use `{root}`, `{host}` (the domain git is served from,
+//! e.g. `github.com`), `{label}` (the host's label, or its domain for repos
+//! on no registered host), `{owner}`, `{name}` and `{full_name}`.
+
+use std::collections::HashMap;
+use std::path::{Component, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::GitrError;
+use crate::models::host::Host;
+use crate::models::repo::Repo;
+
+/// The layout ghq uses: one directory per domain, then owner and name.
+pub const DEFAULT_LAYOUT: &str = "{root}/{host}/{owner}/{name}";
+
+/// Transport used for clone and remote URLs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CloneProtocol {
+    #[default]
+    Https,
+    Ssh,
+}
+
+impl std::fmt::Display for CloneProtocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CloneProtocol::Https => write!(f, "https"),
+            CloneProtocol::Ssh => write!(f, "ssh"),
+        }
+    }
+}
+
+impl std::str::FromStr for CloneProtocol {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "https" => Ok(CloneProtocol::Https),
+            "ssh" => Ok(CloneProtocol::Ssh),
+            _ => Err(format!("unknown clone protocol: {s}")),
+        }
+    }
+}
+
+impl CloneProtocol {
+    /// `url` rewritten to this protocol: `https://github.com/me/a.git`
+    /// becomes `git@github.com:me/a.git` and back. SSH ports are dropped
+    /// going to HTTPS. Local paths and other schemes are returned as-is.
+    pub fn rewrite(self, url: &str) -> String {
+        let Some(remote) = RemoteUrl::parse(url) else {
+            return url.to_string();
+        };
+        match (self, remote.scheme) {
+            (CloneProtocol::Https, "https") | (CloneProtocol::Ssh, "ssh") => url.to_string(),
+            (CloneProtocol::Https, "ssh") => format!("https://{}/{}", remote.domain, remote.path),
+            (CloneProtocol::Ssh, "https") => format!("git@{}:{}", remote.domain, remote.path),
+            _ => url.to_string(),
+        }
+    }
+}
+
+/// The domain `repo` is cloned from, lowercased, or `local` when its clone
+/// URL has none (a path or `file://` URL).
+pub fn clone_domain(repo: &Repo) -> String {
+    RemoteUrl::parse(&repo.clone_url)
+        .map_or("local", |u| u.domain)
+        .to_lowercase()
+}
+
+/// The parts of a git URL the layout needs.
+struct RemoteUrl<'a> {
+    /// `https` or `ssh`; scp-like `user@host:path` counts as `ssh`.
+    scheme: &'a str,
+    domain: &'a str,
+    path: &'a str,
+}
+
+impl<'a> RemoteUrl<'a> {
+    fn parse(url: &'a str) -> Option<Self> {
+        let (scheme, rest, sep) = match url.split_once("://") {
+            Some(("file", _)) => return None,
+            Some((scheme, rest)) => (scheme, rest, '/'),
+            // scp-like syntax needs a ':' before any '/'.
+            None => match (url.find(':'), url.find('/')) {
+                (Some(colon), slash) if slash.is_none_or(|s| colon < s) => ("ssh", url, ':'),
+                _ => return None,
+            },
+        };
+        let rest = match rest.split_once('@') {
+            Some((user, host_path)) if !user.contains('/') => host_path,
+            _ => rest,
+        };
+        let (host, path) = rest.split_once(sep)?;
+        let domain = host.split(':').next().unwrap_or(host);
+        let scheme = match scheme {
+            "https" | "http" => "https",
+            "ssh" | "git+ssh" | "ssh+git" => "ssh",
+            other => other,
+        };
+        (!domain.is_empty() && !path.is_empty()).then_some(Self {
+            scheme,
+            domain,
+            path,
+        })
+    }
+}
+
+/// The `[clone]` config section.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CloneConfig {
+    /// Directory `{root}` stands for; `~/` is expanded. Defaults to `~/src`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root: Option<PathBuf>,
+
+    /// Path template for new clones.
+    #[serde(default = "default_layout")]
+    pub layout: String,
+
+    /// Protocol for clone and `upstream` URLs.
+    #[serde(default)]
+    pub protocol: CloneProtocol,
+
+    /// Protocol overrides keyed by host label.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub hosts: HashMap<String, CloneProtocol>,
+}
+
+fn default_layout() -> String {
+    DEFAULT_LAYOUT.to_string()
+}
+
+impl Default for CloneConfig {
+    fn default() -> Self {
+        Self {
+            root: None,
+            layout: default_layout(),
+            protocol: CloneProtocol::default(),
+            hosts: HashMap::new(),
+        }
+    }
+}
+
+impl CloneConfig {
+    /// The directory `{root}` expands to.
+    pub fn root_dir(&self) -> Result<PathBuf, GitrError> {
+        let home = || {
+            dirs::home_dir().ok_or_else(|| GitrError::Config {
+                message: "could not determine home directory".into(),
+            })
+        };
+        match &self.root {
+            None => Ok(home()?.join("src")),
+            Some(root) => match root.strip_prefix("~") {
+                Ok(rest) => Ok(home()?.join(rest)),
+                Err(_) => Ok(root.clone()),
+            },
+        }
+    }
+
+    /// The protocol to use for repos on the host labelled `label`.
+    pub fn protocol_for(&self, label: Option<&str>) -> CloneProtocol {
+        label
+            .and_then(|l| self.hosts.get(l))
+            .copied()
+            .unwrap_or(self.protocol)
+    }
+
+    /// Where the layout puts `repo`, which lives on `host` (`None` for
+    /// repos on no registered host, whose domain comes from the clone URL).
+    pub fn path_for(&self, repo: &Repo, host: Option<&Host>) -> Result<PathBuf, GitrError> {
+        let domain = host
+            .and_then(|h| h.git_domain())
+            .map_or_else(|| clone_domain(repo), str::to_lowercase);
+        let label = host.map_or(domain.as_str(), |h| h.label.as_str());
+        let root = self.root_dir()?;
+
+        let mut path = String::new();
+        let mut rest = self.layout.as_str();
+        while let Some(start) = rest.find('{') {
+            path.push_str(&rest[..start]);
+            let end = rest[start..]
+                .find('}')
+                .map(|i| start + i)
+                .ok_or_else(|| layout_error(&self.layout, "unclosed '{'"))?;
+            let value = match &rest[start + 1..end] {
+                "root" => root.to_string_lossy(),
+                "host" => domain.as_str().into(),
+                "label" => label.into(),
+                "owner" => repo.owner.as_str().into(),
+                "name" => repo.name.as_str().into(),
+                "full_name" => repo.full_name.as_str().into(),
+                other => {
+                    return Err(layout_error(
+                        &self.layout,
+                        &format!("unknown field {{{other}}}"),
+                    ))
+                }
+            };
+            path.push_str(&value);
+            rest = &rest[end + 1..];
+        }
+        path.push_str(rest);
+
+        let path = PathBuf::from(path);
+        if !path.is_absolute() {
+            return Err(layout_error(&self.layout, "the path must be absolute"));
+        }
+        if path.components().any(|c| c == Component::ParentDir) {
+            return Err(GitrError::Config {
+                message: format!("clone path for {} escapes its directory", repo.full_name),
+            });
+        }
+        Ok(path.components().collect())
+    }
+}
+
+fn layout_error(layout: &str, problem: &str) -> GitrError {
+    GitrError::Config {
+        message: format!("invalid clone layout '{layout}': {problem}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::host::HostKind;
+    use crate::models::repo::DiscoverySource;
+    use std::path::Path;
+
+    #[test]
+    fn test_rewrite() {
+        let ssh = CloneProtocol::Ssh;
+        let https = CloneProtocol::Https;
+        assert_eq!(
+            ssh.rewrite("https://github.com/me/a.git"),
+            "git@github.com:me/a.git"
+        );
+        assert_eq!(
+            https.rewrite("git@github.com:me/a.git"),
+            "https://github.com/me/a.git"
+        );
+        assert_eq!(
+            https.rewrite("ssh://git@gitlab.example.com:2222/group/sub/a.git"),
+            "https://gitlab.example.com/group/sub/a.git"
+        );
+        assert_eq!(
+            https.rewrite("https://github.com/me/a.git"),
+            "https://github.com/me/a.git"
+        );
+        assert_eq!(ssh.rewrite("/srv/git/a.git"), "/srv/git/a.git");
+        assert_eq!(
+            ssh.rewrite("file:///srv/git/a.git"),
+            "file:///srv/git/a.git"
+        );
+    }
+
+    #[test]
+    fn test_path_for() {
+        let config = CloneConfig {
+            root: Some("/src".into()),
+            ..CloneConfig::default()
+        };
+        let host = Host::new("gh".to_string(), HostKind::GitHub, "me".to_string());
+        let repo = Repo::new(
+            "Me/tool".to_string(),
+            host.id.clone(),
+            "https://github.com/Me/tool.git".to_string(),
+            "main".to_string(),
+            DiscoverySource::Api,
+        );
+        assert_eq!(
+            config.path_for(&repo, Some(&host)).unwrap(),
+            Path::new("/src/github.com/Me/tool")
+        );
+
+        let plain = Repo::without_host(
+            "them/lib".to_string(),
+            "git@codeberg.org:them/lib.git".to_string(),
+            "main".to_string(),
+            DiscoverySource::Manual,
+        );
+        let by_label = CloneConfig {
+            layout: "{root}/{label}/{full_name}".to_string(),
+            ..config.clone()
+        };
+        assert_eq!(
+            by_label.path_for(&repo, Some(&host)).unwrap(),
+            Path::new("/src/gh/Me/tool")
+        );
+        assert_eq!(
+            by_label.path_for(&plain, None).unwrap(),
+            Path::new("/src/codeberg.org/them/lib")
+        );
+
+        for layout in ["{root}/{nope}", "{root}/{name", "{host}/{name}"] {
+            let bad = CloneConfig {
+                layout: layout.to_string(),
+                ..config.clone()
+            };
+            assert!(bad.path_for(&repo, Some(&host)).is_err(), "{layout}");
+        }
+    }
+
+    #[test]
+    fn test_protocol_for() {
+        let config: CloneConfig = toml::from_str(
+            r#"
+            protocol = "ssh"
+
+            [hosts]
+            work = "https"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.layout, DEFAULT_LAYOUT);
+        assert_eq!(config.protocol_for(Some("gh")), CloneProtocol::Ssh);
+        assert_eq!(config.protocol_for(Some("work")), CloneProtocol::Https);
+        assert_eq!(config.protocol_for(None), CloneProtocol::Ssh);
+    }
+}
@@ -1,3 +1,10 @@
+pub mod changelog;
 pub mod config;
 pub mod error;
+pub mod filter;
+pub mod hooks;
+pub mod layout;
+pub mod metrics;
 pub mod models;
+pub mod notify;
+pub mod retry;
@@ -0,0 +1,201 @@
+use std::path::Path;
+use std::sync::LazyLock;
+
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGaugeVec, Opts, Registry, TextEncoder,
+};
+
+use crate::error::GitrError;
+use crate::models::sync_state::SyncStatus;
+
+/// Process-wide Prometheus metrics, served on `/metrics` by `gitr daemon` and
+/// written as a node_exporter textfile after one-shot commands.
+struct Metrics {
+    registry: Registry,
+    sync_attempts: IntCounterVec,
+    commits_transferred: IntCounterVec,
+    behind: IntGaugeVec,
+    api_requests: IntCounterVec,
+    rate_limit_remaining: IntGaugeVec,
+    scan_duration: HistogramVec,
+}
+
+static METRICS: LazyLock<Metrics> = LazyLock::new(|| {
+    let registry = Registry::new();
+    let sync_attempts = IntCounterVec::new(
+        Opts::new("gitr_sync_attempts_total", "Repo sync attempts by outcome"),
+        &["repo", "status"],
+    )
+    .expect("valid metric");
+    let commits_transferred = IntCounterVec::new(
+        Opts::new(
+            "gitr_commits_transferred_total",
+            "Upstream commits brought into repos by syncs",
+        ),
+        &["repo"],
+    )
+    .expect("valid metric");
+    let behind = IntGaugeVec::new(
+        Opts::new(
+            "gitr_repo_behind_commits",
+            "Commits a fork branch is behind its upstream, as of the last refresh",
+        ),
+        &["repo", "branch"],
+    )
+    .expect("valid metric");
+    let api_requests = IntCounterVec::new(
+        Opts::new(
+            "gitr_api_requests_total",
+            "Host API requests by HTTP status",
+        ),
+        &["host", "status"],
+    )
+    .expect("valid metric");
+    let rate_limit_remaining = IntGaugeVec::new(
+        Opts::new(
+            "gitr_api_rate_limit_remaining",
+            "API requests left in the host's current rate-limit window",
+        ),
+        &["host"],
+    )
+    .expect("valid metric");
+    let scan_duration = HistogramVec::new(
+        HistogramOpts::new("gitr_scan_duration_seconds", "Time spent discovering repos")
+            .buckets(vec![0.1, 0.5, 1.0, 5.0, 15.0, 60.0, 300.0]),
+        &["host", "phase"],
+    )
+    .expect("valid metric");
+
+    registry
+        .register(Box::new(sync_attempts.clone()))
+        .expect("metric registered once");
+    registry
+        .register(Box::new(commits_transferred.clone()))
+        .expect("metric registered once");
+    registry
+        .register(Box::new(behind.clone()))
+        .expect("metric registered once");
+    registry
+        .register(Box::new(api_requests.clone()))
+        .expect("metric registered once");
+    registry
+        .register(Box::new(rate_limit_remaining.clone()))
+        .expect("metric registered once");
+    registry
+        .register(Box::new(scan_duration.clone()))
+        .expect("metric registered once");
+
+    Metrics {
+        registry,
+        sync_attempts,
+        commits_transferred,
+        behind,
+        api_requests,
+        rate_limit_remaining,
+        scan_duration,
+    }
+});
+
+/// Count a finished sync of `repo` and the commits it brought in.
+pub fn record_sync(repo: &str, status: &SyncStatus, commits: u32) {
+    let status = status.to_string();
+    METRICS
+        .sync_attempts
+        .with_label_values(&[repo, status.as_str()])
+        .inc();
+    if commits > 0 {
+        METRICS
+            .commits_transferred
+            .with_label_values(&[repo])
+            .inc_by(u64::from(commits));
+    }
+}
+
+/// Set how far `branch` of `repo` is behind its upstream.
+pub fn set_behind(repo: &str, branch: &str, behind: u32) {
+    METRICS
+        .behind
+        .with_label_values(&[repo, branch])
+        .set(i64::from(behind));
+}
+
+/// Count an API request to `host`; `status` is the HTTP status, or `None` if
+/// no response arrived.
+pub fn record_api_request(host: &str, status: Option<u16>) {
+    let status = status.map_or_else(|| "error".to_string(), |s| s.to_string());
+    METRICS
+        .api_requests
+        .with_label_values(&[host, status.as_str()])
+        .inc();
+}
+
+pub fn set_rate_limit_remaining(host: &str, remaining: u32) {
+    METRICS
+        .rate_limit_remaining
+        .with_label_values(&[host])
+        .set(i64::from(remaining));
+}
+
+/// Record how long one phase of a scan (`filesystem`, `api`) took for `host`.
+pub fn observe_scan(host: &str, phase: &str, seconds: f64) {
+    METRICS
+        .scan_duration
+        .with_label_values(&[host, phase])
+        .observe(seconds);
+}
+
+/// All metrics in the Prometheus text exposition format.
+pub fn render() -> String {
+    let mut buf = Vec::new();
+    TextEncoder::new()
+        .encode(&METRICS.registry.gather(), &mut buf)
+        .expect("text encoding into a Vec cannot fail");
+    String::from_utf8(buf).expect("exposition format is UTF-8")
+}
+
+/// Write all metrics to `path` for node_exporter's textfile collector. The
+/// file is replaced atomically so a scrape never sees a partial write.
+pub fn write_textfile(path: &Path) -> Result<(), GitrError> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    std::fs::write(&tmp, render())?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_and_write_textfile() {
+        record_sync("me/metrics-test", &SyncStatus::Success, 3);
+        record_sync("me/metrics-test", &SyncStatus::Failed, 0);
+        set_behind("me/metrics-test", "main", 7);
+        record_api_request("api.example.com", Some(200));
+        record_api_request("api.example.com", None);
+        set_rate_limit_remaining("api.example.com", 4999);
+        observe_scan("gh", "api", 0.25);
+
+        let text = render();
+        assert!(
+            text.contains(r#"gitr_sync_attempts_total{repo="me/metrics-test",status="success"} 1"#)
+        );
+        assert!(text.contains(r#"gitr_commits_transferred_total{repo="me/metrics-test"} 3"#));
+        assert!(
+            text.contains(r#"gitr_repo_behind_commits{branch="main",repo="me/metrics-test"} 7"#)
+        );
+        assert!(
+            text.contains(r#"gitr_api_requests_total{host="api.example.com",status="error"} 1"#)
+        );
+        assert!(text.contains(r#"gitr_api_rate_limit_remaining{host="api.example.com"} 4999"#));
+        assert!(text.contains(r#"gitr_scan_duration_seconds_count{host="gh",phase="api"} 1"#));
+
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("gitr.prom");
+        write_textfile(&path).unwrap();
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert!(written.contains("gitr_sync_attempts_total"));
+        assert!(!tmp.path().join("gitr.prom.tmp").exists());
+    }
+}
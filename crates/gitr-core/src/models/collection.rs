@@ -17,6 +17,12 @@ impl CollectionId {
     }
 }
 
+impl Default for CollectionId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl std::fmt::Display for CollectionId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.0)
@@ -29,6 +35,11 @@ pub struct Collection {
     pub id: CollectionId,
     pub name: String,
     pub description: Option<String>,
+    /// A saved [`Filter`](crate::filter::Filter) expression. When set, the
+    /// members are whichever repos match it at use time rather than the
+    /// `collection_members` rows.
+    #[serde(default)]
+    pub query: Option<String>,
 }
 
 impl Collection {
@@ -37,8 +48,14 @@ impl Collection {
             id: CollectionId::new(),
             name,
             description,
+            query: None,
         }
     }
+
+    /// Whether membership comes from a saved query.
+    pub fn is_smart(&self) -> bool {
+        self.query.is_some()
+    }
 }
 
 /// A membership entry linking a collection to a repo.
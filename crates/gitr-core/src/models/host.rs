@@ -16,6 +16,12 @@ impl HostId {
     }
 }
 
+impl Default for HostId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl std::fmt::Display for HostId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.0)
@@ -98,4 +104,17 @@ impl Host {
             credential_key,
         }
     }
+
+    /// Domain the host serves git from, judged by its API URL:
+    /// `api.github.com` serves `github.com`, while a self-hosted
+    /// `git.example.com/api/v4` serves `git.example.com`.
+    pub fn git_domain(&self) -> Option<&str> {
+        let domain = self.api_url.host_str()?;
+        Some(domain.strip_prefix("api.").unwrap_or(domain))
+    }
+
+    /// Key of the shared secret used to verify this host's webhooks.
+    pub fn webhook_secret_key(&self) -> String {
+        format!("{}:webhook", self.credential_key)
+    }
 }
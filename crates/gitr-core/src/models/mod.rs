@@ -1,5 +1,7 @@
 pub mod collection;
 pub mod host;
 pub mod repo;
+pub mod scan_index;
+pub mod schedule;
 pub mod sync_link;
 pub mod sync_state;
@@ -19,6 +19,12 @@ impl RepoId {
     }
 }
 
+impl Default for RepoId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl std::fmt::Display for RepoId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.0)
@@ -67,10 +73,19 @@ pub struct Repo {
     pub full_name: String,
     pub owner: String,
     pub name: String,
-    pub host_id: HostId,
+    /// The registered host the repo lives on; `None` for repos tracked with
+    /// plain git only.
+    pub host_id: Option<HostId>,
     pub clone_url: String,
     pub local_path: Option<PathBuf>,
     pub is_fork: bool,
+    #[serde(default)]
+    pub is_private: bool,
+    #[serde(default)]
+    pub is_archived: bool,
+    /// Primary language reported by the host.
+    #[serde(default)]
+    pub language: Option<String>,
     pub upstream_repo_id: Option<RepoId>,
     pub upstream_full_name: Option<String>,
     pub upstream_clone_url: Option<String>,
@@ -78,6 +93,13 @@ pub struct Repo {
     pub discovery_source: DiscoverySource,
     pub last_synced_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
+    /// Description reported by the host.
+    #[serde(default)]
+    pub description: Option<String>,
+    /// When a scan found the repo deleted on its host (or no longer
+    /// visible to the account); `None` while it exists.
+    #[serde(default)]
+    pub gone_at: Option<DateTime<Utc>>,
 }
 
 impl Repo {
@@ -100,10 +122,13 @@ impl Repo {
             full_name,
             owner,
             name,
-            host_id,
+            host_id: Some(host_id),
             clone_url,
             local_path: None,
             is_fork: false,
+            is_private: false,
+            is_archived: false,
+            language: None,
             upstream_repo_id: None,
             upstream_full_name: None,
             upstream_clone_url: None,
@@ -111,6 +136,80 @@ impl Repo {
             discovery_source,
             last_synced_at: None,
             created_at: now,
+            description: None,
+            gone_at: None,
+        }
+    }
+
+    /// A repo on no registered host, tracked with plain git only.
+    pub fn without_host(
+        full_name: String,
+        clone_url: String,
+        default_branch: String,
+        discovery_source: DiscoverySource,
+    ) -> Self {
+        Self {
+            host_id: None,
+            ..Self::new(
+                full_name,
+                HostId::default(),
+                clone_url,
+                default_branch,
+                discovery_source,
+            )
+        }
+    }
+
+    /// Browser URL of the repo, derived from its clone URL.
+    pub fn web_url(&self) -> String {
+        let url = self.clone_url.trim_end_matches('/');
+        let url = url.strip_suffix(".git").unwrap_or(url);
+        // scp-like `git@host:owner/repo` separates host and path with ':'.
+        let (scheme, rest, sep) = match url.split_once("://") {
+            Some((scheme, rest)) => (scheme, rest, '/'),
+            None => ("ssh", url, ':'),
+        };
+        let rest = match rest.split_once('@') {
+            Some((user, host_path)) if !user.contains('/') => host_path,
+            _ => rest,
+        };
+        let (host, path) = rest.split_once(sep).unwrap_or((rest, ""));
+        // An ssh port isn't the web port.
+        let host = match scheme {
+            "ssh" | "git" => host.split(':').next().unwrap_or(host),
+            _ => host,
+        };
+        let scheme = if scheme == "http" { "http" } else { "https" };
+        format!("{scheme}://{host}/{path}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_web_url() {
+        let repo = |url: &str| {
+            Repo::new(
+                "me/fork".to_string(),
+                HostId::new(),
+                url.to_string(),
+                "main".to_string(),
+                DiscoverySource::Api,
+            )
+        };
+        for url in [
+            "https://github.com/me/fork.git",
+            "git@github.com:me/fork.git",
+            "ssh://git@github.com/me/fork",
+            "ssh://git@github.com:22/me/fork.git",
+        ] {
+            assert_eq!(repo(url).web_url(), "https://github.com/me/fork", "{url}");
         }
+        assert_eq!(
+            repo("http://git.local:3000/Team/Repo.git").web_url(),
+            "http://git.local:3000/Team/Repo"
+        );
     }
 }
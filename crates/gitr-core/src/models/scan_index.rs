@@ -0,0 +1,64 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+/// How a checkout relates to its repo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckoutKind {
+    /// The main working tree (`.git` directory, gitfile, or `core.worktree`).
+    Primary,
+    /// A linked worktree created by `git worktree add`.
+    Worktree,
+    /// A submodule whose git directory lives in its superproject's `.git/modules`.
+    Submodule,
+    /// A bare repository with no working tree.
+    Bare,
+}
+
+impl std::fmt::Display for CheckoutKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckoutKind::Primary => write!(f, "primary"),
+            CheckoutKind::Worktree => write!(f, "worktree"),
+            CheckoutKind::Submodule => write!(f, "submodule"),
+            CheckoutKind::Bare => write!(f, "bare"),
+        }
+    }
+}
+
+impl std::str::FromStr for CheckoutKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "primary" => Ok(CheckoutKind::Primary),
+            "worktree" => Ok(CheckoutKind::Worktree),
+            "submodule" => Ok(CheckoutKind::Submodule),
+            "bare" => Ok(CheckoutKind::Bare),
+            _ => Err(format!("unknown checkout kind: {s}")),
+        }
+    }
+}
+
+/// One place a repo is checked out.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkout {
+    pub path: PathBuf,
+    pub kind: CheckoutKind,
+}
+
+/// A directory as the filesystem scan last saw it. While its mtime is
+/// unchanged its subdirectory list and checkout are reused instead of
+/// reading the directory again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanIndexEntry {
+    pub path: PathBuf,
+    /// Modification time in nanoseconds since the Unix epoch.
+    pub mtime_ns: i64,
+    /// Names of the directory's subdirectories (not following symlinks),
+    /// or `None` if the scan did not descend into it.
+    pub subdirs: Option<Vec<String>>,
+    /// The git directory and checkout found here, if the directory is one.
+    pub checkout: Option<(PathBuf, Checkout)>,
+}
@@ -0,0 +1,15 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Persisted state of a scheduled daemon job, so restarts don't lose or
+/// repeat runs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScheduleState {
+    /// `link:<sync link id>` or `forks` for the fork-sync schedule.
+    pub job_id: String,
+    /// Cron expression the state was computed from. A changed expression
+    /// resets the schedule.
+    pub cron: String,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub next_run_at: DateTime<Utc>,
+}
@@ -17,6 +17,12 @@ impl SyncLinkId {
     }
 }
 
+impl Default for SyncLinkId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl std::fmt::Display for SyncLinkId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.0)
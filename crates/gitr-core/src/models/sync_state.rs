@@ -49,8 +49,14 @@ pub struct SyncRecord {
     pub branches_synced: u32,
     pub branches_failed: u32,
     pub commits_transferred: u32,
+    /// Attempts made, counting retries of transient failures (1 = no retries).
+    pub attempts: u32,
     pub status: SyncStatus,
     pub errors: Vec<String>,
+    /// Per-branch SHAs and incoming commits. Stored in `sync_history_detail`
+    /// and only loaded on request.
+    #[serde(default)]
+    pub details: Vec<SyncBranchDetail>,
     pub started_at: DateTime<Utc>,
     pub finished_at: DateTime<Utc>,
 }
@@ -65,14 +71,53 @@ impl SyncRecord {
             branches_synced: 0,
             branches_failed: 0,
             commits_transferred: 0,
+            attempts: 1,
             status: SyncStatus::Success,
             errors: Vec::new(),
+            details: Vec::new(),
             started_at: now,
             finished_at: now,
         }
     }
 }
 
+/// How one branch moved during a sync.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncBranchDetail {
+    pub branch: String,
+    pub before_sha: Option<String>,
+    pub after_sha: Option<String>,
+    /// Commits brought in from upstream, newest first.
+    pub commits: Vec<IncomingCommit>,
+}
+
+/// A commit brought in from upstream.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IncomingCommit {
+    pub sha: String,
+    pub author: String,
+    pub subject: String,
+    pub date: DateTime<Utc>,
+}
+
+impl IncomingCommit {
+    /// Conventional-commit type of the subject (`feat`, `fix`, ...), if any.
+    ///
+    /// Accepts `type: ...`, `type(scope): ...` and the breaking-change
+    /// marker `type!: ...`.
+    pub fn conventional_type(&self) -> Option<&str> {
+        let (head, _) = self.subject.split_once(':')?;
+        let head = head.strip_suffix('!').unwrap_or(head);
+        let ty = match head.split_once('(') {
+            Some((ty, scope)) if scope.ends_with(')') => ty,
+            Some(_) => return None,
+            None => head,
+        };
+        let ty = ty.strip_suffix('!').unwrap_or(ty);
+        (!ty.is_empty() && ty.chars().all(|c| c.is_ascii_alphabetic())).then_some(ty)
+    }
+}
+
 /// Snapshot of a branch's state for behind/ahead tracking.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BranchSnapshot {
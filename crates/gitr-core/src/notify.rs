@@ -0,0 +1,167 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::repo::RepoId;
+
+/// The `[notify]` section of config.toml: where alerts go and when they fire.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotifyConfig {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub channels: Vec<ChannelConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<NotifyRule>,
+}
+
+/// A named notification destination.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChannelConfig {
+    pub name: String,
+    #[serde(flatten)]
+    pub kind: ChannelKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ChannelKind {
+    /// POST the alert as JSON.
+    Webhook { url: String },
+    /// POST a Slack-compatible `{"text": ...}` message.
+    Slack { url: String },
+    /// Send an email over SMTP. The password, if `username` is set, is read
+    /// from the credential store (see [`ChannelConfig::password_key`]).
+    Email {
+        smtp_host: String,
+        #[serde(default = "default_smtp_port")]
+        smtp_port: u16,
+        #[serde(default)]
+        tls: SmtpTls,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        username: Option<String>,
+        from: String,
+        to: Vec<String>,
+    },
+    /// Run a shell command with the alert in `GITR_NOTIFY_*` variables.
+    Command { command: String },
+}
+
+/// How the SMTP connection is secured.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpTls {
+    /// Plain connection upgraded with STARTTLS (port 587).
+    #[default]
+    Starttls,
+    /// TLS from the start (port 465).
+    Tls,
+    /// Unencrypted; only for local relays.
+    None,
+}
+
+fn default_smtp_port() -> u16 {
+    587
+}
+
+impl ChannelConfig {
+    /// Credential-store key of the SMTP password for an email channel.
+    pub fn password_key(&self) -> String {
+        format!("gitr:notify:{}", self.name)
+    }
+}
+
+/// When to alert, and on which channels.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotifyRule {
+    #[serde(flatten)]
+    pub condition: RuleCondition,
+    /// Channel names; all channels when empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub channels: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "when", rename_all = "snake_case")]
+pub enum RuleCondition {
+    /// The last `count` syncs of a repo all failed.
+    ConsecutiveFailures { count: u32 },
+    /// A fork branch is more than `threshold` commits behind upstream.
+    Behind { threshold: u32 },
+}
+
+impl NotifyRule {
+    /// Stable identifier used to de-duplicate alerts, e.g. `behind>100`.
+    pub fn key(&self) -> String {
+        match &self.condition {
+            RuleCondition::ConsecutiveFailures { count } => format!("failures>={count}"),
+            RuleCondition::Behind { threshold } => format!("behind>{threshold}"),
+        }
+    }
+
+    /// Whether alerts from this rule go to `channel`.
+    pub fn targets(&self, channel: &str) -> bool {
+        self.channels.is_empty() || self.channels.iter().any(|c| c == channel)
+    }
+}
+
+/// A rule that started matching a repo. Sent as JSON by webhook channels.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Alert {
+    /// [`NotifyRule::key`] of the rule that fired.
+    pub rule: String,
+    pub repo: String,
+    pub repo_id: RepoId,
+    /// The value that crossed the threshold (failures in a row, commits behind).
+    pub value: u32,
+    pub message: String,
+    pub fired_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notify_config_from_toml() {
+        let config: NotifyConfig = toml::from_str(
+            r#"
+            [[channels]]
+            name = "ops"
+            kind = "slack"
+            url = "https://hooks.slack.com/services/T/B/X"
+
+            [[channels]]
+            name = "mail"
+            kind = "email"
+            smtp_host = "localhost"
+            tls = "none"
+            from = "gitr@example.com"
+            to = ["me@example.com"]
+
+            [[rules]]
+            when = "consecutive_failures"
+            count = 2
+
+            [[rules]]
+            when = "behind"
+            threshold = 100
+            channels = ["ops"]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.channels.len(), 2);
+        match &config.channels[1].kind {
+            ChannelKind::Email { smtp_port, tls, .. } => {
+                assert_eq!(*smtp_port, 587);
+                assert_eq!(*tls, SmtpTls::None);
+            }
+            other => panic!("expected email channel, got {other:?}"),
+        }
+        assert_eq!(config.rules[0].key(), "failures>=2");
+        assert!(config.rules[0].targets("mail"));
+        assert_eq!(config.rules[1].key(), "behind>100");
+        assert!(!config.rules[1].targets("mail"));
+
+        let roundtrip: NotifyConfig = toml::from_str(&toml::to_string(&config).unwrap()).unwrap();
+        assert_eq!(roundtrip, config);
+    }
+}
@@ -0,0 +1,161 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::error::GitrError;
+
+/// Retry budget for transient git and API failures.
+///
+/// Delays grow exponentially from `base_delay_ms`, capped at `max_delay_ms`,
+/// with "full jitter" so that parallel syncs hitting the same outage don't
+/// retry in lockstep. Rate limits are waited out until they reset, unless
+/// that is further off than `max_rate_limit_wait_secs`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    /// Total attempts per operation, including the first one. `1` disables retries.
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+
+    /// Delay before the first retry, in milliseconds.
+    #[serde(default = "default_base_delay_ms")]
+    pub base_delay_ms: u64,
+
+    /// Upper bound on any single delay, in milliseconds.
+    #[serde(default = "default_max_delay_ms")]
+    pub max_delay_ms: u64,
+
+    /// Longest wait for a rate limit to reset, in seconds. Limits that reset
+    /// later fail at once, with the reset time in the error.
+    #[serde(default = "default_max_rate_limit_wait_secs")]
+    pub max_rate_limit_wait_secs: u64,
+}
+
+fn default_max_attempts() -> u32 {
+    3
+}
+
+fn default_base_delay_ms() -> u64 {
+    500
+}
+
+fn default_max_delay_ms() -> u64 {
+    30_000
+}
+
+fn default_max_rate_limit_wait_secs() -> u64 {
+    900
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_max_attempts(),
+            base_delay_ms: default_base_delay_ms(),
+            max_delay_ms: default_max_delay_ms(),
+            max_rate_limit_wait_secs: default_max_rate_limit_wait_secs(),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries.
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Whether `err`, raised on attempt number `attempt` (1-based), should be retried.
+    pub fn should_retry(&self, attempt: u32, err: &GitrError) -> bool {
+        if let GitrError::RateLimited {
+            retry_after_secs, ..
+        } = err
+        {
+            if *retry_after_secs > self.max_rate_limit_wait_secs {
+                return false;
+            }
+        }
+        attempt < self.max_attempts && err.is_transient()
+    }
+
+    /// Delay to wait after failed attempt number `attempt` (1-based).
+    ///
+    /// A rate limit is waited out in full: its `retry_after_secs` is a floor
+    /// capped only by `max_rate_limit_wait_secs`, not `max_delay_ms`.
+    pub fn delay_for(&self, attempt: u32, err: &GitrError) -> Duration {
+        let exp = attempt.saturating_sub(1).min(20);
+        let ceiling = self
+            .base_delay_ms
+            .saturating_mul(1u64 << exp)
+            .min(self.max_delay_ms);
+        let delay_ms = fastrand::u64(ceiling / 2..=ceiling);
+
+        if let GitrError::RateLimited {
+            retry_after_secs, ..
+        } = err
+        {
+            let wait_secs = (*retry_after_secs).min(self.max_rate_limit_wait_secs);
+            return Duration::from_millis(delay_ms.max(wait_secs.saturating_mul(1000)));
+        }
+
+        Duration::from_millis(delay_ms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn network_error() -> GitrError {
+        GitrError::GitError {
+            message:
+                "git fetch upstream --prune failed: fatal: the remote end hung up unexpectedly"
+                    .into(),
+        }
+    }
+
+    #[test]
+    fn test_should_retry_respects_budget_and_classification() {
+        let policy = RetryPolicy::default();
+        assert!(policy.should_retry(1, &network_error()));
+        assert!(policy.should_retry(2, &network_error()));
+        assert!(!policy.should_retry(3, &network_error()));
+
+        let conflict = GitrError::MergeConflict {
+            branch: "upstream/main".into(),
+            message: "CONFLICT (content)".into(),
+        };
+        assert!(!policy.should_retry(1, &conflict));
+        assert!(!RetryPolicy::none().should_retry(1, &network_error()));
+    }
+
+    #[test]
+    fn test_delay_is_bounded() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            base_delay_ms: 100,
+            max_delay_ms: 1_000,
+            max_rate_limit_wait_secs: 120,
+        };
+        for attempt in 1..10 {
+            let delay = policy.delay_for(attempt, &network_error());
+            assert!(delay <= Duration::from_millis(1_000));
+        }
+        let first = policy.delay_for(1, &network_error());
+        assert!(first >= Duration::from_millis(50) && first <= Duration::from_millis(100));
+
+        let rate_limited = GitrError::RateLimited {
+            host: "github.com".into(),
+            retry_after_secs: 60,
+        };
+        assert_eq!(policy.delay_for(1, &rate_limited), Duration::from_secs(60));
+        assert!(policy.should_retry(1, &rate_limited));
+
+        let long_limit = GitrError::RateLimited {
+            host: "github.com".into(),
+            retry_after_secs: 3_600,
+        };
+        assert!(!policy.should_retry(1, &long_limit));
+        assert_eq!(policy.delay_for(1, &long_limit), Duration::from_secs(120));
+    }
+}
@@ -1,5 +1,6 @@
 pub mod migration;
 pub mod ops;
+pub mod query;
 pub mod schema;
 
 use rusqlite::Connection;
@@ -16,6 +16,38 @@ pub fn run_migrations(conn: &Connection) -> anyhow::Result<()> {
         migrate_v2(conn)?;
     }
 
+    if current < 3 {
+        migrate_v3(conn)?;
+    }
+
+    if current < 4 {
+        migrate_v4(conn)?;
+    }
+
+    if current < 5 {
+        migrate_v5(conn)?;
+    }
+
+    if current < 6 {
+        migrate_v6(conn)?;
+    }
+
+    if current < 7 {
+        migrate_v7(conn)?;
+    }
+
+    if current < 8 {
+        migrate_v8(conn)?;
+    }
+
+    if current < 9 {
+        migrate_v9(conn)?;
+    }
+
+    if current < 10 {
+        migrate_v10(conn)?;
+    }
+
     Ok(())
 }
 
@@ -58,6 +90,145 @@ fn migrate_v2(conn: &Connection) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Migration v3: add attempts column to sync_history.
+/// Idempotent for the same reason as v2.
+fn migrate_v3(conn: &Connection) -> anyhow::Result<()> {
+    tracing::info!("applying migration v3: sync_history.attempts column");
+    let has_column: bool = conn
+        .query_row(
+            "SELECT COUNT(*) FROM pragma_table_info('sync_history') WHERE name='attempts'",
+            [],
+            |row| row.get::<_, i64>(0),
+        )
+        .unwrap_or(0)
+        > 0;
+    if !has_column {
+        conn.execute_batch(
+            "ALTER TABLE sync_history ADD COLUMN attempts INTEGER NOT NULL DEFAULT 1",
+        )?;
+    }
+    set_version(conn, 3)?;
+    Ok(())
+}
+
+/// Migration v4: add sync_history_detail table (per-branch SHAs and incoming commits).
+fn migrate_v4(conn: &Connection) -> anyhow::Result<()> {
+    tracing::info!("applying migration v4: sync_history_detail table");
+    conn.execute_batch(schema::CREATE_SYNC_HISTORY_DETAIL)?;
+    set_version(conn, 4)?;
+    Ok(())
+}
+
+/// Migration v5: add schedule_state table for `gitr daemon`.
+fn migrate_v5(conn: &Connection) -> anyhow::Result<()> {
+    tracing::info!("applying migration v5: schedule_state table");
+    conn.execute_batch(schema::CREATE_SCHEDULE_STATE)?;
+    set_version(conn, 5)?;
+    Ok(())
+}
+
+/// Migration v6: add notification_state table for alert de-duplication.
+fn migrate_v6(conn: &Connection) -> anyhow::Result<()> {
+    tracing::info!("applying migration v6: notification_state table");
+    conn.execute_batch(schema::CREATE_NOTIFICATION_STATE)?;
+    set_version(conn, 6)?;
+    Ok(())
+}
+
+/// Migration v7: repo metadata used by filters (private, archived, language)
+/// and saved queries for smart collections. Idempotent like v2.
+fn migrate_v7(conn: &Connection) -> anyhow::Result<()> {
+    tracing::info!("applying migration v7: repo metadata and collection queries");
+    let columns = [
+        ("repos", "is_private", "INTEGER NOT NULL DEFAULT 0"),
+        ("repos", "is_archived", "INTEGER NOT NULL DEFAULT 0"),
+        ("repos", "language", "TEXT"),
+        ("collections", "query", "TEXT"),
+    ];
+    add_missing_columns(conn, &columns)?;
+    set_version(conn, 7)?;
+    Ok(())
+}
+
+/// Add each `(table, column, declaration)` the table doesn't have yet.
+fn add_missing_columns(conn: &Connection, columns: &[(&str, &str, &str)]) -> anyhow::Result<()> {
+    for (table, column, decl) in columns {
+        let has_column: bool = conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info(?1) WHERE name = ?2",
+                [table, column],
+                |row| row.get::<_, i64>(0),
+            )
+            .unwrap_or(0)
+            > 0;
+        if !has_column {
+            conn.execute_batch(&format!("ALTER TABLE {table} ADD COLUMN {column} {decl}"))?;
+        }
+    }
+    Ok(())
+}
+
+/// Migration v8: add scan_index table for incremental filesystem scans.
+fn migrate_v8(conn: &Connection) -> anyhow::Result<()> {
+    tracing::info!("applying migration v8: scan_index table");
+    conn.execute_batch(schema::CREATE_SCAN_INDEX)?;
+    set_version(conn, 8)?;
+    Ok(())
+}
+
+/// Migration v9: allow repos on no registered host (NULL `host_id`), for
+/// plain-git repos adopted from disk. SQLite can't drop a NOT NULL
+/// constraint, so the table is rebuilt with foreign keys switched off.
+fn migrate_v9(conn: &Connection) -> anyhow::Result<()> {
+    tracing::info!("applying migration v9: nullable repos.host_id");
+    let not_null: bool = conn.query_row(
+        "SELECT \"notnull\" FROM pragma_table_info('repos') WHERE name = 'host_id'",
+        [],
+        |row| row.get(0),
+    )?;
+    if not_null {
+        let foreign_keys: bool = conn.query_row("PRAGMA foreign_keys", [], |row| row.get(0))?;
+        conn.execute_batch("PRAGMA foreign_keys=OFF")?;
+        let columns = "id, full_name, owner, name, host_id, clone_url, local_path, is_fork, \
+                       is_private, is_archived, language, upstream_repo_id, upstream_full_name, \
+                       upstream_clone_url, default_branch, discovery_source, last_synced_at, \
+                       created_at";
+        let rebuilt = conn.execute_batch(&format!(
+            "BEGIN;
+             {create};
+             INSERT INTO repos_new ({columns}) SELECT {columns} FROM repos;
+             DROP TABLE repos;
+             ALTER TABLE repos_new RENAME TO repos;
+             COMMIT;",
+            create = schema::CREATE_REPOS.replacen("repos", "repos_new", 1),
+        ));
+        if rebuilt.is_err() {
+            let _ = conn.execute_batch("ROLLBACK");
+        }
+        if foreign_keys {
+            conn.execute_batch("PRAGMA foreign_keys=ON")?;
+        }
+        rebuilt?;
+    }
+    set_version(conn, 9)?;
+    Ok(())
+}
+
+/// Migration v10: repo descriptions, and when a repo disappeared from its
+/// host. Idempotent like v7.
+fn migrate_v10(conn: &Connection) -> anyhow::Result<()> {
+    tracing::info!("applying migration v10: repo description and gone_at");
+    add_missing_columns(
+        conn,
+        &[
+            ("repos", "description", "TEXT"),
+            ("repos", "gone_at", "TEXT"),
+        ],
+    )?;
+    set_version(conn, 10)?;
+    Ok(())
+}
+
 /// Migration v1: create all initial tables.
 fn migrate_v1(conn: &Connection) -> anyhow::Result<()> {
     tracing::info!("applying migration v1: initial schema");
@@ -81,6 +252,58 @@ mod tests {
         let conn = Connection::open_in_memory().unwrap();
         run_migrations(&conn).unwrap();
         run_migrations(&conn).unwrap();
-        assert_eq!(get_version(&conn).unwrap(), 2);
+        assert_eq!(get_version(&conn).unwrap(), 10);
+    }
+
+    #[test]
+    fn test_migration_v9_rebuilds_repos() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys=ON").unwrap();
+        run_migrations(&conn).unwrap();
+        // Recreate the pre-v9 table, with the v7 columns appended at the end.
+        conn.execute_batch(
+            "DROP TABLE repos;
+             CREATE TABLE repos (
+                 id TEXT PRIMARY KEY, full_name TEXT NOT NULL, owner TEXT NOT NULL,
+                 name TEXT NOT NULL, host_id TEXT NOT NULL, clone_url TEXT NOT NULL,
+                 local_path TEXT, is_fork INTEGER NOT NULL DEFAULT 0,
+                 upstream_repo_id TEXT, upstream_full_name TEXT, upstream_clone_url TEXT,
+                 default_branch TEXT NOT NULL DEFAULT 'main',
+                 discovery_source TEXT NOT NULL DEFAULT 'api', last_synced_at TEXT,
+                 created_at TEXT NOT NULL,
+                 is_private INTEGER NOT NULL DEFAULT 0, is_archived INTEGER NOT NULL DEFAULT 0,
+                 language TEXT,
+                 FOREIGN KEY (host_id) REFERENCES hosts(id) ON DELETE CASCADE);
+             INSERT INTO hosts (id, label, kind, api_url, username, credential_key)
+                 VALUES ('h', 'gh', 'github', 'https://api.github.com/', 'me', 'gitr:gh');
+             INSERT INTO repos (id, full_name, owner, name, host_id, clone_url, created_at, language)
+                 VALUES ('r', 'me/a', 'me', 'a', 'h', 'https://github.com/me/a.git', 'now', 'Rust');
+             INSERT INTO branch_snapshots (repo_id, branch, updated_at) VALUES ('r', 'main', 'now');
+             DELETE FROM schema_version WHERE version >= 9;",
+        )
+        .unwrap();
+
+        run_migrations(&conn).unwrap();
+        let language: String = conn
+            .query_row("SELECT language FROM repos WHERE id = 'r'", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(language, "Rust");
+        conn.execute(
+            "INSERT INTO repos (id, full_name, owner, name, clone_url, created_at)
+             VALUES ('local', 'x/y', 'x', 'y', '/src/y', 'now')",
+            [],
+        )
+        .unwrap();
+        // Foreign keys are back on and still point at the rebuilt table.
+        conn.execute("DELETE FROM repos WHERE id = 'r'", [])
+            .unwrap();
+        let snapshots: i64 = conn
+            .query_row("SELECT COUNT(*) FROM branch_snapshots", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(snapshots, 0);
     }
 }
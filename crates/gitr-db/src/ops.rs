@@ -1,14 +1,21 @@
 use chrono::{DateTime, Utc};
-use rusqlite::{params, Connection};
+use rusqlite::{params, params_from_iter, Connection};
 use uuid::Uuid;
 
+use crate::query;
+
+use gitr_core::filter::Filter;
 use gitr_core::models::collection::{Collection, CollectionId, CollectionMember};
 use gitr_core::models::host::{Host, HostId, HostKind};
 use gitr_core::models::repo::{DiscoverySource, Repo, RepoId};
+use gitr_core::models::scan_index::{Checkout, ScanIndexEntry};
+use gitr_core::models::schedule::ScheduleState;
 use gitr_core::models::sync_link::{
     MergeStrategy, SyncDirection, SyncLink, SyncLinkId, SyncTrigger,
 };
-use gitr_core::models::sync_state::{BranchSnapshot, SyncRecord, SyncStatus};
+use gitr_core::models::sync_state::{
+    BranchSnapshot, SyncBranchDetail, SyncRecord, SyncStatus,
+};
 
 // ── Helpers ──
 
@@ -73,7 +80,7 @@ pub fn list_hosts(conn: &Connection) -> anyhow::Result<Vec<Host>> {
         "SELECT id, label, kind, api_url, username, credential_key
          FROM hosts ORDER BY label",
     )?;
-    let rows = stmt.query_map([], |row| row_to_host(row))?;
+    let rows = stmt.query_map([], row_to_host)?;
     Ok(rows.filter_map(|r| r.ok()).collect())
 }
 
@@ -109,17 +116,20 @@ fn row_to_host(row: &rusqlite::Row) -> rusqlite::Result<Host> {
 
 pub fn insert_repo(conn: &Connection, repo: &Repo) -> anyhow::Result<()> {
     conn.execute(
-        "INSERT INTO repos (id, full_name, owner, name, host_id, clone_url, local_path, is_fork, upstream_repo_id, upstream_full_name, upstream_clone_url, default_branch, discovery_source, last_synced_at, created_at)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+        "INSERT INTO repos (id, full_name, owner, name, host_id, clone_url, local_path, is_fork, is_private, is_archived, language, upstream_repo_id, upstream_full_name, upstream_clone_url, default_branch, discovery_source, last_synced_at, created_at, description, gone_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20)",
         params![
             repo.id.0.to_string(),
             repo.full_name,
             repo.owner,
             repo.name,
-            repo.host_id.0.to_string(),
+            repo.host_id.as_ref().map(|id| id.0.to_string()),
             repo.clone_url,
             repo.local_path.as_ref().map(|p| p.to_string_lossy().to_string()),
             repo.is_fork as i32,
+            repo.is_private as i32,
+            repo.is_archived as i32,
+            repo.language,
             repo.upstream_repo_id.as_ref().map(|id| id.0.to_string()),
             repo.upstream_full_name,
             repo.upstream_clone_url,
@@ -127,6 +137,8 @@ pub fn insert_repo(conn: &Connection, repo: &Repo) -> anyhow::Result<()> {
             repo.discovery_source.to_string(),
             opt_dt(&repo.last_synced_at),
             fmt_dt(&repo.created_at),
+            repo.description,
+            opt_dt(&repo.gone_at),
         ],
     )?;
     Ok(())
@@ -134,7 +146,7 @@ pub fn insert_repo(conn: &Connection, repo: &Repo) -> anyhow::Result<()> {
 
 pub fn get_repo_by_id(conn: &Connection, id: &RepoId) -> anyhow::Result<Option<Repo>> {
     let mut stmt = conn.prepare(
-        "SELECT id, full_name, owner, name, host_id, clone_url, local_path, is_fork, upstream_repo_id, upstream_full_name, upstream_clone_url, default_branch, discovery_source, last_synced_at, created_at
+        "SELECT id, full_name, owner, name, host_id, clone_url, local_path, is_fork, is_private, is_archived, language, upstream_repo_id, upstream_full_name, upstream_clone_url, default_branch, discovery_source, last_synced_at, created_at, description, gone_at
          FROM repos WHERE id = ?1",
     )?;
     let mut rows = stmt.query(params![id.0.to_string()])?;
@@ -150,7 +162,7 @@ pub fn get_repo_by_full_name(
     full_name: &str,
 ) -> anyhow::Result<Option<Repo>> {
     let mut stmt = conn.prepare(
-        "SELECT id, full_name, owner, name, host_id, clone_url, local_path, is_fork, upstream_repo_id, upstream_full_name, upstream_clone_url, default_branch, discovery_source, last_synced_at, created_at
+        "SELECT id, full_name, owner, name, host_id, clone_url, local_path, is_fork, is_private, is_archived, language, upstream_repo_id, upstream_full_name, upstream_clone_url, default_branch, discovery_source, last_synced_at, created_at, description, gone_at
          FROM repos WHERE host_id = ?1 AND full_name = ?2",
     )?;
     let mut rows = stmt.query(params![host_id.0.to_string(), full_name])?;
@@ -162,28 +174,28 @@ pub fn get_repo_by_full_name(
 
 pub fn list_repos(conn: &Connection) -> anyhow::Result<Vec<Repo>> {
     let mut stmt = conn.prepare(
-        "SELECT id, full_name, owner, name, host_id, clone_url, local_path, is_fork, upstream_repo_id, upstream_full_name, upstream_clone_url, default_branch, discovery_source, last_synced_at, created_at
+        "SELECT id, full_name, owner, name, host_id, clone_url, local_path, is_fork, is_private, is_archived, language, upstream_repo_id, upstream_full_name, upstream_clone_url, default_branch, discovery_source, last_synced_at, created_at, description, gone_at
          FROM repos ORDER BY full_name",
     )?;
-    let rows = stmt.query_map([], |row| row_to_repo(row))?;
+    let rows = stmt.query_map([], row_to_repo)?;
     Ok(rows.filter_map(|r| r.ok()).collect())
 }
 
 pub fn list_repos_for_host(conn: &Connection, host_id: &HostId) -> anyhow::Result<Vec<Repo>> {
     let mut stmt = conn.prepare(
-        "SELECT id, full_name, owner, name, host_id, clone_url, local_path, is_fork, upstream_repo_id, upstream_full_name, upstream_clone_url, default_branch, discovery_source, last_synced_at, created_at
+        "SELECT id, full_name, owner, name, host_id, clone_url, local_path, is_fork, is_private, is_archived, language, upstream_repo_id, upstream_full_name, upstream_clone_url, default_branch, discovery_source, last_synced_at, created_at, description, gone_at
          FROM repos WHERE host_id = ?1 ORDER BY full_name",
     )?;
-    let rows = stmt.query_map(params![host_id.0.to_string()], |row| row_to_repo(row))?;
+    let rows = stmt.query_map(params![host_id.0.to_string()], row_to_repo)?;
     Ok(rows.filter_map(|r| r.ok()).collect())
 }
 
 pub fn list_fork_repos(conn: &Connection) -> anyhow::Result<Vec<Repo>> {
     let mut stmt = conn.prepare(
-        "SELECT id, full_name, owner, name, host_id, clone_url, local_path, is_fork, upstream_repo_id, upstream_full_name, upstream_clone_url, default_branch, discovery_source, last_synced_at, created_at
+        "SELECT id, full_name, owner, name, host_id, clone_url, local_path, is_fork, is_private, is_archived, language, upstream_repo_id, upstream_full_name, upstream_clone_url, default_branch, discovery_source, last_synced_at, created_at, description, gone_at
          FROM repos WHERE is_fork = 1 ORDER BY full_name",
     )?;
-    let rows = stmt.query_map([], |row| row_to_repo(row))?;
+    let rows = stmt.query_map([], row_to_repo)?;
     Ok(rows.filter_map(|r| r.ok()).collect())
 }
 
@@ -227,27 +239,37 @@ fn row_to_repo(row: &rusqlite::Row) -> rusqlite::Result<Repo> {
     let full_name: String = row.get(1)?;
     let owner: String = row.get(2)?;
     let name: String = row.get(3)?;
-    let host_id_str: String = row.get(4)?;
+    let host_id_str: Option<String> = row.get(4)?;
     let clone_url: String = row.get(5)?;
     let local_path: Option<String> = row.get(6)?;
     let is_fork: i32 = row.get(7)?;
-    let upstream_repo_id: Option<String> = row.get(8)?;
-    let upstream_full_name: Option<String> = row.get(9)?;
-    let upstream_clone_url: Option<String> = row.get(10)?;
-    let default_branch: String = row.get(11)?;
-    let discovery_source_str: String = row.get(12)?;
-    let last_synced_str: Option<String> = row.get(13)?;
-    let created_str: String = row.get(14)?;
+    let is_private: i32 = row.get(8)?;
+    let is_archived: i32 = row.get(9)?;
+    let language: Option<String> = row.get(10)?;
+    let upstream_repo_id: Option<String> = row.get(11)?;
+    let upstream_full_name: Option<String> = row.get(12)?;
+    let upstream_clone_url: Option<String> = row.get(13)?;
+    let default_branch: String = row.get(14)?;
+    let discovery_source_str: String = row.get(15)?;
+    let last_synced_str: Option<String> = row.get(16)?;
+    let created_str: String = row.get(17)?;
+    let description: Option<String> = row.get(18)?;
+    let gone_str: Option<String> = row.get(19)?;
 
     Ok(Repo {
         id: RepoId::from_uuid(Uuid::parse_str(&id_str).unwrap_or_default()),
         full_name,
         owner,
         name,
-        host_id: HostId::from_uuid(Uuid::parse_str(&host_id_str).unwrap_or_default()),
+        host_id: host_id_str
+            .and_then(|s| Uuid::parse_str(&s).ok())
+            .map(HostId::from_uuid),
         clone_url,
         local_path: local_path.map(Into::into),
         is_fork: is_fork != 0,
+        is_private: is_private != 0,
+        is_archived: is_archived != 0,
+        language,
         upstream_repo_id: upstream_repo_id
             .and_then(|s| Uuid::parse_str(&s).ok())
             .map(RepoId::from_uuid),
@@ -259,6 +281,8 @@ fn row_to_repo(row: &rusqlite::Row) -> rusqlite::Result<Repo> {
             .unwrap_or(DiscoverySource::Api),
         last_synced_at: last_synced_str.map(|s| parse_dt(&s)),
         created_at: parse_dt(&created_str),
+        description,
+        gone_at: gone_str.map(|s| parse_dt(&s)),
     })
 }
 
@@ -275,27 +299,132 @@ pub fn update_repo_upstream(
     Ok(())
 }
 
+/// Link a fork to the tracked repo it was forked from, or unlink it with `None`.
+pub fn set_repo_upstream_id(
+    conn: &Connection,
+    id: &RepoId,
+    upstream_repo_id: Option<&RepoId>,
+) -> anyhow::Result<()> {
+    conn.execute(
+        "UPDATE repos SET upstream_repo_id = ?1 WHERE id = ?2",
+        params![upstream_repo_id.map(|u| u.0.to_string()), id.0.to_string()],
+    )?;
+    Ok(())
+}
+
+/// Update the host-reported metadata that filters match on.
+pub fn update_repo_metadata(
+    conn: &Connection,
+    id: &RepoId,
+    is_private: bool,
+    is_archived: bool,
+    language: Option<&str>,
+    description: Option<&str>,
+) -> anyhow::Result<()> {
+    conn.execute(
+        "UPDATE repos SET is_private = ?1, is_archived = ?2, language = ?3, description = ?4
+         WHERE id = ?5",
+        params![
+            is_private as i32,
+            is_archived as i32,
+            language,
+            description,
+            id.0.to_string()
+        ],
+    )?;
+    Ok(())
+}
+
+/// Point a repo at the name and URL its host now knows it by, after a
+/// rename or transfer.
+pub fn rename_repo(
+    conn: &Connection,
+    id: &RepoId,
+    full_name: &str,
+    clone_url: &str,
+) -> anyhow::Result<()> {
+    let (owner, name) = full_name.split_once('/').unwrap_or(("", full_name));
+    conn.execute(
+        "UPDATE repos SET full_name = ?1, owner = ?2, name = ?3, clone_url = ?4 WHERE id = ?5",
+        params![full_name, owner, name, clone_url, id.0.to_string()],
+    )?;
+    Ok(())
+}
+
+/// Record when a repo disappeared from its host, or clear it with `None`.
+pub fn set_repo_gone(
+    conn: &Connection,
+    id: &RepoId,
+    gone_at: Option<&DateTime<Utc>>,
+) -> anyhow::Result<()> {
+    conn.execute(
+        "UPDATE repos SET gone_at = ?1 WHERE id = ?2",
+        params![gone_at.map(fmt_dt), id.0.to_string()],
+    )?;
+    Ok(())
+}
+
+/// Repos matching a filter, ordered by full name. Behind/ahead terms use the
+/// default branch's snapshot.
+pub fn list_repos_matching(conn: &Connection, filter: &Filter) -> anyhow::Result<Vec<Repo>> {
+    let (clause, values) = query::where_clause(filter, Utc::now());
+    let mut stmt = conn.prepare(&format!(
+        "SELECT r.id, r.full_name, r.owner, r.name, r.host_id, r.clone_url, r.local_path, r.is_fork, r.is_private, r.is_archived, r.language, r.upstream_repo_id, r.upstream_full_name, r.upstream_clone_url, r.default_branch, r.discovery_source, r.last_synced_at, r.created_at, r.description, r.gone_at
+         FROM {} WHERE {clause} ORDER BY r.full_name",
+        query::FROM_REPOS
+    ))?;
+    let rows = stmt.query_map(params_from_iter(values), row_to_repo)?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
 // ── Collections ──
 
 pub fn insert_collection(conn: &Connection, col: &Collection) -> anyhow::Result<()> {
     conn.execute(
-        "INSERT INTO collections (id, name, description) VALUES (?1, ?2, ?3)",
-        params![col.id.0.to_string(), col.name, col.description],
+        "INSERT INTO collections (id, name, description, query) VALUES (?1, ?2, ?3, ?4)",
+        params![col.id.0.to_string(), col.name, col.description, col.query],
     )?;
     Ok(())
 }
 
 pub fn list_collections(conn: &Connection) -> anyhow::Result<Vec<Collection>> {
     let mut stmt =
-        conn.prepare("SELECT id, name, description FROM collections ORDER BY name")?;
-    let rows = stmt.query_map([], |row| {
-        let id_str: String = row.get(0)?;
-        Ok(Collection {
-            id: CollectionId::from_uuid(Uuid::parse_str(&id_str).unwrap_or_default()),
-            name: row.get(1)?,
-            description: row.get(2)?,
-        })
-    })?;
+        conn.prepare("SELECT id, name, description, query FROM collections ORDER BY name")?;
+    let rows = stmt.query_map([], row_to_collection)?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+pub fn get_collection_by_name(conn: &Connection, name: &str) -> anyhow::Result<Option<Collection>> {
+    let mut stmt =
+        conn.prepare("SELECT id, name, description, query FROM collections WHERE name = ?1")?;
+    let mut rows = stmt.query(params![name])?;
+    match rows.next()? {
+        Some(row) => Ok(Some(row_to_collection(row)?)),
+        None => Ok(None),
+    }
+}
+
+fn row_to_collection(row: &rusqlite::Row) -> rusqlite::Result<Collection> {
+    let id_str: String = row.get(0)?;
+    Ok(Collection {
+        id: CollectionId::from_uuid(Uuid::parse_str(&id_str).unwrap_or_default()),
+        name: row.get(1)?,
+        description: row.get(2)?,
+        query: row.get(3)?,
+    })
+}
+
+/// Repos in a collection, ordered by full name.
+pub fn list_collection_members(
+    conn: &Connection,
+    collection_id: &CollectionId,
+) -> anyhow::Result<Vec<Repo>> {
+    let mut stmt = conn.prepare(
+        "SELECT r.id, r.full_name, r.owner, r.name, r.host_id, r.clone_url, r.local_path, r.is_fork, r.is_private, r.is_archived, r.language, r.upstream_repo_id, r.upstream_full_name, r.upstream_clone_url, r.default_branch, r.discovery_source, r.last_synced_at, r.created_at, r.description, r.gone_at
+         FROM repos r JOIN collection_members m ON m.repo_id = r.id
+         WHERE m.collection_id = ?1 ORDER BY r.full_name",
+    )?;
+    let rows = stmt.query_map(params![collection_id.0.to_string()], row_to_repo)?;
     Ok(rows.filter_map(|r| r.ok()).collect())
 }
 
@@ -357,7 +486,7 @@ pub fn list_sync_links(conn: &Connection) -> anyhow::Result<Vec<SyncLink>> {
         "SELECT id, source_repo_id, target_repo_id, direction, merge_strategy, trigger, instructions, enabled
          FROM sync_links ORDER BY id",
     )?;
-    let rows = stmt.query_map([], |row| row_to_sync_link(row))?;
+    let rows = stmt.query_map([], row_to_sync_link)?;
     Ok(rows.filter_map(|r| r.ok()).collect())
 }
 
@@ -408,8 +537,8 @@ pub fn insert_sync_record(conn: &Connection, record: &SyncRecord) -> anyhow::Res
     let errors_json =
         serde_json::to_string(&record.errors).unwrap_or_else(|_| "[]".to_string());
     conn.execute(
-        "INSERT INTO sync_history (id, repo_id, sync_link_id, branches_synced, branches_failed, commits_transferred, status, errors, started_at, finished_at)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        "INSERT INTO sync_history (id, repo_id, sync_link_id, branches_synced, branches_failed, commits_transferred, status, errors, started_at, finished_at, attempts)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
         params![
             record.id.to_string(),
             record.repo_id.0.to_string(),
@@ -421,11 +550,65 @@ pub fn insert_sync_record(conn: &Connection, record: &SyncRecord) -> anyhow::Res
             errors_json,
             fmt_dt(&record.started_at),
             fmt_dt(&record.finished_at),
+            record.attempts as i64,
         ],
     )?;
+    for detail in &record.details {
+        conn.execute(
+            "INSERT OR REPLACE INTO sync_history_detail (sync_id, branch, before_sha, after_sha, commits)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                record.id.to_string(),
+                detail.branch,
+                detail.before_sha,
+                detail.after_sha,
+                serde_json::to_string(&detail.commits)?,
+            ],
+        )?;
+    }
     Ok(())
 }
 
+/// Look up a sync record by full ID or unique ID prefix, with its details.
+/// Errors if the prefix matches more than one record.
+pub fn get_sync_record(conn: &Connection, id_prefix: &str) -> anyhow::Result<Option<SyncRecord>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, repo_id, sync_link_id, branches_synced, branches_failed, commits_transferred, status, errors, started_at, finished_at, attempts
+         FROM sync_history WHERE substr(id, 1, length(?1)) = ?1 LIMIT 2",
+    )?;
+    // A plain comparison: LIKE would read `%` and `_` in the prefix as wildcards.
+    let mut records: Vec<SyncRecord> = stmt
+        .query_map(params![id_prefix.to_lowercase()], row_to_sync_record)?
+        .filter_map(|r| r.ok())
+        .collect();
+    if records.len() > 1 {
+        anyhow::bail!("sync ID prefix '{id_prefix}' is ambiguous");
+    }
+    let Some(mut record) = records.pop() else {
+        return Ok(None);
+    };
+    record.details = get_sync_details(conn, &record.id)?;
+    Ok(Some(record))
+}
+
+/// Per-branch details of a sync record.
+pub fn get_sync_details(conn: &Connection, sync_id: &Uuid) -> anyhow::Result<Vec<SyncBranchDetail>> {
+    let mut stmt = conn.prepare(
+        "SELECT branch, before_sha, after_sha, commits FROM sync_history_detail
+         WHERE sync_id = ?1 ORDER BY branch",
+    )?;
+    let rows = stmt.query_map(params![sync_id.to_string()], |row| {
+        let commits_str: String = row.get(3)?;
+        Ok(SyncBranchDetail {
+            branch: row.get(0)?,
+            before_sha: row.get(1)?,
+            after_sha: row.get(2)?,
+            commits: serde_json::from_str(&commits_str).unwrap_or_default(),
+        })
+    })?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
 pub fn list_sync_history(
     conn: &Connection,
     repo_id: Option<&RepoId>,
@@ -433,12 +616,12 @@ pub fn list_sync_history(
 ) -> anyhow::Result<Vec<SyncRecord>> {
     let (sql, bind_id) = match repo_id {
         Some(id) => (
-            "SELECT id, repo_id, sync_link_id, branches_synced, branches_failed, commits_transferred, status, errors, started_at, finished_at
+            "SELECT id, repo_id, sync_link_id, branches_synced, branches_failed, commits_transferred, status, errors, started_at, finished_at, attempts
              FROM sync_history WHERE repo_id = ?1 ORDER BY started_at DESC LIMIT ?2",
             Some(id.0.to_string()),
         ),
         None => (
-            "SELECT id, repo_id, sync_link_id, branches_synced, branches_failed, commits_transferred, status, errors, started_at, finished_at
+            "SELECT id, repo_id, sync_link_id, branches_synced, branches_failed, commits_transferred, status, errors, started_at, finished_at, attempts
              FROM sync_history ORDER BY started_at DESC LIMIT ?2",
             None,
         ),
@@ -446,20 +629,40 @@ pub fn list_sync_history(
 
     let mut stmt = conn.prepare(sql)?;
     let rows = if let Some(ref id_str) = bind_id {
-        stmt.query_map(params![id_str, limit], |row| row_to_sync_record(row))?
+        stmt.query_map(params![id_str, limit], row_to_sync_record)?
     } else {
         // When no repo_id filter, ?2 becomes ?1 positionally — re-prepare
         drop(stmt);
         let mut stmt2 = conn.prepare(
-            "SELECT id, repo_id, sync_link_id, branches_synced, branches_failed, commits_transferred, status, errors, started_at, finished_at
+            "SELECT id, repo_id, sync_link_id, branches_synced, branches_failed, commits_transferred, status, errors, started_at, finished_at, attempts
              FROM sync_history ORDER BY started_at DESC LIMIT ?1",
         )?;
-        let rows = stmt2.query_map(params![limit], |row| row_to_sync_record(row))?;
+        let rows = stmt2.query_map(params![limit], row_to_sync_record)?;
         return Ok(rows.filter_map(|r| r.ok()).collect());
     };
     Ok(rows.filter_map(|r| r.ok()).collect())
 }
 
+/// Recent sync records for any of `repo_ids`.
+pub fn list_sync_history_for_repos(
+    conn: &Connection,
+    repo_ids: &[RepoId],
+    limit: u32,
+) -> anyhow::Result<Vec<SyncRecord>> {
+    if repo_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    let placeholders = vec!["?"; repo_ids.len()].join(", ");
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, repo_id, sync_link_id, branches_synced, branches_failed, commits_transferred, status, errors, started_at, finished_at, attempts
+         FROM sync_history WHERE repo_id IN ({placeholders})
+         ORDER BY started_at DESC LIMIT {limit}"
+    ))?;
+    let ids: Vec<String> = repo_ids.iter().map(|id| id.0.to_string()).collect();
+    let rows = stmt.query_map(rusqlite::params_from_iter(ids), row_to_sync_record)?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
 fn row_to_sync_record(row: &rusqlite::Row) -> rusqlite::Result<SyncRecord> {
     let id_str: String = row.get(0)?;
     let repo_id_str: String = row.get(1)?;
@@ -471,6 +674,7 @@ fn row_to_sync_record(row: &rusqlite::Row) -> rusqlite::Result<SyncRecord> {
     let errors_str: String = row.get(7)?;
     let started_str: String = row.get(8)?;
     let finished_str: String = row.get(9)?;
+    let attempts: i64 = row.get(10)?;
 
     Ok(SyncRecord {
         id: Uuid::parse_str(&id_str).unwrap_or_default(),
@@ -481,8 +685,10 @@ fn row_to_sync_record(row: &rusqlite::Row) -> rusqlite::Result<SyncRecord> {
         branches_synced: branches_synced as u32,
         branches_failed: branches_failed as u32,
         commits_transferred: commits as u32,
+        attempts: attempts as u32,
         status: status_str.parse().unwrap_or(SyncStatus::Failed),
         errors: serde_json::from_str(&errors_str).unwrap_or_default(),
+        details: Vec::new(),
         started_at: parse_dt(&started_str),
         finished_at: parse_dt(&finished_str),
     })
@@ -539,12 +745,161 @@ pub fn get_branch_snapshots(
     Ok(rows.filter_map(|r| r.ok()).collect())
 }
 
+// ── Schedule State ──
+
+pub fn upsert_schedule_state(conn: &Connection, state: &ScheduleState) -> anyhow::Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO schedule_state (job_id, cron, last_run_at, next_run_at)
+         VALUES (?1, ?2, ?3, ?4)",
+        params![
+            state.job_id,
+            state.cron,
+            opt_dt(&state.last_run_at),
+            fmt_dt(&state.next_run_at),
+        ],
+    )?;
+    Ok(())
+}
+
+pub fn list_schedule_states(conn: &Connection) -> anyhow::Result<Vec<ScheduleState>> {
+    let mut stmt = conn.prepare(
+        "SELECT job_id, cron, last_run_at, next_run_at FROM schedule_state ORDER BY job_id",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        let last_run: Option<String> = row.get(2)?;
+        let next_run: String = row.get(3)?;
+        Ok(ScheduleState {
+            job_id: row.get(0)?,
+            cron: row.get(1)?,
+            last_run_at: last_run.map(|s| parse_dt(&s)),
+            next_run_at: parse_dt(&next_run),
+        })
+    })?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+pub fn delete_schedule_state(conn: &Connection, job_id: &str) -> anyhow::Result<()> {
+    conn.execute("DELETE FROM schedule_state WHERE job_id = ?1", params![job_id])?;
+    Ok(())
+}
+
+// ── Notification State ──
+
+/// Rule/repo pairs that have already alerted and not yet recovered.
+pub fn list_fired_alerts(conn: &Connection) -> anyhow::Result<Vec<(String, RepoId)>> {
+    let mut stmt = conn.prepare("SELECT rule, repo_id FROM notification_state")?;
+    let rows = stmt.query_map([], |row| {
+        let repo_id: String = row.get(1)?;
+        Ok((
+            row.get(0)?,
+            RepoId::from_uuid(Uuid::parse_str(&repo_id).unwrap_or_default()),
+        ))
+    })?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+pub fn mark_alert_fired(
+    conn: &Connection,
+    rule: &str,
+    repo_id: &RepoId,
+    fired_at: &DateTime<Utc>,
+) -> anyhow::Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO notification_state (rule, repo_id, fired_at) VALUES (?1, ?2, ?3)",
+        params![rule, repo_id.0.to_string(), fmt_dt(fired_at)],
+    )?;
+    Ok(())
+}
+
+pub fn clear_alert(conn: &Connection, rule: &str, repo_id: &RepoId) -> anyhow::Result<()> {
+    conn.execute(
+        "DELETE FROM notification_state WHERE rule = ?1 AND repo_id = ?2",
+        params![rule, repo_id.0.to_string()],
+    )?;
+    Ok(())
+}
+
+// ── Scan Index ──
+
+/// SQL condition matching `root` and everything beneath it.
+const UNDER_ROOT: &str = "(path = ?1 OR substr(path, 1, length(?1) + 1) = ?1 || '/')";
+
+/// Directories recorded by the last scan of `root`, including `root` itself.
+pub fn get_scan_index(conn: &Connection, root: &str) -> anyhow::Result<Vec<ScanIndexEntry>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT path, mtime_ns, subdirs, git_dir, checkout_path, checkout_kind
+         FROM scan_index WHERE {UNDER_ROOT} ORDER BY path"
+    ))?;
+    let rows = stmt.query_map(params![root], |row| {
+        let path: String = row.get(0)?;
+        let subdirs: Option<String> = row.get(2)?;
+        let git_dir: Option<String> = row.get(3)?;
+        let checkout_path: Option<String> = row.get(4)?;
+        let checkout_kind: Option<String> = row.get(5)?;
+        let checkout = match (git_dir, checkout_path, checkout_kind) {
+            (Some(git_dir), Some(path), Some(kind)) => kind.parse().ok().map(|kind| {
+                (
+                    git_dir.into(),
+                    Checkout {
+                        path: path.into(),
+                        kind,
+                    },
+                )
+            }),
+            _ => None,
+        };
+        Ok(ScanIndexEntry {
+            path: path.into(),
+            mtime_ns: row.get(1)?,
+            subdirs: subdirs.and_then(|s| serde_json::from_str(&s).ok()),
+            checkout,
+        })
+    })?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+/// Replace everything recorded under `root` with `entries`.
+pub fn replace_scan_index(
+    conn: &Connection,
+    root: &str,
+    entries: &[ScanIndexEntry],
+) -> anyhow::Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    tx.execute(
+        &format!("DELETE FROM scan_index WHERE {UNDER_ROOT}"),
+        params![root],
+    )?;
+    {
+        let mut stmt = tx.prepare(
+            "INSERT OR REPLACE INTO scan_index (path, mtime_ns, subdirs, git_dir, checkout_path, checkout_kind)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        )?;
+        for entry in entries {
+            let (git_dir, checkout) = match &entry.checkout {
+                Some((git_dir, checkout)) => (Some(git_dir), Some(checkout)),
+                None => (None, None),
+            };
+            stmt.execute(params![
+                entry.path.to_string_lossy(),
+                entry.mtime_ns,
+                entry.subdirs.as_ref().map(serde_json::to_string).transpose()?,
+                git_dir.map(|p| p.to_string_lossy().into_owned()),
+                checkout.map(|c| c.path.to_string_lossy().into_owned()),
+                checkout.map(|c| c.kind.to_string()),
+            ])?;
+        }
+    }
+    tx.commit()?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::open_memory_db;
     use gitr_core::models::host::HostKind;
     use gitr_core::models::repo::DiscoverySource;
+    use gitr_core::models::sync_state::IncomingCommit;
 
     #[test]
     fn test_host_crud() {
@@ -592,8 +947,182 @@ mod tests {
         let all = list_repos(&conn).unwrap();
         assert_eq!(all.len(), 1);
 
+        rename_repo(
+            &conn,
+            &repo.id,
+            "neworg/renamed",
+            "https://github.com/neworg/renamed.git",
+        )
+        .unwrap();
+        let gone_at = Utc::now();
+        set_repo_gone(&conn, &repo.id, Some(&gone_at)).unwrap();
+        let found = get_repo_by_id(&conn, &repo.id).unwrap().unwrap();
+        assert_eq!(found.owner, "neworg");
+        assert_eq!(found.name, "renamed");
+        assert_eq!(found.clone_url, "https://github.com/neworg/renamed.git");
+        assert_eq!(found.gone_at.map(|t| t.timestamp()), Some(gone_at.timestamp()));
+
+        let fork = Repo::new(
+            "other/renamed".to_string(),
+            host.id.clone(),
+            "https://github.com/other/renamed.git".to_string(),
+            "main".to_string(),
+            DiscoverySource::Api,
+        );
+        insert_repo(&conn, &fork).unwrap();
+        set_repo_upstream_id(&conn, &fork.id, Some(&repo.id)).unwrap();
+        let found = get_repo_by_id(&conn, &fork.id).unwrap().unwrap();
+        assert_eq!(found.upstream_repo_id, Some(repo.id.clone()));
+
         delete_repo(&conn, &repo.id).unwrap();
         assert!(get_repo_by_id(&conn, &repo.id).unwrap().is_none());
+        // Untracking an upstream unlinks its forks.
+        let found = get_repo_by_id(&conn, &fork.id).unwrap().unwrap();
+        assert_eq!(found.upstream_repo_id, None);
+    }
+
+    #[test]
+    fn test_collection_members() {
+        let conn = open_memory_db().unwrap();
+        let host = Host::new("gh".to_string(), HostKind::GitHub, "testuser".to_string());
+        insert_host(&conn, &host).unwrap();
+        let repos: Vec<Repo> = ["testuser/b", "testuser/a", "testuser/c"]
+            .into_iter()
+            .map(|name| {
+                Repo::new(
+                    name.to_string(),
+                    host.id.clone(),
+                    format!("https://github.com/{name}.git"),
+                    "main".to_string(),
+                    DiscoverySource::Api,
+                )
+            })
+            .collect();
+        for repo in &repos {
+            insert_repo(&conn, repo).unwrap();
+        }
+
+        let col = Collection::new("work".to_string(), None);
+        insert_collection(&conn, &col).unwrap();
+        for repo in &repos[..2] {
+            let member = CollectionMember {
+                collection_id: col.id.clone(),
+                repo_id: repo.id.clone(),
+            };
+            add_collection_member(&conn, &member).unwrap();
+            // Adding twice is a no-op
+            add_collection_member(&conn, &member).unwrap();
+        }
+
+        let found = get_collection_by_name(&conn, "work").unwrap().unwrap();
+        assert_eq!(found.id, col.id);
+        assert!(get_collection_by_name(&conn, "personal").unwrap().is_none());
+
+        let members = list_collection_members(&conn, &col.id).unwrap();
+        let names: Vec<&str> = members.iter().map(|r| r.full_name.as_str()).collect();
+        assert_eq!(names, ["testuser/a", "testuser/b"]);
+
+        let mut record = SyncRecord::new(repos[2].id.clone());
+        insert_sync_record(&conn, &record).unwrap();
+        record = SyncRecord::new(repos[0].id.clone());
+        insert_sync_record(&conn, &record).unwrap();
+        let ids: Vec<RepoId> = list_collection_members(&conn, &col.id)
+            .unwrap()
+            .into_iter()
+            .map(|r| r.id)
+            .collect();
+        let history = list_sync_history_for_repos(&conn, &ids, 10).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].repo_id, repos[0].id);
+
+        remove_collection_member(&conn, &col.id, &repos[1].id).unwrap();
+        assert_eq!(list_collection_members(&conn, &col.id).unwrap().len(), 1);
+
+        delete_collection(&conn, &col.id).unwrap();
+        assert!(list_collections(&conn).unwrap().is_empty());
+        // Deleting a collection leaves its repos alone
+        assert_eq!(list_repos(&conn).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_list_repos_matching() {
+        let conn = open_memory_db().unwrap();
+        let host = Host::new("gh".to_string(), HostKind::GitHub, "testuser".to_string());
+        insert_host(&conn, &host).unwrap();
+
+        let mut fork = Repo::new(
+            "acme/widgets".to_string(),
+            host.id.clone(),
+            "https://github.com/acme/widgets.git".to_string(),
+            "main".to_string(),
+            DiscoverySource::Api,
+        );
+        fork.is_fork = true;
+        insert_repo(&conn, &fork).unwrap();
+        update_repo_metadata(&conn, &fork.id, false, false, Some("Rust"), None).unwrap();
+        let archived = Repo::new(
+            "acme/old".to_string(),
+            host.id.clone(),
+            "https://github.com/acme/old.git".to_string(),
+            "main".to_string(),
+            DiscoverySource::Api,
+        );
+        insert_repo(&conn, &archived).unwrap();
+        update_repo_metadata(&conn, &archived.id, true, true, None, None).unwrap();
+
+        let found = get_repo_by_id(&conn, &archived.id).unwrap().unwrap();
+        assert!(found.is_private && found.is_archived);
+        assert_eq!(found.language, None);
+
+        upsert_branch_snapshot(
+            &conn,
+            &BranchSnapshot {
+                repo_id: fork.id.clone(),
+                branch: "main".to_string(),
+                local_sha: None,
+                remote_sha: None,
+                upstream_sha: None,
+                behind_count: 4,
+                ahead_count: 0,
+                updated_at: Utc::now(),
+            },
+        )
+        .unwrap();
+
+        let names = |query: &str| -> Vec<String> {
+            list_repos_matching(&conn, &query.parse().unwrap())
+                .unwrap()
+                .into_iter()
+                .map(|r| r.full_name)
+                .collect()
+        };
+        assert_eq!(
+            names("host:gh fork:true owner:acme behind>0 lang:rust archived:false"),
+            ["acme/widgets"]
+        );
+        assert_eq!(names("owner:acme"), ["acme/old", "acme/widgets"]);
+        assert_eq!(names("archived:true private:true"), ["acme/old"]);
+        assert!(names("behind>4").is_empty());
+        assert!(names("host:work").is_empty());
+        assert_eq!(names("name:*g?ts behind<=4 ahead:0"), ["acme/widgets"]);
+
+        update_repo_last_synced(&conn, &fork.id, &(Utc::now() - chrono::Duration::days(3)))
+            .unwrap();
+        update_repo_local_path(&conn, &fork.id, Some(std::path::Path::new("/src/widgets")))
+            .unwrap();
+        assert_eq!(names("synced<7d local:true"), ["acme/widgets"]);
+        assert_eq!(names("synced>1d"), ["acme/old", "acme/widgets"]);
+        assert_eq!(names("synced:never"), ["acme/old"]);
+        assert_eq!(names("not lang:rust"), ["acme/old"]);
+        assert_eq!(names("-fork:true or name:wid*"), ["acme/old", "acme/widgets"]);
+        assert_eq!(names("(archived:true or behind>3) not name:o?d"), ["acme/widgets"]);
+
+        let mut smart = Collection::new("rusty".to_string(), None);
+        smart.query = Some("lang:rust".to_string());
+        insert_collection(&conn, &smart).unwrap();
+        let found = get_collection_by_name(&conn, "rusty").unwrap().unwrap();
+        assert_eq!(found.query.as_deref(), Some("lang:rust"));
+        assert!(found.is_smart());
     }
 
     #[test]
@@ -613,11 +1142,130 @@ mod tests {
 
         let mut record = SyncRecord::new(repo.id.clone());
         record.branches_synced = 1;
+        record.attempts = 3;
         record.status = SyncStatus::Success;
         insert_sync_record(&conn, &record).unwrap();
 
         let history = list_sync_history(&conn, Some(&repo.id), 10).unwrap();
         assert_eq!(history.len(), 1);
         assert_eq!(history[0].branches_synced, 1);
+        assert_eq!(history[0].attempts, 3);
+    }
+
+    #[test]
+    fn test_sync_record_details() {
+        let conn = open_memory_db().unwrap();
+        let host = Host::new("gh".to_string(), HostKind::GitHub, "user".to_string());
+        insert_host(&conn, &host).unwrap();
+        let repo = Repo::new(
+            "user/repo".to_string(),
+            host.id.clone(),
+            "https://github.com/user/repo.git".to_string(),
+            "main".to_string(),
+            DiscoverySource::Api,
+        );
+        insert_repo(&conn, &repo).unwrap();
+
+        let mut record = SyncRecord::new(repo.id.clone());
+        record.details.push(SyncBranchDetail {
+            branch: "main".to_string(),
+            before_sha: Some("aaa".to_string()),
+            after_sha: Some("bbb".to_string()),
+            commits: vec![IncomingCommit {
+                sha: "bbb".to_string(),
+                author: "dev".to_string(),
+                subject: "feat: new thing".to_string(),
+                date: Utc::now(),
+            }],
+        });
+        insert_sync_record(&conn, &record).unwrap();
+
+        let prefix = &record.id.to_string()[..13];
+        let loaded = get_sync_record(&conn, prefix).unwrap().unwrap();
+        assert_eq!(loaded.id, record.id);
+        assert_eq!(loaded.details, record.details);
+        assert!(get_sync_record(&conn, "ffffffff").unwrap().is_none());
+        let upper = get_sync_record(&conn, &prefix.to_uppercase()).unwrap();
+        assert_eq!(upper.map(|r| r.id), Some(record.id));
+        assert!(get_sync_record(&conn, "%").unwrap().is_none());
+        assert!(get_sync_record(&conn, "________").unwrap().is_none());
+
+        // Listing stays cheap: details are loaded on request.
+        let history = list_sync_history(&conn, None, 10).unwrap();
+        assert!(history[0].details.is_empty());
+    }
+
+    #[test]
+    fn test_schedule_state_crud() {
+        let conn = open_memory_db().unwrap();
+        let mut state = ScheduleState {
+            job_id: "forks".to_string(),
+            cron: "0 */6 * * *".to_string(),
+            last_run_at: None,
+            next_run_at: parse_dt("2026-01-01T06:00:00+00:00"),
+        };
+        upsert_schedule_state(&conn, &state).unwrap();
+        state.last_run_at = Some(state.next_run_at);
+        state.next_run_at = parse_dt("2026-01-01T12:00:00+00:00");
+        upsert_schedule_state(&conn, &state).unwrap();
+
+        assert_eq!(list_schedule_states(&conn).unwrap(), vec![state]);
+        delete_schedule_state(&conn, "forks").unwrap();
+        assert!(list_schedule_states(&conn).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_notification_state() {
+        let conn = open_memory_db().unwrap();
+        let host = Host::new("gh".to_string(), HostKind::GitHub, "testuser".to_string());
+        insert_host(&conn, &host).unwrap();
+        let repo = Repo::new(
+            "testuser/fork".to_string(),
+            host.id.clone(),
+            "https://github.com/testuser/fork.git".to_string(),
+            "main".to_string(),
+            DiscoverySource::Api,
+        );
+        insert_repo(&conn, &repo).unwrap();
+
+        mark_alert_fired(&conn, "behind>100", &repo.id, &Utc::now()).unwrap();
+        mark_alert_fired(&conn, "behind>100", &repo.id, &Utc::now()).unwrap();
+        assert_eq!(
+            list_fired_alerts(&conn).unwrap(),
+            vec![("behind>100".to_string(), repo.id.clone())]
+        );
+        clear_alert(&conn, "behind>100", &repo.id).unwrap();
+        assert!(list_fired_alerts(&conn).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_scan_index() {
+        use gitr_core::models::scan_index::CheckoutKind;
+
+        let conn = open_memory_db().unwrap();
+        let entry = |path: &str, subdirs: &[&str]| ScanIndexEntry {
+            path: path.into(),
+            mtime_ns: 42,
+            subdirs: Some(subdirs.iter().map(|s| s.to_string()).collect()),
+            checkout: None,
+        };
+        let mut repo = entry("/code/app", &[]);
+        repo.subdirs = None;
+        repo.checkout = Some((
+            "/code/app/.git".into(),
+            Checkout {
+                path: "/code/app".into(),
+                kind: CheckoutKind::Primary,
+            },
+        ));
+        let entries = vec![entry("/code", &["app"]), repo];
+        replace_scan_index(&conn, "/code", &entries).unwrap();
+        replace_scan_index(&conn, "/code2", &[entry("/code2", &[])]).unwrap();
+        assert_eq!(get_scan_index(&conn, "/code").unwrap(), entries);
+
+        // Replacing one root leaves its sibling prefix alone.
+        replace_scan_index(&conn, "/code", &[]).unwrap();
+        assert!(get_scan_index(&conn, "/code").unwrap().is_empty());
+        assert_eq!(get_scan_index(&conn, "/code2").unwrap().len(), 1);
     }
 }
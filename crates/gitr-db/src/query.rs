@@ -0,0 +1,148 @@
+//! Compiling repo filters to SQL.
+//!
+//! The clause produced by [`where_clause`] refers to `repos r`, its host
+//! `hosts h` and the snapshot of its default branch `branch_snapshots s`;
+//! see [`FROM_REPOS`].
+
+use chrono::{DateTime, Utc};
+use rusqlite::types::Value;
+
+use gitr_core::filter::{Expr, Field, Filter, Op, Term};
+
+/// The tables a filter's WHERE clause is evaluated over.
+pub const FROM_REPOS: &str = "repos r
+         LEFT JOIN hosts h ON h.id = r.host_id
+         LEFT JOIN branch_snapshots s ON s.repo_id = r.id AND s.branch = r.default_branch";
+
+/// Compile `filter` to a WHERE clause and its positional parameters.
+/// `synced` ages are measured back from `now`.
+pub fn where_clause(filter: &Filter, now: DateTime<Utc>) -> (String, Vec<Value>) {
+    let mut params = Vec::new();
+    let sql = expr_sql(&filter.expr, now, &mut params);
+    (sql, params)
+}
+
+fn expr_sql(expr: &Expr, now: DateTime<Utc>, params: &mut Vec<Value>) -> String {
+    let join = |exprs: &[Expr], sep: &str, params: &mut Vec<Value>| {
+        let parts: Vec<String> = exprs.iter().map(|e| expr_sql(e, now, params)).collect();
+        format!("({})", parts.join(sep))
+    };
+    match expr {
+        Expr::Term(term) => term_sql(term, now, params),
+        Expr::And(exprs) => join(exprs, " AND ", params),
+        Expr::Or(exprs) => join(exprs, " OR ", params),
+        Expr::Not(expr) => format!("NOT {}", expr_sql(expr, now, params)),
+    }
+}
+
+/// One term as a condition that is never NULL, so `NOT` of a term that
+/// can't match (say `lang:rust` on a repo with no language) is true.
+fn term_sql(term: &Term, now: DateTime<Utc>, params: &mut Vec<Value>) -> String {
+    let text = |column: &str, params: &mut Vec<Value>| {
+        params.push(Value::Text(term.value.clone()));
+        format!("COALESCE({column} = ? COLLATE NOCASE, 0)")
+    };
+    let flag = |column: &str| format!("{column} = {}", i32::from(term.value == "true"));
+    let count = |column: &str, params: &mut Vec<Value>| {
+        params.push(Value::Integer(term.count().into()));
+        format!("COALESCE({column}, 0) {} ?", sql_op(term.op))
+    };
+
+    match term.field {
+        Field::Host => text("h.label", params),
+        Field::Owner => text("r.owner", params),
+        Field::Lang => text("r.language", params),
+        Field::Source => text("r.discovery_source", params),
+        Field::Name => {
+            params.push(Value::Text(glob_to_like(&term.value)));
+            "r.name LIKE ? ESCAPE '\\'".to_string()
+        }
+        Field::Fork => flag("r.is_fork"),
+        Field::Private => flag("r.is_private"),
+        Field::Archived => flag("r.is_archived"),
+        Field::Gone => match term.value.as_str() {
+            "true" => "r.gone_at IS NOT NULL".to_string(),
+            _ => "r.gone_at IS NULL".to_string(),
+        },
+        Field::Local => match term.value.as_str() {
+            "true" => "r.local_path IS NOT NULL".to_string(),
+            _ => "r.local_path IS NULL".to_string(),
+        },
+        Field::Behind => count("s.behind_count", params),
+        Field::Ahead => count("s.ahead_count", params),
+        Field::Synced => match term.age() {
+            None => "r.last_synced_at IS NULL".to_string(),
+            Some(age) => {
+                params.push(Value::Text((now - age).to_rfc3339()));
+                // Older than the age means synced before the cutoff; never
+                // synced counts as older than any age.
+                let cutoff = "julianday(?)";
+                let at = "julianday(r.last_synced_at)";
+                match term.op {
+                    Op::Gt => format!("(r.last_synced_at IS NULL OR {at} < {cutoff})"),
+                    Op::Ge => format!("(r.last_synced_at IS NULL OR {at} <= {cutoff})"),
+                    Op::Lt => format!("COALESCE({at} > {cutoff}, 0)"),
+                    Op::Le | Op::Eq => format!("COALESCE({at} >= {cutoff}, 0)"),
+                }
+            }
+        },
+    }
+}
+
+fn sql_op(op: Op) -> &'static str {
+    match op {
+        Op::Eq => "=",
+        Op::Gt => ">",
+        Op::Ge => ">=",
+        Op::Lt => "<",
+        Op::Le => "<=",
+    }
+}
+
+/// Turn a `*`/`?` wildcard pattern into a LIKE pattern escaped with `\`.
+fn glob_to_like(pattern: &str) -> String {
+    let mut like = String::with_capacity(pattern.len());
+    for c in pattern.chars() {
+        match c {
+            '*' => like.push('%'),
+            '?' => like.push('_'),
+            '%' | '_' | '\\' => {
+                like.push('\\');
+                like.push(c);
+            }
+            c => like.push(c),
+        }
+    }
+    like
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_where_clause() {
+        let filter: Filter = "host:gh (behind>2 or not local:true) name:web_*"
+            .parse()
+            .unwrap();
+        let (sql, params) = where_clause(&filter, Utc::now());
+        assert_eq!(
+            sql,
+            "(COALESCE(h.label = ? COLLATE NOCASE, 0) AND (COALESCE(s.behind_count, 0) > ? OR NOT r.local_path IS NOT NULL) AND r.name LIKE ? ESCAPE '\\')"
+        );
+        assert_eq!(
+            params,
+            [
+                Value::Text("gh".to_string()),
+                Value::Integer(2),
+                Value::Text("web\\_%".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_glob_to_like() {
+        assert_eq!(glob_to_like("a*b?c"), "a%b_c");
+        assert_eq!(glob_to_like("100%_x\\"), "100\\%\\_x\\\\");
+    }
+}
@@ -1,4 +1,4 @@
-/// SQL statements for creating the Gitr database schema.
+//! SQL statements for creating the Gitr database schema.
 
 pub const CREATE_SCHEMA_VERSION: &str = "
 CREATE TABLE IF NOT EXISTS schema_version (
@@ -22,10 +22,13 @@ CREATE TABLE IF NOT EXISTS repos (
     full_name           TEXT NOT NULL,
     owner               TEXT NOT NULL,
     name                TEXT NOT NULL,
-    host_id             TEXT NOT NULL,
+    host_id             TEXT,
     clone_url           TEXT NOT NULL,
     local_path          TEXT,
     is_fork             INTEGER NOT NULL DEFAULT 0,
+    is_private          INTEGER NOT NULL DEFAULT 0,
+    is_archived         INTEGER NOT NULL DEFAULT 0,
+    language            TEXT,
     upstream_repo_id    TEXT,
     upstream_full_name  TEXT,
     upstream_clone_url  TEXT,
@@ -33,6 +36,8 @@ CREATE TABLE IF NOT EXISTS repos (
     discovery_source    TEXT NOT NULL DEFAULT 'api',
     last_synced_at      TEXT,
     created_at          TEXT NOT NULL,
+    description         TEXT,
+    gone_at             TEXT,
     FOREIGN KEY (host_id) REFERENCES hosts(id) ON DELETE CASCADE,
     FOREIGN KEY (upstream_repo_id) REFERENCES repos(id) ON DELETE SET NULL
 )";
@@ -41,7 +46,8 @@ pub const CREATE_COLLECTIONS: &str = "
 CREATE TABLE IF NOT EXISTS collections (
     id          TEXT PRIMARY KEY,
     name        TEXT NOT NULL UNIQUE,
-    description TEXT
+    description TEXT,
+    query       TEXT
 )";
 
 pub const CREATE_COLLECTION_MEMBERS: &str = "
@@ -75,6 +81,7 @@ CREATE TABLE IF NOT EXISTS sync_history (
     branches_synced     INTEGER NOT NULL DEFAULT 0,
     branches_failed     INTEGER NOT NULL DEFAULT 0,
     commits_transferred INTEGER NOT NULL DEFAULT 0,
+    attempts            INTEGER NOT NULL DEFAULT 1,
     status              TEXT NOT NULL,
     errors              TEXT NOT NULL DEFAULT '[]',
     started_at          TEXT NOT NULL,
@@ -83,6 +90,17 @@ CREATE TABLE IF NOT EXISTS sync_history (
     FOREIGN KEY (sync_link_id) REFERENCES sync_links(id) ON DELETE SET NULL
 )";
 
+pub const CREATE_SYNC_HISTORY_DETAIL: &str = "
+CREATE TABLE IF NOT EXISTS sync_history_detail (
+    sync_id         TEXT NOT NULL,
+    branch          TEXT NOT NULL,
+    before_sha      TEXT,
+    after_sha       TEXT,
+    commits         TEXT NOT NULL DEFAULT '[]',
+    PRIMARY KEY (sync_id, branch),
+    FOREIGN KEY (sync_id) REFERENCES sync_history(id) ON DELETE CASCADE
+)";
+
 pub const CREATE_BRANCH_SNAPSHOTS: &str = "
 CREATE TABLE IF NOT EXISTS branch_snapshots (
     repo_id         TEXT NOT NULL,
@@ -97,6 +115,33 @@ CREATE TABLE IF NOT EXISTS branch_snapshots (
     FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
 )";
 
+pub const CREATE_SCHEDULE_STATE: &str = "
+CREATE TABLE IF NOT EXISTS schedule_state (
+    job_id          TEXT PRIMARY KEY,
+    cron            TEXT NOT NULL,
+    last_run_at     TEXT,
+    next_run_at     TEXT NOT NULL
+)";
+
+pub const CREATE_NOTIFICATION_STATE: &str = "
+CREATE TABLE IF NOT EXISTS notification_state (
+    rule            TEXT NOT NULL,
+    repo_id         TEXT NOT NULL,
+    fired_at        TEXT NOT NULL,
+    PRIMARY KEY (rule, repo_id),
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+)";
+
+pub const CREATE_SCAN_INDEX: &str = "
+CREATE TABLE IF NOT EXISTS scan_index (
+    path            TEXT PRIMARY KEY,
+    mtime_ns        INTEGER NOT NULL,
+    subdirs         TEXT,
+    git_dir         TEXT,
+    checkout_path   TEXT,
+    checkout_kind   TEXT
+)";
+
 /// All table creation statements in order.
 pub const ALL_TABLES: &[&str] = &[
     CREATE_SCHEMA_VERSION,
@@ -106,5 +151,9 @@ pub const ALL_TABLES: &[&str] = &[
     CREATE_COLLECTION_MEMBERS,
     CREATE_SYNC_LINKS,
     CREATE_SYNC_HISTORY,
+    CREATE_SYNC_HISTORY_DETAIL,
     CREATE_BRANCH_SNAPSHOTS,
+    CREATE_SCHEDULE_STATE,
+    CREATE_NOTIFICATION_STATE,
+    CREATE_SCAN_INDEX,
 ];
@@ -0,0 +1,248 @@
+//! Turning checkouts and URLs no host reported into tracked repos.
+
+use std::path::Path;
+use std::process::Command;
+
+use gitr_core::models::host::Host;
+use gitr_core::models::repo::{DiscoverySource, Repo};
+
+use crate::reconcile::normalize_url;
+use crate::scanner::{ScannedRemote, ScannedRepo};
+
+/// The remote a checkout is tracked through: `origin`, else its first one.
+pub fn origin(local: &ScannedRepo) -> Option<&ScannedRemote> {
+    local
+        .remotes
+        .iter()
+        .find(|r| r.name == "origin")
+        .or_else(|| local.remotes.first())
+}
+
+/// The registered host serving `url`, matched by domain. SSH endpoints on an
+/// `ssh.` subdomain (`ssh.github.com`) count as the host's own.
+pub fn infer_host<'a>(url: &str, hosts: &'a [Host]) -> Option<&'a Host> {
+    let normalized = normalize_url(url);
+    let domain = normalized.split('/').next()?;
+    if domain.is_empty() {
+        return None;
+    }
+    hosts.iter().find(|h| {
+        h.git_domain().is_some_and(|d| {
+            d.eq_ignore_ascii_case(domain) || domain.strip_prefix("ssh.") == Some(d)
+        })
+    })
+}
+
+/// The `owner/name` a git URL points at: its path after the domain, or the
+/// last two components of a local path.
+pub fn full_name_from_url(url: &str) -> Option<String> {
+    let url = url.trim_end_matches('/');
+    let url = url.strip_suffix(".git").unwrap_or(url);
+    let is_local = url.starts_with('/') || url.starts_with("file://") || url.starts_with('.');
+    if is_local {
+        let mut parts = url.rsplit('/').filter(|p| !p.is_empty() && *p != ".");
+        let name = parts.next()?;
+        return Some(match parts.next() {
+            Some(owner) => format!("{owner}/{name}"),
+            None => name.to_string(),
+        });
+    }
+    // Keep the case of the path, which normalize_url lowercases.
+    let normalized = normalize_url(url);
+    let path_len = normalized.split_once('/')?.1.len();
+    let path = &url[url.len().checked_sub(path_len)?..];
+    // `ssh://host:22/owner/repo` normalizes with the port as a path component.
+    let path = match path.split_once('/') {
+        Some((port, rest)) if port.bytes().all(|b| b.is_ascii_digit()) => rest,
+        _ => path,
+    };
+    (!path.is_empty()).then(|| path.to_string())
+}
+
+/// Build a repo row for a local checkout no host reported. It is filed
+/// under the registered host serving its `origin` URL, or tracked without a
+/// host otherwise. An `upstream` remote pointing elsewhere makes it a fork of
+/// that URL; a host-less clone without one uses `origin` as its plain-git
+/// upstream. Returns `None` if the checkout has no remotes.
+pub fn adopt(local: &ScannedRepo, hosts: &[Host], source: DiscoverySource) -> Option<Repo> {
+    let origin = origin(local)?;
+    let full_name = full_name_from_url(&origin.url)?;
+    let default_branch = default_branch(&local.git_dir, &origin.name);
+    let host = infer_host(&origin.url, hosts);
+
+    let mut repo = match host {
+        Some(host) => Repo::new(
+            full_name,
+            host.id.clone(),
+            origin.url.clone(),
+            default_branch,
+            source,
+        ),
+        None => Repo::without_host(full_name, origin.url.clone(), default_branch, source),
+    };
+    repo.local_path = local.working_tree().map(Into::into);
+
+    let upstream = local
+        .remotes
+        .iter()
+        .find(|r| r.name == "upstream" && normalize_url(&r.url) != normalize_url(&origin.url));
+    match upstream {
+        Some(upstream) => {
+            repo.is_fork = true;
+            repo.upstream_full_name = full_name_from_url(&upstream.url);
+            repo.upstream_clone_url = Some(upstream.url.clone());
+        }
+        None if host.is_none() => {
+            repo.upstream_full_name = Some(repo.full_name.clone());
+            repo.upstream_clone_url = Some(repo.clone_url.clone());
+        }
+        None => {}
+    }
+    Some(repo)
+}
+
+/// The branch `remote/HEAD` points at, else the checked-out branch, else
+/// `main`.
+pub fn default_branch(git_dir: &Path, remote: &str) -> String {
+    let symref = |path: &Path, prefix: &str| {
+        let content = std::fs::read_to_string(path).ok()?;
+        let target = content.trim().strip_prefix("ref: ")?.strip_prefix(prefix)?;
+        Some(target.to_string())
+    };
+    let remote_head = git_dir.join("refs/remotes").join(remote).join("HEAD");
+    symref(&remote_head, &format!("refs/remotes/{remote}/"))
+        .or_else(|| symref(&git_dir.join("HEAD"), "refs/heads/"))
+        .unwrap_or_else(|| "main".to_string())
+}
+
+/// The default branch of the repo at `url`, asking the server with
+/// `git ls-remote --symref`. `None` if it can't be reached.
+pub fn remote_default_branch(url: &str) -> Option<String> {
+    let output = Command::new("git")
+        .args(["ls-remote", "--symref", "--", url, "HEAD"])
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| {
+            let (target, name) = line.strip_prefix("ref: ")?.split_once('\t')?;
+            (name == "HEAD").then(|| {
+                target
+                    .strip_prefix("refs/heads/")
+                    .unwrap_or(target)
+                    .to_string()
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gitr_core::models::host::HostKind;
+
+    fn scanned(remotes: &[(&str, &str)], git_dir: &Path) -> ScannedRepo {
+        ScannedRepo {
+            path: "/src/tool".into(),
+            git_dir: git_dir.to_path_buf(),
+            checkouts: vec![crate::scanner::Checkout {
+                path: "/src/tool".into(),
+                kind: crate::scanner::CheckoutKind::Primary,
+            }],
+            remotes: remotes
+                .iter()
+                .map(|(name, url)| ScannedRemote {
+                    name: name.to_string(),
+                    url: url.to_string(),
+                    push_urls: Vec::new(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_full_name_from_url() {
+        assert_eq!(
+            full_name_from_url("git@github.com:Rust-Lang/rust.git").as_deref(),
+            Some("Rust-Lang/rust")
+        );
+        assert_eq!(
+            full_name_from_url("https://gitlab.com/group/sub/proj/").as_deref(),
+            Some("group/sub/proj")
+        );
+        assert_eq!(
+            full_name_from_url("ssh://git@ssh.github.com:443/me/a.git").as_deref(),
+            Some("me/a")
+        );
+        assert_eq!(
+            full_name_from_url("/srv/git/tools/x.git").as_deref(),
+            Some("tools/x")
+        );
+        assert_eq!(full_name_from_url("https://example.com"), None);
+    }
+
+    #[test]
+    fn test_infer_host() {
+        let gh = Host::new("gh".to_string(), HostKind::GitHub, "me".to_string());
+        let mut work = Host::new("work".to_string(), HostKind::GitLab, "me".to_string());
+        work.api_url = "https://git.example.com/api/v4".parse().unwrap();
+        let hosts = [gh, work];
+
+        let label = |url| infer_host(url, &hosts).map(|h| h.label.as_str());
+        assert_eq!(label("git@github.com:me/a.git"), Some("gh"));
+        assert_eq!(label("ssh://git@ssh.github.com:443/me/a.git"), Some("gh"));
+        assert_eq!(label("https://git.example.com/ops/infra"), Some("work"));
+        assert_eq!(label("https://codeberg.org/x/y.git"), None);
+        assert_eq!(label("/srv/git/x.git"), None);
+    }
+
+    #[test]
+    fn test_adopt() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("refs/remotes/origin")).unwrap();
+        std::fs::write(
+            dir.path().join("refs/remotes/origin/HEAD"),
+            "ref: refs/remotes/origin/trunk\n",
+        )
+        .unwrap();
+        let hosts = [Host::new(
+            "gh".to_string(),
+            HostKind::GitHub,
+            "me".to_string(),
+        )];
+
+        // A fork on a registered host, with its upstream elsewhere.
+        let fork = scanned(
+            &[
+                ("origin", "git@github.com:me/tool.git"),
+                ("upstream", "https://codeberg.org/them/tool.git"),
+            ],
+            dir.path(),
+        );
+        let repo = adopt(&fork, &hosts, DiscoverySource::Manual).unwrap();
+        assert_eq!(repo.host_id, Some(hosts[0].id.clone()));
+        assert_eq!(repo.full_name, "me/tool");
+        assert_eq!(repo.default_branch, "trunk");
+        assert!(repo.is_fork);
+        assert_eq!(repo.upstream_full_name.as_deref(), Some("them/tool"));
+        assert_eq!(repo.local_path.as_deref(), Some(Path::new("/src/tool")));
+
+        // A third-party clone on no registered host pulls from origin.
+        let clone = scanned(
+            &[("origin", "https://codeberg.org/them/tool.git")],
+            dir.path(),
+        );
+        let repo = adopt(&clone, &hosts, DiscoverySource::Filesystem).unwrap();
+        assert_eq!(repo.host_id, None);
+        assert!(!repo.is_fork);
+        assert_eq!(
+            repo.upstream_clone_url.as_deref(),
+            Some("https://codeberg.org/them/tool.git")
+        );
+
+        assert!(adopt(&scanned(&[], dir.path()), &hosts, DiscoverySource::Manual).is_none());
+    }
+}
@@ -0,0 +1,204 @@
+//! What changed on a host since a scan last recorded its repos: renames
+//! and transfers, deletions, and archive or visibility flips.
+
+use std::collections::HashMap;
+
+use gitr_core::error::GitrError;
+use gitr_core::models::repo::Repo;
+use gitr_host::{HostProvider, RemoteRepo};
+use serde::Serialize;
+
+/// One change to a tracked repo, for the scan's report.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum RemoteChange {
+    /// Renamed or transferred; the host redirects the old name to `to`.
+    Renamed {
+        from: String,
+        to: String,
+    },
+    /// Deleted on the host, or no longer visible to the account.
+    Gone {
+        repo: String,
+    },
+    /// Found on the host again after being marked gone.
+    Returned {
+        repo: String,
+    },
+    Archived {
+        repo: String,
+    },
+    Unarchived {
+        repo: String,
+    },
+    MadePrivate {
+        repo: String,
+    },
+    MadePublic {
+        repo: String,
+    },
+}
+
+impl std::fmt::Display for RemoteChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RemoteChange::Renamed { from, to } => write!(f, "renamed {from} -> {to}"),
+            RemoteChange::Gone { repo } => write!(f, "gone {repo}"),
+            RemoteChange::Returned { repo } => write!(f, "returned {repo}"),
+            RemoteChange::Archived { repo } => write!(f, "archived {repo}"),
+            RemoteChange::Unarchived { repo } => write!(f, "unarchived {repo}"),
+            RemoteChange::MadePrivate { repo } => write!(f, "made private {repo}"),
+            RemoteChange::MadePublic { repo } => write!(f, "made public {repo}"),
+        }
+    }
+}
+
+/// A host's listing compared with the repos tracked on it.
+#[derive(Debug, Default)]
+pub struct HostDiff<'a> {
+    /// Tracked repos the listing left out: renamed, transferred, deleted,
+    /// or simply not the account's own. See [`resolve_missing`].
+    pub missing: Vec<&'a Repo>,
+    /// Changes to tracked repos the listing included.
+    pub changes: Vec<RemoteChange>,
+}
+
+/// Compare the repos tracked on a host with what it listed. Names are
+/// compared case-insensitively, as hosts treat them.
+pub fn diff<'a, 'r>(
+    tracked: &'a [Repo],
+    listed: impl IntoIterator<Item = &'r RemoteRepo>,
+) -> HostDiff<'a> {
+    let listed: HashMap<String, &RemoteRepo> = listed
+        .into_iter()
+        .map(|r| (r.full_name.to_lowercase(), r))
+        .collect();
+    let mut result = HostDiff::default();
+    for repo in tracked {
+        match listed.get(&repo.full_name.to_lowercase()) {
+            Some(remote) => result.changes.extend(metadata_changes(repo, remote)),
+            None => result.missing.push(repo),
+        }
+    }
+    result
+}
+
+/// Changes between a tracked repo and the host's current view of it.
+pub fn metadata_changes(repo: &Repo, remote: &RemoteRepo) -> Vec<RemoteChange> {
+    let name = || remote.full_name.clone();
+    let mut changes = Vec::new();
+    if repo.gone_at.is_some() {
+        changes.push(RemoteChange::Returned { repo: name() });
+    }
+    match (repo.is_archived, remote.is_archived) {
+        (false, true) => changes.push(RemoteChange::Archived { repo: name() }),
+        (true, false) => changes.push(RemoteChange::Unarchived { repo: name() }),
+        _ => {}
+    }
+    match (repo.is_private, remote.is_private) {
+        (false, true) => changes.push(RemoteChange::MadePrivate { repo: name() }),
+        (true, false) => changes.push(RemoteChange::MadePublic { repo: name() }),
+        _ => {}
+    }
+    changes
+}
+
+/// What became of a tracked repo its host didn't list.
+#[derive(Debug)]
+pub enum Resolution {
+    /// Still there under the same name, just not among the account's repos.
+    Exists(RemoteRepo),
+    /// Renamed or transferred: the host redirected the old name here.
+    Moved(RemoteRepo),
+    /// The host doesn't know the repo any more.
+    Deleted,
+}
+
+/// Look up a repo missing from its host's listing by its recorded name.
+/// Hosts redirect renamed and transferred repos, so a lookup that comes
+/// back under another name is a move.
+pub async fn resolve_missing(
+    provider: &dyn HostProvider,
+    repo: &Repo,
+) -> Result<Resolution, GitrError> {
+    Ok(match provider.get_repo(&repo.owner, &repo.name).await? {
+        None => Resolution::Deleted,
+        Some(remote) if remote.full_name.eq_ignore_ascii_case(&repo.full_name) => {
+            Resolution::Exists(remote)
+        }
+        Some(remote) => Resolution::Moved(remote),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gitr_core::models::host::HostId;
+    use gitr_core::models::repo::DiscoverySource;
+
+    fn tracked(full_name: &str) -> Repo {
+        Repo::new(
+            full_name.to_string(),
+            HostId::new(),
+            format!("https://github.com/{full_name}.git"),
+            "main".to_string(),
+            DiscoverySource::Api,
+        )
+    }
+
+    fn listed(full_name: &str) -> RemoteRepo {
+        let (owner, name) = full_name.split_once('/').unwrap();
+        RemoteRepo {
+            full_name: full_name.to_string(),
+            owner: owner.to_string(),
+            name: name.to_string(),
+            clone_url: format!("https://github.com/{full_name}.git"),
+            ssh_url: format!("git@github.com:{full_name}.git"),
+            default_branch: "main".to_string(),
+            is_fork: false,
+            upstream_full_name: None,
+            upstream_clone_url: None,
+            description: None,
+            is_private: false,
+            is_archived: false,
+            language: None,
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn test_diff() {
+        let mut archived = tracked("me/old-tool");
+        archived.is_archived = true;
+        let mut gone = tracked("me/back");
+        gone.gone_at = Some(chrono::Utc::now());
+        let repos = vec![
+            tracked("Me/Tool"),
+            archived,
+            gone,
+            tracked("me/renamed-away"),
+        ];
+
+        let mut now_private = listed("me/tool");
+        now_private.is_private = true;
+        let host = vec![now_private, listed("me/old-tool"), listed("me/back")];
+
+        let diff = diff(&repos, &host);
+        let missing: Vec<&str> = diff.missing.iter().map(|r| r.full_name.as_str()).collect();
+        assert_eq!(missing, ["me/renamed-away"]);
+        assert_eq!(
+            diff.changes,
+            vec![
+                RemoteChange::MadePrivate {
+                    repo: "me/tool".to_string()
+                },
+                RemoteChange::Unarchived {
+                    repo: "me/old-tool".to_string()
+                },
+                RemoteChange::Returned {
+                    repo: "me/back".to_string()
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,737 @@
+//! A reader for git's config format, enough to resolve remotes the way git
+//! does: `[include]`/`[includeIf]` files, `url.<base>.insteadOf` and
+//! `pushInsteadOf` rewrites, `pushurl`, quoting, escapes and line
+//! continuations.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use globset::GlobBuilder;
+
+/// Includes nested deeper than this are an error, as in git.
+const MAX_INCLUDE_DEPTH: usize = 10;
+
+/// One `section.subsection.key = value` setting.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// Lowercased section name.
+    pub section: String,
+    /// Subsection, case preserved (lowercased for legacy `[section.sub]`).
+    pub subsection: Option<String>,
+    /// Lowercased key name.
+    pub key: String,
+    /// `None` for a key written without `=`, which git reads as true.
+    pub value: Option<String>,
+}
+
+/// Config entries in the order git would see them; later entries win.
+#[derive(Debug, Clone, Default)]
+pub struct GitConfig {
+    entries: Vec<Entry>,
+}
+
+/// The system and global config files git reads before a repo's own,
+/// lowest priority first. Honors `GIT_CONFIG_NOSYSTEM`, `GIT_CONFIG_SYSTEM`,
+/// `GIT_CONFIG_GLOBAL` and `XDG_CONFIG_HOME`.
+pub fn global_config_files() -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    if std::env::var_os("GIT_CONFIG_NOSYSTEM").is_none() {
+        files.push(
+            std::env::var_os("GIT_CONFIG_SYSTEM")
+                .map(PathBuf::from)
+                .unwrap_or_else(|| PathBuf::from("/etc/gitconfig")),
+        );
+    }
+    if let Some(global) = std::env::var_os("GIT_CONFIG_GLOBAL") {
+        files.push(PathBuf::from(global));
+    } else {
+        let xdg = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| dirs::home_dir().map(|h| h.join(".config")));
+        if let Some(xdg) = xdg {
+            files.push(xdg.join("git/config"));
+        }
+        if let Some(home) = dirs::home_dir() {
+            files.push(home.join(".gitconfig"));
+        }
+    }
+    files.retain(|f| f.is_file());
+    files
+}
+
+impl GitConfig {
+    /// The effective config of the repo whose git directory is `git_dir`:
+    /// `global_files` followed by the repo's own `config`.
+    pub fn for_repo(git_dir: &Path, global_files: &[PathBuf]) -> anyhow::Result<Self> {
+        let mut config = GitConfig::default();
+        for file in global_files {
+            config.extend(GitConfig::load(file, Some(git_dir))?);
+        }
+        config.extend(GitConfig::load(&git_dir.join("config"), Some(git_dir))?);
+        Ok(config)
+    }
+
+    /// Read one config file and the files it includes. `git_dir` is used to
+    /// evaluate `includeIf` conditions; without it they never match.
+    pub fn load(path: &Path, git_dir: Option<&Path>) -> anyhow::Result<Self> {
+        let mut config = GitConfig::default();
+        config.read_file(path, git_dir, 0)?;
+        Ok(config)
+    }
+
+    /// Parse config text; relative include paths resolve against `origin`'s
+    /// directory.
+    pub fn parse(text: &str, origin: &Path, git_dir: Option<&Path>) -> anyhow::Result<Self> {
+        let mut config = GitConfig::default();
+        config.parse_into(text, origin, git_dir, 0)?;
+        Ok(config)
+    }
+
+    /// Ask git itself for the remote and URL settings of a repo, for config
+    /// this reader can't handle.
+    pub fn from_git(git_dir: &Path) -> anyhow::Result<Self> {
+        let output = Command::new("git")
+            .arg("--git-dir")
+            .arg(git_dir)
+            .args(["config", "-z", "--get-regexp", r"^(remote|url|core)\."])
+            .output()?;
+        // Exit code 1 means no matching keys.
+        if !output.status.success() && output.status.code() != Some(1) {
+            anyhow::bail!(
+                "git config failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        let mut config = GitConfig::default();
+        for record in String::from_utf8_lossy(&output.stdout).split('\0') {
+            if record.is_empty() {
+                continue;
+            }
+            let (name, value) = match record.split_once('\n') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (record, None),
+            };
+            let Some((section, rest)) = name.split_once('.') else {
+                continue;
+            };
+            let (subsection, key) = match rest.rsplit_once('.') {
+                Some((sub, key)) => (Some(sub.to_string()), key),
+                None => (None, rest),
+            };
+            config.entries.push(Entry {
+                section: section.to_lowercase(),
+                subsection,
+                key: key.to_lowercase(),
+                value,
+            });
+        }
+        Ok(config)
+    }
+
+    /// Append `other`'s entries, which take precedence over these.
+    pub fn extend(&mut self, other: GitConfig) {
+        self.entries.extend(other.entries);
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Every value of a key, in order. Keys without a value read as "true".
+    pub fn get_all(&self, section: &str, subsection: Option<&str>, key: &str) -> Vec<&str> {
+        self.entries
+            .iter()
+            .filter(|e| {
+                e.section == section
+                    && e.subsection.as_deref() == subsection
+                    && e.key.eq_ignore_ascii_case(key)
+            })
+            .map(|e| e.value.as_deref().unwrap_or("true"))
+            .collect()
+    }
+
+    /// The effective (last) value of a key.
+    pub fn get(&self, section: &str, subsection: Option<&str>, key: &str) -> Option<&str> {
+        self.get_all(section, subsection, key).pop()
+    }
+
+    /// The distinct subsections of a section, in order of first appearance.
+    pub fn subsections(&self, section: &str) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::new();
+        for entry in self.entries.iter().filter(|e| e.section == section) {
+            if let Some(sub) = entry.subsection.as_deref() {
+                if !names.contains(&sub) {
+                    names.push(sub);
+                }
+            }
+        }
+        names
+    }
+
+    /// Apply the longest matching `url.<base>.insteadOf` (or
+    /// `pushInsteadOf` when `push`) prefix to `url`, if any. Of equally long
+    /// prefixes the first one wins, as in git.
+    pub fn rewrite_url(&self, url: &str, push: bool) -> Option<String> {
+        let key = if push { "pushinsteadof" } else { "insteadof" };
+        let mut best: Option<(&str, &str)> = None;
+        for entry in self
+            .entries
+            .iter()
+            .filter(|e| e.section == "url" && e.key == key)
+        {
+            let (Some(base), Some(prefix)) = (entry.subsection.as_deref(), entry.value.as_deref())
+            else {
+                continue;
+            };
+            if url.starts_with(prefix) && best.is_none_or(|(_, p)| p.len() < prefix.len()) {
+                best = Some((base, prefix));
+            }
+        }
+        best.map(|(base, prefix)| format!("{base}{}", &url[prefix.len()..]))
+    }
+
+    /// The URLs git fetches `remote` from.
+    pub fn fetch_urls(&self, remote: &str) -> Vec<String> {
+        self.get_all("remote", Some(remote), "url")
+            .into_iter()
+            .map(|url| {
+                self.rewrite_url(url, false)
+                    .unwrap_or_else(|| url.to_string())
+            })
+            .collect()
+    }
+
+    /// The URLs git pushes `remote` to: its `pushurl`s if set, otherwise its
+    /// `url`s with `pushInsteadOf` taking precedence over `insteadOf`.
+    pub fn push_urls(&self, remote: &str) -> Vec<String> {
+        let push_urls = self.get_all("remote", Some(remote), "pushurl");
+        if !push_urls.is_empty() {
+            return push_urls
+                .into_iter()
+                .map(|url| {
+                    self.rewrite_url(url, false)
+                        .unwrap_or_else(|| url.to_string())
+                })
+                .collect();
+        }
+        self.get_all("remote", Some(remote), "url")
+            .into_iter()
+            .map(|url| {
+                self.rewrite_url(url, true)
+                    .or_else(|| self.rewrite_url(url, false))
+                    .unwrap_or_else(|| url.to_string())
+            })
+            .collect()
+    }
+
+    fn read_file(
+        &mut self,
+        path: &Path,
+        git_dir: Option<&Path>,
+        depth: usize,
+    ) -> anyhow::Result<()> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("{}: {e}", path.display()))?;
+        self.parse_into(&text, path, git_dir, depth)
+    }
+
+    fn parse_into(
+        &mut self,
+        text: &str,
+        origin: &Path,
+        git_dir: Option<&Path>,
+        depth: usize,
+    ) -> anyhow::Result<()> {
+        let mut lexer = Lexer {
+            chars: text.trim_start_matches('\u{feff}').chars().collect(),
+            pos: 0,
+            line: 1,
+        };
+        let fail = |line: usize, what: &str| anyhow::anyhow!("{}:{line}: {what}", origin.display());
+        let mut section: Option<(String, Option<String>)> = None;
+
+        loop {
+            lexer.skip_whitespace();
+            let Some(c) = lexer.peek() else { break };
+            match c {
+                '\n' => lexer.bump(),
+                '#' | ';' => lexer.skip_line(),
+                '[' => {
+                    section = Some(lexer.section_header().map_err(|e| fail(lexer.line, e))?);
+                }
+                c if c.is_ascii_alphabetic() => {
+                    let line = lexer.line;
+                    let key = lexer.key();
+                    let value = lexer.value_part().map_err(|e| fail(line, e))?;
+                    let (name, sub) = section
+                        .clone()
+                        .ok_or_else(|| fail(line, "key outside of any section"))?;
+
+                    let include = match (name.as_str(), sub.as_deref()) {
+                        ("include", None) => true,
+                        ("includeif", Some(condition)) => {
+                            include_condition_holds(condition, origin, git_dir)
+                        }
+                        _ => false,
+                    };
+                    if include && key == "path" {
+                        if let Some(value) = &value {
+                            self.include(value, origin, git_dir, depth)?;
+                        }
+                    }
+                    self.entries.push(Entry {
+                        section: name,
+                        subsection: sub,
+                        key,
+                        value,
+                    });
+                }
+                _ => return Err(fail(lexer.line, "unexpected character")),
+            }
+        }
+        Ok(())
+    }
+
+    fn include(
+        &mut self,
+        value: &str,
+        origin: &Path,
+        git_dir: Option<&Path>,
+        depth: usize,
+    ) -> anyhow::Result<()> {
+        if depth >= MAX_INCLUDE_DEPTH {
+            anyhow::bail!("{}: includes nested too deeply", origin.display());
+        }
+        let path = resolve_path(value, origin);
+        // Git silently skips include files that don't exist.
+        if path.is_file() {
+            self.read_file(&path, git_dir, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+/// Expand `~/` and resolve a relative path against the including file.
+fn resolve_path(value: &str, origin: &Path) -> PathBuf {
+    if let Some(rest) = value.strip_prefix("~/") {
+        if let Some(home) = dirs::home_dir() {
+            return home.join(rest);
+        }
+    }
+    let path = Path::new(value);
+    if path.is_absolute() {
+        return path.to_path_buf();
+    }
+    origin.parent().unwrap_or(Path::new(".")).join(path)
+}
+
+/// Whether an `[includeIf "<condition>"]` applies. Supports `gitdir:`,
+/// `gitdir/i:` and `onbranch:`; other conditions never match.
+fn include_condition_holds(condition: &str, origin: &Path, git_dir: Option<&Path>) -> bool {
+    let Some(git_dir) = git_dir else {
+        return false;
+    };
+    let (pattern, case_insensitive, target) =
+        if let Some(pattern) = condition.strip_prefix("gitdir:") {
+            (
+                gitdir_pattern(pattern, origin),
+                false,
+                git_dir_candidates(git_dir),
+            )
+        } else if let Some(pattern) = condition.strip_prefix("gitdir/i:") {
+            (
+                gitdir_pattern(pattern, origin),
+                true,
+                git_dir_candidates(git_dir),
+            )
+        } else if let Some(pattern) = condition.strip_prefix("onbranch:") {
+            let Some(branch) = current_branch(git_dir) else {
+                return false;
+            };
+            let pattern = match pattern.strip_suffix('/') {
+                Some(_) => format!("{pattern}**"),
+                None => pattern.to_string(),
+            };
+            (pattern, false, vec![branch])
+        } else {
+            return false;
+        };
+
+    let Ok(glob) = GlobBuilder::new(&pattern)
+        .literal_separator(true)
+        .case_insensitive(case_insensitive)
+        .build()
+    else {
+        return false;
+    };
+    let matcher = glob.compile_matcher();
+    target.iter().any(|t| matcher.is_match(t))
+}
+
+/// Turn a `gitdir:` pattern into a glob the way git does.
+fn gitdir_pattern(pattern: &str, origin: &Path) -> String {
+    let mut pattern = if let Some(rest) = pattern.strip_prefix("~/") {
+        match dirs::home_dir() {
+            Some(home) => home.join(rest).to_string_lossy().into_owned(),
+            None => pattern.to_string(),
+        }
+    } else if let Some(rest) = pattern.strip_prefix("./") {
+        let base = origin.parent().unwrap_or(Path::new("."));
+        base.join(rest).to_string_lossy().into_owned()
+    } else if Path::new(pattern).is_absolute() {
+        pattern.to_string()
+    } else {
+        format!("**/{pattern}")
+    };
+    if pattern.ends_with('/') {
+        pattern.push_str("**");
+    }
+    pattern
+}
+
+/// The git directory as written and fully resolved, as git tries both.
+fn git_dir_candidates(git_dir: &Path) -> Vec<String> {
+    let mut candidates = vec![git_dir.to_string_lossy().into_owned()];
+    if let Ok(real) = std::fs::canonicalize(git_dir) {
+        candidates.push(real.to_string_lossy().into_owned());
+    }
+    candidates
+}
+
+/// The branch `HEAD` points at, if it is on one.
+fn current_branch(git_dir: &Path) -> Option<String> {
+    let head = std::fs::read_to_string(git_dir.join("HEAD")).ok()?;
+    head.trim()
+        .strip_prefix("ref: refs/heads/")
+        .map(str::to_string)
+}
+
+struct Lexer {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+}
+
+impl Lexer {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn bump(&mut self) {
+        if self.peek() == Some('\n') {
+            self.line += 1;
+        }
+        self.pos += 1;
+    }
+
+    /// Skip spaces and tabs, but not newlines.
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|c| c != '\n' && c.is_whitespace()) {
+            self.bump();
+        }
+    }
+
+    fn skip_line(&mut self) {
+        while let Some(c) = self.peek() {
+            self.bump();
+            if c == '\n' {
+                break;
+            }
+        }
+    }
+
+    /// `[section]`, `[section "subsection"]` or legacy `[section.sub]`.
+    fn section_header(&mut self) -> Result<(String, Option<String>), &'static str> {
+        self.bump();
+        let mut name = String::new();
+        while let Some(c) = self.peek() {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '.' {
+                name.push(c.to_ascii_lowercase());
+                self.bump();
+            } else {
+                break;
+            }
+        }
+        if name.is_empty() {
+            return Err("empty section name");
+        }
+        self.skip_whitespace();
+        let subsection = match self.peek() {
+            Some('"') => {
+                self.bump();
+                let mut sub = String::new();
+                loop {
+                    match self.peek() {
+                        Some('"') => {
+                            self.bump();
+                            break;
+                        }
+                        Some('\\') => {
+                            self.bump();
+                            match self.peek() {
+                                Some(c) if c != '\n' => {
+                                    sub.push(c);
+                                    self.bump();
+                                }
+                                _ => return Err("bad escape in subsection name"),
+                            }
+                        }
+                        Some('\n') | None => return Err("unterminated subsection name"),
+                        Some(c) => {
+                            sub.push(c);
+                            self.bump();
+                        }
+                    }
+                }
+                Some(sub)
+            }
+            _ => match name.split_once('.') {
+                Some((section, sub)) => {
+                    let sub = sub.to_string();
+                    name = section.to_string();
+                    Some(sub)
+                }
+                None => None,
+            },
+        };
+        self.skip_whitespace();
+        if self.peek() != Some(']') {
+            return Err("malformed section header");
+        }
+        self.bump();
+        Ok((name, subsection))
+    }
+
+    fn key(&mut self) -> String {
+        let mut key = String::new();
+        while let Some(c) = self.peek() {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                key.push(c.to_ascii_lowercase());
+                self.bump();
+            } else {
+                break;
+            }
+        }
+        key
+    }
+
+    /// What follows a key: `= value`, or nothing for a bare boolean key.
+    fn value_part(&mut self) -> Result<Option<String>, &'static str> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('=') => {
+                self.bump();
+                self.value().map(Some)
+            }
+            None | Some('\n') => Ok(None),
+            Some('#') | Some(';') => {
+                self.skip_line();
+                Ok(None)
+            }
+            Some(_) => Err("expected '=' after key"),
+        }
+    }
+
+    /// A value up to the end of its line. Whitespace outside quotes is kept
+    /// one space per character between words, as git does.
+    fn value(&mut self) -> Result<String, &'static str> {
+        let mut value = String::new();
+        let mut quoted = false;
+        let mut spaces = 0;
+        let mut comment = false;
+        while let Some(c) = self.peek() {
+            if c == '\n' {
+                if quoted {
+                    return Err("unterminated quote");
+                }
+                break;
+            }
+            self.bump();
+            if comment {
+                continue;
+            }
+            if c.is_whitespace() && !quoted {
+                if !value.is_empty() {
+                    spaces += 1;
+                }
+                continue;
+            }
+            if !quoted && (c == '#' || c == ';') {
+                comment = true;
+                continue;
+            }
+            value.extend(std::iter::repeat_n(' ', spaces));
+            spaces = 0;
+            match c {
+                '"' => quoted = !quoted,
+                '\\' => {
+                    let escaped = self.peek().ok_or("bad escape at end of file")?;
+                    self.bump();
+                    match escaped {
+                        // Line continuation.
+                        '\n' => {}
+                        'n' => value.push('\n'),
+                        't' => value.push('\t'),
+                        'b' => value.push('\u{8}'),
+                        '\\' | '"' => value.push(escaped),
+                        _ => return Err("bad escape in value"),
+                    }
+                }
+                c => value.push(c),
+            }
+        }
+        if quoted {
+            return Err("unterminated quote");
+        }
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> GitConfig {
+        GitConfig::parse(text, Path::new("/tmp/config"), None).unwrap()
+    }
+
+    #[test]
+    fn test_parse_syntax() {
+        let config = parse(
+            "# comment\n\
+             [core]\n\
+             \tbare\n\
+             \tEditor=vim   ; trailing comment\n\
+             [remote \"Up \\\"stream\\\"\"]\n\
+             url=\"https://example.com/a b.git\"  # quoted\n\
+             fetch = +refs/heads/*:refs/remotes/up/* \\\n\
+             \t+refs/tags/*:refs/tags/*\n\
+             [Branch.Main] remote = origin\n",
+        );
+        assert_eq!(config.get("core", None, "bare"), Some("true"));
+        assert_eq!(config.get("core", None, "editor"), Some("vim"));
+        assert_eq!(config.subsections("remote"), ["Up \"stream\""]);
+        assert_eq!(
+            config.get("remote", Some("Up \"stream\""), "url"),
+            Some("https://example.com/a b.git")
+        );
+        assert_eq!(
+            config.get("remote", Some("Up \"stream\""), "fetch"),
+            Some("+refs/heads/*:refs/remotes/up/*  +refs/tags/*:refs/tags/*")
+        );
+        assert_eq!(config.get("branch", Some("main"), "remote"), Some("origin"));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for bad in [
+            "key = value\n",
+            "[core\n",
+            "[remote \"x]\n",
+            "[core]\nurl = \"open\n",
+            "[core]\nurl = a\\qb\n",
+            "[core]\n= value\n",
+        ] {
+            assert!(
+                GitConfig::parse(bad, Path::new("config"), None).is_err(),
+                "{bad:?} should not parse"
+            );
+        }
+    }
+
+    #[test]
+    fn test_remote_urls_with_rewrites() {
+        let config = parse(
+            "[url \"git@github.com:\"]\n\
+             \tinsteadOf = gh:\n\
+             [url \"https://github.com/\"]\n\
+             \tinsteadOf = gh:\n\
+             \tinsteadOf = github:\n\
+             [url \"ssh://git@github.com/\"]\n\
+             \tpushInsteadOf = https://github.com/\n\
+             [remote \"origin\"]\n\
+             \turl = github:me/app.git\n\
+             [remote \"fork\"]\n\
+             \turl = gh:me/fork\n\
+             \tpushurl = gh:me/fork-push\n",
+        );
+        assert_eq!(
+            config.fetch_urls("origin"),
+            ["https://github.com/me/app.git"]
+        );
+        assert_eq!(
+            config.push_urls("origin"),
+            ["https://github.com/me/app.git"]
+        );
+        // Equal prefixes: the first one wins, as in git.
+        assert_eq!(config.fetch_urls("fork"), ["git@github.com:me/fork"]);
+        assert_eq!(config.push_urls("fork"), ["git@github.com:me/fork-push"]);
+
+        let config = parse(
+            "[url \"ssh://git@github.com/\"]\n\
+             \tpushInsteadOf = https://github.com/\n\
+             [remote \"origin\"]\n\
+             \turl = https://github.com/me/app.git\n",
+        );
+        assert_eq!(
+            config.push_urls("origin"),
+            ["ssh://git@github.com/me/app.git"]
+        );
+    }
+
+    #[test]
+    fn test_includes() {
+        let dir = tempfile::tempdir().unwrap();
+        let git_dir = dir.path().join("work/app/.git");
+        std::fs::create_dir_all(&git_dir).unwrap();
+        std::fs::write(git_dir.join("HEAD"), "ref: refs/heads/release/1.0\n").unwrap();
+        std::fs::write(
+            dir.path().join("always.inc"),
+            "[url \"https://a/\"]\n\tinsteadOf = a:\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("work.inc"),
+            "[url \"https://w/\"]\n\tinsteadOf = w:\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("home.inc"),
+            "[url \"https://h/\"]\n\tinsteadOf = h:\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("rel.inc"),
+            "[url \"https://r/\"]\n\tinsteadOf = r:\n",
+        )
+        .unwrap();
+        let main = dir.path().join("gitconfig");
+        std::fs::write(
+            &main,
+            "[include]\n\tpath = always.inc\n\tpath = missing.inc\n\
+             [includeIf \"gitdir:work/\"]\n\tpath = work.inc\n\
+             [includeIf \"gitdir:/nowhere/\"]\n\tpath = home.inc\n\
+             [includeIf \"onbranch:release/\"]\n\tpath = rel.inc\n",
+        )
+        .unwrap();
+
+        let config = GitConfig::load(&main, Some(&git_dir)).unwrap();
+        let rewrite = |url: &str| config.rewrite_url(url, false);
+        assert_eq!(rewrite("a:x").as_deref(), Some("https://a/x"));
+        assert_eq!(rewrite("w:x").as_deref(), Some("https://w/x"));
+        assert_eq!(rewrite("r:x").as_deref(), Some("https://r/x"));
+        assert_eq!(rewrite("h:x"), None);
+
+        // Without a repo, conditional includes don't apply.
+        let config = GitConfig::load(&main, None).unwrap();
+        assert_eq!(config.rewrite_url("w:x", false), None);
+
+        std::fs::write(
+            dir.path().join("loop.inc"),
+            "[include]\n\tpath = loop.inc\n",
+        )
+        .unwrap();
+        assert!(GitConfig::load(&dir.path().join("loop.inc"), None).is_err());
+    }
+}
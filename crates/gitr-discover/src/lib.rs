@@ -1,31 +1,38 @@
+pub mod adopt;
+pub mod changes;
+pub mod gitconfig;
+pub mod lineage;
 pub mod reconcile;
 pub mod scanner;
 
+use std::time::Instant;
+
 use gitr_core::error::GitrError;
+use gitr_core::metrics;
 use gitr_core::models::host::Host;
 use gitr_host::HostProvider;
 
-use crate::reconcile::{reconcile, ReconcileResult};
-use crate::scanner::scan_directory;
+use crate::reconcile::{reconcile, HostRepos, ReconcileResult};
+use crate::scanner::ScannedRepo;
 
-/// Discover repos by scanning the filesystem and querying a host API, then reconcile.
+/// Discover repos by querying each host's API, then reconcile them all at
+/// once with the repos a filesystem scan found.
 pub async fn discover(
-    host: &Host,
-    provider: &dyn HostProvider,
-    scan_paths: &[std::path::PathBuf],
-    max_depth: usize,
+    hosts: &[(&Host, &dyn HostProvider)],
+    local_repos: &[ScannedRepo],
 ) -> Result<ReconcileResult, GitrError> {
-    // 1. Local filesystem scan
-    let mut local_repos = Vec::new();
-    for path in scan_paths {
-        if path.exists() {
-            local_repos.extend(scan_directory(path, max_depth));
-        }
+    // 1. API queries
+    let mut remote = Vec::with_capacity(hosts.len());
+    for (host, provider) in hosts {
+        let started = Instant::now();
+        let repos = provider.list_repos().await?;
+        metrics::observe_scan(&host.label, "api", started.elapsed().as_secs_f64());
+        remote.push(HostRepos {
+            host_label: host.label.clone(),
+            repos,
+        });
     }
 
-    // 2. API query
-    let remote_repos = provider.list_repos().await?;
-
-    // 3. Reconcile
-    Ok(reconcile(&local_repos, &remote_repos, &host.label))
+    // 2. Reconcile
+    Ok(reconcile(local_repos, &remote))
 }
@@ -0,0 +1,240 @@
+//! Fork lineage between tracked repos: which tracked repo each fork was
+//! forked from, and the trees those links form.
+
+use std::collections::{HashMap, HashSet};
+
+use gitr_core::models::repo::{Repo, RepoId};
+
+use crate::reconcile::normalize_url;
+
+/// The `upstream_repo_id` each fork should have, for the forks whose
+/// stored link is out of date. A fork's upstream is the tracked repo its
+/// upstream clone URL points at, on whichever host that is; failing that,
+/// the repo named by its upstream full name on the fork's own host.
+/// Non-forks and forks with no tracked upstream get `None`.
+pub fn resolve_upstreams(repos: &[Repo]) -> Vec<(RepoId, Option<RepoId>)> {
+    let by_url: HashMap<String, &Repo> = repos
+        .iter()
+        .map(|r| (normalize_url(&r.clone_url), r))
+        .collect();
+    let mut links = Vec::new();
+    for repo in repos {
+        let upstream = repo
+            .is_fork
+            .then(|| upstream_of(repo, repos, &by_url))
+            .flatten()
+            .map(|u| u.id.clone());
+        if upstream != repo.upstream_repo_id {
+            links.push((repo.id.clone(), upstream));
+        }
+    }
+    links
+}
+
+fn upstream_of<'a>(
+    fork: &Repo,
+    repos: &'a [Repo],
+    by_url: &HashMap<String, &'a Repo>,
+) -> Option<&'a Repo> {
+    let by_url = fork
+        .upstream_clone_url
+        .as_ref()
+        .and_then(|url| by_url.get(&normalize_url(url)).copied());
+    let by_name = || {
+        let name = fork.upstream_full_name.as_deref()?;
+        repos.iter().find(|r| {
+            fork.host_id.is_some()
+                && r.host_id == fork.host_id
+                && r.full_name.eq_ignore_ascii_case(name)
+        })
+    };
+    by_url.or_else(by_name).filter(|u| u.id != fork.id)
+}
+
+/// Tracked repos arranged by their `upstream_repo_id` links.
+pub struct ForkTree<'a> {
+    repos: HashMap<&'a RepoId, &'a Repo>,
+    children: HashMap<&'a RepoId, Vec<&'a Repo>>,
+}
+
+impl<'a> ForkTree<'a> {
+    pub fn new(repos: &'a [Repo]) -> Self {
+        let mut children: HashMap<&RepoId, Vec<&Repo>> = HashMap::new();
+        for repo in repos {
+            if let Some(upstream) = &repo.upstream_repo_id {
+                children.entry(upstream).or_default().push(repo);
+            }
+        }
+        Self {
+            repos: repos.iter().map(|r| (&r.id, r)).collect(),
+            children,
+        }
+    }
+
+    /// The tracked repo `repo` was forked from.
+    pub fn parent(&self, repo: &Repo) -> Option<&'a Repo> {
+        self.repos.get(repo.upstream_repo_id.as_ref()?).copied()
+    }
+
+    /// `repo`'s parent, its parent's parent and so on, nearest first.
+    pub fn ancestors(&self, repo: &Repo) -> Vec<&'a Repo> {
+        let mut seen = HashSet::from([&repo.id]);
+        let mut ancestors = Vec::new();
+        let mut current = self.parent(repo);
+        while let Some(parent) = current {
+            // Links can't form a cycle on a real host, but the database
+            // doesn't prevent one.
+            if !seen.insert(&parent.id) {
+                break;
+            }
+            ancestors.push(parent);
+            current = self.parent(parent);
+        }
+        ancestors
+    }
+
+    /// The tracked forks of `repo`, in the order the tree was built from.
+    pub fn children(&self, repo: &Repo) -> &[&'a Repo] {
+        self.children.get(&repo.id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Forks of `repo`, forks of those and so on, depth first, each with
+    /// its depth below `repo` (1 for direct forks).
+    pub fn descendants(&self, repo: &Repo) -> Vec<(usize, &'a Repo)> {
+        let mut seen = HashSet::from([&repo.id]);
+        let mut descendants = Vec::new();
+        let mut stack: Vec<(usize, &Repo)> =
+            self.children(repo).iter().rev().map(|c| (1, *c)).collect();
+        while let Some((depth, child)) = stack.pop() {
+            if !seen.insert(&child.id) {
+                continue;
+            }
+            descendants.push((depth, child));
+            stack.extend(self.children(child).iter().rev().map(|c| (depth + 1, *c)));
+        }
+        descendants
+    }
+
+    /// The repo at the top of `repo`'s family: its furthest tracked
+    /// ancestor, or itself.
+    pub fn root<'r>(&self, repo: &'r Repo) -> &'r Repo
+    where
+        'a: 'r,
+    {
+        self.ancestors(repo).last().copied().unwrap_or(repo)
+    }
+
+    /// Whether `repo` has tracked relatives: a parent or forks.
+    pub fn has_family(&self, repo: &Repo) -> bool {
+        self.parent(repo).is_some() || !self.children(repo).is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gitr_core::models::host::HostId;
+    use gitr_core::models::repo::DiscoverySource;
+
+    fn repo(full_name: &str, host: &HostId, url: &str) -> Repo {
+        Repo::new(
+            full_name.to_string(),
+            host.clone(),
+            url.to_string(),
+            "main".to_string(),
+            DiscoverySource::Api,
+        )
+    }
+
+    fn fork_of(mut repo: Repo, upstream: &str, url: Option<&str>) -> Repo {
+        repo.is_fork = true;
+        repo.upstream_full_name = Some(upstream.to_string());
+        repo.upstream_clone_url = url.map(str::to_string);
+        repo
+    }
+
+    #[test]
+    fn test_resolve_upstreams() {
+        let gh = HostId::new();
+        let gl = HostId::new();
+        let org = repo("acme/tool", &gh, "https://github.com/acme/tool.git");
+        // Found by URL, in the other protocol.
+        let mine = fork_of(
+            repo("me/tool", &gh, "https://github.com/me/tool.git"),
+            "acme/tool",
+            Some("git@github.com:acme/tool.git"),
+        );
+        // A fork of a fork on another host, found by URL.
+        let mirror = fork_of(
+            repo("me/tool", &gl, "https://gitlab.com/me/tool.git"),
+            "me/tool",
+            Some("https://github.com/me/tool"),
+        );
+        // No URL recorded: found by name on its own host only.
+        let by_name = fork_of(
+            repo("you/tool", &gh, "https://github.com/you/tool.git"),
+            "Acme/Tool",
+            None,
+        );
+        let elsewhere = fork_of(
+            repo("you/tool", &gl, "https://gitlab.com/you/tool.git"),
+            "acme/tool",
+            None,
+        );
+        let mut stale = repo("me/other", &gh, "https://github.com/me/other.git");
+        stale.upstream_repo_id = Some(org.id.clone());
+
+        let repos = vec![org, mine, mirror, by_name, elsewhere, stale];
+        let links = resolve_upstreams(&repos);
+        let id = |i: usize| repos[i].id.clone();
+        assert_eq!(
+            links,
+            vec![
+                (id(1), Some(id(0))),
+                (id(2), Some(id(1))),
+                (id(3), Some(id(0))),
+                (id(5), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fork_tree() {
+        let gh = HostId::new();
+        let mut repos = vec![
+            repo("acme/tool", &gh, "https://github.com/acme/tool"),
+            repo("me/tool", &gh, "https://github.com/me/tool"),
+            repo("you/tool", &gh, "https://github.com/you/tool"),
+            repo("me/tool-fix", &gh, "https://github.com/me/tool-fix"),
+            repo("me/unrelated", &gh, "https://github.com/me/unrelated"),
+        ];
+        repos[1].upstream_repo_id = Some(repos[0].id.clone());
+        repos[2].upstream_repo_id = Some(repos[0].id.clone());
+        repos[3].upstream_repo_id = Some(repos[1].id.clone());
+        let tree = ForkTree::new(&repos);
+        let names = |list: Vec<&Repo>| -> Vec<String> {
+            list.iter().map(|r| r.full_name.clone()).collect()
+        };
+
+        assert_eq!(names(tree.ancestors(&repos[3])), ["me/tool", "acme/tool"]);
+        assert_eq!(tree.root(&repos[3]).full_name, "acme/tool");
+        assert_eq!(tree.root(&repos[4]).full_name, "me/unrelated");
+        let descendants: Vec<(usize, &str)> = tree
+            .descendants(&repos[0])
+            .into_iter()
+            .map(|(depth, r)| (depth, r.full_name.as_str()))
+            .collect();
+        assert_eq!(
+            descendants,
+            [(1, "me/tool"), (2, "me/tool-fix"), (1, "you/tool")]
+        );
+        assert!(tree.has_family(&repos[2]));
+        assert!(!tree.has_family(&repos[4]));
+
+        // A cycle in the stored links doesn't hang the walk.
+        repos[0].upstream_repo_id = Some(repos[3].id.clone());
+        let tree = ForkTree::new(&repos);
+        assert_eq!(tree.ancestors(&repos[3]).len(), 2);
+        assert_eq!(tree.descendants(&repos[0]).len(), 3);
+    }
+}
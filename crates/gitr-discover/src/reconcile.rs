@@ -1,25 +1,66 @@
+use std::collections::{HashMap, HashSet};
+
 use gitr_host::RemoteRepo;
 
 use crate::scanner::ScannedRepo;
 
+/// The repos one host's API reported.
+#[derive(Debug, Clone)]
+pub struct HostRepos {
+    pub host_label: String,
+    pub repos: Vec<RemoteRepo>,
+}
+
 /// Classification of a repo during reconciliation.
 #[derive(Debug, Clone)]
 pub enum RepoMatch {
-    /// Found both locally and on the remote host.
+    /// Found both locally and on a host. A checkout with several remotes
+    /// (a fork's `origin` and `upstream`) matches once per repo they name.
     Matched {
         local: ScannedRepo,
         remote: RemoteRepo,
+        host_label: String,
+        /// The local remote whose URL matched, e.g. `origin`.
+        remote_name: String,
+        /// Whether this is the checkout's own repo rather than one it
+        /// merely tracks: the first match, trying `origin` before other
+        /// remotes.
+        primary: bool,
     },
-    /// Found locally but not on the remote host.
+    /// Found locally but on none of the hosts.
     LocalOnly(ScannedRepo),
-    /// Found on the remote host but not locally.
-    RemoteOnly(RemoteRepo),
+    /// Found on a host but not locally.
+    RemoteOnly {
+        remote: RemoteRepo,
+        host_label: String,
+    },
 }
 
-/// Result of reconciling local and remote repos.
+impl RepoMatch {
+    /// The host a matched or remote-only repo is on.
+    pub fn host_label(&self) -> Option<&str> {
+        match self {
+            RepoMatch::Matched { host_label, .. } | RepoMatch::RemoteOnly { host_label, .. } => {
+                Some(host_label)
+            }
+            RepoMatch::LocalOnly(_) => None,
+        }
+    }
+
+    /// The host's repo, for matched and remote-only repos.
+    pub fn remote(&self) -> Option<&RemoteRepo> {
+        match self {
+            RepoMatch::Matched { remote, .. } | RepoMatch::RemoteOnly { remote, .. } => {
+                Some(remote)
+            }
+            RepoMatch::LocalOnly(_) => None,
+        }
+    }
+}
+
+/// Result of reconciling local repos with every host's repos.
 #[derive(Debug)]
 pub struct ReconcileResult {
-    pub host_label: String,
     pub matches: Vec<RepoMatch>,
 }
 
@@ -41,66 +82,88 @@ impl ReconcileResult {
     pub fn remote_only_count(&self) -> usize {
         self.matches
             .iter()
-            .filter(|m| matches!(m, RepoMatch::RemoteOnly(_)))
+            .filter(|m| matches!(m, RepoMatch::RemoteOnly { .. }))
             .count()
     }
+
+    /// Matched and remote-only entries for one host.
+    pub fn for_host<'a>(&'a self, host_label: &'a str) -> impl Iterator<Item = &'a RepoMatch> {
+        self.matches
+            .iter()
+            .filter(move |m| m.host_label() == Some(host_label))
+    }
 }
 
-/// Reconcile scanned local repos with remote repos by normalizing URLs.
-pub fn reconcile(
-    local: &[ScannedRepo],
-    remote: &[RemoteRepo],
-    host_label: &str,
-) -> ReconcileResult {
+/// Reconcile scanned local repos with the repos of all hosts at once.
+/// Remote repos are indexed by their normalized clone and SSH URLs, and
+/// each local repo is looked up by the fetch and push URLs of all its
+/// remotes, so it can match repos on several hosts.
+pub fn reconcile(local: &[ScannedRepo], hosts: &[HostRepos]) -> ReconcileResult {
+    let mut by_url: HashMap<String, Vec<(usize, usize)>> = HashMap::new();
+    for (host_idx, host) in hosts.iter().enumerate() {
+        for (repo_idx, repo) in host.repos.iter().enumerate() {
+            let mut urls = vec![normalize_url(&repo.clone_url)];
+            if !repo.ssh_url.is_empty() {
+                urls.push(normalize_url(&repo.ssh_url));
+            }
+            urls.dedup();
+            for url in urls {
+                by_url.entry(url).or_default().push((host_idx, repo_idx));
+            }
+        }
+    }
+
     let mut matches = Vec::new();
-    let mut matched_remote_indices = std::collections::HashSet::new();
+    let mut matched: HashSet<(usize, usize)> = HashSet::new();
 
     for local_repo in local {
-        let mut found = false;
-        for (idx, remote_repo) in remote.iter().enumerate() {
-            if urls_match(local_repo, remote_repo) {
-                matches.push(RepoMatch::Matched {
-                    local: local_repo.clone(),
-                    remote: remote_repo.clone(),
-                });
-                matched_remote_indices.insert(idx);
-                found = true;
-                break;
+        let mut remotes: Vec<_> = local_repo.remotes.iter().collect();
+        remotes.sort_by_key(|r| r.name != "origin");
+
+        let mut seen: HashSet<(usize, usize)> = HashSet::new();
+        for remote in remotes {
+            let urls = std::iter::once(&remote.url).chain(&remote.push_urls);
+            for url in urls {
+                let Some(found) = by_url.get(&normalize_url(url)) else {
+                    continue;
+                };
+                for &(host_idx, repo_idx) in found {
+                    if !seen.insert((host_idx, repo_idx)) {
+                        continue;
+                    }
+                    matched.insert((host_idx, repo_idx));
+                    matches.push(RepoMatch::Matched {
+                        local: local_repo.clone(),
+                        remote: hosts[host_idx].repos[repo_idx].clone(),
+                        host_label: hosts[host_idx].host_label.clone(),
+                        remote_name: remote.name.clone(),
+                        primary: seen.len() == 1,
+                    });
+                }
             }
         }
-        if !found {
+        if seen.is_empty() {
             matches.push(RepoMatch::LocalOnly(local_repo.clone()));
         }
     }
 
-    for (idx, remote_repo) in remote.iter().enumerate() {
-        if !matched_remote_indices.contains(&idx) {
-            matches.push(RepoMatch::RemoteOnly(remote_repo.clone()));
+    for (host_idx, host) in hosts.iter().enumerate() {
+        for (repo_idx, repo) in host.repos.iter().enumerate() {
+            if !matched.contains(&(host_idx, repo_idx)) {
+                matches.push(RepoMatch::RemoteOnly {
+                    remote: repo.clone(),
+                    host_label: host.host_label.clone(),
+                });
+            }
         }
     }
 
-    ReconcileResult {
-        host_label: host_label.to_string(),
-        matches,
-    }
-}
-
-/// Check if any of the local repo's remote URLs match the remote repo's clone/SSH URL.
-fn urls_match(local: &ScannedRepo, remote: &RemoteRepo) -> bool {
-    for scanned_remote in &local.remotes {
-        let local_normalized = normalize_url(&scanned_remote.url);
-        if local_normalized == normalize_url(&remote.clone_url)
-            || local_normalized == normalize_url(&remote.ssh_url)
-        {
-            return true;
-        }
-    }
-    false
+    ReconcileResult { matches }
 }
 
 /// Normalize a git URL for comparison.
 /// Strips protocol, trailing .git, and converts SSH to HTTPS-style path.
-fn normalize_url(url: &str) -> String {
+pub fn normalize_url(url: &str) -> String {
     let mut s = url.to_lowercase();
 
     // Strip protocol
@@ -158,21 +221,30 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_reconcile_match() {
-        let local = vec![ScannedRepo {
-            path: "/home/user/repos/myrepo".into(),
-            remotes: vec![crate::scanner::ScannedRemote {
-                name: "origin".to_string(),
-                url: "https://github.com/user/myrepo.git".to_string(),
-            }],
-        }];
-        let remote = vec![RemoteRepo {
-            full_name: "user/myrepo".to_string(),
-            owner: "user".to_string(),
-            name: "myrepo".to_string(),
-            clone_url: "https://github.com/user/myrepo.git".to_string(),
-            ssh_url: "git@github.com:user/myrepo.git".to_string(),
+    fn local(path: &str, remotes: &[(&str, &str)]) -> ScannedRepo {
+        ScannedRepo {
+            path: path.into(),
+            git_dir: format!("{path}/.git").into(),
+            checkouts: Vec::new(),
+            remotes: remotes
+                .iter()
+                .map(|(name, url)| crate::scanner::ScannedRemote {
+                    name: name.to_string(),
+                    url: url.to_string(),
+                    push_urls: Vec::new(),
+                })
+                .collect(),
+        }
+    }
+
+    fn remote(domain: &str, full_name: &str) -> RemoteRepo {
+        let (owner, name) = full_name.split_once('/').unwrap();
+        RemoteRepo {
+            full_name: full_name.to_string(),
+            owner: owner.to_string(),
+            name: name.to_string(),
+            clone_url: format!("https://{domain}/{full_name}.git"),
+            ssh_url: format!("git@{domain}:{full_name}.git"),
             default_branch: "main".to_string(),
             is_fork: false,
             upstream_full_name: None,
@@ -180,12 +252,94 @@ mod tests {
             description: None,
             is_private: false,
             is_archived: false,
+            language: None,
             updated_at: None,
+        }
+    }
+
+    #[test]
+    fn test_reconcile_match() {
+        let local = vec![local(
+            "/home/user/repos/myrepo",
+            &[("origin", "https://github.com/user/myrepo.git")],
+        )];
+        let hosts = vec![HostRepos {
+            host_label: "gh".to_string(),
+            repos: vec![remote("github.com", "user/myrepo")],
         }];
 
-        let result = reconcile(&local, &remote, "gh");
+        let result = reconcile(&local, &hosts);
         assert_eq!(result.matched_count(), 1);
         assert_eq!(result.local_only_count(), 0);
         assert_eq!(result.remote_only_count(), 0);
     }
+
+    #[test]
+    fn test_reconcile_across_hosts() {
+        let local = vec![
+            // A GitHub fork checkout that also tracks its upstream.
+            local(
+                "/src/tool",
+                &[
+                    ("upstream", "git@github.com:them/tool.git"),
+                    ("origin", "https://github.com/me/tool"),
+                ],
+            ),
+            local(
+                "/src/infra",
+                &[("work", "git@gitlab.example.com:ops/infra.git")],
+            ),
+            local(
+                "/src/scratch",
+                &[("origin", "https://example.org/me/scratch.git")],
+            ),
+        ];
+        let hosts = vec![
+            HostRepos {
+                host_label: "gh".to_string(),
+                repos: vec![
+                    remote("github.com", "me/tool"),
+                    remote("github.com", "them/tool"),
+                    remote("github.com", "me/unused"),
+                ],
+            },
+            HostRepos {
+                host_label: "work".to_string(),
+                repos: vec![remote("gitlab.example.com", "ops/infra")],
+            },
+        ];
+
+        let result = reconcile(&local, &hosts);
+        let matched: Vec<_> = result
+            .matches
+            .iter()
+            .filter_map(|m| match m {
+                RepoMatch::Matched {
+                    remote,
+                    host_label,
+                    remote_name,
+                    primary,
+                    ..
+                } => Some((
+                    host_label.as_str(),
+                    remote.full_name.as_str(),
+                    remote_name.as_str(),
+                    *primary,
+                )),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            matched,
+            [
+                ("gh", "me/tool", "origin", true),
+                ("gh", "them/tool", "upstream", false),
+                ("work", "ops/infra", "work", true),
+            ]
+        );
+        assert_eq!(result.local_only_count(), 1);
+        assert_eq!(result.remote_only_count(), 1);
+        assert_eq!(result.for_host("work").count(), 1);
+        assert_eq!(result.for_host("gh").count(), 3);
+    }
 }
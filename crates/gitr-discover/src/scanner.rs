@@ -1,22 +1,53 @@
-use std::path::{Path, PathBuf};
-use walkdir::WalkDir;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
 
-/// A repo found on the local filesystem.
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::Match;
+use rayon::prelude::*;
+
+use gitr_core::models::scan_index::ScanIndexEntry;
+pub use gitr_core::models::scan_index::{Checkout, CheckoutKind};
+
+use crate::gitconfig::{global_config_files, GitConfig};
+
+/// A repo found on the local filesystem, with every checkout of it the
+/// scan came across. Linked worktrees share their main repo's entry.
 #[derive(Debug, Clone)]
 pub struct ScannedRepo {
+    /// The primary working tree, or the repo directory if it is bare;
+    /// otherwise the first checkout found.
     pub path: PathBuf,
+    /// The git directory shared by all checkouts (the "common dir").
+    pub git_dir: PathBuf,
+    pub checkouts: Vec<Checkout>,
     pub remotes: Vec<ScannedRemote>,
 }
 
+impl ScannedRepo {
+    /// A working tree to run git in: the primary one if it was found, else
+    /// any non-bare checkout.
+    pub fn working_tree(&self) -> Option<&Path> {
+        self.checkouts
+            .iter()
+            .find(|c| c.kind == CheckoutKind::Primary)
+            .or_else(|| self.checkouts.iter().find(|c| c.kind != CheckoutKind::Bare))
+            .map(|c| c.path.as_path())
+    }
+}
+
 /// A git remote parsed from a local repo's config.
 #[derive(Debug, Clone)]
 pub struct ScannedRemote {
     pub name: String,
+    /// The URL git fetches from, after `insteadOf` rewrites.
     pub url: String,
+    /// The URLs git pushes to, after `pushurl` and `pushInsteadOf`.
+    pub push_urls: Vec<String>,
 }
 
 /// Directories to skip during scanning.
-const SKIP_DIRS: &[&str] = &[
+pub const SKIP_DIRS: &[&str] = &[
     "node_modules",
     "target",
     "vendor",
@@ -26,74 +57,391 @@ const SKIP_DIRS: &[&str] = &[
     "venv",
 ];
 
+/// Per-directory file of gitignore-style patterns for directories the scan
+/// should skip, relative to the directory it sits in.
+pub const IGNORE_FILE: &str = ".gitrignore";
+
+/// Directories modified this recently (in nanoseconds) when the scan looks
+/// at them are not trusted on the next scan: a change in the same clock
+/// tick would leave their mtime unchanged.
+const RACY_WINDOW_NS: i64 = 2_000_000_000;
+
+/// How to walk a scan path.
+#[derive(Debug, Clone)]
+pub struct ScanOptions {
+    /// Deepest directory level to look at; the scan path itself is level 0.
+    pub max_depth: usize,
+    /// Gitignore-style patterns, relative to the scan path, for directories
+    /// to skip on top of [`SKIP_DIRS`] and [`IGNORE_FILE`]s.
+    pub ignore: Vec<String>,
+    /// Keep descending into repos to find nested ones and submodules.
+    pub nested: bool,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: 4,
+            ignore: Vec::new(),
+            nested: false,
+        }
+    }
+}
+
+/// The result of scanning one path.
+#[derive(Debug, Clone)]
+pub struct Scan {
+    pub repos: Vec<ScannedRepo>,
+    /// Every directory looked at, to pass back in on the next scan.
+    pub index: Vec<ScanIndexEntry>,
+    /// How many of those directories were unchanged since the last scan and
+    /// not read again.
+    pub unchanged: usize,
+}
+
 /// Scan a directory tree for git repos, up to `max_depth` levels deep.
+/// Checkouts sharing a git directory are reported as one repo.
 pub fn scan_directory(root: &Path, max_depth: usize) -> Vec<ScannedRepo> {
-    let mut repos = Vec::new();
+    let options = ScanOptions {
+        max_depth,
+        ..ScanOptions::default()
+    };
+    match scan(root, &options, Vec::new()) {
+        Ok(scan) => scan.repos,
+        Err(e) => {
+            tracing::warn!("scanning {} failed: {e}", root.display());
+            Vec::new()
+        }
+    }
+}
 
-    let walker = WalkDir::new(root)
-        .max_depth(max_depth)
-        .follow_links(false)
-        .into_iter()
-        .filter_entry(|entry| {
-            if !entry.file_type().is_dir() {
-                return true;
-            }
-            let name = entry.file_name().to_string_lossy();
-            !SKIP_DIRS.contains(&name.as_ref())
-        });
+/// Scan a directory tree for git repos in parallel. Directories whose mtime
+/// matches their entry in `previous` (the index of the last scan of `root`)
+/// are not read again: their subdirectories and checkout come from the
+/// index. The scan stops descending at the repos it finds unless
+/// `options.nested` is set, and never descends into bare repos.
+pub fn scan(
+    root: &Path,
+    options: &ScanOptions,
+    previous: Vec<ScanIndexEntry>,
+) -> anyhow::Result<Scan> {
+    let mut builder = GitignoreBuilder::new(root);
+    for pattern in &options.ignore {
+        builder.add_line(None, pattern)?;
+    }
+    let walk = Walk {
+        options,
+        previous: previous.into_iter().map(|e| (e.path.clone(), e)).collect(),
+        racy_after: now_ns() - RACY_WINDOW_NS,
+    };
+    let ignores = IgnoreChain {
+        matcher: Some(builder.build()?),
+        parent: None,
+    };
+    let visit = walk.visit(root, 0, &ignores);
 
-    for entry in walker.filter_map(|e| e.ok()) {
-        if !entry.file_type().is_dir() {
-            continue;
+    let mut groups: Vec<(PathBuf, Vec<Checkout>)> = Vec::new();
+    let mut by_git_dir: HashMap<PathBuf, usize> = HashMap::new();
+    for (git_dir, checkout) in visit.found {
+        let git_dir = canonical(&git_dir);
+        let idx = *by_git_dir.entry(git_dir.clone()).or_insert_with(|| {
+            groups.push((git_dir, Vec::new()));
+            groups.len() - 1
+        });
+        let checkouts = &mut groups[idx].1;
+        if !checkouts
+            .iter()
+            .any(|c| canonical(&c.path) == canonical(&checkout.path))
+        {
+            checkouts.push(checkout);
         }
-        let git_dir = entry.path().join(".git");
-        if git_dir.is_dir() {
-            let config_path = git_dir.join("config");
-            if config_path.exists() {
-                let remotes = parse_git_config(&config_path);
-                repos.push(ScannedRepo {
-                    path: entry.path().to_path_buf(),
-                    remotes,
-                });
+    }
+
+    let global_files = global_config_files();
+    let repos = groups
+        .into_par_iter()
+        .map(|(git_dir, checkouts)| {
+            let path = checkouts
+                .iter()
+                .find(|c| matches!(c.kind, CheckoutKind::Primary | CheckoutKind::Bare))
+                .unwrap_or(&checkouts[0])
+                .path
+                .clone();
+            ScannedRepo {
+                path,
+                remotes: parse_git_config(&git_dir, &global_files),
+                git_dir,
+                checkouts,
+            }
+        })
+        .collect();
+
+    Ok(Scan {
+        repos,
+        index: visit.index,
+        unchanged: visit.unchanged,
+    })
+}
+
+/// Shared state of one scan.
+struct Walk<'a> {
+    options: &'a ScanOptions,
+    previous: HashMap<PathBuf, ScanIndexEntry>,
+    /// Directories modified after this are recorded as never seen.
+    racy_after: i64,
+}
+
+/// What a directory and everything below it turned up, in walk order.
+#[derive(Default)]
+struct Visit {
+    index: Vec<ScanIndexEntry>,
+    found: Vec<(PathBuf, Checkout)>,
+    unchanged: usize,
+}
+
+impl Visit {
+    fn merge(mut self, other: Visit) -> Visit {
+        self.index.extend(other.index);
+        self.found.extend(other.found);
+        self.unchanged += other.unchanged;
+        self
+    }
+}
+
+impl Walk<'_> {
+    fn visit(&self, dir: &Path, depth: usize, ignores: &IgnoreChain) -> Visit {
+        let Some(mtime_ns) = mtime_ns(dir) else {
+            return Visit::default();
+        };
+        let cached = self.previous.get(dir).filter(|e| e.mtime_ns == mtime_ns);
+        let checkout = match cached {
+            Some(entry) => entry.checkout.clone(),
+            None => inspect(dir),
+        };
+        let descend = depth < self.options.max_depth
+            && match &checkout {
+                None => true,
+                Some((_, c)) => c.kind != CheckoutKind::Bare && self.options.nested,
+            };
+        let subdirs = if descend {
+            cached
+                .and_then(|e| e.subdirs.clone())
+                .or_else(|| read_subdirs(dir))
+        } else {
+            None
+        };
+
+        let mut visit = Visit {
+            index: vec![ScanIndexEntry {
+                path: dir.to_path_buf(),
+                mtime_ns: if mtime_ns > self.racy_after {
+                    0
+                } else {
+                    mtime_ns
+                },
+                subdirs: subdirs.clone(),
+                checkout: checkout.clone(),
+            }],
+            found: checkout.into_iter().collect(),
+            unchanged: usize::from(cached.is_some()),
+        };
+        let Some(subdirs) = subdirs else {
+            return visit;
+        };
+
+        let ignores = IgnoreChain {
+            matcher: load_ignore_file(dir),
+            parent: Some(ignores),
+        };
+        let below = subdirs
+            .par_iter()
+            .filter(|name| !SKIP_DIRS.contains(&name.as_str()))
+            .map(|name| dir.join(name))
+            .filter(|path| !ignores.is_ignored(path))
+            .map(|path| self.visit(&path, depth + 1, &ignores))
+            .reduce(Visit::default, Visit::merge);
+        visit = visit.merge(below);
+        visit
+    }
+}
+
+/// Ignore rules in effect for a directory: its own [`IGNORE_FILE`], then
+/// its parents', then the configured patterns. The nearest match wins.
+struct IgnoreChain<'a> {
+    matcher: Option<Gitignore>,
+    parent: Option<&'a IgnoreChain<'a>>,
+}
+
+impl IgnoreChain<'_> {
+    fn is_ignored(&self, dir: &Path) -> bool {
+        let mut link = Some(self);
+        while let Some(chain) = link {
+            if let Some(matcher) = &chain.matcher {
+                match matcher.matched(dir, true) {
+                    Match::Ignore(_) => return true,
+                    Match::Whitelist(_) => return false,
+                    Match::None => {}
+                }
             }
+            link = chain.parent;
         }
+        false
     }
+}
 
-    repos
+/// The patterns in `dir`'s [`IGNORE_FILE`], if it has one.
+fn load_ignore_file(dir: &Path) -> Option<Gitignore> {
+    let path = dir.join(IGNORE_FILE);
+    if !path.is_file() {
+        return None;
+    }
+    let mut builder = GitignoreBuilder::new(dir);
+    if let Some(e) = builder.add(&path) {
+        tracing::warn!("{}: {e}", path.display());
+    }
+    builder.build().ok()
+}
+
+/// Names of `dir`'s subdirectories, sorted, not following symlinks.
+fn read_subdirs(dir: &Path) -> Option<Vec<String>> {
+    let mut names: Vec<String> = std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect();
+    names.sort();
+    Some(names)
+}
+
+fn mtime_ns(dir: &Path) -> Option<i64> {
+    let meta = std::fs::metadata(dir).ok()?;
+    if !meta.is_dir() {
+        return None;
+    }
+    let since_epoch = meta.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+    i64::try_from(since_epoch.as_nanos()).ok()
+}
+
+fn now_ns() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| i64::try_from(d.as_nanos()).unwrap_or(i64::MAX))
 }
 
-/// Parse remote URLs from a .git/config file.
-fn parse_git_config(config_path: &Path) -> Vec<ScannedRemote> {
-    let content = match std::fs::read_to_string(config_path) {
-        Ok(c) => c,
-        Err(_) => return Vec::new(),
+/// Work out whether `dir` is a checkout or a bare repo, returning its git
+/// directory (resolved to the common dir for linked worktrees).
+fn inspect(dir: &Path) -> Option<(PathBuf, Checkout)> {
+    let checkout = |path: &Path, kind| Checkout {
+        path: path.to_path_buf(),
+        kind,
     };
+    let dot_git = dir.join(".git");
 
-    let mut remotes = Vec::new();
-    let mut current_remote: Option<String> = None;
-
-    for line in content.lines() {
-        let trimmed = line.trim();
-        if trimmed.starts_with("[remote \"") && trimmed.ends_with("\"]") {
-            let name = trimmed
-                .strip_prefix("[remote \"")
-                .and_then(|s| s.strip_suffix("\"]"))
-                .unwrap_or("")
-                .to_string();
-            current_remote = Some(name);
-        } else if trimmed.starts_with('[') {
-            current_remote = None;
-        } else if let Some(ref remote_name) = current_remote {
-            if let Some(url) = trimmed.strip_prefix("url = ") {
-                remotes.push(ScannedRemote {
-                    name: remote_name.clone(),
-                    url: url.trim().to_string(),
-                });
-            }
+    if dot_git.is_dir() {
+        if !dot_git.join("config").exists() {
+            return None;
+        }
+        let path = core_worktree(&dot_git).unwrap_or_else(|| dir.to_path_buf());
+        return Some((dot_git, checkout(&path, CheckoutKind::Primary)));
+    }
+
+    if dot_git.is_file() {
+        let git_dir = read_gitfile(&dot_git)?;
+        if let Some(common) = read_commondir(&git_dir) {
+            return Some((common, checkout(dir, CheckoutKind::Worktree)));
         }
+        let kind = if is_module_dir(&git_dir) {
+            CheckoutKind::Submodule
+        } else {
+            CheckoutKind::Primary
+        };
+        return Some((git_dir, checkout(dir, kind)));
+    }
+
+    if is_git_dir(dir) {
+        // A git directory kept apart from its working tree names it in
+        // core.worktree; otherwise the repo is bare.
+        let found = match core_worktree(dir) {
+            Some(path) => checkout(&path, CheckoutKind::Primary),
+            None => checkout(dir, CheckoutKind::Bare),
+        };
+        return Some((dir.to_path_buf(), found));
     }
 
-    remotes
+    None
+}
+
+/// Whether `dir` looks like a git directory: `HEAD`, `objects` and `refs`.
+fn is_git_dir(dir: &Path) -> bool {
+    dir.join("HEAD").is_file() && dir.join("objects").is_dir() && dir.join("refs").is_dir()
+}
+
+/// Resolve a `.git` file (`gitdir: <path>`) to the git directory it names.
+fn read_gitfile(path: &Path) -> Option<PathBuf> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let target = content.lines().next()?.strip_prefix("gitdir:")?.trim();
+    let base = path.parent()?;
+    Some(base.join(target))
+}
+
+/// The common dir of a linked worktree's git directory, from its
+/// `commondir` file.
+fn read_commondir(git_dir: &Path) -> Option<PathBuf> {
+    let content = std::fs::read_to_string(git_dir.join("commondir")).ok()?;
+    Some(git_dir.join(content.trim()))
+}
+
+/// Whether a git directory lives under a superproject's `.git/modules`.
+fn is_module_dir(git_dir: &Path) -> bool {
+    let parts: Vec<Component> = git_dir.components().collect();
+    parts
+        .windows(2)
+        .any(|w| w[0].as_os_str() == ".git" && w[1].as_os_str() == "modules")
+}
+
+/// The working tree named by `core.worktree` in a git directory's config,
+/// resolved against the git directory.
+fn core_worktree(git_dir: &Path) -> Option<PathBuf> {
+    let config = GitConfig::load(&git_dir.join("config"), Some(git_dir)).ok()?;
+    let worktree = config.get("core", None, "worktree")?;
+    Some(canonical(&git_dir.join(worktree)))
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Resolve a repo's remotes to their effective fetch and push URLs, reading
+/// `global_files` first as git does. Falls back to asking git when the
+/// config can't be read here.
+fn parse_git_config(git_dir: &Path, global_files: &[PathBuf]) -> Vec<ScannedRemote> {
+    let config = match GitConfig::for_repo(git_dir, global_files) {
+        Ok(config) => config,
+        Err(e) => {
+            tracing::debug!("reading config of {} via git: {e}", git_dir.display());
+            match GitConfig::from_git(git_dir) {
+                Ok(config) => config,
+                Err(e) => {
+                    tracing::warn!("could not read config of {}: {e}", git_dir.display());
+                    return Vec::new();
+                }
+            }
+        }
+    };
+
+    config
+        .subsections("remote")
+        .into_iter()
+        .filter_map(|name| {
+            let url = config.fetch_urls(name).into_iter().next()?;
+            Some(ScannedRemote {
+                name: name.to_string(),
+                url,
+                push_urls: config.push_urls(name),
+            })
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -118,10 +466,262 @@ mod tests {
         let config_path = dir.path().join("config");
         std::fs::write(&config_path, content).unwrap();
 
-        let remotes = parse_git_config(&config_path);
+        let remotes = parse_git_config(dir.path(), &[]);
         assert_eq!(remotes.len(), 2);
         assert_eq!(remotes[0].name, "origin");
         assert!(remotes[0].url.contains("user/repo"));
+        assert_eq!(remotes[0].push_urls, [remotes[0].url.clone()]);
         assert_eq!(remotes[1].name, "upstream");
     }
+
+    #[test]
+    fn test_parse_git_config_shorthand_urls() {
+        let dir = tempfile::tempdir().unwrap();
+        let global = dir.path().join("gitconfig");
+        std::fs::write(
+            &global,
+            "[url \"https://github.com/\"]\n\tinsteadOf = gh:\n\
+             [url \"git@github.com:\"]\n\tpushInsteadOf = gh:\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("config"),
+            "[remote \"origin\"]\n\turl=gh:user/repo\n[remote \"empty\"]\n\tfetch = x\n",
+        )
+        .unwrap();
+
+        let remotes = parse_git_config(dir.path(), &[global]);
+        assert_eq!(remotes.len(), 1);
+        assert_eq!(remotes[0].url, "https://github.com/user/repo");
+        assert_eq!(remotes[0].push_urls, ["git@github.com:user/repo"]);
+    }
+
+    /// Lay out a minimal git directory with an origin remote.
+    fn fake_git_dir(dir: &Path, url: &str, extra_config: &str) {
+        std::fs::create_dir_all(dir.join("objects")).unwrap();
+        std::fs::create_dir_all(dir.join("refs")).unwrap();
+        std::fs::write(dir.join("HEAD"), "ref: refs/heads/main\n").unwrap();
+        std::fs::write(
+            dir.join("config"),
+            format!("{extra_config}[remote \"origin\"]\n\turl = {url}\n"),
+        )
+        .unwrap();
+    }
+
+    fn kinds(repo: &ScannedRepo) -> Vec<CheckoutKind> {
+        let mut kinds: Vec<_> = repo.checkouts.iter().map(|c| c.kind).collect();
+        kinds.sort_by_key(|k| k.to_string());
+        kinds
+    }
+
+    #[test]
+    fn test_scan_worktrees_share_one_repo() {
+        let dir = tempfile::tempdir().unwrap();
+        let main = dir.path().join("app");
+        fake_git_dir(&main.join(".git"), "https://github.com/user/app.git", "");
+        for name in ["app-fix", "app-review"] {
+            let admin = main.join(".git/worktrees").join(name);
+            std::fs::create_dir_all(&admin).unwrap();
+            std::fs::write(admin.join("commondir"), "../..\n").unwrap();
+            let checkout = dir.path().join(name);
+            std::fs::create_dir_all(&checkout).unwrap();
+            std::fs::write(
+                checkout.join(".git"),
+                format!("gitdir: {}\n", admin.display()),
+            )
+            .unwrap();
+        }
+
+        let repos = scan_directory(dir.path(), 3);
+        assert_eq!(repos.len(), 1);
+        let repo = &repos[0];
+        assert_eq!(repo.path, main);
+        assert_eq!(repo.working_tree(), Some(main.as_path()));
+        assert_eq!(repo.remotes.len(), 1);
+        assert_eq!(
+            kinds(repo),
+            [
+                CheckoutKind::Primary,
+                CheckoutKind::Worktree,
+                CheckoutKind::Worktree
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scan_bare_and_submodule() {
+        let dir = tempfile::tempdir().unwrap();
+        let bare = dir.path().join("mirror.git");
+        fake_git_dir(
+            &bare,
+            "https://github.com/user/mirror.git",
+            "[core]\n\tbare = true\n",
+        );
+        // Looks like a repo, but sits inside the bare one and must be skipped.
+        fake_git_dir(&bare.join("refs/nested"), "https://example.com/x.git", "");
+
+        let superproject = dir.path().join("site");
+        fake_git_dir(
+            &superproject.join(".git"),
+            "https://github.com/user/site.git",
+            "",
+        );
+        fake_git_dir(
+            &superproject.join(".git/modules/theme"),
+            "https://github.com/other/theme.git",
+            "[core]\n\tworktree = ../../../theme\n",
+        );
+        let theme = superproject.join("theme");
+        std::fs::create_dir_all(&theme).unwrap();
+        std::fs::write(theme.join(".git"), "gitdir: ../.git/modules/theme\n").unwrap();
+
+        let options = ScanOptions {
+            nested: true,
+            ..ScanOptions::default()
+        };
+        let mut repos = scan(dir.path(), &options, Vec::new()).unwrap().repos;
+        repos.sort_by(|a, b| a.path.cmp(&b.path));
+        let summary: Vec<_> = repos
+            .iter()
+            .map(|r| {
+                (
+                    r.path.file_name().unwrap().to_string_lossy().to_string(),
+                    kinds(r),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("mirror.git".to_string(), vec![CheckoutKind::Bare]),
+                ("site".to_string(), vec![CheckoutKind::Primary]),
+                ("theme".to_string(), vec![CheckoutKind::Submodule]),
+            ]
+        );
+        assert_eq!(repos[0].working_tree(), None);
+        assert!(repos[2].remotes[0].url.contains("other/theme"));
+    }
+
+    #[test]
+    fn test_scan_core_worktree() {
+        let dir = tempfile::tempdir().unwrap();
+        let work = dir.path().join("work");
+        std::fs::create_dir_all(&work).unwrap();
+        fake_git_dir(
+            &dir.path().join("meta/work.git"),
+            "https://github.com/user/work.git",
+            "[core]\n\tworktree = ../../work\n",
+        );
+
+        let repos = scan_directory(dir.path(), 3);
+        assert_eq!(repos.len(), 1);
+        assert_eq!(kinds(&repos[0]), [CheckoutKind::Primary]);
+        assert_eq!(repos[0].path, canonical(&work));
+    }
+
+    fn names(repos: &[ScannedRepo]) -> Vec<String> {
+        let mut names: Vec<_> = repos
+            .iter()
+            .map(|r| r.path.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_scan_stops_at_repos() {
+        let dir = tempfile::tempdir().unwrap();
+        let outer = dir.path().join("outer");
+        fake_git_dir(&outer.join(".git"), "https://github.com/user/outer.git", "");
+        fake_git_dir(
+            &outer.join("vendor-libs/inner/.git"),
+            "https://github.com/user/inner.git",
+            "",
+        );
+
+        let found = scan(dir.path(), &ScanOptions::default(), Vec::new()).unwrap();
+        assert_eq!(names(&found.repos), ["outer"]);
+        let outer_entry = found.index.iter().find(|e| e.path == outer).unwrap();
+        assert_eq!(outer_entry.subdirs, None);
+
+        let options = ScanOptions {
+            nested: true,
+            ..ScanOptions::default()
+        };
+        let found = scan(dir.path(), &options, Vec::new()).unwrap();
+        assert_eq!(names(&found.repos), ["inner", "outer"]);
+    }
+
+    #[test]
+    fn test_scan_ignore_patterns() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in [
+            "work/app",
+            "work/old/legacy",
+            "archive/2019",
+            "scratch/keep",
+        ] {
+            fake_git_dir(
+                &dir.path().join(name).join(".git"),
+                "https://example.com/x.git",
+                "",
+            );
+        }
+        std::fs::write(dir.path().join("work").join(IGNORE_FILE), "old/\n").unwrap();
+        std::fs::write(dir.path().join("scratch").join(IGNORE_FILE), "!keep\n").unwrap();
+
+        let options = ScanOptions {
+            ignore: vec!["archive".to_string(), "scratch/*".to_string()],
+            ..ScanOptions::default()
+        };
+        let found = scan(dir.path(), &options, Vec::new()).unwrap();
+        assert_eq!(names(&found.repos), ["app", "keep"]);
+        assert!(found.index.iter().all(|e| !e.path.ends_with("archive")));
+
+        let bad = ScanOptions {
+            ignore: vec!["{a,b".to_string()],
+            ..ScanOptions::default()
+        };
+        assert!(scan(dir.path(), &bad, Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_scan_reuses_unchanged_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        fake_git_dir(
+            &dir.path().join("a/app/.git"),
+            "https://example.com/app.git",
+            "",
+        );
+        std::fs::create_dir_all(dir.path().join("b/empty")).unwrap();
+
+        // Everything was just created, so nothing is trusted yet.
+        let first = scan(dir.path(), &ScanOptions::default(), Vec::new()).unwrap();
+        assert_eq!(first.unchanged, 0);
+        assert!(first.index.iter().all(|e| e.mtime_ns == 0));
+
+        // Pretend the first scan ran long after the directories were made.
+        let settled: Vec<ScanIndexEntry> = first
+            .index
+            .iter()
+            .cloned()
+            .map(|mut e| {
+                e.mtime_ns = mtime_ns(&e.path).unwrap();
+                e
+            })
+            .collect();
+        let second = scan(dir.path(), &ScanOptions::default(), settled.clone()).unwrap();
+        assert_eq!(second.unchanged, first.index.len());
+        assert_eq!(names(&second.repos), ["app"]);
+
+        // A new clone changes only its parent's mtime; that subtree is read again.
+        fake_git_dir(
+            &dir.path().join("b/lib/.git"),
+            "https://example.com/lib.git",
+            "",
+        );
+        let third = scan(dir.path(), &ScanOptions::default(), settled).unwrap();
+        assert_eq!(names(&third.repos), ["app", "lib"]);
+        assert_eq!(third.unchanged, first.index.len() - 1);
+    }
 }
@@ -6,15 +6,19 @@ use std::sync::Arc;
 use tokio::task::JoinSet;
 
 use gitr_core::error::GitrError;
+use gitr_core::metrics;
 use gitr_core::models::host::HostKind;
+use gitr_core::retry::RetryPolicy;
 
-use crate::{ForkSyncStatus, HostProvider, RateLimitInfo, RemoteBranch, RemoteRepo};
+use crate::retry::with_retry;
+use crate::{ForkSyncStatus, HostProvider, PullRequest, RateLimitInfo, RemoteBranch, RemoteRepo};
 
 pub struct GitHubProvider {
     client: reqwest::Client,
     api_url: url::Url,
     #[allow(dead_code)]
     username: String,
+    retry: RetryPolicy,
 }
 
 impl GitHubProvider {
@@ -45,14 +49,134 @@ impl GitHubProvider {
             client,
             api_url,
             username,
+            retry: RetryPolicy::default(),
         }
     }
 
+    /// Override the retry policy used for transient API failures.
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Behind/ahead counts of `branch` in `owner/name` against `base`, a
+    /// ref in the same fork network such as `acme:main`.
+    async fn compare(
+        &self,
+        owner: &str,
+        name: &str,
+        base: &str,
+        branch: &str,
+    ) -> Result<ForkSyncStatus, GitrError> {
+        let url = self.url(&format!("/repos/{owner}/{name}/compare/{base}...{branch}"));
+        let resp = self.send(|| self.client.get(&url)).await?;
+
+        // Reporting 0/0 here would make a failed comparison look like an
+        // identical fork.
+        if !resp.status().is_success() {
+            let status = resp.status().as_u16();
+            let body = resp.text().await.unwrap_or_default();
+            return Err(GitrError::ApiError {
+                status,
+                message: body,
+            });
+        }
+
+        #[derive(Deserialize)]
+        struct CompareResp {
+            behind_by: u32,
+            ahead_by: u32,
+        }
+
+        let compare: CompareResp =
+            resp.json().await.map_err(|e| GitrError::ApiError {
+                status: 0,
+                message: format!("JSON parse error: {e}"),
+            })?;
+
+        Ok(ForkSyncStatus {
+            branch: branch.to_string(),
+            behind_by: compare.behind_by,
+            ahead_by: compare.ahead_by,
+        })
+    }
+
     fn url(&self, path: &str) -> String {
         let base = self.api_url.as_str().trim_end_matches('/');
         format!("{base}{path}")
     }
 
+    /// Send the request produced by `build`, retrying network errors, 429s and
+    /// 5xx responses. Any other response is returned for the caller to inspect.
+    /// Requests that change something (merge-upstream, archive, delete) are
+    /// only retried after a rate limit, which GitHub answers without acting:
+    /// after a timeout or 5xx the change may have happened anyway.
+    async fn send(
+        &self,
+        build: impl Fn() -> reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, GitrError> {
+        let host = self.api_url.host_str().unwrap_or("unknown");
+        let safe = build().build().is_ok_and(|req| req.method().is_safe());
+        let retryable = |e: &GitrError| safe || matches!(e, GitrError::RateLimited { .. });
+        with_retry(&self.retry, "GitHub API request", retryable, || {
+            let req = build();
+            async move {
+                let resp = req.send().await.map_err(|e| {
+                    metrics::record_api_request(host, None);
+                    GitrError::ApiError {
+                        status: 0,
+                        message: e.to_string(),
+                    }
+                })?;
+                let status = resp.status().as_u16();
+                metrics::record_api_request(host, Some(status));
+                let remaining = resp
+                    .headers()
+                    .get("x-ratelimit-remaining")
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.parse().ok());
+                if let Some(remaining) = remaining {
+                    metrics::set_rate_limit_remaining(host, remaining);
+                }
+                // An exhausted primary limit: wait until it resets.
+                if status == 403 && remaining == Some(0) {
+                    let retry_after_secs = resp
+                        .headers()
+                        .get("x-ratelimit-reset")
+                        .and_then(|v| v.to_str().ok())
+                        .and_then(|v| v.parse::<i64>().ok())
+                        .map(|reset| (reset - Utc::now().timestamp()).max(1) as u64)
+                        .unwrap_or(60);
+                    return Err(GitrError::RateLimited {
+                        host: "github.com".to_string(),
+                        retry_after_secs,
+                    });
+                }
+                if status == 429 {
+                    let retry_after_secs = resp
+                        .headers()
+                        .get(header::RETRY_AFTER)
+                        .and_then(|v| v.to_str().ok())
+                        .and_then(|v| v.parse().ok())
+                        .unwrap_or(60);
+                    return Err(GitrError::RateLimited {
+                        host: "github.com".to_string(),
+                        retry_after_secs,
+                    });
+                }
+                if resp.status().is_server_error() {
+                    let body = resp.text().await.unwrap_or_default();
+                    return Err(GitrError::ApiError {
+                        status,
+                        message: body,
+                    });
+                }
+                Ok(resp)
+            }
+        })
+        .await
+    }
+
     async fn paginated_get<T: for<'de> Deserialize<'de>>(
         &self,
         path: &str,
@@ -63,15 +187,7 @@ impl GitHubProvider {
 
         loop {
             let url = format!("{}?per_page={per_page}&page={page}", self.url(path));
-            let resp = self
-                .client
-                .get(&url)
-                .send()
-                .await
-                .map_err(|e| GitrError::ApiError {
-                    status: 0,
-                    message: e.to_string(),
-                })?;
+            let resp = self.send(|| self.client.get(&url)).await?;
 
             let status = resp.status().as_u16();
             if status == 403 || status == 429 {
@@ -119,6 +235,7 @@ struct GhRepo {
     description: Option<String>,
     private: bool,
     archived: bool,
+    language: Option<String>,
     updated_at: Option<String>,
 }
 
@@ -138,6 +255,26 @@ struct GhCommitRef {
     sha: String,
 }
 
+#[derive(Deserialize)]
+struct GhPull {
+    number: u64,
+    title: String,
+    html_url: String,
+    head: GhPullHead,
+}
+
+#[derive(Deserialize)]
+struct GhPullHead {
+    #[serde(rename = "ref")]
+    branch: String,
+    repo: Option<GhPullRepo>,
+}
+
+#[derive(Deserialize)]
+struct GhPullRepo {
+    full_name: String,
+}
+
 #[derive(Deserialize)]
 struct GhRateLimit {
     rate: GhRate,
@@ -165,6 +302,7 @@ impl From<GhRepo> for RemoteRepo {
             description: r.description,
             is_private: r.private,
             is_archived: r.archived,
+            language: r.language,
             updated_at: r
                 .updated_at
                 .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
@@ -176,15 +314,7 @@ impl From<GhRepo> for RemoteRepo {
 #[async_trait]
 impl HostProvider for GitHubProvider {
     async fn validate_credentials(&self) -> Result<bool, GitrError> {
-        let resp = self
-            .client
-            .get(self.url("/user"))
-            .send()
-            .await
-            .map_err(|e| GitrError::ApiError {
-                status: 0,
-                message: e.to_string(),
-            })?;
+        let resp = self.send(|| self.client.get(self.url("/user"))).await?;
         Ok(resp.status().is_success())
     }
 
@@ -242,15 +372,7 @@ impl HostProvider for GitHubProvider {
 
     async fn get_repo(&self, owner: &str, name: &str) -> Result<Option<RemoteRepo>, GitrError> {
         let url = self.url(&format!("/repos/{owner}/{name}"));
-        let resp = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| GitrError::ApiError {
-                status: 0,
-                message: e.to_string(),
-            })?;
+        let resp = self.send(|| self.client.get(&url)).await?;
 
         if resp.status().as_u16() == 404 {
             return Ok(None);
@@ -312,57 +434,26 @@ impl HostProvider for GitHubProvider {
         };
 
         let branch = &repo.default_branch;
-        let url = self.url(&format!(
-            "/repos/{owner}/{name}/compare/{upstream}:{branch}...{branch}"
-        ));
-        let resp = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| GitrError::ApiError {
-                status: 0,
-                message: e.to_string(),
-            })?;
-
-        if !resp.status().is_success() {
-            return Ok(vec![ForkSyncStatus {
-                branch: branch.clone(),
-                behind_by: 0,
-                ahead_by: 0,
-            }]);
-        }
-
-        #[derive(Deserialize)]
-        struct CompareResp {
-            behind_by: u32,
-            ahead_by: u32,
-        }
-
-        let compare: CompareResp =
-            resp.json().await.map_err(|e| GitrError::ApiError {
-                status: 0,
-                message: format!("JSON parse error: {e}"),
-            })?;
+        let status = self
+            .compare(owner, name, &format!("{upstream}:{branch}"), branch)
+            .await?;
+        Ok(vec![status])
+    }
 
-        Ok(vec![ForkSyncStatus {
-            branch: branch.clone(),
-            behind_by: compare.behind_by,
-            ahead_by: compare.ahead_by,
-        }])
+    async fn compare_branch(
+        &self,
+        owner: &str,
+        name: &str,
+        upstream_owner: &str,
+        branch: &str,
+    ) -> Result<ForkSyncStatus, GitrError> {
+        self.compare(owner, name, &format!("{upstream_owner}:{branch}"), branch)
+            .await
     }
 
     async fn rate_limit_status(&self) -> Result<RateLimitInfo, GitrError> {
         let url = self.url("/rate_limit");
-        let resp = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| GitrError::ApiError {
-                status: 0,
-                message: e.to_string(),
-            })?;
+        let resp = self.send(|| self.client.get(&url)).await?;
 
         let rl: GhRateLimit = resp.json().await.map_err(|e| GitrError::ApiError {
             status: 0,
@@ -387,16 +478,7 @@ impl HostProvider for GitHubProvider {
     ) -> Result<bool, GitrError> {
         let url = self.url(&format!("/repos/{owner}/{name}/merge-upstream"));
         let body = serde_json::json!({ "branch": branch });
-        let resp = self
-            .client
-            .post(&url)
-            .json(&body)
-            .send()
-            .await
-            .map_err(|e| GitrError::ApiError {
-                status: 0,
-                message: e.to_string(),
-            })?;
+        let resp = self.send(|| self.client.post(&url).json(&body)).await?;
 
         match resp.status().as_u16() {
             200 => Ok(true),  // synced
@@ -408,7 +490,61 @@ impl HostProvider for GitHubProvider {
         }
     }
 
+    async fn list_open_pull_requests(
+        &self,
+        owner: &str,
+        name: &str,
+    ) -> Result<Vec<PullRequest>, GitrError> {
+        // The pulls endpoint lists open pull requests unless asked otherwise.
+        let path = format!("/repos/{owner}/{name}/pulls");
+        let pulls: Vec<GhPull> = self.paginated_get(&path, 100).await?;
+        Ok(pulls
+            .into_iter()
+            .map(|p| PullRequest {
+                number: p.number,
+                title: p.title,
+                url: p.html_url,
+                head_repo: p.head.repo.map(|r| r.full_name),
+                head_branch: p.head.branch,
+            })
+            .collect())
+    }
+
+    async fn archive_repo(&self, owner: &str, name: &str) -> Result<(), GitrError> {
+        let url = self.url(&format!("/repos/{owner}/{name}"));
+        let body = serde_json::json!({ "archived": true });
+        let resp = self.send(|| self.client.patch(&url).json(&body)).await?;
+        expect_success(resp, owner, name).await
+    }
+
+    async fn delete_repo(&self, owner: &str, name: &str) -> Result<(), GitrError> {
+        let url = self.url(&format!("/repos/{owner}/{name}"));
+        let resp = self.send(|| self.client.delete(&url)).await?;
+        expect_success(resp, owner, name).await
+    }
+
     fn kind(&self) -> HostKind {
         HostKind::GitHub
     }
 }
+
+/// Turn the response to a change on `owner/name` into a result.
+async fn expect_success(
+    resp: reqwest::Response,
+    owner: &str,
+    name: &str,
+) -> Result<(), GitrError> {
+    match resp.status().as_u16() {
+        200..=299 => Ok(()),
+        404 => Err(GitrError::RepoNotFound {
+            name: format!("{owner}/{name}"),
+        }),
+        status => {
+            let body = resp.text().await.unwrap_or_default();
+            Err(GitrError::ApiError {
+                status,
+                message: body,
+            })
+        }
+    }
+}
@@ -3,7 +3,7 @@ use async_trait::async_trait;
 use gitr_core::error::GitrError;
 use gitr_core::models::host::HostKind;
 
-use crate::{ForkSyncStatus, HostProvider, RateLimitInfo, RemoteBranch, RemoteRepo};
+use crate::{ForkSyncStatus, HostProvider, PullRequest, RateLimitInfo, RemoteBranch, RemoteRepo};
 
 pub struct GitLabProvider;
 
@@ -29,6 +29,10 @@ impl HostProvider for GitLabProvider {
         Err(GitrError::ProviderNotImplemented { kind: "gitlab".into() })
     }
 
+    async fn compare_branch(&self, _owner: &str, _name: &str, _upstream_owner: &str, _branch: &str) -> Result<ForkSyncStatus, GitrError> {
+        Err(GitrError::ProviderNotImplemented { kind: "gitlab".into() })
+    }
+
     async fn rate_limit_status(&self) -> Result<RateLimitInfo, GitrError> {
         Err(GitrError::ProviderNotImplemented { kind: "gitlab".into() })
     }
@@ -37,6 +41,18 @@ impl HostProvider for GitLabProvider {
         Err(GitrError::ProviderNotImplemented { kind: "gitlab".into() })
     }
 
+    async fn list_open_pull_requests(&self, _owner: &str, _name: &str) -> Result<Vec<PullRequest>, GitrError> {
+        Err(GitrError::ProviderNotImplemented { kind: "gitlab".into() })
+    }
+
+    async fn archive_repo(&self, _owner: &str, _name: &str) -> Result<(), GitrError> {
+        Err(GitrError::ProviderNotImplemented { kind: "gitlab".into() })
+    }
+
+    async fn delete_repo(&self, _owner: &str, _name: &str) -> Result<(), GitrError> {
+        Err(GitrError::ProviderNotImplemented { kind: "gitlab".into() })
+    }
+
     fn kind(&self) -> HostKind {
         HostKind::GitLab
     }
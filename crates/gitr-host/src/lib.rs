@@ -3,11 +3,13 @@ pub mod gitlab;
 pub mod gitea;
 pub mod bitbucket;
 pub mod azure_devops;
+pub mod retry;
 
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use gitr_core::error::GitrError;
 use gitr_core::models::host::HostKind;
+use gitr_core::retry::RetryPolicy;
 
 /// A repo as returned by a hosting API.
 #[derive(Debug, Clone)]
@@ -24,6 +26,8 @@ pub struct RemoteRepo {
     pub description: Option<String>,
     pub is_private: bool,
     pub is_archived: bool,
+    /// Primary language, if the host reports one.
+    pub language: Option<String>,
     pub updated_at: Option<DateTime<Utc>>,
 }
 
@@ -43,6 +47,17 @@ pub struct ForkSyncStatus {
     pub ahead_by: u32,
 }
 
+/// An open pull request, as returned by a hosting API.
+#[derive(Debug, Clone)]
+pub struct PullRequest {
+    pub number: u64,
+    pub title: String,
+    pub url: String,
+    /// Full name of the repo the changes come from; `None` if it was deleted.
+    pub head_repo: Option<String>,
+    pub head_branch: String,
+}
+
 /// Rate limit information.
 #[derive(Debug, Clone)]
 pub struct RateLimitInfo {
@@ -73,6 +88,16 @@ pub trait HostProvider: Send + Sync {
         name: &str,
     ) -> Result<Vec<ForkSyncStatus>, GitrError>;
 
+    /// Behind/ahead counts of `branch` in a fork against the branch of the
+    /// same name in its upstream, owned by `upstream_owner`.
+    async fn compare_branch(
+        &self,
+        owner: &str,
+        name: &str,
+        upstream_owner: &str,
+        branch: &str,
+    ) -> Result<ForkSyncStatus, GitrError>;
+
     /// Get current rate limit status.
     async fn rate_limit_status(&self) -> Result<RateLimitInfo, GitrError>;
 
@@ -85,23 +110,37 @@ pub trait HostProvider: Send + Sync {
         branch: &str,
     ) -> Result<bool, GitrError>;
 
+    /// List the open pull requests into a repo (handles pagination).
+    async fn list_open_pull_requests(
+        &self,
+        owner: &str,
+        name: &str,
+    ) -> Result<Vec<PullRequest>, GitrError>;
+
+    /// Archive a repo, making it read-only.
+    async fn archive_repo(&self, owner: &str, name: &str) -> Result<(), GitrError>;
+
+    /// Delete a repo. This cannot be undone.
+    async fn delete_repo(&self, owner: &str, name: &str) -> Result<(), GitrError>;
+
     /// The kind of host this provider handles.
     fn kind(&self) -> HostKind;
 }
 
 /// Create a HostProvider for the given host kind.
+/// Transient API failures are retried according to `retry`.
 pub fn create_provider(
     kind: &HostKind,
     api_url: &url::Url,
     token: &str,
     username: &str,
+    retry: &RetryPolicy,
 ) -> Result<Box<dyn HostProvider>, GitrError> {
     match kind {
-        HostKind::GitHub => Ok(Box::new(github::GitHubProvider::new(
-            api_url.clone(),
-            token.to_string(),
-            username.to_string(),
-        ))),
+        HostKind::GitHub => Ok(Box::new(
+            github::GitHubProvider::new(api_url.clone(), token.to_string(), username.to_string())
+                .with_retry_policy(retry.clone()),
+        )),
         other => Err(GitrError::ProviderNotImplemented {
             kind: other.to_string(),
         }),
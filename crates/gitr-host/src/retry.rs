@@ -0,0 +1,37 @@
+use std::future::Future;
+
+use gitr_core::error::GitrError;
+use gitr_core::retry::RetryPolicy;
+
+/// Run an async operation under `policy`, sleeping between transient failures
+/// (network errors, 5xx responses, rate limits) that `retryable` also
+/// accepts. Permanent failures and the last transient failure are returned
+/// as-is.
+pub async fn with_retry<T, F, Fut>(
+    policy: &RetryPolicy,
+    what: &str,
+    retryable: impl Fn(&GitrError) -> bool,
+    mut op: F,
+) -> Result<T, GitrError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, GitrError>>,
+{
+    let mut attempt = 1;
+    loop {
+        match op().await {
+            Ok(v) => return Ok(v),
+            Err(e) if retryable(&e) && policy.should_retry(attempt, &e) => {
+                let delay = policy.delay_for(attempt, &e);
+                tracing::warn!(
+                    "{what} failed (attempt {attempt}/{}), retrying in {:.1}s: {e}",
+                    policy.max_attempts,
+                    delay.as_secs_f32()
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use gitr_core::config::GitBackendKind;
+use gitr_core::error::GitrError;
+use gitr_core::models::sync_state::IncomingCommit;
+
+use crate::git_ops::{self, CloneOptions, RemoteRef};
+
+/// The git operations the sync engine needs, independent of how they run.
+pub trait GitBackend: Send + Sync {
+    /// Short name for logs ("cli", "gitoxide").
+    fn name(&self) -> &'static str;
+
+    /// Clone a repo to a local path.
+    fn clone_repo(&self, url: &str, dest: &Path, opts: &CloneOptions) -> Result<(), GitrError>;
+
+    /// Fetch a remote, pruning deleted branches.
+    fn fetch(&self, dir: &Path, remote: &str) -> Result<(), GitrError>;
+
+    /// Checkout a branch.
+    fn checkout(&self, dir: &Path, branch: &str) -> Result<(), GitrError>;
+
+    /// Fast-forward merge from a remote branch.
+    fn merge_ff(&self, dir: &Path, remote_branch: &str) -> Result<(), GitrError>;
+
+    /// Regular merge from a remote branch.
+    fn merge(&self, dir: &Path, remote_branch: &str) -> Result<(), GitrError>;
+
+    /// Rebase onto a remote branch, aborting on conflict.
+    fn rebase(&self, dir: &Path, remote_branch: &str) -> Result<(), GitrError>;
+
+    /// Hard-reset the current branch to a ref.
+    fn reset_hard(&self, dir: &Path, target: &str) -> Result<(), GitrError>;
+
+    /// Reset the current branch to a ref, keeping uncommitted changes to
+    /// files the reset doesn't touch (and failing if it would lose any).
+    fn reset_keep(&self, dir: &Path, target: &str) -> Result<(), GitrError>;
+
+    /// Push a branch to a remote.
+    fn push(&self, dir: &Path, remote: &str, branch: &str) -> Result<(), GitrError>;
+
+    /// Add a remote (no-op if it already exists).
+    fn remote_add(&self, dir: &Path, name: &str, url: &str) -> Result<(), GitrError>;
+
+    /// List remote names.
+    fn remote_list(&self, dir: &Path) -> Result<Vec<String>, GitrError>;
+
+    /// Count commits that `a` is behind `b` (`git rev-list --count a..b`).
+    fn rev_list_count(&self, dir: &Path, a: &str, b: &str) -> Result<u32, GitrError>;
+
+    /// Current branch name, or `HEAD` when detached.
+    fn current_branch(&self, dir: &Path) -> Result<String, GitrError>;
+
+    /// Resolve a ref to a SHA, or `None` if it doesn't exist.
+    fn rev_parse(&self, dir: &Path, refspec: &str) -> Result<Option<String>, GitrError>;
+
+    /// Branch heads advertised by a remote.
+    fn ls_remote(&self, dir: &Path, remote: &str) -> Result<Vec<RemoteRef>, GitrError>;
+
+    /// Up to `limit` commits in `a..b`, newest first.
+    fn log(&self, dir: &Path, a: &str, b: &str, limit: u32) -> Result<Vec<IncomingCommit>, GitrError>;
+}
+
+/// Default backend: every operation shells out to the `git` binary, so SSH
+/// agents, credential helpers and GPG signing behave exactly as on the command line.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CliBackend;
+
+impl GitBackend for CliBackend {
+    fn name(&self) -> &'static str {
+        "cli"
+    }
+
+    fn clone_repo(&self, url: &str, dest: &Path, opts: &CloneOptions) -> Result<(), GitrError> {
+        git_ops::clone(url, dest, opts)
+    }
+
+    fn fetch(&self, dir: &Path, remote: &str) -> Result<(), GitrError> {
+        git_ops::fetch(dir, remote)
+    }
+
+    fn checkout(&self, dir: &Path, branch: &str) -> Result<(), GitrError> {
+        git_ops::checkout(dir, branch)
+    }
+
+    fn merge_ff(&self, dir: &Path, remote_branch: &str) -> Result<(), GitrError> {
+        git_ops::merge_ff(dir, remote_branch)
+    }
+
+    fn merge(&self, dir: &Path, remote_branch: &str) -> Result<(), GitrError> {
+        git_ops::merge(dir, remote_branch)
+    }
+
+    fn rebase(&self, dir: &Path, remote_branch: &str) -> Result<(), GitrError> {
+        git_ops::rebase(dir, remote_branch)
+    }
+
+    fn reset_hard(&self, dir: &Path, target: &str) -> Result<(), GitrError> {
+        git_ops::reset_hard(dir, target)
+    }
+
+    fn reset_keep(&self, dir: &Path, target: &str) -> Result<(), GitrError> {
+        git_ops::reset_keep(dir, target)
+    }
+
+    fn push(&self, dir: &Path, remote: &str, branch: &str) -> Result<(), GitrError> {
+        git_ops::push(dir, remote, branch)
+    }
+
+    fn remote_add(&self, dir: &Path, name: &str, url: &str) -> Result<(), GitrError> {
+        git_ops::remote_add(dir, name, url)
+    }
+
+    fn remote_list(&self, dir: &Path) -> Result<Vec<String>, GitrError> {
+        git_ops::remote_list(dir)
+    }
+
+    fn rev_list_count(&self, dir: &Path, a: &str, b: &str) -> Result<u32, GitrError> {
+        git_ops::rev_list_count(dir, a, b)
+    }
+
+    fn current_branch(&self, dir: &Path) -> Result<String, GitrError> {
+        git_ops::current_branch(dir)
+    }
+
+    fn rev_parse(&self, dir: &Path, refspec: &str) -> Result<Option<String>, GitrError> {
+        git_ops::rev_parse(dir, refspec)
+    }
+
+    fn ls_remote(&self, dir: &Path, remote: &str) -> Result<Vec<RemoteRef>, GitrError> {
+        git_ops::ls_remote(dir, remote)
+    }
+
+    fn log(&self, dir: &Path, a: &str, b: &str, limit: u32) -> Result<Vec<IncomingCommit>, GitrError> {
+        git_ops::log(dir, a, b, limit)
+    }
+}
+
+/// Create the backend selected in config. Falls back to the CLI backend (with
+/// a warning) when `gitoxide` is requested but gitr was built without it.
+pub fn create_backend(kind: &GitBackendKind) -> Arc<dyn GitBackend> {
+    match kind {
+        GitBackendKind::Cli => Arc::new(CliBackend),
+        #[cfg(feature = "gitoxide")]
+        GitBackendKind::Gitoxide => Arc::new(crate::gix_backend::GixBackend::new()),
+        #[cfg(not(feature = "gitoxide"))]
+        GitBackendKind::Gitoxide => {
+            tracing::warn!(
+                "git_backend = \"gitoxide\" but gitr was built without the `gitoxide` feature; using the git CLI"
+            );
+            Arc::new(CliBackend)
+        }
+    }
+}
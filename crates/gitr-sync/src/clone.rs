@@ -0,0 +1,166 @@
+use std::path::{Path, PathBuf};
+
+use gitr_core::error::GitrError;
+use gitr_core::models::repo::{Repo, RepoId};
+use gitr_core::retry::RetryPolicy;
+
+use crate::backend::GitBackend;
+use crate::git_ops::CloneOptions;
+use crate::retry::Retrier;
+
+/// A repo to clone, with its URLs and destination already worked out.
+pub struct CloneTarget {
+    pub repo: Repo,
+    /// URL to clone from, in the preferred protocol.
+    pub url: String,
+    pub dest: PathBuf,
+    /// Added as the `upstream` remote, for forks.
+    pub upstream_url: Option<String>,
+}
+
+/// What happened to one clone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloneStatus {
+    Cloned,
+    /// The destination already held a git checkout, which was kept.
+    Existing,
+    Failed,
+}
+
+impl std::fmt::Display for CloneStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CloneStatus::Cloned => write!(f, "cloned"),
+            CloneStatus::Existing => write!(f, "existing"),
+            CloneStatus::Failed => write!(f, "failed"),
+        }
+    }
+}
+
+/// Outcome of cloning a single repo.
+#[derive(Debug)]
+pub struct CloneResult {
+    pub repo_id: RepoId,
+    pub repo_full_name: String,
+    pub dest: PathBuf,
+    pub status: CloneStatus,
+    pub error: Option<String>,
+}
+
+/// Clone `target.repo` to `target.dest` and add its `upstream` remote.
+///
+/// A destination that is already a git checkout is kept as-is and reported
+/// as [`CloneStatus::Existing`]; any other non-empty destination fails the
+/// clone rather than being overwritten. The clone is retried on transient
+/// failures.
+pub fn clone_target(
+    target: &CloneTarget,
+    git: &dyn GitBackend,
+    opts: &CloneOptions,
+    retry: &RetryPolicy,
+) -> CloneResult {
+    let outcome = clone_inner(target, git, opts, retry);
+    let (status, error) = match outcome {
+        Ok(status) => (status, None),
+        Err(e) => (CloneStatus::Failed, Some(e.to_string())),
+    };
+    CloneResult {
+        repo_id: target.repo.id.clone(),
+        repo_full_name: target.repo.full_name.clone(),
+        dest: target.dest.clone(),
+        status,
+        error,
+    }
+}
+
+fn clone_inner(
+    target: &CloneTarget,
+    git: &dyn GitBackend,
+    opts: &CloneOptions,
+    retry: &RetryPolicy,
+) -> Result<CloneStatus, GitrError> {
+    let dest = &target.dest;
+    if dest.join(".git").exists() {
+        return Ok(CloneStatus::Existing);
+    }
+    if !is_empty_or_missing(dest)? {
+        return Err(GitrError::GitError {
+            message: format!(
+                "{} already exists and is not a git checkout",
+                dest.display()
+            ),
+        });
+    }
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    tracing::info!("cloning {} to {}", target.url, dest.display());
+    let mut retrier = Retrier::new(retry);
+    retrier.run("clone", || git.clone_repo(&target.url, dest, opts))?;
+
+    if let Some(url) = &target.upstream_url {
+        git.remote_add(dest, "upstream", url)?;
+    }
+    Ok(CloneStatus::Cloned)
+}
+
+fn is_empty_or_missing(dir: &Path) -> Result<bool, GitrError> {
+    match std::fs::read_dir(dir) {
+        Ok(mut entries) => Ok(entries.next().is_none()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(true),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::CliBackend;
+    use crate::test_util::git;
+    use gitr_core::models::host::HostId;
+    use gitr_core::models::repo::DiscoverySource;
+
+    #[test]
+    fn test_clone_target() {
+        let tmp = tempfile::tempdir().unwrap();
+        let origin = tmp.path().join("origin");
+        std::fs::create_dir(&origin).unwrap();
+        git(&origin, &["init", "-q", "-b", "main"]);
+        git(&origin, &["commit", "-q", "--allow-empty", "-m", "first"]);
+
+        let repo = Repo::new(
+            "me/tool".to_string(),
+            HostId::new(),
+            origin.to_string_lossy().to_string(),
+            "main".to_string(),
+            DiscoverySource::Api,
+        );
+        let target = CloneTarget {
+            repo,
+            url: origin.to_string_lossy().to_string(),
+            dest: tmp.path().join("src/host/me/tool"),
+            upstream_url: Some("https://example.com/them/tool.git".to_string()),
+        };
+        let retry = RetryPolicy::default();
+        let opts = CloneOptions::default();
+
+        let result = clone_target(&target, &CliBackend, &opts, &retry);
+        assert_eq!(result.status, CloneStatus::Cloned, "{:?}", result.error);
+        let remotes = CliBackend.remote_list(&target.dest).unwrap();
+        assert!(remotes.contains(&"upstream".to_string()));
+
+        let again = clone_target(&target, &CliBackend, &opts, &retry);
+        assert_eq!(again.status, CloneStatus::Existing);
+
+        let occupied = tmp.path().join("occupied");
+        std::fs::create_dir(&occupied).unwrap();
+        std::fs::write(occupied.join("notes.txt"), "x").unwrap();
+        let blocked = CloneTarget {
+            dest: occupied,
+            ..target
+        };
+        let result = clone_target(&blocked, &CliBackend, &opts, &retry);
+        assert_eq!(result.status, CloneStatus::Failed);
+    }
+}
@@ -1,22 +1,99 @@
 use std::path::Path;
 use std::sync::Arc;
 
-use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+use tokio::sync::mpsc::UnboundedSender;
 use tokio::sync::Semaphore;
 
-use gitr_core::models::repo::Repo;
-use gitr_core::models::sync_link::MergeStrategy;
+use gitr_core::hooks::HooksConfig;
+use gitr_core::models::repo::{Repo, RepoId};
+use gitr_core::models::sync_link::{MergeStrategy, SyncDirection, SyncLink};
+use gitr_core::models::sync_state::SyncStatus;
+use gitr_core::retry::RetryPolicy;
 
-use crate::fork_sync::{sync_fork, ForkSyncResult};
+use crate::backend::{CliBackend, GitBackend};
+use crate::clone::{clone_target, CloneResult, CloneTarget};
+use crate::fork_sync::{sync_fork, ForkSyncOptions, ForkSyncResult};
+use crate::git_ops::CloneOptions;
+use crate::refresh::{refresh_target, RefreshResult, RefreshTarget};
+
+/// Progress reported while the engine works, for live views like the
+/// status dashboard.
+#[derive(Debug, Clone)]
+pub enum SyncEvent {
+    SyncStarted {
+        repo_id: RepoId,
+        repo: String,
+    },
+    SyncFinished {
+        repo_id: RepoId,
+        repo: String,
+        status: SyncStatus,
+        errors: Vec<String>,
+    },
+    RefreshStarted {
+        repo_id: RepoId,
+        repo: String,
+    },
+    RefreshFinished {
+        repo_id: RepoId,
+        repo: String,
+        error: Option<String>,
+    },
+}
 
 /// Sync engine that runs fork syncs in parallel with a concurrency limit.
 pub struct SyncEngine {
     concurrency: usize,
+    retry: RetryPolicy,
+    backend: Arc<dyn GitBackend>,
+    hooks: HooksConfig,
+    events: Option<UnboundedSender<SyncEvent>>,
 }
 
 impl SyncEngine {
-    pub fn new(concurrency: usize) -> Self {
-        Self { concurrency }
+    pub fn new(concurrency: usize, retry: RetryPolicy) -> Self {
+        Self {
+            concurrency,
+            retry,
+            backend: Arc::new(CliBackend),
+            hooks: HooksConfig::default(),
+            events: None,
+        }
+    }
+
+    /// Use a different git backend (the CLI backend is the default).
+    pub fn with_backend(mut self, backend: Arc<dyn GitBackend>) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Run these hooks around each fork sync.
+    pub fn with_hooks(mut self, hooks: HooksConfig) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Report progress as [`SyncEvent`]s on `tx` instead of drawing
+    /// progress bars.
+    pub fn with_events(mut self, tx: UnboundedSender<SyncEvent>) -> Self {
+        self.events = Some(tx);
+        self
+    }
+
+    fn emit(events: &Option<UnboundedSender<SyncEvent>>, event: SyncEvent) {
+        if let Some(tx) = events {
+            // The receiver going away just means nobody is watching.
+            let _ = tx.send(event);
+        }
+    }
+
+    fn draw_target(&self) -> ProgressDrawTarget {
+        if self.events.is_some() {
+            ProgressDrawTarget::hidden()
+        } else {
+            ProgressDrawTarget::stderr()
+        }
     }
 
     /// Sync all forks in parallel. Each repo needs its upstream clone URL.
@@ -28,7 +105,7 @@ impl SyncEngine {
         dry_run: bool,
     ) -> Vec<ForkSyncResult> {
         let semaphore = Arc::new(Semaphore::new(self.concurrency));
-        let multi = MultiProgress::new();
+        let multi = MultiProgress::with_draw_target(self.draw_target());
         let style = ProgressStyle::with_template("{spinner:.green} {msg}")
             .unwrap()
             .tick_chars("⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏");
@@ -44,13 +121,40 @@ impl SyncEngine {
             pb.set_message(format!("syncing {}", repo.full_name));
             let base = clone_base.clone();
             let s = strat.clone();
+            let retry = self.retry.clone();
+            let backend = self.backend.clone();
+            let hooks = self.hooks.for_repo(&repo.full_name);
+            let events = self.events.clone();
 
             // Acquire permit in async context before handing off to spawn_blocking.
             // Dropping it inside the blocking closure releases the slot when done.
             let permit = sem.acquire_owned().await.expect("semaphore closed");
             let handle = tokio::task::spawn_blocking(move || {
                 let _permit = permit;
-                let result = sync_fork(&repo, &upstream_url, &base, &s, dry_run);
+                Self::emit(
+                    &events,
+                    SyncEvent::SyncStarted {
+                        repo_id: repo.id.clone(),
+                        repo: repo.full_name.clone(),
+                    },
+                );
+                let opts = ForkSyncOptions {
+                    strategy: &s,
+                    retry: &retry,
+                    hooks: &hooks,
+                    upstream_remote: "upstream",
+                    dry_run,
+                };
+                let result = sync_fork(&repo, &upstream_url, &base, backend.as_ref(), &opts);
+                Self::emit(
+                    &events,
+                    SyncEvent::SyncFinished {
+                        repo_id: repo.id.clone(),
+                        repo: repo.full_name.clone(),
+                        status: result.record.status.clone(),
+                        errors: result.record.errors.clone(),
+                    },
+                );
                 pb.finish_with_message(format!(
                     "{}: {}",
                     result.repo_full_name,
@@ -70,4 +174,210 @@ impl SyncEngine {
 
         results
     }
+
+    /// Refresh branch snapshots for many repos in parallel.
+    ///
+    /// Repos with a local clone are inspected with git (fetching first when
+    /// `fetch` is set); the rest fall back to the host's compare API.
+    pub async fn refresh_all(&self, targets: Vec<RefreshTarget>, fetch: bool) -> Vec<RefreshResult> {
+        let semaphore = Arc::new(Semaphore::new(self.concurrency));
+        let pb = ProgressBar::with_draw_target(Some(targets.len() as u64), self.draw_target());
+        pb.set_style(
+            ProgressStyle::with_template("{spinner:.green} [{bar:30}] {pos}/{len} {msg}")
+                .unwrap()
+                .progress_chars("=> "),
+        );
+
+        let mut handles = Vec::new();
+        for target in targets {
+            let permit = semaphore.clone().acquire_owned().await.expect("semaphore closed");
+            let backend = self.backend.clone();
+            let retry = self.retry.clone();
+            let pb = pb.clone();
+            let events = self.events.clone();
+            handles.push(tokio::spawn(async move {
+                let _permit = permit;
+                let repo_id = target.repo.id.clone();
+                pb.set_message(target.repo.full_name.clone());
+                Self::emit(
+                    &events,
+                    SyncEvent::RefreshStarted {
+                        repo_id: repo_id.clone(),
+                        repo: target.repo.full_name.clone(),
+                    },
+                );
+                let result = refresh_target(target, backend, retry, fetch).await;
+                Self::emit(
+                    &events,
+                    SyncEvent::RefreshFinished {
+                        repo_id,
+                        repo: result.repo_full_name.clone(),
+                        error: result.error.clone(),
+                    },
+                );
+                pb.inc(1);
+                result
+            }));
+        }
+
+        let mut results = Vec::new();
+        for handle in handles {
+            if let Ok(result) = handle.await {
+                results.push(result);
+            }
+        }
+        pb.finish_and_clear();
+
+        results
+    }
+
+    /// Clone many repos in parallel into their resolved destinations.
+    pub async fn clone_all(&self, targets: Vec<CloneTarget>, opts: &CloneOptions) -> Vec<CloneResult> {
+        let semaphore = Arc::new(Semaphore::new(self.concurrency));
+        let pb = ProgressBar::with_draw_target(Some(targets.len() as u64), self.draw_target());
+        pb.set_style(
+            ProgressStyle::with_template("{spinner:.green} [{bar:30}] {pos}/{len} {msg}")
+                .unwrap()
+                .progress_chars("=> "),
+        );
+
+        let mut handles = Vec::new();
+        for target in targets {
+            let permit = semaphore.clone().acquire_owned().await.expect("semaphore closed");
+            let backend = self.backend.clone();
+            let retry = self.retry.clone();
+            let opts = opts.clone();
+            let pb = pb.clone();
+            handles.push(tokio::task::spawn_blocking(move || {
+                let _permit = permit;
+                pb.set_message(target.repo.full_name.clone());
+                let result = clone_target(&target, backend.as_ref(), &opts, &retry);
+                pb.inc(1);
+                result
+            }));
+        }
+
+        let mut results = Vec::new();
+        for handle in handles {
+            if let Ok(result) = handle.await {
+                results.push(result);
+            }
+        }
+        pb.finish_and_clear();
+
+        results
+    }
+
+    /// Run a sync link. `Push` brings the target up to date with the
+    /// source, `Pull` the source with the target, and `Both` does a push
+    /// then a pull. Only default branches are synced.
+    ///
+    /// The other side is fetched into a `gitr-link-<id>` remote so it never
+    /// clashes with a fork's own `upstream`.
+    pub async fn sync_link(
+        &self,
+        link: &SyncLink,
+        source: &Repo,
+        target: &Repo,
+        clone_base_dir: &Path,
+    ) -> Vec<ForkSyncResult> {
+        let legs = match link.direction {
+            SyncDirection::Push => vec![(target, source)],
+            SyncDirection::Pull => vec![(source, target)],
+            SyncDirection::Both => vec![(target, source), (source, target)],
+        };
+        let remote = format!("gitr-link-{}", link.id.0.simple());
+
+        let mut results = Vec::new();
+        for (dest, src) in legs {
+            let dest = dest.clone();
+            let src_url = src.clone_url.clone();
+            let base = clone_base_dir.to_path_buf();
+            let strategy = link.merge_strategy.clone();
+            let retry = self.retry.clone();
+            let backend = self.backend.clone();
+            let hooks = self.hooks.for_repo(&dest.full_name);
+            let remote = remote.clone();
+            let events = self.events.clone();
+
+            let handle = tokio::task::spawn_blocking(move || {
+                Self::emit(
+                    &events,
+                    SyncEvent::SyncStarted {
+                        repo_id: dest.id.clone(),
+                        repo: dest.full_name.clone(),
+                    },
+                );
+                let opts = ForkSyncOptions {
+                    strategy: &strategy,
+                    retry: &retry,
+                    hooks: &hooks,
+                    upstream_remote: &remote,
+                    dry_run: false,
+                };
+                let result = sync_fork(&dest, &src_url, &base, backend.as_ref(), &opts);
+                Self::emit(
+                    &events,
+                    SyncEvent::SyncFinished {
+                        repo_id: dest.id.clone(),
+                        repo: dest.full_name.clone(),
+                        status: result.record.status.clone(),
+                        errors: result.record.errors.clone(),
+                    },
+                );
+                result
+            });
+            if let Ok(mut result) = handle.await {
+                result.record.sync_link_id = Some(link.id.clone());
+                let failed = result.record.status == SyncStatus::Failed;
+                results.push(result);
+                if failed {
+                    break;
+                }
+            }
+        }
+
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gitr_core::models::host::HostId;
+    use gitr_core::models::repo::DiscoverySource;
+
+    #[tokio::test]
+    async fn test_refresh_all_reports_events() {
+        let repo = Repo::new(
+            "me/fork".to_string(),
+            HostId::new(),
+            "https://example.com/me/fork.git".to_string(),
+            "main".to_string(),
+            DiscoverySource::Api,
+        );
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let engine = SyncEngine::new(2, RetryPolicy::default()).with_events(tx);
+        let target = RefreshTarget {
+            repo: repo.clone(),
+            local_path: None,
+            upstream_clone_url: None,
+            provider: None,
+        };
+
+        let results = engine.refresh_all(vec![target], false).await;
+        assert!(results[0].error.is_some());
+
+        match rx.recv().await.unwrap() {
+            SyncEvent::RefreshStarted { repo_id, .. } => assert_eq!(repo_id, repo.id),
+            other => panic!("expected RefreshStarted, got {other:?}"),
+        }
+        match rx.recv().await.unwrap() {
+            SyncEvent::RefreshFinished { repo, error, .. } => {
+                assert_eq!(repo, "me/fork");
+                assert!(error.is_some());
+            }
+            other => panic!("expected RefreshFinished, got {other:?}"),
+        }
+    }
 }
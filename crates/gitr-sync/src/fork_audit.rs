@@ -0,0 +1,387 @@
+//! Sorting forks by whether they still hold anything of their own, for
+//! `gitr forks audit` and `gitr forks prune`.
+
+use std::collections::HashMap;
+
+use gitr_core::error::GitrError;
+use gitr_core::models::repo::Repo;
+use gitr_core::models::sync_state::BranchSnapshot;
+use gitr_host::{HostProvider, PullRequest};
+
+/// What a fork holds relative to its upstream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ForkClass {
+    /// Same default branch as the upstream, and no branches of its own.
+    Identical,
+    /// Only missing upstream commits.
+    BehindOnly,
+    /// Commits or branches the upstream doesn't have.
+    HasOwnCommits,
+    /// Open pull requests from the fork into its upstream.
+    HasOpenPrs,
+    /// The upstream was deleted or can no longer be seen.
+    UpstreamGone,
+}
+
+impl std::fmt::Display for ForkClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ForkClass::Identical => write!(f, "identical-to-upstream"),
+            ForkClass::BehindOnly => write!(f, "behind-only"),
+            ForkClass::HasOwnCommits => write!(f, "has-own-commits"),
+            ForkClass::HasOpenPrs => write!(f, "has-open-prs"),
+            ForkClass::UpstreamGone => write!(f, "upstream-gone"),
+        }
+    }
+}
+
+impl std::str::FromStr for ForkClass {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "identical-to-upstream" | "identical" => Ok(ForkClass::Identical),
+            "behind-only" => Ok(ForkClass::BehindOnly),
+            "has-own-commits" => Ok(ForkClass::HasOwnCommits),
+            "has-open-prs" => Ok(ForkClass::HasOpenPrs),
+            "upstream-gone" => Ok(ForkClass::UpstreamGone),
+            _ => Err(format!("unknown fork class: {s}")),
+        }
+    }
+}
+
+impl ForkClass {
+    /// Whether the fork holds nothing its upstream lacks, so archiving or
+    /// deleting it loses no work.
+    pub fn is_redundant(self) -> bool {
+        matches!(self, ForkClass::Identical | ForkClass::BehindOnly)
+    }
+
+    /// What to do about a fork in this class.
+    pub fn suggestion(self) -> &'static str {
+        match self {
+            ForkClass::Identical => "nothing of its own: archive or delete it",
+            ForkClass::BehindOnly => {
+                "nothing of its own: sync it if you still use it, otherwise archive or delete it"
+            }
+            ForkClass::HasOwnCommits => {
+                "has work of its own: keep it, or open a pull request upstream"
+            }
+            ForkClass::HasOpenPrs => "keep it until its pull requests are merged or closed",
+            ForkClass::UpstreamGone => {
+                "its upstream is gone: keep it if it is the copy you rely on"
+            }
+        }
+    }
+}
+
+/// What the host and the last comparison say about a fork.
+#[derive(Debug, Clone, Default)]
+pub struct ForkFacts {
+    pub upstream_gone: bool,
+    /// Behind and ahead counts of the default branch, if it was compared.
+    pub compare: Option<(u32, u32)>,
+    /// Branches with commits the upstream doesn't have: ones it has no
+    /// branch of the same name for, and same-named ones that are ahead of it.
+    pub own_branches: Vec<String>,
+    /// Open pull requests from the fork into its upstream.
+    pub open_prs: Vec<PullRequest>,
+}
+
+/// The fork's class, or `None` when only the missing comparison could tell.
+pub fn classify(facts: &ForkFacts) -> Option<ForkClass> {
+    if facts.upstream_gone {
+        return Some(ForkClass::UpstreamGone);
+    }
+    if !facts.open_prs.is_empty() {
+        return Some(ForkClass::HasOpenPrs);
+    }
+    if !facts.own_branches.is_empty() {
+        return Some(ForkClass::HasOwnCommits);
+    }
+    Some(match facts.compare? {
+        (_, ahead) if ahead > 0 => ForkClass::HasOwnCommits,
+        (behind, _) if behind > 0 => ForkClass::BehindOnly,
+        _ => ForkClass::Identical,
+    })
+}
+
+/// Ask `repo`'s host about its upstream: whether it still exists, which
+/// of its open pull requests come from `repo`, and which of `repo`'s
+/// branches hold commits it lacks. `compare` is the default branch's
+/// latest snapshot.
+pub async fn gather(
+    repo: &Repo,
+    compare: Option<&BranchSnapshot>,
+    provider: &dyn HostProvider,
+) -> Result<ForkFacts, GitrError> {
+    let gone = ForkFacts {
+        upstream_gone: true,
+        ..ForkFacts::default()
+    };
+    let Some((owner, name)) = repo
+        .upstream_full_name
+        .as_deref()
+        .and_then(|u| u.split_once('/'))
+    else {
+        return Ok(gone);
+    };
+    if provider.get_repo(owner, name).await?.is_none() {
+        return Ok(gone);
+    }
+
+    let open_prs = provider
+        .list_open_pull_requests(owner, name)
+        .await?
+        .into_iter()
+        .filter(|pr| {
+            pr.head_repo
+                .as_deref()
+                .is_some_and(|head| head.eq_ignore_ascii_case(&repo.full_name))
+        })
+        .collect();
+    let upstream_branches: HashMap<String, String> = provider
+        .list_branches(owner, name)
+        .await?
+        .into_iter()
+        .map(|b| (b.name, b.sha))
+        .collect();
+    let mut own_branches = Vec::new();
+    for branch in provider.list_branches(&repo.owner, &repo.name).await? {
+        if branch.name == repo.default_branch {
+            continue;
+        }
+        // A same-named branch is only a copy from when the fork was made if
+        // it has nothing the upstream's lacks.
+        let own = match upstream_branches.get(&branch.name) {
+            None => true,
+            Some(sha) if *sha == branch.sha => false,
+            Some(_) => {
+                provider
+                    .compare_branch(&repo.owner, &repo.name, owner, &branch.name)
+                    .await?
+                    .ahead_by
+                    > 0
+            }
+        };
+        if own {
+            own_branches.push(branch.name);
+        }
+    }
+
+    Ok(ForkFacts {
+        upstream_gone: false,
+        compare: compare.map(|s| (s.behind_count, s.ahead_count)),
+        own_branches,
+        open_prs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use gitr_core::models::host::{HostId, HostKind};
+    use gitr_core::models::repo::DiscoverySource;
+    use gitr_host::{ForkSyncStatus, RateLimitInfo, RemoteBranch, RemoteRepo};
+
+    /// `acme/tool` and its fork `me/tool`, with a branch list each and how
+    /// far ahead each fork branch is of its upstream namesake.
+    struct FakeHost {
+        upstream: Vec<(&'static str, &'static str)>,
+        fork: Vec<(&'static str, &'static str)>,
+        ahead: HashMap<&'static str, u32>,
+    }
+
+    #[async_trait]
+    impl HostProvider for FakeHost {
+        async fn validate_credentials(&self) -> Result<bool, GitrError> {
+            unreachable!()
+        }
+
+        async fn list_repos(&self) -> Result<Vec<RemoteRepo>, GitrError> {
+            unreachable!()
+        }
+
+        async fn get_repo(&self, owner: &str, name: &str) -> Result<Option<RemoteRepo>, GitrError> {
+            Ok(Some(RemoteRepo {
+                full_name: format!("{owner}/{name}"),
+                owner: owner.to_string(),
+                name: name.to_string(),
+                clone_url: format!("https://github.com/{owner}/{name}.git"),
+                ssh_url: format!("git@github.com:{owner}/{name}.git"),
+                default_branch: "main".to_string(),
+                is_fork: false,
+                upstream_full_name: None,
+                upstream_clone_url: None,
+                description: None,
+                is_private: false,
+                is_archived: false,
+                language: None,
+                updated_at: None,
+            }))
+        }
+
+        async fn list_branches(
+            &self,
+            owner: &str,
+            _name: &str,
+        ) -> Result<Vec<RemoteBranch>, GitrError> {
+            let branches = if owner == "acme" {
+                &self.upstream
+            } else {
+                &self.fork
+            };
+            Ok(branches
+                .iter()
+                .map(|(name, sha)| RemoteBranch {
+                    name: name.to_string(),
+                    sha: sha.to_string(),
+                    is_default: *name == "main",
+                })
+                .collect())
+        }
+
+        async fn fork_sync_status(
+            &self,
+            _owner: &str,
+            _name: &str,
+        ) -> Result<Vec<ForkSyncStatus>, GitrError> {
+            unreachable!()
+        }
+
+        async fn compare_branch(
+            &self,
+            owner: &str,
+            _name: &str,
+            upstream_owner: &str,
+            branch: &str,
+        ) -> Result<ForkSyncStatus, GitrError> {
+            assert_eq!((owner, upstream_owner), ("me", "acme"));
+            Ok(ForkSyncStatus {
+                branch: branch.to_string(),
+                behind_by: 1,
+                ahead_by: self.ahead[branch],
+            })
+        }
+
+        async fn rate_limit_status(&self) -> Result<RateLimitInfo, GitrError> {
+            unreachable!()
+        }
+
+        async fn sync_fork_upstream(
+            &self,
+            _owner: &str,
+            _name: &str,
+            _branch: &str,
+        ) -> Result<bool, GitrError> {
+            unreachable!()
+        }
+
+        async fn list_open_pull_requests(
+            &self,
+            _owner: &str,
+            _name: &str,
+        ) -> Result<Vec<PullRequest>, GitrError> {
+            Ok(Vec::new())
+        }
+
+        async fn archive_repo(&self, _owner: &str, _name: &str) -> Result<(), GitrError> {
+            unreachable!()
+        }
+
+        async fn delete_repo(&self, _owner: &str, _name: &str) -> Result<(), GitrError> {
+            unreachable!()
+        }
+
+        fn kind(&self) -> HostKind {
+            HostKind::GitHub
+        }
+    }
+
+    #[tokio::test]
+    async fn test_gather_compares_same_named_branches() {
+        let mut fork = Repo::new(
+            "me/tool".to_string(),
+            HostId::new(),
+            "https://github.com/me/tool.git".to_string(),
+            "main".to_string(),
+            DiscoverySource::Api,
+        );
+        fork.is_fork = true;
+        fork.upstream_full_name = Some("acme/tool".to_string());
+        let host = FakeHost {
+            upstream: vec![
+                ("main", "a1"),
+                ("develop", "b2"),
+                ("release", "c3"),
+                ("copy", "e1"),
+            ],
+            fork: vec![
+                ("main", "a0"),
+                ("develop", "b1"),
+                ("release", "c2"),
+                ("copy", "e1"),
+                ("topic", "d1"),
+            ],
+            // `develop` has unmerged work; `release` is only behind.
+            ahead: HashMap::from([("develop", 2), ("release", 0)]),
+        };
+        let facts = gather(&fork, None, &host).await.unwrap();
+        assert_eq!(facts.own_branches, ["develop", "topic"]);
+        assert_eq!(classify(&facts), Some(ForkClass::HasOwnCommits));
+    }
+
+    #[test]
+    fn test_classify() {
+        let compared = |behind, ahead| ForkFacts {
+            compare: Some((behind, ahead)),
+            ..ForkFacts::default()
+        };
+        assert_eq!(classify(&compared(0, 0)), Some(ForkClass::Identical));
+        assert_eq!(classify(&compared(12, 0)), Some(ForkClass::BehindOnly));
+        assert_eq!(classify(&compared(12, 1)), Some(ForkClass::HasOwnCommits));
+        assert_eq!(classify(&ForkFacts::default()), None);
+
+        let branched = ForkFacts {
+            own_branches: vec!["feature".to_string()],
+            ..compared(0, 0)
+        };
+        assert_eq!(classify(&branched), Some(ForkClass::HasOwnCommits));
+
+        let pr = PullRequest {
+            number: 7,
+            title: "Fix it".to_string(),
+            url: "https://github.com/acme/tool/pull/7".to_string(),
+            head_repo: Some("me/tool".to_string()),
+            head_branch: "fix".to_string(),
+        };
+        let proposed = ForkFacts {
+            open_prs: vec![pr],
+            ..ForkFacts::default()
+        };
+        assert_eq!(classify(&proposed), Some(ForkClass::HasOpenPrs));
+
+        let gone = ForkFacts {
+            upstream_gone: true,
+            ..compared(0, 0)
+        };
+        assert_eq!(classify(&gone), Some(ForkClass::UpstreamGone));
+
+        assert!(ForkClass::BehindOnly.is_redundant());
+        assert!(!ForkClass::UpstreamGone.is_redundant());
+    }
+
+    #[test]
+    fn test_fork_class_round_trip() {
+        for class in [
+            ForkClass::Identical,
+            ForkClass::BehindOnly,
+            ForkClass::HasOwnCommits,
+            ForkClass::HasOpenPrs,
+            ForkClass::UpstreamGone,
+        ] {
+            assert_eq!(class.to_string().parse::<ForkClass>(), Ok(class));
+        }
+    }
+}
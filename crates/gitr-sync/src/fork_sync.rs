@@ -1,12 +1,25 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::{Condvar, Mutex};
 
 use chrono::Utc;
 use gitr_core::error::GitrError;
+use gitr_core::hooks::{HookPoint, SyncHooks};
+use gitr_core::layout::clone_domain;
+use gitr_core::metrics;
 use gitr_core::models::repo::Repo;
 use gitr_core::models::sync_link::MergeStrategy;
-use gitr_core::models::sync_state::{SyncRecord, SyncStatus};
+use gitr_core::models::sync_state::{SyncBranchDetail, SyncRecord, SyncStatus};
+use gitr_core::retry::RetryPolicy;
 
-use crate::git_ops;
+use crate::backend::GitBackend;
+use crate::git_ops::{self, CloneOptions};
+use crate::hooks::{run_hook, HookEnv};
+use crate::refresh::is_clone_of;
+use crate::retry::Retrier;
+
+/// Cap on incoming commits recorded per branch, so a long-neglected fork
+/// doesn't bloat the history table.
+const MAX_RECORDED_COMMITS: u32 = 500;
 
 /// Result of syncing a single fork.
 #[derive(Debug)]
@@ -16,36 +29,66 @@ pub struct ForkSyncResult {
     pub dry_run: bool,
 }
 
+/// Settings for a single fork sync.
+pub struct ForkSyncOptions<'a> {
+    pub strategy: &'a MergeStrategy,
+    pub retry: &'a RetryPolicy,
+    /// Hooks already resolved for this repo (see `HooksConfig::for_repo`).
+    pub hooks: &'a SyncHooks,
+    /// Remote the upstream is fetched into; `upstream` for forks.
+    pub upstream_remote: &'a str,
+    pub dry_run: bool,
+}
+
 /// Sync a fork with its upstream.
 ///
 /// Flow:
 /// 1. Ensure local clone exists (clone if not)
 /// 2. Add upstream remote if missing
-/// 3. Fetch upstream
+/// 3. Fetch upstream (between the pre-fetch and post-fetch hooks)
 /// 4. Checkout default branch
 /// 5. Apply merge strategy
-/// 6. Push to origin
-/// 7. Return SyncRecord
+/// 6. Run the pre-push hook (a failure undoes the merge and skips the push)
+/// 7. Push to origin
+/// 8. Run the post-sync or on-failure hook and return SyncRecord
+///
+/// Clone, fetch and push are retried on transient failures according to
+/// `opts.retry`; the total attempt count is recorded on the `SyncRecord`.
+/// Hooks don't run in dry-run mode.
 pub fn sync_fork(
     repo: &Repo,
     upstream_clone_url: &str,
     clone_base_dir: &Path,
-    strategy: &MergeStrategy,
-    dry_run: bool,
+    backend: &dyn GitBackend,
+    opts: &ForkSyncOptions,
 ) -> ForkSyncResult {
     let started_at = Utc::now();
     let mut record = SyncRecord::new(repo.id.clone());
     record.started_at = started_at;
 
-    let result = sync_fork_inner(repo, upstream_clone_url, clone_base_dir, strategy, dry_run);
+    let local_path = local_clone_path(repo, clone_base_dir);
+    let _lock = CloneLock::acquire(&local_path);
+    let mut retrier = Retrier::new(opts.retry);
+    let mut cleanup_errors = Vec::new();
+    let result = sync_fork_inner(
+        repo,
+        upstream_clone_url,
+        &local_path,
+        opts,
+        &mut retrier,
+        backend,
+        &mut cleanup_errors,
+    );
 
     record.finished_at = Utc::now();
+    record.attempts = retrier.attempts();
 
     match result {
-        Ok(commits) => {
+        Ok((commits, detail)) => {
             record.branches_synced = 1;
             record.commits_transferred = commits;
-            record.status = if dry_run {
+            record.details.extend(detail);
+            record.status = if opts.dry_run {
                 SyncStatus::Skipped
             } else {
                 SyncStatus::Success
@@ -57,109 +100,524 @@ pub fn sync_fork(
             record.errors.push(e.to_string());
         }
     }
+    record.errors.extend(cleanup_errors);
+
+    if !opts.dry_run {
+        run_final_hook(repo, &local_path, clone_base_dir, backend, opts.hooks, &mut record);
+        metrics::record_sync(&repo.full_name, &record.status, record.commits_transferred);
+    }
 
     ForkSyncResult {
         repo_full_name: repo.full_name.clone(),
         record,
-        dry_run,
+        dry_run: opts.dry_run,
     }
 }
 
-fn sync_fork_inner(
+/// Where the local clone of `repo` lives: its recorded `local_path`, or
+/// `<clone_base_dir>/<domain>/<owner>/<name>` for repos gitr cloned itself,
+/// so same-named repos on different owners or hosts never share a clone.
+/// A clone of `repo` left at `<clone_base_dir>/<name>` by older versions is
+/// used until the new path exists.
+pub fn local_clone_path(repo: &Repo, clone_base_dir: &Path) -> PathBuf {
+    if let Some(p) = &repo.local_path {
+        return p.clone();
+    }
+    let path = clone_base_dir
+        .join(clone_domain(repo))
+        .join(&repo.owner)
+        .join(&repo.name);
+    let legacy = clone_base_dir.join(&repo.name);
+    if !path.exists() && is_clone_of(&legacy, repo) {
+        return legacy;
+    }
+    path
+}
+
+/// Move gitr's clone of `repo` to where it belongs once the repo has been
+/// renamed to `full_name` at `clone_url`, and point its `origin` there.
+/// Clones at a recorded `local_path` are the user's and stay put; so does
+/// one whose new path is taken. Returns the new path if the clone moved.
+pub fn follow_rename(
     repo: &Repo,
-    upstream_clone_url: &str,
+    full_name: &str,
+    clone_url: &str,
+    clone_base_dir: &Path,
+) -> Result<Option<PathBuf>, GitrError> {
+    if repo.local_path.is_some() {
+        return Ok(None);
+    }
+    let from = local_clone_path(repo, clone_base_dir);
+    let (owner, name) = full_name.split_once('/').unwrap_or(("", full_name));
+    let renamed = Repo {
+        full_name: full_name.to_string(),
+        owner: owner.to_string(),
+        name: name.to_string(),
+        clone_url: clone_url.to_string(),
+        ..repo.clone()
+    };
+    let to = local_clone_path(&renamed, clone_base_dir);
+    if from == to || to.exists() || !is_clone_of(&from, repo) {
+        return Ok(None);
+    }
+
+    let _lock = CloneLock::acquire(&from);
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::rename(&from, &to)?;
+    git_ops::remote_set_url(&to, "origin", clone_url)?;
+    Ok(Some(to))
+}
+
+/// Clones a sync is working in right now.
+static BUSY_CLONES: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+static CLONE_FREED: Condvar = Condvar::new();
+
+/// Exclusive use of a clone directory for as long as it's held. Syncs of
+/// different repos can still land in one clone (both legs of a link, or a
+/// repo synced by a link and as a fork at once), so they take turns.
+struct CloneLock {
+    path: PathBuf,
+}
+
+impl CloneLock {
+    /// Block until no other sync in this process is using `path`.
+    fn acquire(path: &Path) -> Self {
+        let mut busy = BUSY_CLONES.lock().unwrap_or_else(|e| e.into_inner());
+        while busy.iter().any(|p| p == path) {
+            busy = CLONE_FREED.wait(busy).unwrap_or_else(|e| e.into_inner());
+        }
+        busy.push(path.to_path_buf());
+        Self {
+            path: path.to_path_buf(),
+        }
+    }
+}
+
+impl Drop for CloneLock {
+    fn drop(&mut self) {
+        let mut busy = BUSY_CLONES.lock().unwrap_or_else(|e| e.into_inner());
+        busy.retain(|p| p != &self.path);
+        CLONE_FREED.notify_all();
+    }
+}
+
+/// Run post-sync or on-failure. These can't change the outcome, so a failing
+/// hook is only added to the record's errors.
+fn run_final_hook(
+    repo: &Repo,
+    local_path: &Path,
     clone_base_dir: &Path,
-    strategy: &MergeStrategy,
-    dry_run: bool,
-) -> Result<u32, GitrError> {
-    // Determine local path
-    let local_path = match &repo.local_path {
-        Some(p) => p.clone(),
-        None => clone_base_dir.join(&repo.name),
+    git: &dyn GitBackend,
+    hooks: &SyncHooks,
+    record: &mut SyncRecord,
+) {
+    let point = if record.status == SyncStatus::Failed {
+        HookPoint::OnFailure
+    } else {
+        HookPoint::PostSync
+    };
+    if hooks.get(point).is_none() {
+        return;
+    }
+
+    let branch = &repo.default_branch;
+    let current = git.rev_parse(local_path, branch).ok().flatten();
+    let detail = record.details.first();
+    let old_sha = detail.and_then(|d| d.before_sha.clone()).or(current.clone());
+    let new_sha = detail.and_then(|d| d.after_sha.clone()).or(current);
+    // The clone may not exist if cloning is what failed.
+    let dir = if local_path.exists() { local_path } else { clone_base_dir };
+
+    let env = HookEnv {
+        repo,
+        branch,
+        dir,
+        old_sha: old_sha.as_deref(),
+        new_sha: new_sha.as_deref(),
+        status: Some(&record.status),
+        commits: record.commits_transferred,
     };
+    if let Err(e) = run_hook(hooks, point, &env) {
+        tracing::warn!("{}: {e}", repo.full_name);
+        record.errors.push(e.to_string());
+    }
+}
+
+fn sync_fork_inner(
+    repo: &Repo,
+    upstream_clone_url: &str,
+    local_path: &Path,
+    opts: &ForkSyncOptions,
+    retrier: &mut Retrier,
+    git: &dyn GitBackend,
+    cleanup_errors: &mut Vec<String>,
+) -> Result<(u32, Option<SyncBranchDetail>), GitrError> {
+    let dry_run = opts.dry_run;
+    let branch = &repo.default_branch;
+    let remote = opts.upstream_remote;
+    let upstream_ref = format!("{remote}/{branch}");
 
     // 1. Clone if needed
     if !local_path.join(".git").exists() {
         if dry_run {
             tracing::info!("[dry-run] would clone {} to {}", repo.clone_url, local_path.display());
-            return Ok(0);
+            return Ok((0, None));
         }
         tracing::info!("cloning {} to {}", repo.clone_url, local_path.display());
-        git_ops::clone(&repo.clone_url, &local_path)?;
+        retrier.run("clone", || {
+            git.clone_repo(&repo.clone_url, local_path, &CloneOptions::default())
+        })?;
     }
 
     // 2. Add upstream remote if missing
-    let remotes = git_ops::remote_list(&local_path)?;
-    if !remotes.iter().any(|r| r == "upstream") {
+    let remotes = git.remote_list(local_path)?;
+    if !remotes.iter().any(|r| r == remote) {
         if dry_run {
-            tracing::info!("[dry-run] would add upstream remote: {upstream_clone_url}");
+            tracing::info!("[dry-run] would add {remote} remote: {upstream_clone_url}");
         } else {
-            git_ops::remote_add(&local_path, "upstream", upstream_clone_url)?;
+            git.remote_add(local_path, remote, upstream_clone_url)?;
         }
     }
 
     // 3. Fetch upstream
     if !dry_run {
-        git_ops::fetch(&local_path, "upstream")?;
+        let local_sha = git.rev_parse(local_path, branch)?;
+        let mut env = HookEnv {
+            repo,
+            branch,
+            dir: local_path,
+            old_sha: local_sha.as_deref(),
+            new_sha: None,
+            status: None,
+            commits: 0,
+        };
+        run_hook(opts.hooks, HookPoint::PreFetch, &env)?;
+        retrier.run(&format!("fetch {remote}"), || git.fetch(local_path, remote))?;
+        let upstream_sha = git.rev_parse(local_path, &upstream_ref)?;
+        env.new_sha = upstream_sha.as_deref();
+        run_hook(opts.hooks, HookPoint::PostFetch, &env)?;
     }
 
     // 4. Check behind count
-    let branch = &repo.default_branch;
-    let upstream_ref = format!("upstream/{branch}");
-
     if dry_run {
         // For dry-run, try to get the behind count if we have the refs
-        let behind = git_ops::rev_list_count(&local_path, branch, &upstream_ref).unwrap_or(0);
+        let behind = git.rev_list_count(local_path, branch, &upstream_ref).unwrap_or(0);
         tracing::info!(
             "[dry-run] {}: {behind} commits behind upstream on {branch}",
             repo.full_name
         );
-        return Ok(behind);
+        return Ok((behind, None));
     }
 
-    let behind = git_ops::rev_list_count(&local_path, branch, &upstream_ref)?;
+    let behind = git.rev_list_count(local_path, branch, &upstream_ref)?;
     if behind == 0 {
         tracing::info!("{}: already up to date on {branch}", repo.full_name);
-        return Ok(0);
+        return Ok((0, None));
     }
 
     tracing::info!(
         "{}: {behind} commits behind upstream on {branch}, syncing with strategy {}",
         repo.full_name,
-        strategy
+        opts.strategy
     );
 
-    // 5. Checkout default branch
-    git_ops::checkout(&local_path, branch)?;
+    // 5. Checkout default branch and note what's about to come in
+    git.checkout(local_path, branch)?;
+    let before_sha = git.rev_parse(local_path, branch)?;
+    let commits = git
+        .log(local_path, branch, &upstream_ref, MAX_RECORDED_COMMITS)
+        .unwrap_or_else(|e| {
+            tracing::warn!("{}: could not list incoming commits: {e}", repo.full_name);
+            Vec::new()
+        });
 
     // 6. Apply strategy
-    match strategy {
-        MergeStrategy::FastForward => git_ops::merge_ff(&local_path, &upstream_ref)?,
-        MergeStrategy::Merge => git_ops::merge(&local_path, &upstream_ref)?,
-        MergeStrategy::Rebase => git_ops::rebase(&local_path, &upstream_ref)?,
+    match opts.strategy {
+        MergeStrategy::FastForward => git.merge_ff(local_path, &upstream_ref)?,
+        MergeStrategy::Merge => git.merge(local_path, &upstream_ref)?,
+        MergeStrategy::Rebase => git.rebase(local_path, &upstream_ref)?,
         MergeStrategy::ForcePush => {
             // Reset to upstream and force push
-            git_ops::checkout(&local_path, branch)?;
-            let out = std::process::Command::new("git")
-                .args(["reset", "--hard", &upstream_ref])
-                .current_dir(&local_path)
-                .output()
-                .map_err(|e| GitrError::GitError {
-                    message: format!("git reset failed: {e}"),
-                })?;
-            if !out.status.success() {
-                return Err(GitrError::GitError {
-                    message: format!(
-                        "git reset --hard failed: {}",
-                        String::from_utf8_lossy(&out.stderr).trim()
-                    ),
-                });
+            git.checkout(local_path, branch)?;
+            git.reset_hard(local_path, &upstream_ref)?;
+        }
+    }
+    let after_sha = git.rev_parse(local_path, branch)?;
+
+    // 7. Pre-push hook. On failure, undo the merge so the next sync retries
+    // it instead of finding the local branch already up to date. The clone
+    // may be the user's own checkout, so uncommitted changes are kept.
+    let env = HookEnv {
+        repo,
+        branch,
+        dir: local_path,
+        old_sha: before_sha.as_deref(),
+        new_sha: after_sha.as_deref(),
+        status: None,
+        commits: behind,
+    };
+    if let Err(e) = run_hook(opts.hooks, HookPoint::PrePush, &env) {
+        if let Some(sha) = &before_sha {
+            if let Err(reset) = git.reset_keep(local_path, sha) {
+                cleanup_errors.push(format!("undoing the merge failed: {reset}"));
             }
         }
+        return Err(e);
+    }
+
+    // 8. Push to origin
+    retrier.run("push origin", || git.push(local_path, "origin", branch))?;
+
+    let detail = SyncBranchDetail {
+        branch: branch.clone(),
+        before_sha,
+        after_sha,
+        commits,
+    };
+    Ok((behind, Some(detail)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::CliBackend;
+    use crate::test_util::{commit, git, AUTHOR};
+    use gitr_core::models::host::HostId;
+    use gitr_core::models::repo::DiscoverySource;
+
+    /// Upstream with two commits the fork (a bare repo) doesn't have yet.
+    /// Returns the fork repo, upstream path and clone base dir.
+    fn fixture(tmp: &Path) -> (Repo, PathBuf, PathBuf) {
+        let upstream = tmp.join("upstream");
+        std::fs::create_dir_all(&upstream).unwrap();
+        git(&upstream, &["init", "-q", "-b", "main"]);
+        commit(&upstream, "initial");
+        git(tmp, &["clone", "-q", "--bare", "upstream", "fork.git"]);
+        commit(&upstream, "feat: first");
+        commit(&upstream, "fix: second");
+
+        let repo = Repo::new(
+            "me/fork".to_string(),
+            HostId::new(),
+            tmp.join("fork.git").to_string_lossy().to_string(),
+            "main".to_string(),
+            DiscoverySource::Manual,
+        );
+        (repo, upstream, tmp.join("clones"))
+    }
+
+    fn run(repo: &Repo, upstream: &Path, base: &Path, hooks: &SyncHooks) -> ForkSyncResult {
+        let opts = ForkSyncOptions {
+            strategy: &MergeStrategy::FastForward,
+            retry: &RetryPolicy::none(),
+            hooks,
+            upstream_remote: "upstream",
+            dry_run: false,
+        };
+        sync_fork(repo, upstream.to_str().unwrap(), base, &CliBackend, &opts)
+    }
+
+    #[test]
+    fn test_local_clone_path() {
+        let repo = |full_name: &str, url: &str| {
+            Repo::new(
+                full_name.to_string(),
+                HostId::new(),
+                url.to_string(),
+                "main".to_string(),
+                DiscoverySource::Api,
+            )
+        };
+        let base = Path::new("/clones");
+        let mine = repo("me/tool", "https://github.com/me/tool.git");
+        let theirs = repo("them/tool", "https://github.com/them/tool.git");
+        let mirror = repo("me/tool", "git@gitlab.example.com:me/tool.git");
+
+        assert_eq!(local_clone_path(&mine, base), base.join("github.com/me/tool"));
+        assert_eq!(local_clone_path(&theirs, base), base.join("github.com/them/tool"));
+        assert_eq!(local_clone_path(&mirror, base), base.join("gitlab.example.com/me/tool"));
+
+        let mut recorded = mine.clone();
+        recorded.local_path = Some(PathBuf::from("/src/tool"));
+        assert_eq!(local_clone_path(&recorded, base), Path::new("/src/tool"));
+    }
+
+    #[test]
+    fn test_old_clones_are_found_and_follow_renames() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (repo, _, base) = fixture(tmp.path());
+        std::fs::create_dir_all(&base).unwrap();
+        let legacy = base.join("fork");
+        git(&base, &["clone", "-q", &repo.clone_url, "fork"]);
+
+        assert_eq!(local_clone_path(&repo, &base), legacy);
+        let mut other = repo.clone();
+        other.full_name = "them/fork".to_string();
+        other.owner = "them".to_string();
+        other.clone_url = tmp.path().join("other.git").to_string_lossy().to_string();
+        assert_eq!(local_clone_path(&other, &base), base.join("local/them/fork"));
+
+        let url = tmp.path().join("renamed.git").to_string_lossy().to_string();
+        let moved = follow_rename(&repo, "me/renamed", &url, &base).unwrap();
+        let to = base.join("local/me/renamed");
+        assert_eq!(moved, Some(to.clone()));
+        assert!(!legacy.exists());
+        assert_eq!(git_ops::remote_url(&to, "origin").unwrap(), Some(url.clone()));
+
+        // Nothing left to move the second time.
+        assert_eq!(follow_rename(&repo, "me/renamed", &url, &base).unwrap(), None);
     }
 
-    // 7. Push to origin
-    git_ops::push(&local_path, "origin", branch)?;
+    #[test]
+    fn test_clone_lock_is_exclusive() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
 
-    Ok(behind)
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("clone");
+        let other = CloneLock::acquire(&tmp.path().join("other"));
+        let held = CloneLock::acquire(&path);
+
+        let acquired = Arc::new(AtomicBool::new(false));
+        let waiter = std::thread::spawn({
+            let path = path.clone();
+            let acquired = acquired.clone();
+            move || {
+                let _lock = CloneLock::acquire(&path);
+                acquired.store(true, Ordering::SeqCst);
+            }
+        });
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        assert!(!acquired.load(Ordering::SeqCst), "second sync got a busy clone");
+
+        drop(held);
+        waiter.join().unwrap();
+        assert!(acquired.load(Ordering::SeqCst));
+        drop(other);
+    }
+
+    #[test]
+    fn test_sync_records_incoming_commits() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (repo, upstream, base) = fixture(tmp.path());
+        let result = run(&repo, &upstream, &base, &SyncHooks::default());
+
+        assert_eq!(result.record.status, SyncStatus::Success, "{:?}", result.record.errors);
+        assert_eq!(result.record.commits_transferred, 2);
+        let detail = &result.record.details[0];
+        assert_eq!(detail.branch, "main");
+        assert_ne!(detail.before_sha, detail.after_sha);
+        let subjects: Vec<&str> = detail.commits.iter().map(|c| c.subject.as_str()).collect();
+        assert_eq!(subjects, ["fix: second", "feat: first"]);
+        assert_eq!(detail.commits[0].author, AUTHOR);
+        assert_eq!(detail.commits[0].sha, detail.after_sha.clone().unwrap());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_failing_pre_push_hook_aborts_push() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (repo, upstream, base) = fixture(tmp.path());
+        let log = tmp.path().join("hooks.log");
+        let hooks = SyncHooks {
+            pre_push: Some("echo \"checks failed for $GITR_REPO\"; exit 3".to_string()),
+            on_failure: Some(format!(
+                "echo \"$GITR_HOOK $GITR_STATUS $GITR_BRANCH\" >> {}",
+                log.display()
+            )),
+            ..SyncHooks::default()
+        };
+        let fork_head = || {
+            git_ops::rev_parse(&tmp.path().join("fork.git"), "main").unwrap()
+        };
+        let before = fork_head();
+
+        let result = run(&repo, &upstream, &base, &hooks);
+        assert_eq!(result.record.status, SyncStatus::Failed);
+        assert_eq!(
+            result.record.errors,
+            ["pre-push hook failed: checks failed for me/fork"]
+        );
+        assert_eq!(fork_head(), before, "push should not have happened");
+        assert_eq!(std::fs::read_to_string(&log).unwrap(), "on-failure failed main\n");
+
+        // The merge was undone, so a sync without the hook still transfers both commits.
+        let retry = run(&repo, &upstream, &base, &SyncHooks::default());
+        assert_eq!(retry.record.commits_transferred, 2);
+        assert_ne!(fork_head(), before);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_failed_undo_keeps_hook_error() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (repo, upstream, base) = fixture(tmp.path());
+        // A stale index lock makes the reset after the hook fail.
+        let hooks = SyncHooks {
+            pre_push: Some("touch .git/index.lock; echo nope; exit 1".to_string()),
+            ..SyncHooks::default()
+        };
+
+        let result = run(&repo, &upstream, &base, &hooks);
+        assert_eq!(result.record.status, SyncStatus::Failed);
+        let errors = &result.record.errors;
+        assert_eq!(errors.len(), 2, "{errors:?}");
+        assert_eq!(errors[0], "pre-push hook failed: nope");
+        assert!(errors[1].starts_with("undoing the merge failed: "), "{errors:?}");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_failed_pre_push_keeps_uncommitted_changes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (mut repo, upstream, base) = fixture(tmp.path());
+        // The user's own checkout, with an edit they haven't committed.
+        let checkout = tmp.path().join("checkout");
+        git(tmp.path(), &["clone", "-q", "fork.git", "checkout"]);
+        std::fs::write(checkout.join("initial"), "work in progress").unwrap();
+        repo.local_path = Some(checkout.clone());
+        let before = git_ops::rev_parse(&checkout, "main").unwrap();
+        let hooks = SyncHooks {
+            pre_push: Some("exit 1".to_string()),
+            ..SyncHooks::default()
+        };
+
+        let result = run(&repo, &upstream, &base, &hooks);
+        assert_eq!(result.record.status, SyncStatus::Failed);
+        assert_eq!(result.record.errors.len(), 1, "{:?}", result.record.errors);
+        assert_eq!(git_ops::rev_parse(&checkout, "main").unwrap(), before);
+        assert_eq!(
+            std::fs::read_to_string(checkout.join("initial")).unwrap(),
+            "work in progress"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_post_sync_hook_sees_shas() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (repo, upstream, base) = fixture(tmp.path());
+        let log = tmp.path().join("hooks.log");
+        let hooks = SyncHooks {
+            post_sync: Some(format!(
+                "echo \"$GITR_OLD_SHA $GITR_NEW_SHA $GITR_COMMITS $GITR_STATUS\" > {}",
+                log.display()
+            )),
+            ..SyncHooks::default()
+        };
+
+        let result = run(&repo, &upstream, &base, &hooks);
+        assert_eq!(result.record.status, SyncStatus::Success, "{:?}", result.record.errors);
+        let detail = &result.record.details[0];
+        assert_eq!(
+            std::fs::read_to_string(&log).unwrap().trim(),
+            format!(
+                "{} {} 2 success",
+                detail.before_sha.as_deref().unwrap(),
+                detail.after_sha.as_deref().unwrap()
+            )
+        );
+    }
 }
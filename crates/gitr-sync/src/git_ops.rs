@@ -1,7 +1,9 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
+use chrono::{DateTime, Utc};
 use gitr_core::error::GitrError;
+use gitr_core::models::sync_state::IncomingCommit;
 
 /// Result of a git command execution.
 #[derive(Debug)]
@@ -43,11 +45,29 @@ fn git_ok(dir: &Path, args: &[&str]) -> Result<String, GitrError> {
     Ok(out.stdout)
 }
 
+/// How much of a repo's history a clone fetches.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CloneOptions {
+    /// Only fetch this many commits of history (`--depth`).
+    pub depth: Option<u32>,
+    /// Partial clone filter such as `blob:none` (`--filter`).
+    pub filter: Option<String>,
+}
+
 /// Clone a repo to a local path.
-pub fn clone(url: &str, dest: &Path) -> Result<(), GitrError> {
-    let dest_str = dest.to_string_lossy();
+pub fn clone(url: &str, dest: &Path, opts: &CloneOptions) -> Result<(), GitrError> {
+    let mut args = vec!["clone".to_string()];
+    if let Some(depth) = opts.depth {
+        args.push(format!("--depth={depth}"));
+    }
+    if let Some(filter) = &opts.filter {
+        args.push(format!("--filter={filter}"));
+    }
+    args.extend(["--".to_string(), url.to_string()]);
     let output = Command::new("git")
-        .args(["clone", url, &dest_str])
+        .args(&args)
+        .arg(dest)
+        .env("GIT_TERMINAL_PROMPT", "0")
         .output()
         .map_err(|e| GitrError::GitError {
             message: format!("failed to clone {url}: {e}"),
@@ -135,12 +155,24 @@ pub fn remote_add(dir: &Path, name: &str, url: &str) -> Result<(), GitrError> {
     Ok(())
 }
 
+/// Point an existing remote at a new URL.
+pub fn remote_set_url(dir: &Path, name: &str, url: &str) -> Result<(), GitrError> {
+    git_ok(dir, &["remote", "set-url", name, url])?;
+    Ok(())
+}
+
 /// List remotes.
 pub fn remote_list(dir: &Path) -> Result<Vec<String>, GitrError> {
     let stdout = git_ok(dir, &["remote"])?;
     Ok(stdout.lines().map(|l| l.trim().to_string()).filter(|l| !l.is_empty()).collect())
 }
 
+/// URL of a remote, or `None` if there is no such remote.
+pub fn remote_url(dir: &Path, remote: &str) -> Result<Option<String>, GitrError> {
+    let out = git(dir, &["remote", "get-url", remote])?;
+    Ok(out.success.then(|| out.stdout.trim().to_string()))
+}
+
 /// Count commits that `a` is behind `b`: `git rev-list --count a..b`
 pub fn rev_list_count(dir: &Path, a: &str, b: &str) -> Result<u32, GitrError> {
     let range = format!("{a}..{b}");
@@ -163,3 +195,109 @@ pub fn rev_parse(dir: &Path, refspec: &str) -> Result<Option<String>, GitrError>
         Ok(None)
     }
 }
+
+/// Hard-reset the current branch to a ref.
+pub fn reset_hard(dir: &Path, target: &str) -> Result<(), GitrError> {
+    git_ok(dir, &["reset", "--hard", target])?;
+    Ok(())
+}
+
+/// Reset the current branch to a ref without discarding local changes:
+/// `git reset --keep`.
+pub fn reset_keep(dir: &Path, target: &str) -> Result<(), GitrError> {
+    git_ok(dir, &["reset", "--keep", target])?;
+    Ok(())
+}
+
+/// A ref advertised by a remote.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteRef {
+    pub name: String,
+    pub sha: String,
+}
+
+/// List branch heads advertised by a remote: `git ls-remote --heads <remote>`.
+pub fn ls_remote(dir: &Path, remote: &str) -> Result<Vec<RemoteRef>, GitrError> {
+    let stdout = git_ok(dir, &["ls-remote", "--heads", remote])?;
+    Ok(stdout
+        .lines()
+        .filter_map(|line| {
+            let (sha, name) = line.split_once('\t')?;
+            Some(RemoteRef {
+                name: name.trim().to_string(),
+                sha: sha.trim().to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Commits reachable from `b` but not `a`, newest first, at most `limit`:
+/// `git log --max-count=<limit> a..b`.
+pub fn log(dir: &Path, a: &str, b: &str, limit: u32) -> Result<Vec<IncomingCommit>, GitrError> {
+    let range = format!("{a}..{b}");
+    let max_count = format!("--max-count={limit}");
+    let stdout = git_ok(
+        dir,
+        &["log", &max_count, "--format=%H%x1f%an%x1f%cI%x1f%s", &range],
+    )?;
+    Ok(stdout
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(4, '\x1f');
+            let sha = fields.next()?;
+            let author = fields.next()?;
+            let date = DateTime::parse_from_rfc3339(fields.next()?).ok()?;
+            let subject = fields.next().unwrap_or_default();
+            Some(IncomingCommit {
+                sha: sha.to_string(),
+                author: author.to_string(),
+                subject: subject.to_string(),
+                date: date.with_timezone(&Utc),
+            })
+        })
+        .collect())
+}
+
+/// Whether a working tree has uncommitted changes or untracked files.
+pub fn is_dirty(dir: &Path) -> Result<bool, GitrError> {
+    let stdout = git_ok(dir, &["status", "--porcelain"])?;
+    Ok(!stdout.trim().is_empty())
+}
+
+/// Whether `dir` is a bare repository.
+pub fn is_bare(dir: &Path) -> Result<bool, GitrError> {
+    let stdout = git_ok(dir, &["rev-parse", "--is-bare-repository"])?;
+    Ok(stdout.trim() == "true")
+}
+
+/// The superproject's working tree if `dir` is a submodule.
+pub fn superproject(dir: &Path) -> Result<Option<String>, GitrError> {
+    let stdout = git_ok(dir, &["rev-parse", "--show-superproject-working-tree"])?;
+    let path = stdout.trim();
+    Ok((!path.is_empty()).then(|| path.to_string()))
+}
+
+/// Paths of a repo's worktrees, the main one (or the bare repo) first:
+/// `git worktree list --porcelain`.
+pub fn worktree_list(dir: &Path) -> Result<Vec<PathBuf>, GitrError> {
+    let stdout = git_ok(dir, &["worktree", "list", "--porcelain"])?;
+    Ok(stdout
+        .lines()
+        .filter_map(|line| line.strip_prefix("worktree "))
+        .map(PathBuf::from)
+        .collect())
+}
+
+/// Reconnect worktrees after a move: `git worktree repair [<path>...]`.
+/// Run in a moved linked worktree, it fixes the main repo's link to it;
+/// run in the main repo with the worktrees' paths, it fixes theirs.
+pub fn worktree_repair(dir: &Path, worktrees: &[PathBuf]) -> Result<(), GitrError> {
+    let paths: Vec<String> = worktrees
+        .iter()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect();
+    let mut args = vec!["worktree", "repair"];
+    args.extend(paths.iter().map(String::as_str));
+    git_ok(dir, &args)?;
+    Ok(())
+}
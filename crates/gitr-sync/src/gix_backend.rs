@@ -0,0 +1,373 @@
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+
+use gix::bstr::{BString, ByteSlice};
+use gix::progress;
+use gix::remote::{ref_map, Direction};
+use gix::revision::walk::Sorting;
+use gix::ObjectId;
+
+use gitr_core::error::GitrError;
+use gitr_core::models::sync_state::IncomingCommit;
+
+use crate::backend::{CliBackend, GitBackend};
+use crate::git_ops::{CloneOptions, RemoteRef};
+
+/// gitoxide-backed git backend.
+///
+/// Read-only operations (rev-parse, rev-list counts, remote listing, current
+/// branch) and the read side of the network (fetch, ls-remote) run
+/// in-process, which avoids spawning a `git` process per call during `gitr
+/// sync all`. gitoxide reads the same credential helpers and runs `ssh` for
+/// SSH remotes. Anything that writes the working tree or the remote — clone,
+/// checkout, merge, rebase, reset, push — is delegated to the git CLI so GPG
+/// signing and push hooks keep working.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GixBackend {
+    cli: CliBackend,
+}
+
+impl GixBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+fn open(dir: &Path) -> Result<gix::Repository, GitrError> {
+    gix::open(dir).map_err(|e| GitrError::GitError {
+        message: format!("failed to open {}: {e}", dir.display()),
+    })
+}
+
+fn resolve_commit(repo: &gix::Repository, spec: &str) -> Result<ObjectId, GitrError> {
+    repo.rev_parse_single(format!("{spec}^{{commit}}").as_str())
+        .map(|id| id.detach())
+        .map_err(|e| GitrError::GitError {
+            message: format!("failed to resolve {spec}: {e}"),
+        })
+}
+
+/// Count commits reachable from `tip` but not from `hidden`: `git rev-list
+/// --count hidden..tip`.
+///
+/// The walk hides the merge bases rather than `hidden` itself. That is the
+/// same set of commits, but it keeps every hidden tip an ancestor of `tip`:
+/// gix stops walking once only hidden commits are queued, which can count
+/// commits `hidden` reaches through a parent whose date is skewed.
+fn count_exclusive(
+    repo: &gix::Repository,
+    hidden: ObjectId,
+    tip: ObjectId,
+) -> Result<u32, GitrError> {
+    let walk_err = |e: &dyn std::fmt::Display| GitrError::GitError {
+        message: format!("failed to walk {hidden}..{tip}: {e}"),
+    };
+    // Empty for unrelated histories, which share nothing.
+    let bases: Vec<ObjectId> = repo
+        .merge_bases_many(tip, &[hidden])
+        .map_err(|e| walk_err(&e))?
+        .into_iter()
+        .map(|id| id.detach())
+        .collect();
+    let walk = repo
+        .rev_walk([tip])
+        .sorting(Sorting::ByCommitTime(Default::default()))
+        .with_hidden(bases)
+        .all()
+        .map_err(|e| walk_err(&e))?;
+    let mut count = 0;
+    for info in walk {
+        info.map_err(|e| walk_err(&e))?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// Connect to `remote` (a configured name or a URL) for fetching.
+fn find_remote<'r>(repo: &'r gix::Repository, remote: &str) -> Result<gix::Remote<'r>, GitrError> {
+    repo.find_fetch_remote(Some(remote.into()))
+        .map_err(|e| GitrError::GitError {
+            message: format!("unknown remote {remote}: {e}"),
+        })
+}
+
+fn network_error(op: &str, remote: &str, e: impl std::error::Error) -> GitrError {
+    let mut message = format!("git {op} {remote} failed: {e}");
+    let mut source = e.source();
+    while let Some(cause) = source {
+        message.push_str(&format!(": {cause}"));
+        source = cause.source();
+    }
+    GitrError::GitError { message }
+}
+
+/// Delete `refs/remotes/<remote>/*` refs the fetch no longer maps to a
+/// remote branch, as `git fetch --prune` does. The remote's `HEAD` is kept.
+fn prune_tracking_refs(
+    repo: &gix::Repository,
+    remote: &str,
+    kept: &HashSet<BString>,
+) -> Result<(), GitrError> {
+    let prefix = format!("refs/remotes/{remote}/");
+    let prune_err = |e: &dyn std::fmt::Display| GitrError::GitError {
+        message: format!("failed to prune {prefix}: {e}"),
+    };
+    let platform = repo.references().map_err(|e| prune_err(&e))?;
+    let stale: Vec<gix::Reference<'_>> = platform
+        .prefixed(prefix.as_str())
+        .map_err(|e| prune_err(&e))?
+        .filter_map(Result::ok)
+        .filter(|r| {
+            let name = r.name().as_bstr();
+            !kept.contains(name) && !name.ends_with(b"/HEAD")
+        })
+        .collect();
+    for reference in stale {
+        reference.delete().map_err(|e| prune_err(&e))?;
+    }
+    Ok(())
+}
+
+impl GitBackend for GixBackend {
+    fn name(&self) -> &'static str {
+        "gitoxide"
+    }
+
+    fn clone_repo(&self, url: &str, dest: &Path, opts: &CloneOptions) -> Result<(), GitrError> {
+        self.cli.clone_repo(url, dest, opts)
+    }
+
+    fn fetch(&self, dir: &Path, remote: &str) -> Result<(), GitrError> {
+        let mut repo = open(dir)?;
+        // Ref updates are logged; git falls back to user@host when no
+        // committer is configured, and so should we.
+        repo.committer_or_set_generic_fallback()
+            .map_err(|e| network_error("fetch", remote, e))?;
+        let outcome = find_remote(&repo, remote)?
+            .connect(Direction::Fetch)
+            .map_err(|e| network_error("fetch", remote, e))?
+            .prepare_fetch(progress::Discard, Default::default())
+            .map_err(|e| network_error("fetch", remote, e))?
+            .receive(progress::Discard, &AtomicBool::new(false))
+            .map_err(|e| network_error("fetch", remote, e))?;
+        // Only named remotes have tracking refs to prune.
+        if repo.find_remote(remote).is_ok() {
+            let kept = outcome
+                .ref_map
+                .mappings
+                .into_iter()
+                .filter_map(|m| m.local)
+                .collect();
+            prune_tracking_refs(&repo, remote, &kept)?;
+        }
+        Ok(())
+    }
+
+    fn checkout(&self, dir: &Path, branch: &str) -> Result<(), GitrError> {
+        self.cli.checkout(dir, branch)
+    }
+
+    fn merge_ff(&self, dir: &Path, remote_branch: &str) -> Result<(), GitrError> {
+        self.cli.merge_ff(dir, remote_branch)
+    }
+
+    fn merge(&self, dir: &Path, remote_branch: &str) -> Result<(), GitrError> {
+        self.cli.merge(dir, remote_branch)
+    }
+
+    fn rebase(&self, dir: &Path, remote_branch: &str) -> Result<(), GitrError> {
+        self.cli.rebase(dir, remote_branch)
+    }
+
+    fn reset_hard(&self, dir: &Path, target: &str) -> Result<(), GitrError> {
+        self.cli.reset_hard(dir, target)
+    }
+
+    fn reset_keep(&self, dir: &Path, target: &str) -> Result<(), GitrError> {
+        self.cli.reset_keep(dir, target)
+    }
+
+    fn push(&self, dir: &Path, remote: &str, branch: &str) -> Result<(), GitrError> {
+        self.cli.push(dir, remote, branch)
+    }
+
+    fn remote_add(&self, dir: &Path, name: &str, url: &str) -> Result<(), GitrError> {
+        self.cli.remote_add(dir, name, url)
+    }
+
+    fn remote_list(&self, dir: &Path) -> Result<Vec<String>, GitrError> {
+        let repo = open(dir)?;
+        Ok(repo
+            .remote_names()
+            .into_iter()
+            .map(|name| name.to_string())
+            .collect())
+    }
+
+    fn rev_list_count(&self, dir: &Path, a: &str, b: &str) -> Result<u32, GitrError> {
+        let repo = open(dir)?;
+        let hidden = resolve_commit(&repo, a)?;
+        let tip = resolve_commit(&repo, b)?;
+        count_exclusive(&repo, hidden, tip)
+    }
+
+    fn current_branch(&self, dir: &Path) -> Result<String, GitrError> {
+        let repo = open(dir)?;
+        let head = repo.head_name().map_err(|e| GitrError::GitError {
+            message: format!("failed to read HEAD: {e}"),
+        })?;
+        Ok(head
+            .map(|name| name.shorten().to_string())
+            .unwrap_or_else(|| "HEAD".to_string()))
+    }
+
+    fn rev_parse(&self, dir: &Path, refspec: &str) -> Result<Option<String>, GitrError> {
+        let repo = open(dir)?;
+        Ok(repo.rev_parse_single(refspec).ok().map(|id| id.to_string()))
+    }
+
+    fn ls_remote(&self, dir: &Path, remote: &str) -> Result<Vec<RemoteRef>, GitrError> {
+        let repo = open(dir)?;
+        // Only used to list refs; nothing is fetched.
+        let heads = gix::refspec::parse(
+            "refs/heads/*:refs/heads/*".into(),
+            gix::refspec::parse::Operation::Fetch,
+        )
+        .expect("valid refspec")
+        .to_owned();
+        let options = ref_map::Options {
+            extra_refspecs: vec![heads],
+            ..Default::default()
+        };
+        let (map, _) = find_remote(&repo, remote)?
+            .connect(Direction::Fetch)
+            .map_err(|e| network_error("ls-remote", remote, e))?
+            .ref_map(progress::Discard, options)
+            .map_err(|e| network_error("ls-remote", remote, e))?;
+        Ok(map
+            .remote_refs
+            .iter()
+            .filter_map(|r| {
+                let (name, target, _) = r.unpack();
+                let name = name
+                    .to_str()
+                    .ok()
+                    .filter(|n| n.starts_with("refs/heads/"))?;
+                Some(RemoteRef {
+                    name: name.to_string(),
+                    sha: target?.to_string(),
+                })
+            })
+            .collect())
+    }
+
+    fn log(
+        &self,
+        dir: &Path,
+        a: &str,
+        b: &str,
+        limit: u32,
+    ) -> Result<Vec<IncomingCommit>, GitrError> {
+        self.cli.log(dir, a, b, limit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{commit_at, git_at};
+
+    #[test]
+    fn test_matches_cli_on_skewed_merge_history() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        git_at(dir, &["init", "-q", "-b", "main"], "2020-01-01T00:00:00Z");
+        commit_at(dir, "one", "2020-01-01T00:00:00Z");
+        git_at(dir, &["branch", "side"], "2020-01-01T00:00:00Z");
+        commit_at(dir, "two", "2020-01-02T00:00:00Z");
+        git_at(dir, &["checkout", "-q", "side"], "2020-01-03T00:00:00Z");
+        commit_at(dir, "side", "2020-01-03T00:00:00Z");
+        git_at(dir, &["checkout", "-q", "main"], "2020-01-04T00:00:00Z");
+        git_at(
+            dir,
+            &["merge", "-q", "--no-edit", "side"],
+            "2020-01-04T00:00:00Z",
+        );
+        git_at(dir, &["branch", "fork"], "2020-01-04T00:00:00Z");
+        // Committer clock a year behind its parent.
+        commit_at(dir, "skewed", "2019-01-01T00:00:00Z");
+        git_at(dir, &["checkout", "-q", "fork"], "2020-01-05T00:00:00Z");
+        commit_at(dir, "ours", "2020-01-05T00:00:00Z");
+
+        let gix = GixBackend::new();
+        let cli = CliBackend;
+        for (a, b) in [
+            ("fork", "main"),
+            ("main", "fork"),
+            ("side", "main"),
+            ("main", "side"),
+        ] {
+            assert_eq!(
+                gix.rev_list_count(dir, a, b).unwrap(),
+                cli.rev_list_count(dir, a, b).unwrap(),
+                "{a}..{b}"
+            );
+        }
+        assert_eq!(gix.current_branch(dir).unwrap(), "fork");
+        assert_eq!(
+            gix.rev_parse(dir, "main").unwrap(),
+            cli.rev_parse(dir, "main").unwrap()
+        );
+        assert_eq!(gix.rev_parse(dir, "no-such-branch").unwrap(), None);
+    }
+
+    #[test]
+    fn test_fetch_prunes_and_ls_remote_matches_cli() {
+        let tmp = tempfile::tempdir().unwrap();
+        let upstream = tmp.path().join("upstream");
+        let work = tmp.path().join("work");
+        let date = "2020-01-01T00:00:00Z";
+        std::fs::create_dir(&upstream).unwrap();
+        git_at(&upstream, &["init", "-q", "-b", "main"], date);
+        commit_at(&upstream, "one", date);
+        git_at(&upstream, &["branch", "feature"], date);
+        git_at(
+            tmp.path(),
+            &["clone", "-q", upstream.to_str().unwrap(), "work"],
+            date,
+        );
+
+        commit_at(&upstream, "two", date);
+        git_at(&upstream, &["branch", "-q", "-D", "feature"], date);
+        git_at(&upstream, &["branch", "topic"], date);
+
+        let gix = GixBackend::new();
+        gix.fetch(&work, "origin").unwrap();
+        assert_eq!(
+            gix.rev_parse(&work, "refs/remotes/origin/main").unwrap(),
+            gix.rev_parse(&upstream, "main").unwrap()
+        );
+        assert!(gix
+            .rev_parse(&work, "refs/remotes/origin/topic")
+            .unwrap()
+            .is_some());
+        assert_eq!(
+            gix.rev_parse(&work, "refs/remotes/origin/feature").unwrap(),
+            None
+        );
+
+        let sorted = |mut refs: Vec<RemoteRef>| {
+            refs.sort_by(|a, b| a.name.cmp(&b.name));
+            refs.into_iter()
+                .map(|r| (r.name, r.sha))
+                .collect::<Vec<_>>()
+        };
+        let listed = sorted(gix.ls_remote(&work, "origin").unwrap());
+        assert_eq!(
+            listed,
+            sorted(CliBackend.ls_remote(&work, "origin").unwrap())
+        );
+        assert_eq!(listed.len(), 2);
+    }
+}
@@ -0,0 +1,80 @@
+use std::path::Path;
+use std::process::Command;
+
+use gitr_core::error::GitrError;
+use gitr_core::hooks::{HookPoint, SyncHooks};
+use gitr_core::models::repo::Repo;
+use gitr_core::models::sync_state::SyncStatus;
+
+/// Values exposed to hook commands as `GITR_*` environment variables.
+pub struct HookEnv<'a> {
+    pub repo: &'a Repo,
+    pub branch: &'a str,
+    /// Local clone; also the hook's working directory.
+    pub dir: &'a Path,
+    pub old_sha: Option<&'a str>,
+    pub new_sha: Option<&'a str>,
+    pub status: Option<&'a SyncStatus>,
+    pub commits: u32,
+}
+
+/// Run the hook configured for `point`, if any, through the platform shell.
+///
+/// Hooks see `GITR_HOOK`, `GITR_REPO`, `GITR_REPO_PATH`, `GITR_BRANCH`,
+/// `GITR_OLD_SHA`, `GITR_NEW_SHA`, `GITR_STATUS` and `GITR_COMMITS`. A
+/// non-zero exit becomes `GitrError::HookFailed` carrying the hook's output.
+pub fn run_hook(hooks: &SyncHooks, point: HookPoint, env: &HookEnv) -> Result<(), GitrError> {
+    let Some(command) = hooks.get(point) else {
+        return Ok(());
+    };
+    tracing::info!("{}: running {point} hook: {command}", env.repo.full_name);
+
+    let mut cmd = if cfg!(windows) {
+        let mut c = Command::new("cmd");
+        c.arg("/C");
+        c
+    } else {
+        let mut c = Command::new("sh");
+        c.arg("-c");
+        c
+    };
+    let output = cmd
+        .arg(command)
+        .current_dir(env.dir)
+        .env("GITR_HOOK", point.to_string())
+        .env("GITR_REPO", &env.repo.full_name)
+        .env("GITR_REPO_PATH", env.dir)
+        .env("GITR_BRANCH", env.branch)
+        .env("GITR_OLD_SHA", env.old_sha.unwrap_or_default())
+        .env("GITR_NEW_SHA", env.new_sha.unwrap_or_default())
+        .env(
+            "GITR_STATUS",
+            env.status.map(|s| s.to_string()).unwrap_or_default(),
+        )
+        .env("GITR_COMMITS", env.commits.to_string())
+        .output()
+        .map_err(|e| GitrError::HookFailed {
+            hook: point.to_string(),
+            output: format!("failed to run `{command}`: {e}"),
+        })?;
+
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let mut text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !stderr.trim().is_empty() {
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        text.push_str(stderr.trim());
+    }
+    if text.is_empty() {
+        text = format!("`{command}` exited with {}", output.status);
+    }
+    Err(GitrError::HookFailed {
+        hook: point.to_string(),
+        output: text,
+    })
+}
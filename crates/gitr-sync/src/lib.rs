@@ -1,3 +1,17 @@
+pub mod backend;
+pub mod clone;
 pub mod engine;
+pub mod fork_audit;
 pub mod fork_sync;
 pub mod git_ops;
+#[cfg(feature = "gitoxide")]
+pub mod gix_backend;
+pub mod hooks;
+pub mod notify;
+pub mod refresh;
+pub mod relocate;
+pub mod retry;
+pub mod scheduler;
+#[cfg(test)]
+mod test_util;
+pub mod webhook;
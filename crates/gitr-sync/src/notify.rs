@@ -0,0 +1,701 @@
+use std::collections::HashSet;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use rusqlite::Connection;
+
+use gitr_auth::CredentialStore;
+use gitr_core::error::GitrError;
+use gitr_core::models::repo::RepoId;
+use gitr_core::models::sync_state::SyncStatus;
+use gitr_core::notify::{
+    Alert, ChannelConfig, ChannelKind, NotifyConfig, NotifyRule, RuleCondition, SmtpTls,
+};
+
+/// A destination alerts can be delivered to.
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    /// Channel name from config, used in logs and rule targeting.
+    fn name(&self) -> &str;
+
+    async fn send(&self, alert: &Alert) -> Result<(), GitrError>;
+}
+
+fn failed(channel: &str, message: impl std::fmt::Display) -> GitrError {
+    GitrError::NotifyFailed {
+        channel: channel.to_string(),
+        message: message.to_string(),
+    }
+}
+
+async fn post_json(
+    client: &reqwest::Client,
+    channel: &str,
+    url: &str,
+    body: &impl serde::Serialize,
+) -> Result<(), GitrError> {
+    let resp = client
+        .post(url)
+        .json(body)
+        .send()
+        .await
+        .map_err(|e| failed(channel, e))?;
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let text = resp.text().await.unwrap_or_default();
+        return Err(failed(channel, format!("{status}: {text}")));
+    }
+    Ok(())
+}
+
+/// POSTs the [`Alert`] as JSON.
+pub struct WebhookNotifier {
+    name: String,
+    url: String,
+    client: reqwest::Client,
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn send(&self, alert: &Alert) -> Result<(), GitrError> {
+        post_json(&self.client, &self.name, &self.url, alert).await
+    }
+}
+
+/// POSTs a Slack incoming-webhook message (`{"text": ...}`).
+pub struct SlackNotifier {
+    name: String,
+    url: String,
+    client: reqwest::Client,
+}
+
+#[async_trait]
+impl Notifier for SlackNotifier {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn send(&self, alert: &Alert) -> Result<(), GitrError> {
+        let body = serde_json::json!({
+            "text": format!(":warning: *{}*: {}", alert.repo, alert.message),
+        });
+        post_json(&self.client, &self.name, &self.url, &body).await
+    }
+}
+
+/// Sends a plain-text email over SMTP.
+pub struct EmailNotifier {
+    name: String,
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    to: Vec<Mailbox>,
+}
+
+#[async_trait]
+impl Notifier for EmailNotifier {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn send(&self, alert: &Alert) -> Result<(), GitrError> {
+        let mut builder = Message::builder()
+            .from(self.from.clone())
+            .subject(format!("[gitr] {}: {}", alert.repo, alert.message));
+        for to in &self.to {
+            builder = builder.to(to.clone());
+        }
+        let email = builder
+            .body(format!(
+                "{}\n\nRepo:  {}\nRule:  {}\nValue: {}\nAt:    {}\n",
+                alert.message, alert.repo, alert.rule, alert.value, alert.fired_at
+            ))
+            .map_err(|e| failed(&self.name, e))?;
+        self.transport
+            .send(email)
+            .await
+            .map_err(|e| failed(&self.name, e))?;
+        Ok(())
+    }
+}
+
+/// Runs a shell command with the alert in `GITR_NOTIFY_RULE`,
+/// `GITR_NOTIFY_REPO`, `GITR_NOTIFY_VALUE` and `GITR_NOTIFY_MESSAGE`.
+pub struct CommandNotifier {
+    name: String,
+    command: String,
+}
+
+#[async_trait]
+impl Notifier for CommandNotifier {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn send(&self, alert: &Alert) -> Result<(), GitrError> {
+        let mut cmd = if cfg!(windows) {
+            let mut c = tokio::process::Command::new("cmd");
+            c.arg("/C");
+            c
+        } else {
+            let mut c = tokio::process::Command::new("sh");
+            c.arg("-c");
+            c
+        };
+        let output = cmd
+            .arg(&self.command)
+            .env("GITR_NOTIFY_RULE", &alert.rule)
+            .env("GITR_NOTIFY_REPO", &alert.repo)
+            .env("GITR_NOTIFY_VALUE", alert.value.to_string())
+            .env("GITR_NOTIFY_MESSAGE", &alert.message)
+            .output()
+            .await
+            .map_err(|e| failed(&self.name, format!("failed to run `{}`: {e}", self.command)))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(failed(
+                &self.name,
+                format!(
+                    "`{}` exited with {}: {}",
+                    self.command,
+                    output.status,
+                    stderr.trim()
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Build a notifier for each configured channel. SMTP passwords are read
+/// from `creds` under [`ChannelConfig::password_key`].
+pub fn build_notifiers(
+    channels: &[ChannelConfig],
+    creds: &dyn CredentialStore,
+) -> Result<Vec<Box<dyn Notifier>>, GitrError> {
+    let client = reqwest::Client::builder()
+        .user_agent("gitr/0.1.0")
+        .build()
+        .map_err(|e| GitrError::Other(format!("failed to build HTTP client: {e}")))?;
+
+    channels
+        .iter()
+        .map(|channel| -> Result<Box<dyn Notifier>, GitrError> {
+            let name = channel.name.clone();
+            Ok(match &channel.kind {
+                ChannelKind::Webhook { url } => Box::new(WebhookNotifier {
+                    name,
+                    url: url.clone(),
+                    client: client.clone(),
+                }),
+                ChannelKind::Slack { url } => Box::new(SlackNotifier {
+                    name,
+                    url: url.clone(),
+                    client: client.clone(),
+                }),
+                ChannelKind::Email {
+                    smtp_host,
+                    smtp_port,
+                    tls,
+                    username,
+                    from,
+                    to,
+                } => {
+                    let mut transport = match tls {
+                        SmtpTls::Starttls => {
+                            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(smtp_host)
+                        }
+                        SmtpTls::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(smtp_host),
+                        SmtpTls::None => Ok(
+                            AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(smtp_host),
+                        ),
+                    }
+                    .map_err(|e| failed(&name, e))?
+                    .port(*smtp_port);
+                    if let Some(username) = username {
+                        let password = creds.get(&channel.password_key())?.ok_or_else(|| {
+                            failed(
+                                &name,
+                                format!("no SMTP password stored as '{}'", channel.password_key()),
+                            )
+                        })?;
+                        transport =
+                            transport.credentials(Credentials::new(username.clone(), password));
+                    }
+                    let parse = |addr: &str| -> Result<Mailbox, GitrError> {
+                        addr.parse()
+                            .map_err(|e| failed(&name, format!("invalid address '{addr}': {e}")))
+                    };
+                    Box::new(EmailNotifier {
+                        from: parse(from)?,
+                        to: to.iter().map(|a| parse(a)).collect::<Result<_, _>>()?,
+                        transport: transport.build(),
+                        name,
+                    })
+                }
+                ChannelKind::Command { command } => Box::new(CommandNotifier {
+                    name,
+                    command: command.clone(),
+                }),
+            })
+        })
+        .collect()
+}
+
+/// Check every repo against `rules` and return alerts for rules that have
+/// started matching. A rule alerts once per repo, then stays quiet until the
+/// condition clears; that state is kept in the database, and only set for an
+/// alert once [`record_delivered`] sees it reach a channel.
+pub fn evaluate(conn: &Connection, rules: &[NotifyRule]) -> anyhow::Result<Vec<Alert>> {
+    if rules.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut fired: HashSet<(String, RepoId)> =
+        gitr_db::ops::list_fired_alerts(conn)?.into_iter().collect();
+    // Rules that differ only in channels share a key and fire together.
+    let mut seen = HashSet::new();
+    let keys: Vec<(String, &RuleCondition)> = rules
+        .iter()
+        .map(|r| (r.key(), &r.condition))
+        .filter(|(key, _)| seen.insert(key.clone()))
+        .collect();
+
+    let now = Utc::now();
+    let mut alerts = Vec::new();
+    for repo in gitr_db::ops::list_repos(conn)? {
+        for (key, condition) in &keys {
+            let hit = match condition {
+                RuleCondition::ConsecutiveFailures { count } => {
+                    let history = gitr_db::ops::list_sync_history(conn, Some(&repo.id), *count)?;
+                    (*count > 0
+                        && history.len() == *count as usize
+                        && history.iter().all(|r| r.status == SyncStatus::Failed))
+                    .then(|| {
+                        let error = history[0].errors.first().cloned().unwrap_or_default();
+                        (*count, format!("last {count} syncs failed: {error}"))
+                    })
+                }
+                RuleCondition::Behind { threshold } => {
+                    gitr_db::ops::get_branch_snapshots(conn, &repo.id)?
+                        .into_iter()
+                        .filter(|s| s.behind_count > *threshold)
+                        .max_by_key(|s| s.behind_count)
+                        .map(|s| {
+                            (
+                                s.behind_count,
+                                format!(
+                                    "{} is {} commits behind upstream",
+                                    s.branch, s.behind_count
+                                ),
+                            )
+                        })
+                }
+            };
+
+            let state = (key.clone(), repo.id.clone());
+            match hit {
+                Some((value, message)) if !fired.contains(&state) => {
+                    alerts.push(Alert {
+                        rule: key.clone(),
+                        repo: repo.full_name.clone(),
+                        repo_id: repo.id.clone(),
+                        value,
+                        message,
+                        fired_at: now,
+                    });
+                }
+                None if fired.remove(&state) => {
+                    gitr_db::ops::clear_alert(conn, key, &repo.id)?;
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(alerts)
+}
+
+/// Deliver each alert to the channels its rule targets. Delivery failures
+/// are logged and don't stop other deliveries; returns how many channels
+/// each alert reached.
+pub async fn dispatch(
+    alerts: &[Alert],
+    rules: &[NotifyRule],
+    notifiers: &[Box<dyn Notifier>],
+) -> Vec<usize> {
+    let mut sent = Vec::with_capacity(alerts.len());
+    for alert in alerts {
+        let mut reached = 0;
+        for notifier in notifiers {
+            let targeted = rules
+                .iter()
+                .filter(|r| r.key() == alert.rule)
+                .any(|r| r.targets(notifier.name()));
+            if !targeted {
+                continue;
+            }
+            match notifier.send(alert).await {
+                Ok(()) => reached += 1,
+                Err(e) => tracing::warn!("{}: {e}", alert.repo),
+            }
+        }
+        sent.push(reached);
+    }
+    sent
+}
+
+/// Mark the alerts that reached a channel (per [`dispatch`]'s counts) as
+/// fired, so they stay quiet until their condition clears. The rest are
+/// evaluated and sent again next time.
+pub fn record_delivered(conn: &Connection, alerts: &[Alert], sent: &[usize]) -> anyhow::Result<()> {
+    for (alert, _) in alerts.iter().zip(sent).filter(|(_, n)| **n > 0) {
+        gitr_db::ops::mark_alert_fired(conn, &alert.rule, &alert.repo_id, &alert.fired_at)?;
+    }
+    Ok(())
+}
+
+/// Evaluate the configured rules and send any new alerts.
+pub async fn notify(
+    conn: &Connection,
+    config: &NotifyConfig,
+    creds: &dyn CredentialStore,
+) -> anyhow::Result<()> {
+    let alerts = evaluate(conn, &config.rules)?;
+    if alerts.is_empty() {
+        return Ok(());
+    }
+    let notifiers = build_notifiers(&config.channels, creds)?;
+    let sent = dispatch(&alerts, &config.rules, &notifiers).await;
+    record_delivered(conn, &alerts, &sent)?;
+    tracing::info!(
+        "sent {} notification(s) for {} alert(s)",
+        sent.iter().sum::<usize>(),
+        alerts.len()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gitr_auth::MemoryStore;
+    use gitr_core::models::host::{Host, HostKind};
+    use gitr_core::models::repo::{DiscoverySource, Repo};
+    use gitr_core::models::sync_state::{BranchSnapshot, SyncRecord};
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+
+    fn fixture() -> (Connection, Repo) {
+        let conn = gitr_db::open_memory_db().unwrap();
+        let host = Host::new("gh".to_string(), HostKind::GitHub, "me".to_string());
+        gitr_db::ops::insert_host(&conn, &host).unwrap();
+        let mut repo = Repo::new(
+            "me/fork".to_string(),
+            host.id.clone(),
+            "https://github.com/me/fork.git".to_string(),
+            "main".to_string(),
+            DiscoverySource::Api,
+        );
+        repo.is_fork = true;
+        gitr_db::ops::insert_repo(&conn, &repo).unwrap();
+        (conn, repo)
+    }
+
+    fn record(conn: &Connection, repo: &Repo, status: SyncStatus) {
+        let mut record = SyncRecord::new(repo.id.clone());
+        record.status = status;
+        if record.status == SyncStatus::Failed {
+            record.errors.push("merge conflict".to_string());
+        }
+        gitr_db::ops::insert_sync_record(conn, &record).unwrap();
+        // Distinct start times keep history ordering deterministic.
+        std::thread::sleep(std::time::Duration::from_millis(5));
+    }
+
+    fn snapshot(conn: &Connection, repo: &Repo, behind: u32) {
+        gitr_db::ops::upsert_branch_snapshot(
+            conn,
+            &BranchSnapshot {
+                repo_id: repo.id.clone(),
+                branch: "main".to_string(),
+                local_sha: None,
+                remote_sha: None,
+                upstream_sha: None,
+                behind_count: behind,
+                ahead_count: 0,
+                updated_at: Utc::now(),
+            },
+        )
+        .unwrap();
+    }
+
+    fn rules() -> Vec<NotifyRule> {
+        vec![
+            NotifyRule {
+                condition: RuleCondition::ConsecutiveFailures { count: 2 },
+                channels: Vec::new(),
+            },
+            NotifyRule {
+                condition: RuleCondition::Behind { threshold: 100 },
+                channels: vec!["hook".to_string()],
+            },
+        ]
+    }
+
+    #[test]
+    fn test_evaluate_fires_once_until_cleared() {
+        let (conn, repo) = fixture();
+        let rules = rules();
+
+        record(&conn, &repo, SyncStatus::Failed);
+        assert!(evaluate(&conn, &rules).unwrap().is_empty());
+
+        record(&conn, &repo, SyncStatus::Failed);
+        let alerts = evaluate(&conn, &rules).unwrap();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].rule, "failures>=2");
+        assert_eq!(alerts[0].repo, "me/fork");
+        assert_eq!(alerts[0].repo_id, repo.id);
+        assert!(alerts[0].message.contains("merge conflict"));
+
+        // Undelivered alerts come back; delivered ones don't repeat while
+        // the repo keeps failing.
+        assert_eq!(evaluate(&conn, &rules).unwrap()[0].rule, "failures>=2");
+        record_delivered(&conn, &alerts, &[1]).unwrap();
+        record(&conn, &repo, SyncStatus::Failed);
+        assert!(evaluate(&conn, &rules).unwrap().is_empty());
+
+        // Recovery clears the state, so the next streak alerts again.
+        record(&conn, &repo, SyncStatus::Success);
+        assert!(evaluate(&conn, &rules).unwrap().is_empty());
+        record(&conn, &repo, SyncStatus::Failed);
+        record(&conn, &repo, SyncStatus::Failed);
+        let alerts = evaluate(&conn, &rules).unwrap();
+        assert_eq!(alerts.len(), 1);
+        record_delivered(&conn, &alerts, &[1]).unwrap();
+
+        snapshot(&conn, &repo, 100);
+        assert!(evaluate(&conn, &rules).unwrap().is_empty());
+        snapshot(&conn, &repo, 150);
+        let alerts = evaluate(&conn, &rules).unwrap();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].rule, "behind>100");
+        assert_eq!(alerts[0].value, 150);
+    }
+
+    #[test]
+    fn test_evaluate_alerts_once_per_key() {
+        let (conn, repo) = fixture();
+        // The same condition routed to two channels, with another rule between.
+        let mut rules = rules();
+        rules.push(NotifyRule {
+            condition: RuleCondition::ConsecutiveFailures { count: 2 },
+            channels: vec!["ops".to_string()],
+        });
+
+        record(&conn, &repo, SyncStatus::Failed);
+        record(&conn, &repo, SyncStatus::Failed);
+        let alerts = evaluate(&conn, &rules).unwrap();
+        assert_eq!(alerts.len(), 1, "{alerts:?}");
+        assert_eq!(alerts[0].rule, "failures>=2");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_notify_marks_fired_only_after_delivery() {
+        let (conn, repo) = fixture();
+        record(&conn, &repo, SyncStatus::Failed);
+        record(&conn, &repo, SyncStatus::Failed);
+        let config = |command: &str| NotifyConfig {
+            channels: vec![ChannelConfig {
+                name: "cmd".to_string(),
+                kind: ChannelKind::Command {
+                    command: command.to_string(),
+                },
+            }],
+            rules: rules(),
+        };
+
+        notify(&conn, &config("exit 1"), &MemoryStore::new()).await.unwrap();
+        assert_eq!(evaluate(&conn, &rules()).unwrap().len(), 1, "failed send was recorded");
+
+        notify(&conn, &config("true"), &MemoryStore::new()).await.unwrap();
+        assert!(evaluate(&conn, &rules()).unwrap().is_empty());
+    }
+
+    /// Accept one HTTP request and return its body.
+    fn http_stand_in() -> (String, mpsc::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" {
+                    break;
+                }
+                if let Some(v) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    length = v.trim().parse().unwrap();
+                }
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            reader
+                .get_mut()
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+                .unwrap();
+            tx.send(String::from_utf8(body).unwrap()).unwrap();
+        });
+        (url, rx)
+    }
+
+    /// Accept one SMTP session and return the message data.
+    fn smtp_stand_in() -> (u16, mpsc::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut writer = stream.try_clone().unwrap();
+            let mut reader = BufReader::new(stream);
+            writer.write_all(b"220 stand-in ESMTP\r\n").unwrap();
+            let mut data = String::new();
+            let mut in_data = false;
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap() == 0 {
+                    break;
+                }
+                if in_data {
+                    if line == ".\r\n" {
+                        in_data = false;
+                        writer.write_all(b"250 queued\r\n").unwrap();
+                    } else {
+                        data.push_str(&line);
+                    }
+                    continue;
+                }
+                let verb = line.get(..4).unwrap_or_default().to_ascii_uppercase();
+                match verb.as_str() {
+                    "EHLO" => writer.write_all(b"250 stand-in\r\n").unwrap(),
+                    "DATA" => {
+                        in_data = true;
+                        writer.write_all(b"354 go ahead\r\n").unwrap();
+                    }
+                    "QUIT" => {
+                        writer.write_all(b"221 bye\r\n").unwrap();
+                        break;
+                    }
+                    _ => writer.write_all(b"250 ok\r\n").unwrap(),
+                }
+            }
+            tx.send(data).unwrap();
+        });
+        (port, rx)
+    }
+
+    fn failure_alert() -> Alert {
+        Alert {
+            rule: "failures>=2".to_string(),
+            repo: "me/fork".to_string(),
+            repo_id: RepoId::new(),
+            value: 2,
+            message: "last 2 syncs failed: merge conflict".to_string(),
+            fired_at: Utc::now(),
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_command_channel_gets_alert_in_env() {
+        let tmp = tempfile::tempdir().unwrap();
+        let out = tmp.path().join("cmd.out");
+        let channels = vec![ChannelConfig {
+            name: "cmd".to_string(),
+            kind: ChannelKind::Command {
+                command: format!(
+                    "printf '%s %s' \"$GITR_NOTIFY_REPO\" \"$GITR_NOTIFY_VALUE\" > '{}'",
+                    out.display()
+                ),
+            },
+        }];
+        let notifiers = build_notifiers(&channels, &MemoryStore::new()).unwrap();
+
+        assert_eq!(dispatch(&[failure_alert()], &rules(), &notifiers).await, [1]);
+        assert_eq!(std::fs::read_to_string(&out).unwrap(), "me/fork 2");
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_to_http_and_smtp_stand_ins() {
+        let (hook_url, hook_rx) = http_stand_in();
+        let (slack_url, slack_rx) = http_stand_in();
+        let (smtp_port, smtp_rx) = smtp_stand_in();
+
+        let channels = vec![
+            ChannelConfig {
+                name: "hook".to_string(),
+                kind: ChannelKind::Webhook { url: hook_url },
+            },
+            ChannelConfig {
+                name: "slack".to_string(),
+                kind: ChannelKind::Slack { url: slack_url },
+            },
+            ChannelConfig {
+                name: "mail".to_string(),
+                kind: ChannelKind::Email {
+                    smtp_host: "127.0.0.1".to_string(),
+                    smtp_port,
+                    tls: SmtpTls::None,
+                    username: None,
+                    from: "gitr@example.com".to_string(),
+                    to: vec!["ops@example.com".to_string()],
+                },
+            },
+        ];
+        let notifiers = build_notifiers(&channels, &MemoryStore::new()).unwrap();
+        let alert = failure_alert();
+
+        let sent = dispatch(std::slice::from_ref(&alert), &rules(), &notifiers).await;
+        assert_eq!(sent, [3]);
+
+        let posted: Alert = serde_json::from_str(&hook_rx.recv().unwrap()).unwrap();
+        assert_eq!(posted, alert);
+        let slack: serde_json::Value = serde_json::from_str(&slack_rx.recv().unwrap()).unwrap();
+        assert!(slack["text"].as_str().unwrap().contains("me/fork"));
+        let mail = smtp_rx.recv().unwrap();
+        assert!(mail.contains("Subject: [gitr] me/fork: last 2 syncs failed"));
+        assert!(mail.contains("To: ops@example.com"));
+
+        // The behind rule only targets the webhook channel.
+        let behind = Alert {
+            rule: "behind>100".to_string(),
+            ..alert
+        };
+        let (hook_url, hook_rx) = http_stand_in();
+        let channels = vec![
+            ChannelConfig {
+                name: "hook".to_string(),
+                kind: ChannelKind::Webhook { url: hook_url },
+            },
+            ChannelConfig {
+                name: "cmd".to_string(),
+                kind: ChannelKind::Command {
+                    command: "exit 1".to_string(),
+                },
+            },
+        ];
+        let notifiers = build_notifiers(&channels, &MemoryStore::new()).unwrap();
+        assert_eq!(dispatch(&[behind], &rules(), &notifiers).await, [1]);
+        assert!(hook_rx.recv().is_ok());
+    }
+}
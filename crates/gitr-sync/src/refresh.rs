@@ -0,0 +1,257 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use chrono::Utc;
+use gitr_core::error::GitrError;
+use gitr_core::models::repo::Repo;
+use gitr_core::models::sync_state::BranchSnapshot;
+use gitr_core::retry::RetryPolicy;
+use gitr_discover::reconcile::normalize_url;
+use gitr_host::HostProvider;
+
+use crate::backend::GitBackend;
+use crate::git_ops;
+use crate::retry::Retrier;
+
+/// A fork to refresh, with whatever is available to inspect it.
+pub struct RefreshTarget {
+    pub repo: Repo,
+    /// Local clone, if one exists on disk.
+    pub local_path: Option<PathBuf>,
+    /// Upstream clone URL, used to add the `upstream` remote when missing.
+    pub upstream_clone_url: Option<String>,
+    /// Host API, used when there is no local clone.
+    pub provider: Option<Arc<dyn HostProvider>>,
+}
+
+/// Outcome of refreshing a single repo.
+#[derive(Debug)]
+pub struct RefreshResult {
+    pub repo_full_name: String,
+    pub snapshots: Vec<BranchSnapshot>,
+    pub error: Option<String>,
+}
+
+impl RefreshResult {
+    fn from_outcome(repo: &Repo, outcome: Result<Vec<BranchSnapshot>, GitrError>) -> Self {
+        match outcome {
+            Ok(snapshots) => Self {
+                repo_full_name: repo.full_name.clone(),
+                snapshots,
+                error: None,
+            },
+            Err(e) => Self {
+                repo_full_name: repo.full_name.clone(),
+                snapshots: Vec::new(),
+                error: Some(e.to_string()),
+            },
+        }
+    }
+}
+
+/// Whether `dir` is a clone of `repo`, judged by its `origin` URL. A clone
+/// of some other repo, say a same-named fork on another owner, would give
+/// counts that belong to that repo instead.
+pub fn is_clone_of(dir: &Path, repo: &Repo) -> bool {
+    match git_ops::remote_url(dir, "origin") {
+        Ok(Some(url)) => normalize_url(&url) == normalize_url(&repo.clone_url),
+        _ => false,
+    }
+}
+
+/// Snapshot the default branch of a local clone.
+///
+/// With `fetch`, `origin` and `upstream` are fetched first (adding `upstream`
+/// from `upstream_clone_url` if needed). Behind/ahead compare the fork's
+/// `origin/<branch>` (or the local branch if it has no remote-tracking ref)
+/// against `upstream/<branch>`.
+pub fn refresh_local(
+    repo: &Repo,
+    dir: &Path,
+    upstream_clone_url: Option<&str>,
+    git: &dyn GitBackend,
+    retry: &RetryPolicy,
+    fetch: bool,
+) -> Result<BranchSnapshot, GitrError> {
+    let branch = &repo.default_branch;
+
+    if fetch {
+        let mut retrier = Retrier::new(retry);
+        let mut remotes = git.remote_list(dir)?;
+        if !remotes.iter().any(|r| r == "upstream") {
+            if let Some(url) = upstream_clone_url {
+                git.remote_add(dir, "upstream", url)?;
+                remotes.push("upstream".to_string());
+            }
+        }
+        for remote in ["origin", "upstream"] {
+            if remotes.iter().any(|r| r == remote) {
+                retrier.run(&format!("fetch {remote}"), || git.fetch(dir, remote))?;
+            }
+        }
+    }
+
+    let local_ref = format!("refs/heads/{branch}");
+    let remote_ref = format!("refs/remotes/origin/{branch}");
+    let upstream_ref = format!("refs/remotes/upstream/{branch}");
+
+    let local_sha = git.rev_parse(dir, &local_ref)?;
+    let remote_sha = git.rev_parse(dir, &remote_ref)?;
+    let upstream_sha = git.rev_parse(dir, &upstream_ref)?;
+
+    if upstream_sha.is_none() {
+        return Err(GitrError::GitError {
+            message: format!("upstream/{branch} not found in {}", dir.display()),
+        });
+    }
+    let fork_ref = match (&remote_sha, &local_sha) {
+        (Some(_), _) => remote_ref,
+        (None, Some(_)) => local_ref,
+        (None, None) => {
+            return Err(GitrError::GitError {
+                message: format!("branch {branch} not found in {}", dir.display()),
+            })
+        }
+    };
+
+    let behind_count = git.rev_list_count(dir, &fork_ref, &upstream_ref)?;
+    let ahead_count = git.rev_list_count(dir, &upstream_ref, &fork_ref)?;
+
+    Ok(BranchSnapshot {
+        repo_id: repo.id.clone(),
+        branch: branch.clone(),
+        local_sha,
+        remote_sha,
+        upstream_sha,
+        behind_count,
+        ahead_count,
+        updated_at: Utc::now(),
+    })
+}
+
+/// Snapshot a fork from the host's compare API. SHAs are left unset since the
+/// API only reports counts.
+pub async fn refresh_remote(
+    repo: &Repo,
+    provider: &dyn HostProvider,
+) -> Result<Vec<BranchSnapshot>, GitrError> {
+    let statuses = provider.fork_sync_status(&repo.owner, &repo.name).await?;
+    let now = Utc::now();
+    Ok(statuses
+        .into_iter()
+        .map(|s| BranchSnapshot {
+            repo_id: repo.id.clone(),
+            branch: s.branch,
+            local_sha: None,
+            remote_sha: None,
+            upstream_sha: None,
+            behind_count: s.behind_by,
+            ahead_count: s.ahead_by,
+            updated_at: now,
+        })
+        .collect())
+}
+
+/// Refresh one target: local clone if there is one, otherwise the host API.
+pub(crate) async fn refresh_target(
+    target: RefreshTarget,
+    git: Arc<dyn GitBackend>,
+    retry: RetryPolicy,
+    fetch: bool,
+) -> RefreshResult {
+    let RefreshTarget {
+        repo,
+        local_path,
+        upstream_clone_url,
+        provider,
+    } = target;
+
+    if let Some(dir) = local_path {
+        let full_name = repo.full_name.clone();
+        let handle = tokio::task::spawn_blocking(move || {
+            let outcome = refresh_local(
+                &repo,
+                &dir,
+                upstream_clone_url.as_deref(),
+                git.as_ref(),
+                &retry,
+                fetch,
+            )
+            .map(|snap| vec![snap]);
+            RefreshResult::from_outcome(&repo, outcome)
+        });
+        return match handle.await {
+            Ok(result) => result,
+            Err(e) => RefreshResult {
+                repo_full_name: full_name,
+                snapshots: Vec::new(),
+                error: Some(format!("refresh task failed: {e}")),
+            },
+        };
+    }
+
+    let outcome = match provider {
+        Some(provider) => refresh_remote(&repo, provider.as_ref()).await,
+        None => Err(GitrError::Other(
+            "no local clone and no API access for host".to_string(),
+        )),
+    };
+    RefreshResult::from_outcome(&repo, outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::CliBackend;
+    use crate::test_util::{commit, git};
+    use gitr_core::models::host::HostId;
+    use gitr_core::models::repo::DiscoverySource;
+
+    #[test]
+    fn test_refresh_local_counts_behind_and_ahead() {
+        let tmp = tempfile::tempdir().unwrap();
+        let upstream = tmp.path().join("upstream");
+        let fork = tmp.path().join("fork");
+        let clone = tmp.path().join("clone");
+
+        std::fs::create_dir_all(&upstream).unwrap();
+        git(&upstream, &["init", "-q", "-b", "main"]);
+        commit(&upstream, "base");
+        git(tmp.path(), &["clone", "-q", "--bare", "upstream", "fork"]);
+        commit(&upstream, "up1");
+        commit(&upstream, "up2");
+
+        git(tmp.path(), &["clone", "-q", "fork", "clone"]);
+        commit(&clone, "mine");
+        git(&clone, &["push", "-q", "origin", "main"]);
+
+        let repo = Repo::new(
+            "me/fork".to_string(),
+            HostId::new(),
+            fork.to_string_lossy().to_string(),
+            "main".to_string(),
+            DiscoverySource::Filesystem,
+        );
+        let snap = refresh_local(
+            &repo,
+            &clone,
+            Some(upstream.to_str().unwrap()),
+            &CliBackend,
+            &RetryPolicy::none(),
+            true,
+        )
+        .unwrap();
+
+        assert_eq!(snap.branch, "main");
+        assert_eq!(snap.behind_count, 2);
+        assert_eq!(snap.ahead_count, 1);
+        assert_eq!(snap.local_sha, snap.remote_sha);
+        assert!(snap.upstream_sha.is_some());
+
+        assert!(is_clone_of(&clone, &repo));
+        let mut other = repo.clone();
+        other.clone_url = upstream.to_string_lossy().to_string();
+        assert!(!is_clone_of(&clone, &other));
+        assert!(!is_clone_of(&upstream, &repo), "no origin remote");
+    }
+}
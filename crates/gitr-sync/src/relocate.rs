@@ -0,0 +1,135 @@
+use std::path::{Path, PathBuf};
+
+use gitr_core::error::GitrError;
+
+use crate::git_ops;
+
+/// Check that the checkout at `from` can be moved to `to`: the destination
+/// must not exist or lie inside the checkout, the working tree must be
+/// clean, and submodules stay with their superproject.
+pub fn check(from: &Path, to: &Path) -> Result<(), GitrError> {
+    let refuse = |message: String| Err(GitrError::Other(message));
+    if to.exists() {
+        return refuse(format!("destination {} already exists", to.display()));
+    }
+    if to.starts_with(from) {
+        return refuse(format!(
+            "destination {} is inside the checkout",
+            to.display()
+        ));
+    }
+    if let Some(superproject) = git_ops::superproject(from)? {
+        return refuse(format!("it is a submodule of {superproject}"));
+    }
+    if !git_ops::is_bare(from)? && git_ops::is_dirty(from)? {
+        return refuse("the working tree has uncommitted changes".to_string());
+    }
+    Ok(())
+}
+
+/// Move the checkout at `from` to `to`, creating `to`'s parent directories,
+/// then repair the worktree links the move broke: a moved linked worktree
+/// is reconnected to its main repo, and a moved main repo to its linked
+/// worktrees (including any that moved with it).
+///
+/// The move is a rename, so `to` must be on the same filesystem.
+pub fn relocate(from: &Path, to: &Path) -> Result<(), GitrError> {
+    check(from, to)?;
+    let from = std::fs::canonicalize(from)?;
+    let worktrees = git_ops::worktree_list(&from)?;
+    let is_main = worktrees
+        .first()
+        .is_some_and(|main| canonical(main) == from);
+
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::rename(&from, to).map_err(|e| match e.kind() {
+        std::io::ErrorKind::CrossesDevices => GitrError::Other(format!(
+            "{} is on a different filesystem; move it by hand and run `gitr repo add`",
+            to.display()
+        )),
+        _ => e.into(),
+    })?;
+
+    if is_main {
+        let linked: Vec<PathBuf> = worktrees
+            .iter()
+            .skip(1)
+            .map(|wt| match canonical(wt).strip_prefix(&from) {
+                Ok(rest) => to.join(rest),
+                Err(_) => wt.clone(),
+            })
+            .collect();
+        if !linked.is_empty() {
+            git_ops::worktree_repair(to, &linked)?;
+        }
+    } else {
+        git_ops::worktree_repair(to, &[])?;
+    }
+    Ok(())
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::git;
+
+    #[test]
+    fn test_relocate() {
+        let tmp = tempfile::tempdir().unwrap();
+        let tmp = std::fs::canonicalize(tmp.path()).unwrap();
+        let repo = tmp.join("old/tool");
+        std::fs::create_dir_all(&repo).unwrap();
+        git(&repo, &["init", "-q", "-b", "main"]);
+        git(&repo, &["commit", "-q", "--allow-empty", "-m", "first"]);
+        let outside = tmp.join("wt-outside");
+        let inside = repo.join("wt-inside");
+        git(
+            &repo,
+            &[
+                "worktree",
+                "add",
+                "-q",
+                "-b",
+                "a",
+                outside.to_str().unwrap(),
+            ],
+        );
+        git(
+            &repo,
+            &["worktree", "add", "-q", "-b", "b", inside.to_str().unwrap()],
+        );
+        std::fs::write(repo.join(".git/info/exclude"), "wt-inside\n").unwrap();
+
+        // Moving the main repo keeps both worktrees attached.
+        let moved = tmp.join("src/host/me/tool");
+        relocate(&repo, &moved).unwrap();
+        assert!(!repo.exists());
+        let inside = moved.join("wt-inside");
+        for wt in [&outside, &inside] {
+            assert!(!git_ops::is_dirty(wt).unwrap(), "{}", wt.display());
+        }
+        let mut listed = git_ops::worktree_list(&moved).unwrap();
+        listed.sort();
+        assert_eq!(listed, vec![moved.clone(), inside.clone(), outside.clone()]);
+
+        // Moving a linked worktree updates the main repo's record of it.
+        let outside_moved = tmp.join("src/wt");
+        relocate(&outside, &outside_moved).unwrap();
+        let listed = git_ops::worktree_list(&moved).unwrap();
+        assert!(listed.contains(&outside_moved));
+
+        // Dirty trees and taken destinations are refused.
+        std::fs::write(moved.join("new.txt"), "x").unwrap();
+        assert!(check(&moved, &tmp.join("elsewhere")).is_err());
+        std::fs::remove_file(moved.join("new.txt")).unwrap();
+        assert!(check(&moved, &tmp.join("elsewhere")).is_ok());
+        assert!(check(&moved, &outside_moved).is_err());
+        assert!(check(&moved, &moved.join("sub")).is_err());
+    }
+}
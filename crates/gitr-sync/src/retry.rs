@@ -0,0 +1,99 @@
+use gitr_core::error::GitrError;
+use gitr_core::retry::RetryPolicy;
+
+/// Runs blocking git operations under a [`RetryPolicy`], counting every
+/// attempt so the total can be recorded on the `SyncRecord`.
+pub struct Retrier<'a> {
+    policy: &'a RetryPolicy,
+    attempts: u32,
+}
+
+impl<'a> Retrier<'a> {
+    pub fn new(policy: &'a RetryPolicy) -> Self {
+        Self {
+            policy,
+            attempts: 1,
+        }
+    }
+
+    /// Total attempts so far: 1 plus one per retry across all operations.
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    /// Run `op`, retrying transient failures with jittered exponential backoff.
+    /// Permanent failures and the last transient failure are returned as-is.
+    pub fn run<T>(
+        &mut self,
+        what: &str,
+        mut op: impl FnMut() -> Result<T, GitrError>,
+    ) -> Result<T, GitrError> {
+        let mut attempt = 1;
+        loop {
+            match op() {
+                Ok(v) => return Ok(v),
+                Err(e) if self.policy.should_retry(attempt, &e) => {
+                    let delay = self.policy.delay_for(attempt, &e);
+                    tracing::warn!(
+                        "{what} failed (attempt {attempt}/{}), retrying in {:.1}s: {e}",
+                        self.policy.max_attempts,
+                        delay.as_secs_f32()
+                    );
+                    std::thread::sleep(delay);
+                    attempt += 1;
+                    self.attempts += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fast_policy() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            base_delay_ms: 1,
+            max_delay_ms: 1,
+            max_rate_limit_wait_secs: 0,
+        }
+    }
+
+    #[test]
+    fn test_retries_transient_then_succeeds() {
+        let policy = fast_policy();
+        let mut retrier = Retrier::new(&policy);
+        let mut calls = 0;
+        let result = retrier.run("fetch", || {
+            calls += 1;
+            if calls < 3 {
+                Err(GitrError::GitError {
+                    message: "fatal: the remote end hung up unexpectedly".into(),
+                })
+            } else {
+                Ok(calls)
+            }
+        });
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(retrier.attempts(), 3);
+    }
+
+    #[test]
+    fn test_permanent_error_not_retried() {
+        let policy = fast_policy();
+        let mut retrier = Retrier::new(&policy);
+        let mut calls = 0;
+        let result: Result<(), _> = retrier.run("push", || {
+            calls += 1;
+            Err(GitrError::GitError {
+                message: "! [rejected] main -> main (non-fast-forward)".into(),
+            })
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+        assert_eq!(retrier.attempts(), 1);
+    }
+}
@@ -0,0 +1,273 @@
+use std::collections::BTreeSet;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use cron::Schedule;
+
+use gitr_core::config::MissedRunPolicy;
+use gitr_core::error::GitrError;
+use gitr_core::models::schedule::ScheduleState;
+use gitr_core::models::sync_link::SyncLinkId;
+
+/// Parse a cron expression. Standard 5-field expressions (`min hour dom mon
+/// dow`) are accepted alongside the 6/7-field form with seconds (and year).
+/// In 5-field expressions days of the week count from Sunday = 0 (or 7), as
+/// in crontab; the longer form keeps the `cron` crate's Sunday = 1. Times
+/// are UTC.
+pub fn parse_cron(expr: &str) -> Result<Schedule, GitrError> {
+    let fields: Vec<&str> = expr.split_whitespace().collect();
+    let full = match fields.as_slice() {
+        [min, hour, dom, mon, dow] => {
+            format!("0 {min} {hour} {dom} {mon} {}", crontab_dow(dow))
+        }
+        _ => expr.to_string(),
+    };
+    Schedule::from_str(&full).map_err(|e| GitrError::Config {
+        message: format!("invalid cron expression '{expr}': {e}"),
+    })
+}
+
+/// Renumber a crontab day-of-week field (Sunday = 0 or 7) for the `cron`
+/// crate (Sunday = 1). Numeric items become explicit day lists; names and
+/// anything unparseable pass through for the parser to handle.
+fn crontab_dow(field: &str) -> String {
+    if field == "*" || field == "?" {
+        return field.to_string();
+    }
+    field
+        .split(',')
+        .map(|item| {
+            let (range, step) = match item.split_once('/') {
+                Some((range, step)) => (range, step.parse().ok()),
+                None => (item, Some(1)),
+            };
+            let bounds = match range.split_once('-') {
+                _ if range == "*" => Some((0, 6)),
+                Some((lo, hi)) => lo.parse().ok().zip(hi.parse().ok()),
+                // `n/step` runs from n to the end of the week.
+                None => range
+                    .parse()
+                    .ok()
+                    .map(|d| (d, if item.contains('/') { 6 } else { d })),
+            };
+            match (bounds, step) {
+                (Some((lo, hi)), Some(step)) if lo <= hi && hi <= 7 && step > 0 => {
+                    let days: BTreeSet<u32> = (lo..=hi).step_by(step).map(|d| d % 7 + 1).collect();
+                    days.iter()
+                        .map(u32::to_string)
+                        .collect::<Vec<_>>()
+                        .join(",")
+                }
+                _ => item.to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// What a scheduled job runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JobKind {
+    /// A sync link with a `Schedule` trigger.
+    Link(SyncLinkId),
+    /// Sync all tracked forks (`daemon.fork_sync_cron`).
+    Forks,
+}
+
+impl JobKind {
+    /// Key under which the job's state is persisted.
+    pub fn job_id(&self) -> String {
+        match self {
+            JobKind::Link(id) => format!("link:{id}"),
+            JobKind::Forks => "forks".to_string(),
+        }
+    }
+}
+
+/// A cron-driven job and when it next fires.
+#[derive(Debug, Clone)]
+pub struct ScheduledJob {
+    pub kind: JobKind,
+    pub cron: String,
+    schedule: Schedule,
+    /// `None` once the expression has no future fire times.
+    pub next_run: Option<DateTime<Utc>>,
+    pub last_run: Option<DateTime<Utc>>,
+}
+
+impl ScheduledJob {
+    /// Build a job, resuming from `persisted` state if its cron expression
+    /// is unchanged. A run that fell due while the daemon was down fires
+    /// immediately under `CatchUp` (once, however many were missed) and is
+    /// dropped under `Skip`.
+    pub fn resume(
+        kind: JobKind,
+        cron: &str,
+        persisted: Option<&ScheduleState>,
+        now: DateTime<Utc>,
+        policy: MissedRunPolicy,
+    ) -> Result<Self, GitrError> {
+        let schedule = parse_cron(cron)?;
+        let upcoming = schedule.after(&now).next();
+
+        let (next_run, last_run) = match persisted {
+            Some(state) if state.cron == cron => {
+                let next = if state.next_run_at > now {
+                    Some(state.next_run_at)
+                } else {
+                    match policy {
+                        MissedRunPolicy::CatchUp => Some(now),
+                        MissedRunPolicy::Skip => upcoming,
+                    }
+                };
+                (next, state.last_run_at)
+            }
+            Some(state) => (upcoming, state.last_run_at),
+            None => (upcoming, None),
+        };
+
+        Ok(Self {
+            kind,
+            cron: cron.to_string(),
+            schedule,
+            next_run,
+            last_run,
+        })
+    }
+
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.next_run.is_some_and(|next| next <= now)
+    }
+
+    /// Record a run starting at `now` and advance to the next fire time.
+    pub fn mark_run(&mut self, now: DateTime<Utc>) {
+        self.last_run = Some(now);
+        self.next_run = self.schedule.after(&now).next();
+    }
+
+    /// State to persist, or `None` if the job will never fire again.
+    pub fn state(&self) -> Option<ScheduleState> {
+        Some(ScheduleState {
+            job_id: self.kind.job_id(),
+            cron: self.cron.clone(),
+            last_run_at: self.last_run,
+            next_run_at: self.next_run?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(h: u32, m: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 1, h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn test_parse_cron_accepts_five_fields() {
+        let schedule = parse_cron("30 */6 * * *").unwrap();
+        assert_eq!(schedule.after(&at(0, 0)).next(), Some(at(0, 30)));
+        assert_eq!(schedule.after(&at(0, 30)).next(), Some(at(6, 30)));
+        assert!(parse_cron("0 0 12 * * *").is_ok());
+        assert!(parse_cron("every tuesday").is_err());
+    }
+
+    #[test]
+    fn test_parse_cron_counts_days_from_sunday() {
+        // 2026-03-01 is a Sunday.
+        let sunday = at(0, 0);
+        let monday = sunday + chrono::Duration::days(1);
+        let weekdays = parse_cron("0 9 * * 1-5").unwrap();
+        assert_eq!(
+            weekdays.after(&sunday).next(),
+            Some(monday + chrono::Duration::hours(9))
+        );
+        let saturday = sunday + chrono::Duration::days(6);
+        assert_eq!(
+            weekdays.after(&saturday).next(),
+            Some(saturday + chrono::Duration::days(2) + chrono::Duration::hours(9))
+        );
+
+        for expr in ["0 9 * * 0", "0 9 * * 7", "0 9 * * 6-7/1", "0 9 * * SUN"] {
+            let next = parse_cron(expr).unwrap().after(&sunday).next();
+            assert_eq!(next, Some(at(9, 0)), "{expr}");
+        }
+        // The 6-field form is passed through unchanged.
+        let six = parse_cron("0 0 9 * * 1").unwrap();
+        assert_eq!(six.after(&sunday).next(), Some(at(9, 0)));
+    }
+
+    #[test]
+    fn test_resume_missed_runs() {
+        let persisted = ScheduleState {
+            job_id: "forks".to_string(),
+            cron: "0 * * * *".to_string(),
+            last_run_at: Some(at(8, 0)),
+            next_run_at: at(9, 0),
+        };
+        let now = at(11, 20);
+
+        let catch_up = ScheduledJob::resume(
+            JobKind::Forks,
+            "0 * * * *",
+            Some(&persisted),
+            now,
+            MissedRunPolicy::CatchUp,
+        )
+        .unwrap();
+        assert!(catch_up.is_due(now));
+        assert_eq!(catch_up.last_run, Some(at(8, 0)));
+
+        let skip = ScheduledJob::resume(
+            JobKind::Forks,
+            "0 * * * *",
+            Some(&persisted),
+            now,
+            MissedRunPolicy::Skip,
+        )
+        .unwrap();
+        assert!(!skip.is_due(now));
+        assert_eq!(skip.next_run, Some(at(12, 0)));
+
+        // Not yet due: keep the persisted time regardless of policy.
+        let early = ScheduledJob::resume(
+            JobKind::Forks,
+            "0 * * * *",
+            Some(&persisted),
+            at(8, 30),
+            MissedRunPolicy::CatchUp,
+        )
+        .unwrap();
+        assert_eq!(early.next_run, Some(at(9, 0)));
+
+        // A changed expression starts over from now.
+        let changed = ScheduledJob::resume(
+            JobKind::Forks,
+            "15 * * * *",
+            Some(&persisted),
+            now,
+            MissedRunPolicy::CatchUp,
+        )
+        .unwrap();
+        assert_eq!(changed.next_run, Some(at(12, 15)));
+    }
+
+    #[test]
+    fn test_mark_run_advances() {
+        let mut job = ScheduledJob::resume(
+            JobKind::Forks,
+            "0 */6 * * *",
+            None,
+            at(1, 0),
+            MissedRunPolicy::Skip,
+        )
+        .unwrap();
+        assert_eq!(job.next_run, Some(at(6, 0)));
+        job.mark_run(at(6, 0));
+        assert_eq!(job.last_run, Some(at(6, 0)));
+        assert_eq!(job.next_run, Some(at(12, 0)));
+        assert_eq!(job.state().unwrap().job_id, "forks");
+    }
+}
@@ -0,0 +1,56 @@
+//! Git fixtures shared by this crate's tests.
+
+use std::path::Path;
+use std::process::Command;
+
+/// Author and committer name of every fixture commit.
+pub const AUTHOR: &str = "gitr test";
+
+/// Run git in `dir` as [`AUTHOR`], panicking if it fails.
+pub fn git(dir: &Path, args: &[&str]) {
+    run(dir, args, None);
+}
+
+/// [`git`] with the author and committer dates pinned to `date`.
+#[cfg(feature = "gitoxide")]
+pub fn git_at(dir: &Path, args: &[&str], date: &str) {
+    run(dir, args, Some(date));
+}
+
+/// Commit a new file named after `msg`.
+pub fn commit(dir: &Path, msg: &str) {
+    commit_inner(dir, msg, None);
+}
+
+/// [`commit`] dated `date`.
+#[cfg(feature = "gitoxide")]
+pub fn commit_at(dir: &Path, msg: &str, date: &str) {
+    commit_inner(dir, msg, Some(date));
+}
+
+fn commit_inner(dir: &Path, msg: &str, date: Option<&str>) {
+    let file = msg.replace([':', ' '], "_");
+    std::fs::write(dir.join(&file), msg).unwrap();
+    run(dir, &["add", &file], date);
+    run(dir, &["commit", "-q", "-m", msg], date);
+}
+
+fn run(dir: &Path, args: &[&str], date: Option<&str>) {
+    let mut cmd = Command::new("git");
+    cmd.args(args)
+        .current_dir(dir)
+        .env("GIT_AUTHOR_NAME", AUTHOR)
+        .env("GIT_AUTHOR_EMAIL", "test@example.com")
+        .env("GIT_COMMITTER_NAME", AUTHOR)
+        .env("GIT_COMMITTER_EMAIL", "test@example.com");
+    if let Some(date) = date {
+        cmd.env("GIT_AUTHOR_DATE", date)
+            .env("GIT_COMMITTER_DATE", date);
+    }
+    let output = cmd.output().unwrap();
+    assert!(
+        output.status.success(),
+        "git {args:?} failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,519 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::time::{Duration, Instant};
+
+use hmac::{Hmac, Mac};
+use serde_json::Value;
+use sha2::Sha256;
+
+use gitr_core::error::GitrError;
+use gitr_core::models::host::HostId;
+use gitr_core::models::repo::{Repo, RepoId};
+use gitr_core::models::sync_link::{SyncDirection, SyncLink, SyncLinkId, SyncTrigger};
+use gitr_discover::reconcile::normalize_url;
+
+/// The forge that sent a webhook, as identified by its event header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookSource {
+    GitHub,
+    GitLab,
+    Gitea,
+}
+
+impl std::fmt::Display for WebhookSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WebhookSource::GitHub => write!(f, "github"),
+            WebhookSource::GitLab => write!(f, "gitlab"),
+            WebhookSource::Gitea => write!(f, "gitea"),
+        }
+    }
+}
+
+impl WebhookSource {
+    /// Identify the sender from request headers (names lowercased). Gitea is
+    /// checked first since it also sends GitHub-compatible headers.
+    pub fn detect(headers: &HashMap<String, String>) -> Option<Self> {
+        if headers.contains_key("x-gitea-event") {
+            Some(WebhookSource::Gitea)
+        } else if headers.contains_key("x-gitlab-event") {
+            Some(WebhookSource::GitLab)
+        } else if headers.contains_key("x-github-event") {
+            Some(WebhookSource::GitHub)
+        } else {
+            None
+        }
+    }
+
+    fn event<'a>(&self, headers: &'a HashMap<String, String>) -> Option<&'a str> {
+        let name = match self {
+            WebhookSource::GitHub => "x-github-event",
+            WebhookSource::GitLab => "x-gitlab-event",
+            WebhookSource::Gitea => "x-gitea-event",
+        };
+        headers.get(name).map(String::as_str)
+    }
+}
+
+/// A branch push reported by a webhook.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PushEvent {
+    pub source: WebhookSource,
+    /// `owner/name` (or the GitLab namespace path) of the pushed repo.
+    pub repo_full_name: String,
+    /// Clone URLs of the pushed repo, HTTPS and SSH.
+    pub clone_urls: Vec<String>,
+    pub branch: String,
+    /// SHA the branch now points at.
+    pub after: String,
+}
+
+/// Check a webhook against the shared secret: an HMAC-SHA256 of the body for
+/// GitHub and Gitea, the plain token header for GitLab.
+pub fn verify_signature(
+    source: WebhookSource,
+    headers: &HashMap<String, String>,
+    body: &[u8],
+    secret: &str,
+) -> bool {
+    let signature = match source {
+        WebhookSource::GitHub => headers
+            .get("x-hub-signature-256")
+            .and_then(|s| s.strip_prefix("sha256=")),
+        WebhookSource::Gitea => headers.get("x-gitea-signature").map(String::as_str),
+        WebhookSource::GitLab => {
+            return headers
+                .get("x-gitlab-token")
+                .is_some_and(|token| constant_time_eq(token.as_bytes(), secret.as_bytes()));
+        }
+    };
+    let Some(expected) = signature.and_then(|s| hex::decode(s.trim()).ok()) else {
+        return false;
+    };
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body);
+    mac.verify_slice(&expected).is_ok()
+}
+
+/// Compare secrets without leaking where they differ through timing.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Parse a webhook body into a branch push. Other events (pings, tag pushes,
+/// branch deletions) yield `None`.
+pub fn parse_push(
+    source: WebhookSource,
+    headers: &HashMap<String, String>,
+    body: &[u8],
+) -> Result<Option<PushEvent>, GitrError> {
+    let is_push = match source.event(headers) {
+        Some(event) => match source {
+            WebhookSource::GitLab => event == "Push Hook",
+            WebhookSource::GitHub | WebhookSource::Gitea => event == "push",
+        },
+        None => false,
+    };
+    if !is_push {
+        return Ok(None);
+    }
+
+    let payload: Value = serde_json::from_slice(body)
+        .map_err(|e| GitrError::Serialization(format!("invalid {source} webhook payload: {e}")))?;
+    let field = |path: &[&str]| -> Option<String> {
+        let mut value = &payload;
+        for key in path {
+            value = value.get(key)?;
+        }
+        value.as_str().map(str::to_string)
+    };
+
+    let Some(branch) =
+        field(&["ref"]).and_then(|r| r.strip_prefix("refs/heads/").map(str::to_string))
+    else {
+        return Ok(None);
+    };
+    let after = field(&["after"]).unwrap_or_default();
+    if after.is_empty() || after.bytes().all(|b| b == b'0') {
+        return Ok(None);
+    }
+
+    let (repo_full_name, clone_urls) = match source {
+        WebhookSource::GitLab => (
+            field(&["project", "path_with_namespace"]),
+            [
+                field(&["project", "git_http_url"]),
+                field(&["project", "git_ssh_url"]),
+            ],
+        ),
+        WebhookSource::GitHub | WebhookSource::Gitea => (
+            field(&["repository", "full_name"]),
+            [
+                field(&["repository", "clone_url"]),
+                field(&["repository", "ssh_url"]),
+            ],
+        ),
+    };
+    let repo_full_name = repo_full_name.ok_or_else(|| {
+        GitrError::Serialization(format!("{source} push payload has no repository name"))
+    })?;
+
+    Ok(Some(PushEvent {
+        source,
+        repo_full_name,
+        clone_urls: clone_urls.into_iter().flatten().collect(),
+        branch,
+        after,
+    }))
+}
+
+/// Something to sync in response to a push.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PushTarget {
+    /// A fork whose upstream was pushed.
+    Fork(RepoId),
+    /// An `Always`-triggered link whose pulled-from side was pushed.
+    Link(SyncLinkId),
+}
+
+/// Work out what a push on `host_id` should sync: forks of the pushed repo
+/// tracking the pushed branch, and enabled `Always` links that read from it.
+/// Repos are matched by clone URL, or by full name on the same host.
+pub fn push_targets(
+    event: &PushEvent,
+    host_id: &HostId,
+    repos: &[Repo],
+    links: &[SyncLink],
+) -> Vec<PushTarget> {
+    let urls: Vec<String> = event.clone_urls.iter().map(|u| normalize_url(u)).collect();
+    let is_pushed = |url: Option<&str>, full_name: Option<&str>, on_host: Option<&HostId>| {
+        url.is_some_and(|u| urls.contains(&normalize_url(u)))
+            || (on_host == Some(host_id)
+                && full_name.is_some_and(|n| n.eq_ignore_ascii_case(&event.repo_full_name)))
+    };
+
+    let mut targets: Vec<PushTarget> = repos
+        .iter()
+        .filter(|r| r.is_fork && r.default_branch == event.branch)
+        .filter(|r| {
+            is_pushed(
+                r.upstream_clone_url.as_deref(),
+                r.upstream_full_name.as_deref(),
+                r.host_id.as_ref(),
+            )
+        })
+        .map(|r| PushTarget::Fork(r.id.clone()))
+        .collect();
+
+    let pushed_repo = |id: &RepoId| {
+        repos.iter().find(|r| {
+            &r.id == id
+                && r.default_branch == event.branch
+                && is_pushed(Some(&r.clone_url), Some(&r.full_name), r.host_id.as_ref())
+        })
+    };
+    for link in links {
+        if !link.enabled || link.trigger != SyncTrigger::Always {
+            continue;
+        }
+        let reads_pushed = match link.direction {
+            SyncDirection::Push => pushed_repo(&link.source_repo_id).is_some(),
+            SyncDirection::Pull => pushed_repo(&link.target_repo_id).is_some(),
+            SyncDirection::Both => {
+                pushed_repo(&link.source_repo_id).is_some()
+                    || pushed_repo(&link.target_repo_id).is_some()
+            }
+        };
+        if reads_pushed {
+            targets.push(PushTarget::Link(link.id.clone()));
+        }
+    }
+    targets
+}
+
+/// Coalesces bursts of triggers: a key becomes due once `delay` has passed
+/// without it being touched again.
+#[derive(Debug)]
+pub struct Debouncer<K> {
+    delay: Duration,
+    pending: HashMap<K, Instant>,
+}
+
+impl<K: Eq + Hash + Clone> Debouncer<K> {
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            pending: HashMap::new(),
+        }
+    }
+
+    /// (Re)start the quiet period for `key`.
+    pub fn touch(&mut self, key: K, now: Instant) {
+        self.pending.insert(key, now + self.delay);
+    }
+
+    /// Remove and return the keys whose quiet period has ended.
+    pub fn take_due(&mut self, now: Instant) -> Vec<K> {
+        let due: Vec<K> = self
+            .pending
+            .iter()
+            .filter(|(_, at)| **at <= now)
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in &due {
+            self.pending.remove(key);
+        }
+        due
+    }
+
+    /// When the next key becomes due.
+    pub fn next_due(&self) -> Option<Instant> {
+        self.pending.values().min().copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gitr_core::models::repo::DiscoverySource;
+    use gitr_core::models::sync_link::MergeStrategy;
+
+    const SECRET: &str = "s3cret";
+
+    fn headers(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn sign(body: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(SECRET.as_bytes()).unwrap();
+        mac.update(body);
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    const GITHUB_PUSH: &str = r#"{
+        "ref": "refs/heads/main",
+        "before": "1111111111111111111111111111111111111111",
+        "after": "2222222222222222222222222222222222222222",
+        "repository": {
+            "full_name": "rust-lang/rust",
+            "clone_url": "https://github.com/rust-lang/rust.git",
+            "ssh_url": "git@github.com:rust-lang/rust.git"
+        }
+    }"#;
+
+    fn fork_of(upstream_url: &str, host_id: &HostId) -> Repo {
+        let mut fork = Repo::new(
+            "me/rust".to_string(),
+            host_id.clone(),
+            "https://github.com/me/rust.git".to_string(),
+            "main".to_string(),
+            DiscoverySource::Api,
+        );
+        fork.is_fork = true;
+        fork.upstream_full_name = Some("rust-lang/rust".to_string());
+        fork.upstream_clone_url = Some(upstream_url.to_string());
+        fork
+    }
+
+    #[test]
+    fn test_verify_github_and_gitea_signatures() {
+        let body = GITHUB_PUSH.as_bytes();
+        let github = headers(&[
+            ("x-github-event", "push"),
+            ("x-hub-signature-256", &format!("sha256={}", sign(body))),
+        ]);
+        assert_eq!(WebhookSource::detect(&github), Some(WebhookSource::GitHub));
+        assert!(verify_signature(
+            WebhookSource::GitHub,
+            &github,
+            body,
+            SECRET
+        ));
+        assert!(!verify_signature(
+            WebhookSource::GitHub,
+            &github,
+            body,
+            "wrong"
+        ));
+        assert!(!verify_signature(
+            WebhookSource::GitHub,
+            &github,
+            b"{}",
+            SECRET
+        ));
+
+        let gitea = headers(&[
+            ("x-github-event", "push"),
+            ("x-gitea-event", "push"),
+            ("x-gitea-signature", &sign(body)),
+        ]);
+        assert_eq!(WebhookSource::detect(&gitea), Some(WebhookSource::Gitea));
+        assert!(verify_signature(WebhookSource::Gitea, &gitea, body, SECRET));
+
+        let unsigned = headers(&[("x-github-event", "push")]);
+        assert!(!verify_signature(
+            WebhookSource::GitHub,
+            &unsigned,
+            body,
+            SECRET
+        ));
+    }
+
+    #[test]
+    fn test_verify_gitlab_token() {
+        let gitlab = headers(&[("x-gitlab-event", "Push Hook"), ("x-gitlab-token", SECRET)]);
+        assert!(verify_signature(
+            WebhookSource::GitLab,
+            &gitlab,
+            b"",
+            SECRET
+        ));
+        assert!(!verify_signature(
+            WebhookSource::GitLab,
+            &gitlab,
+            b"",
+            "s3cret2"
+        ));
+    }
+
+    #[test]
+    fn test_parse_pushes() {
+        let github = headers(&[("x-github-event", "push")]);
+        let event = parse_push(WebhookSource::GitHub, &github, GITHUB_PUSH.as_bytes())
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.repo_full_name, "rust-lang/rust");
+        assert_eq!(event.branch, "main");
+        assert_eq!(event.clone_urls.len(), 2);
+
+        let gitlab = headers(&[("x-gitlab-event", "Push Hook")]);
+        let body = r#"{
+            "ref": "refs/heads/develop",
+            "after": "3333333333333333333333333333333333333333",
+            "project": {
+                "path_with_namespace": "group/sub/tool",
+                "git_http_url": "https://gitlab.com/group/sub/tool.git",
+                "git_ssh_url": "git@gitlab.com:group/sub/tool.git"
+            }
+        }"#;
+        let event = parse_push(WebhookSource::GitLab, &gitlab, body.as_bytes())
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.repo_full_name, "group/sub/tool");
+        assert_eq!(event.branch, "develop");
+
+        // Pings, tags and deletions are not branch pushes.
+        let ping = headers(&[("x-github-event", "ping")]);
+        assert_eq!(
+            parse_push(WebhookSource::GitHub, &ping, b"{}").unwrap(),
+            None
+        );
+        let tag = GITHUB_PUSH.replace("refs/heads/main", "refs/tags/v1.0");
+        assert_eq!(
+            parse_push(WebhookSource::GitHub, &github, tag.as_bytes()).unwrap(),
+            None
+        );
+        let deleted =
+            GITHUB_PUSH.replace("2222222222222222222222222222222222222222", &"0".repeat(40));
+        assert_eq!(
+            parse_push(WebhookSource::GitHub, &github, deleted.as_bytes()).unwrap(),
+            None
+        );
+
+        assert!(parse_push(WebhookSource::GitHub, &github, b"not json").is_err());
+    }
+
+    #[test]
+    fn test_push_targets_forks_and_links() {
+        let github = headers(&[("x-github-event", "push")]);
+        let event = parse_push(WebhookSource::GitHub, &github, GITHUB_PUSH.as_bytes())
+            .unwrap()
+            .unwrap();
+        let host = HostId::new();
+        let other_host = HostId::new();
+
+        // Matched by SSH upstream URL from another host, and by name on the same host.
+        let by_url = fork_of("git@github.com:rust-lang/rust", &other_host);
+        let by_name = fork_of("https://mirror.example/rust.git", &host);
+        let mut other_branch = fork_of("https://github.com/rust-lang/rust", &host);
+        other_branch.default_branch = "master".to_string();
+
+        let mirror = Repo::new(
+            "me/rust-mirror".to_string(),
+            other_host.clone(),
+            "https://gitlab.com/me/rust-mirror.git".to_string(),
+            "main".to_string(),
+            DiscoverySource::Manual,
+        );
+        let upstream = Repo::new(
+            "rust-lang/rust".to_string(),
+            host.clone(),
+            "https://github.com/rust-lang/rust.git".to_string(),
+            "main".to_string(),
+            DiscoverySource::Api,
+        );
+
+        let mut push_link = SyncLink::new(
+            upstream.id.clone(),
+            mirror.id.clone(),
+            SyncDirection::Push,
+            MergeStrategy::FastForward,
+        );
+        push_link.trigger = SyncTrigger::Always;
+        let mut pull_link = push_link.clone();
+        pull_link.id = SyncLinkId::new();
+        pull_link.direction = SyncDirection::Pull;
+        let mut manual_link = push_link.clone();
+        manual_link.id = SyncLinkId::new();
+        manual_link.trigger = SyncTrigger::Manual;
+
+        let repos = vec![
+            by_url.clone(),
+            by_name.clone(),
+            other_branch,
+            mirror,
+            upstream,
+        ];
+        let links = vec![push_link.clone(), pull_link, manual_link];
+        let targets = push_targets(&event, &host, &repos, &links);
+        assert_eq!(
+            targets,
+            vec![
+                PushTarget::Fork(by_url.id),
+                PushTarget::Fork(by_name.id),
+                PushTarget::Link(push_link.id),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_debouncer_coalesces_bursts() {
+        let start = Instant::now();
+        let mut debouncer = Debouncer::new(Duration::from_secs(10));
+        debouncer.touch("a", start);
+        debouncer.touch("b", start + Duration::from_secs(2));
+        debouncer.touch("a", start + Duration::from_secs(5));
+
+        assert_eq!(debouncer.next_due(), Some(start + Duration::from_secs(12)));
+        assert!(debouncer
+            .take_due(start + Duration::from_secs(11))
+            .is_empty());
+        assert_eq!(
+            debouncer.take_due(start + Duration::from_secs(12)),
+            vec!["b"]
+        );
+        assert_eq!(
+            debouncer.take_due(start + Duration::from_secs(15)),
+            vec!["a"]
+        );
+        assert!(debouncer.is_empty());
+    }
+}